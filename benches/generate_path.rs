@@ -0,0 +1,40 @@
+//! Benchmarks of `utils::generate_path`, which runs for every chart on every refresh.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use gjallarhorn::history::{self, History, MAX_SAMPLES};
+use gjallarhorn::utils::generate_path;
+
+/// A history of `len` samples spread evenly over the last minute.
+fn history_of(len: usize, nan_every: usize) -> History {
+    let now = history::now();
+    let mut history = History::new(len);
+    for i in 0..len {
+        let time = now - 60.0 + i as f64 * 60.0 / len as f64;
+        let value = if nan_every > 0 && i % nan_every == 0 {
+            f32::NAN
+        } else {
+            (i % 100) as f32
+        };
+        history.push_at(time, value);
+    }
+    history
+}
+
+fn bench_generate_path(c: &mut Criterion) {
+    let mut group = c.benchmark_group("generate_path");
+    // One sample per second, 100 ms refreshes, and the largest history kept
+    for len in [60, 600, MAX_SAMPLES] {
+        let history = history_of(len, 0);
+        group.bench_with_input(BenchmarkId::new("samples", len), &history, |b, h| {
+            b.iter(|| generate_path(black_box(h), 100.0, 60.0))
+        });
+    }
+    let gappy = history_of(MAX_SAMPLES, 10);
+    group.bench_function("samples_with_nan/3600", |b| {
+        b.iter(|| generate_path(black_box(&gappy), 100.0, 60.0))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_generate_path);
+criterion_main!(benches);
@@ -0,0 +1,155 @@
+//! # Alerts Module
+//!
+//! This module keeps track of the conditions that need the user's attention
+//! (e.g. "open file descriptors at 92% of fs.file-max").
+//!
+//! Collectors raise an alert under a stable ID while the condition holds and clear it
+//! once it recovers. Only transitions (new alert, severity change, recovery) are logged,
+//! so a condition that persists for hours produces a single log line instead of one per tick.
+//! The same transitions are queued as `AlertEvent`s for forwarding (e.g. to the journal).
+
+use crate::markers::now_secs;
+use log::{info, warn};
+
+/// Fraction of a limit at which a usage alert becomes a warning.
+pub const WARNING_FRACTION: f32 = 0.8;
+/// Fraction of a limit at which a usage alert becomes critical.
+pub const CRITICAL_FRACTION: f32 = 0.95;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AlertSeverity {
+    Warning,
+    Critical,
+}
+
+/// A currently active alert.
+#[derive(Debug, Clone)]
+pub struct Alert {
+    /// Stable identifier of the condition (e.g. "limits.file-descriptors").
+    pub id: String,
+    pub severity: AlertSeverity,
+    pub message: String,
+    /// Seconds since the UNIX epoch when the alert was first raised.
+    pub since: u64,
+}
+
+/// Kind of change an `AlertEvent` reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertTransition {
+    Raised,
+    /// Severity changed while the alert was active.
+    SeverityChanged,
+    Cleared,
+}
+
+/// A transition of an alert, queued until taken with `AlertCenter::take_events`.
+#[derive(Debug, Clone)]
+pub struct AlertEvent {
+    pub transition: AlertTransition,
+    pub alert: Alert,
+}
+
+/// Set of active alerts, ordered by severity (critical first) and then age.
+#[derive(Debug, Default)]
+pub struct AlertCenter {
+    active: Vec<Alert>,
+    events: Vec<AlertEvent>,
+}
+
+impl AlertCenter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Raises (or updates) the alert `id`. Returns `true` if it is new or its severity changed.
+    pub fn raise(&mut self, id: &str, severity: AlertSeverity, message: String) -> bool {
+        if let Some(alert) = self.active.iter_mut().find(|a| a.id == id) {
+            let escalated = alert.severity != severity;
+            if escalated {
+                warn!("Alert {:?}: {}", severity, message);
+            }
+            alert.severity = severity;
+            alert.message = message;
+            if escalated {
+                self.events.push(AlertEvent {
+                    transition: AlertTransition::SeverityChanged,
+                    alert: alert.clone(),
+                });
+            }
+            self.sort();
+            return escalated;
+        }
+
+        warn!("Alert {:?}: {}", severity, message);
+        let alert = Alert {
+            id: id.to_string(),
+            severity,
+            message,
+            since: now_secs(),
+        };
+        self.events.push(AlertEvent {
+            transition: AlertTransition::Raised,
+            alert: alert.clone(),
+        });
+        self.active.push(alert);
+        self.sort();
+        true
+    }
+
+    /// Clears the alert `id` if it is active.
+    pub fn clear(&mut self, id: &str) {
+        if let Some(pos) = self.active.iter().position(|a| a.id == id) {
+            let alert = self.active.remove(pos);
+            info!("Alert cleared: {}", alert.message);
+            self.events.push(AlertEvent {
+                transition: AlertTransition::Cleared,
+                alert,
+            });
+        }
+    }
+
+    /// Raises or clears `id` depending on how close `used` is to `limit`.
+    /// `what` names the resource in the alert message (e.g. "Open files").
+    pub fn check_usage(&mut self, id: &str, what: &str, used: u64, limit: u64) {
+        if limit == 0 {
+            self.clear(id);
+            return;
+        }
+
+        let fraction = used as f32 / limit as f32;
+        let severity = if fraction >= CRITICAL_FRACTION {
+            AlertSeverity::Critical
+        } else if fraction >= WARNING_FRACTION {
+            AlertSeverity::Warning
+        } else {
+            self.clear(id);
+            return;
+        };
+
+        self.raise(
+            id,
+            severity,
+            format!(
+                "{} at {:.0}% of limit ({} / {})",
+                what,
+                fraction * 100.0,
+                used,
+                limit
+            ),
+        );
+    }
+
+    pub fn active(&self) -> &[Alert] {
+        &self.active
+    }
+
+    /// Returns the transitions since the previous call.
+    pub fn take_events(&mut self) -> Vec<AlertEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    fn sort(&mut self) {
+        self.active
+            .sort_by(|a, b| b.severity.cmp(&a.severity).then(a.since.cmp(&b.since)));
+    }
+}
@@ -0,0 +1,2919 @@
+//! # Application Module
+//!
+//! This module is the Slint desktop application: the main window started by `run()`,
+//! the detached panels and the UI timer that copies `SystemMonitor` data into the models.
+//! It is only built with the `gui` feature (on by default).
+
+use crate::collector::{self, Collector};
+use crate::history::{self, History};
+use crate::monitor::{self, SystemMonitor};
+use crate::processes::SchedPolicy;
+use crate::settings::{
+    validate_bind_address, AppSettings, BackupSettings, CertSettings, ChartScaleSettings,
+    CollectorSettings, CsvSettings, DetachedPanel, GrpcSettings, NotifyChannel, OverlaySettings,
+    PrometheusSettings, WebhookSettings,
+};
+use crate::utils::{
+    brush_to_hex, generate_level_path, generate_path, generate_points_path, generate_span_path,
+    generate_stacked_paths, hex_to_color,
+};
+use crate::{
+    alerts, display, graphics, iommu, kmsg, longterm, markers, processes, routes, screenshot,
+    share, wifi, worker,
+};
+use log::{error, info};
+use slint::{Model, Timer, TimerMode};
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+include!(env!("SLINT_INCLUDE_GENERATED"));
+
+/// Runs the Gjallarhorn application.
+///
+/// This is the main entry point which:
+/// 1. Initializes the `SystemMonitor` to gather resource data.
+/// 2. Loads persistent `AppSettings` from disk.
+/// 3. Sets up the Slint UI (`AppWindow`).
+/// 4. Starts the background `Collector` at the user's refresh rate and a UI timer
+///    that redraws whenever it publishes a new snapshot.
+/// 5. Binds UI callbacks for saving preferences and quitting.
+///
+/// Use `cargo run --release` for optimal performance.
+pub fn run() -> Result<(), slint::PlatformError> {
+    // Initialize logger
+    #[cfg(debug_assertions)]
+    env_logger::Builder::from_default_env()
+        .filter_level(log::LevelFilter::Info)
+        .init();
+
+    #[cfg(not(debug_assertions))]
+    env_logger::Builder::from_default_env()
+        .filter_level(log::LevelFilter::Error)
+        .init();
+
+    let ui = AppWindow::new()?;
+
+    // Load Settings
+    let mut settings = AppSettings::load();
+
+    // Initialize Monitor
+    let monitor = Arc::new(Mutex::new(SystemMonitor::new(&settings)));
+    collector::lock(&monitor).set_watched_processes(settings.watched_processes.clone());
+    collector::lock(&monitor).set_prometheus(&settings.prometheus);
+    collector::lock(&monitor).set_overlay(&settings.overlay);
+    collector::lock(&monitor).set_grpc(&settings.grpc);
+    collector::lock(&monitor).set_scripting(&settings.scripting);
+    collector::lock(&monitor).set_notifications(&settings.notifications, &settings.webhook.url);
+    info!(
+        "Gjallarhorn initialized with {} CPUs",
+        collector::lock(&monitor).get_cpu_count()
+    );
+
+    // --- CPU Model Init ---
+    let cpu_model = Rc::new(slint::VecModel::default());
+    for i in 0..collector::lock(&monitor).get_cpu_count() {
+        // Color management
+        let color_hex = if i < settings.cpu_core_colors.len() {
+            settings.cpu_core_colors[i].clone()
+        } else {
+            let hue = (i as f32 * 360.0 / collector::lock(&monitor).get_cpu_count() as f32) % 360.0;
+            let r = (127.0 + 127.0 * (hue * 0.0174).sin()) as u8;
+            let g = (127.0 + 127.0 * ((hue + 120.0) * 0.0174).sin()) as u8;
+            let b = (127.0 + 127.0 * ((hue + 240.0) * 0.0174).sin()) as u8;
+            let hex = format!("#{:02x}{:02x}{:02x}", r, g, b);
+
+            settings.cpu_core_colors.push(hex.clone());
+            hex
+        };
+
+        cpu_model.push(CpuData {
+            usage_str: "0%".into(),
+            path_commands: "".into(),
+            color: hex_to_color(&color_hex).into(),
+        });
+    }
+    settings.save();
+    ui.set_cpus(slint::ModelRc::from(cpu_model.clone()));
+    let cpu_overlay_model = Rc::new(slint::VecModel::default());
+    ui.set_cpu_overlays(slint::ModelRc::from(cpu_overlay_model.clone()));
+    ui.set_cpu_view(settings.cpu_view);
+    let cpu_stacked_model = Rc::new(slint::VecModel::default());
+    ui.set_cpu_stacked(slint::ModelRc::from(cpu_stacked_model.clone()));
+    let cpu_heat_model = Rc::new(slint::VecModel::<f32>::default());
+    ui.set_cpu_heat(slint::ModelRc::from(cpu_heat_model.clone()));
+
+    // --- GPU Model Init ---
+    let gpu_compute_model = Rc::new(slint::VecModel::default());
+    let gpu_memory_model = Rc::new(slint::VecModel::default());
+
+    let gpu_data = collector::lock(&monitor).get_gpu_data();
+    for data in &gpu_data {
+        gpu_compute_model.push(CpuData {
+            usage_str: format!("{}: 0%", data.name).into(),
+            path_commands: "".into(),
+            color: slint::Color::from_rgb_u8(200, 50, 200).into(),
+        });
+        gpu_memory_model.push(CpuData {
+            usage_str: format!("{}: 0 / 0 MB", data.name).into(),
+            path_commands: "".into(),
+            color: slint::Color::from_rgb_u8(50, 200, 200).into(),
+        });
+    }
+    ui.set_gpu_compute(slint::ModelRc::from(gpu_compute_model.clone()));
+    ui.set_gpu_memory(slint::ModelRc::from(gpu_memory_model.clone()));
+
+    let gpu_process_model = Rc::new(slint::VecModel::default());
+    ui.set_gpu_processes(slint::ModelRc::from(gpu_process_model.clone()));
+
+    // --- Network Model Init ---
+    let network_model = Rc::new(slint::VecModel::default());
+    let net_data = collector::lock(&monitor).get_network_data();
+    for data in &net_data {
+        network_model.push(NetworkChartData {
+            name: data.name.clone().into(),
+            usage_str: format!("{}: 0 KB/s", data.name).into(),
+            rx_path: "".into(),
+            tx_path: "".into(),
+            overlay: ChartOverlay::default(),
+        });
+    }
+    ui.set_networks(slint::ModelRc::from(network_model.clone()));
+
+    // --- Disk Model Init ---
+    let disk_model = Rc::new(slint::VecModel::default());
+    ui.set_disks(slint::ModelRc::from(disk_model.clone()));
+
+    // --- Slice Model Init ---
+    let slice_model = Rc::new(slint::VecModel::default());
+    ui.set_slices(slint::ModelRc::from(slice_model.clone()));
+    let sandbox_model = Rc::new(slint::VecModel::default());
+    ui.set_sandbox_apps(slint::ModelRc::from(sandbox_model.clone()));
+
+    // --- Fan Model Init ---
+    let fan_model = Rc::new(slint::VecModel::default());
+    ui.set_fans(slint::ModelRc::from(fan_model.clone()));
+
+    // --- Sensor Model Init ---
+    let sensor_model = Rc::new(slint::VecModel::default());
+    ui.set_sensor_chips(slint::ModelRc::from(sensor_model.clone()));
+
+    // --- Battery Model Init ---
+    let battery_model = Rc::new(slint::VecModel::default());
+    ui.set_batteries(slint::ModelRc::from(battery_model.clone()));
+
+    // --- Game Session Model Init ---
+    let game_session_model = Rc::new(slint::VecModel::default());
+    ui.set_game_sessions(slint::ModelRc::from(game_session_model.clone()));
+
+    // --- Long-Term History Init (the selected series and range index) ---
+    let long_term_selection: Rc<RefCell<Option<(String, usize)>>> = Rc::new(RefCell::new(None));
+    let range_labels: Vec<slint::SharedString> = longterm::RANGES
+        .iter()
+        .map(|(label, _)| (*label).into())
+        .collect();
+    ui.set_long_term_ranges(slint::ModelRc::new(slint::VecModel::from(range_labels)));
+    // The compared start times (as typed) and span length index, if comparing
+    let long_term_comparison: Rc<RefCell<Option<(String, String, usize)>>> =
+        Rc::new(RefCell::new(None));
+    let length_labels: Vec<slint::SharedString> = longterm::COMPARE_LENGTHS
+        .iter()
+        .map(|(label, _)| (*label).into())
+        .collect();
+    ui.set_long_term_compare_lengths(slint::ModelRc::new(slint::VecModel::from(length_labels)));
+    update_long_term(
+        &ui,
+        &collector::lock(&monitor),
+        &long_term_selection.borrow(),
+        &long_term_comparison.borrow(),
+    );
+
+    let long_term_ui = ui.as_weak();
+    let long_term_monitor = monitor.clone();
+    let show_selection = long_term_selection.clone();
+    let show_comparison = long_term_comparison.clone();
+    ui.on_show_long_term(move |id, range| {
+        let ui = long_term_ui.unwrap();
+        *show_selection.borrow_mut() = Some((id.to_string(), range.max(0) as usize));
+        update_long_term(
+            &ui,
+            &collector::lock(&long_term_monitor),
+            &show_selection.borrow(),
+            &show_comparison.borrow(),
+        );
+    });
+
+    let compare_ui = ui.as_weak();
+    let compare_monitor = monitor.clone();
+    let compare_selection = long_term_selection.clone();
+    let compare_comparison = long_term_comparison.clone();
+    ui.on_compare_long_term(move |first, second, length| {
+        let ui = compare_ui.unwrap();
+        // Two empty start times end the comparison
+        *compare_comparison.borrow_mut() = if first.is_empty() && second.is_empty() {
+            None
+        } else {
+            Some((
+                first.to_string(),
+                second.to_string(),
+                length.max(0) as usize,
+            ))
+        };
+        update_long_term(
+            &ui,
+            &collector::lock(&compare_monitor),
+            &compare_selection.borrow(),
+            &compare_comparison.borrow(),
+        );
+    });
+
+    // --- Drive Temperature Model Init ---
+    let drive_temp_model = Rc::new(slint::VecModel::default());
+    ui.set_drive_temps(slint::ModelRc::from(drive_temp_model.clone()));
+
+    // --- Disk Latency Model Init ---
+    let disk_latency_model = Rc::new(slint::VecModel::default());
+    ui.set_disk_latency(slint::ModelRc::from(disk_latency_model.clone()));
+
+    // --- Process List Model Init ---
+    let process_model = Rc::new(slint::VecModel::default());
+    ui.set_processes(slint::ModelRc::from(process_model.clone()));
+
+    let select_ui = ui.as_weak();
+    let select_monitor = monitor.clone();
+    ui.on_process_selected(move |pid| {
+        let ui = select_ui.unwrap();
+        let mut monitor = collector::lock(&select_monitor);
+        monitor.select_process(u32::try_from(pid).ok());
+        ui.set_selected_pid(pid);
+        update_process_detail(&ui, &monitor);
+    });
+
+    ui.on_set_process_scheduling(move |pid, nice, policy, cpus| {
+        info!(
+            "Requesting scheduling of process {}: nice {}, {}, CPUs {}",
+            pid, nice, policy, cpus
+        );
+        std::thread::spawn(move || {
+            let (pid_arg, nice_arg) = (pid.to_string(), nice.to_string());
+            match worker::run_helper(&[
+                "--set-process-scheduling",
+                &pid_arg,
+                &nice_arg,
+                policy.as_str(),
+                cpus.as_str(),
+            ]) {
+                Ok(_) => info!("Scheduling of process {} changed", pid),
+                Err(e) => error!("Failed to change the scheduling of process {}: {}", pid, e),
+            }
+        });
+    });
+
+    // --- Disk Throughput Model Init ---
+    let disk_io_model = Rc::new(slint::VecModel::default());
+    ui.set_disk_io(slint::ModelRc::from(disk_io_model.clone()));
+
+    // --- Remote Host Model Init ---
+    let remote_model = Rc::new(slint::VecModel::default());
+    ui.set_remote_hosts(slint::ModelRc::from(remote_model.clone()));
+
+    // --- Virtual Machine Model Init ---
+    let vm_model = Rc::new(slint::VecModel::default());
+    ui.set_vms(slint::ModelRc::from(vm_model.clone()));
+
+    // --- Overview Model Init ---
+    let pinned_model = Rc::new(slint::VecModel::default());
+    ui.set_pinned_series(slint::ModelRc::from(pinned_model.clone()));
+    let health_model = Rc::new(slint::VecModel::default());
+    ui.set_health_components(slint::ModelRc::from(health_model.clone()));
+    let pinnable_ids_model = Rc::new(slint::VecModel::<slint::SharedString>::default());
+    ui.set_pinnable_ids(slint::ModelRc::from(pinnable_ids_model.clone()));
+    let pinnable_labels_model = Rc::new(slint::VecModel::<slint::SharedString>::default());
+    ui.set_pinnable_labels(slint::ModelRc::from(pinnable_labels_model.clone()));
+
+    // --- Alert Model Init ---
+    let alert_model = Rc::new(slint::VecModel::default());
+    ui.set_active_alerts(slint::ModelRc::from(alert_model.clone()));
+
+    // --- Network Namespace Model Init ---
+    let netns_model = Rc::new(slint::VecModel::default());
+    ui.set_sys_network_namespaces(slint::ModelRc::from(netns_model.clone()));
+
+    // --- Kernel Log Model Init ---
+    let kernel_log_model = Rc::new(slint::VecModel::default());
+    ui.set_sys_kernel_log(slint::ModelRc::from(kernel_log_model.clone()));
+    let suggestion_model = Rc::new(slint::VecModel::default());
+    ui.set_sys_suggestions(slint::ModelRc::from(suggestion_model.clone()));
+
+    // --- Route/Neighbor Model Init (filled on demand) ---
+    let route_model = Rc::new(slint::VecModel::default());
+    ui.set_sys_routes(slint::ModelRc::from(route_model.clone()));
+    let neighbor_model = Rc::new(slint::VecModel::default());
+    ui.set_sys_neighbors(slint::ModelRc::from(neighbor_model.clone()));
+    ui.set_sys_routes_status("Press Refresh to load the routing table and neighbor cache.".into());
+
+    // --- Protocol Breakdown Model Init ---
+    let protocol_model = Rc::new(slint::VecModel::default());
+    ui.set_protocols(slint::ModelRc::from(protocol_model.clone()));
+
+    let dns_model = Rc::new(slint::VecModel::default());
+    ui.set_dns_latency(slint::ModelRc::from(dns_model.clone()));
+
+    // --- Custom Metrics Model Init ---
+    let custom_metric_model = Rc::new(slint::VecModel::default());
+    ui.set_custom_metrics(slint::ModelRc::from(custom_metric_model.clone()));
+    let script_badge_model = Rc::new(slint::VecModel::default());
+    ui.set_script_badges(slint::ModelRc::from(script_badge_model.clone()));
+
+    // --- Wi-Fi Scan Init (filled on demand) ---
+    ui.set_sys_wifi_status(
+        "Press Scan to look for nearby Wi-Fi networks (requires authentication).".into(),
+    );
+
+    // --- Marker Model Init ---
+    let marker_model = Rc::new(slint::VecModel::default());
+    ui.set_chart_markers(slint::ModelRc::from(marker_model.clone()));
+
+    // Apply Settings
+    ui.set_version(env!("CARGO_PKG_VERSION").into());
+    ui.set_dark_mode(settings.dark_mode);
+    ui.set_use_uniform_cpu(settings.use_uniform_cpu);
+    ui.set_refresh_rate_ms(settings.refresh_rate_ms as f32);
+    ui.set_history_window_secs(settings.history_window_secs as f32);
+    ui.set_history_resolution(collector::lock(&monitor).history_resolution().into());
+    ui.set_cpu_chart_color(hex_to_color(&settings.cpu_color).into());
+    ui.set_ram_chart_color(hex_to_color(&settings.ram_color).into());
+    ui.set_gpu_chart_color(hex_to_color(&settings.gpu_color).into());
+    ui.set_net_chart_color(hex_to_color(&settings.net_color).into());
+    ui.set_watched_processes(settings.watched_processes.join(", ").into());
+    ui.set_aliases(AppSettings::format_aliases(&settings.aliases).into());
+    ui.set_app_profiles(AppSettings::format_aliases(&settings.app_profiles).into());
+    ui.set_derived_metrics(AppSettings::format_derived_metrics(&settings.derived_metrics).into());
+    ui.set_remote_host_list(settings.remote_hosts.join(", ").into());
+
+    // --- System Info Init ---
+    let static_info = collector::lock(&monitor).get_static_info();
+    ui.set_sys_hostname(static_info.hostname.clone().into());
+    ui.set_sys_os_name(static_info.os_name.clone().into());
+    ui.set_sys_kernel(static_info.kernel.clone().into());
+    ui.set_sys_cpu_brand(static_info.cpu_brand.clone().into());
+    ui.set_sys_cpu_cores(static_info.cores as i32);
+    ui.set_sys_total_memory(static_info.total_mem.clone().into());
+    ui.set_sys_bios_version(static_info.bios_version.clone().into());
+    ui.set_sys_storage(static_info.total_storage.clone().into());
+    ui.set_sys_gpu_names(static_info.gpu_names.clone().into());
+    ui.set_sys_cpu_freq(static_info.cpu_freq.clone().into());
+    ui.set_sys_cpu_arch(static_info.cpu_arch.clone().into());
+    ui.set_sys_motherboard(static_info.motherboard.clone().into());
+    ui.set_sys_boot_mode(static_info.boot_mode.clone().into());
+    ui.set_sys_disks(static_info.individual_disks.clone().into());
+
+    // Capability Report (static, detected at startup)
+    let capabilities = collector::lock(&monitor).capabilities.clone();
+    let capability_rows: Vec<CapabilityData> = capabilities
+        .report()
+        .into_iter()
+        .map(|c| CapabilityData {
+            name: c.name.into(),
+            available: c.available,
+            enables: c.enables.into(),
+            hint: c.hint.into(),
+        })
+        .collect();
+    ui.set_sys_capabilities(slint::ModelRc::from(Rc::new(slint::VecModel::from(
+        capability_rows,
+    ))));
+    ui.set_sys_missing_capabilities(capabilities.missing_count() as i32);
+
+    // Graphics API Report (vulkaninfo/glxinfo are slow to start, so load in background)
+    ui.set_sys_graphics_api_status("Loading Vulkan and OpenGL information...".into());
+    let graphics_handle = ui.as_weak();
+    std::thread::spawn(move || {
+        let info = graphics::get_graphics_api_info();
+        let _ = graphics_handle.upgrade_in_event_loop(move |ui| {
+            let mut rows: Vec<GraphicsApiData> = info
+                .vulkan_devices
+                .into_iter()
+                .map(|d| GraphicsApiData {
+                    api: "Vulkan".into(),
+                    device: format!("{} ({})", d.name, d.device_type).into(),
+                    version: d.api_version.into(),
+                    driver: d.driver.into(),
+                })
+                .collect();
+            if let Some(gl) = info.opengl {
+                rows.push(GraphicsApiData {
+                    api: "OpenGL".into(),
+                    device: gl.renderer.into(),
+                    version: gl.version.into(),
+                    driver: gl.vendor.into(),
+                });
+            }
+            let status = match (&info.vulkan_instance, rows.is_empty()) {
+                (_, true) => {
+                    "No Vulkan or OpenGL information (requires vulkaninfo or glxinfo).".to_string()
+                }
+                (Some(version), false) => format!("Vulkan instance version {}", version),
+                (None, false) => "Vulkan information requires vulkaninfo.".to_string(),
+            };
+            ui.set_sys_graphics_api_status(status.into());
+            ui.set_sys_graphics_apis(slint::ModelRc::from(Rc::new(slint::VecModel::from(rows))));
+        });
+    });
+
+    // Display Session Info (refreshed on demand)
+    let monitor_model = Rc::new(slint::VecModel::default());
+    ui.set_sys_monitors(slint::ModelRc::from(monitor_model.clone()));
+    update_display_info(&ui, &monitor_model);
+
+    // IOMMU Groups (refreshed on demand, drivers change when binding to vfio-pci)
+    update_iommu_info(&ui);
+
+    // Detailed Hardware Info
+    let cpu_details = collector::lock(&monitor).get_cpu_detailed_info();
+    ui.set_sys_cpu_detailed_info(CpuDetailedInfo {
+        name: cpu_details.name.into(),
+        vendor: cpu_details.vendor.into(),
+        architecture: cpu_details.architecture.into(),
+        cores_physical: cpu_details.cores_physical as i32,
+        cores_logical: cpu_details.cores_logical as i32,
+        frequency_current: cpu_details.frequency_current,
+        frequency_max: cpu_details.frequency_max,
+        frequency_min: cpu_details.frequency_min,
+        cache_l1d: cpu_details.cache_l1d.into(),
+        cache_l1i: cpu_details.cache_l1i.into(),
+        cache_l2: cpu_details.cache_l2.into(),
+        cache_l3: cpu_details.cache_l3.into(),
+        virtualization: cpu_details.virtualization.into(),
+        flags: cpu_details.flags.into(),
+    });
+
+    // Detailed Memory Info
+    let mem_details = collector::lock(&monitor).get_memory_detailed_info();
+    ui.set_sys_memory_detailed_info(MemoryDetailedInfo {
+        total_capacity: mem_details.total_capacity.into(),
+        used_capacity: mem_details.used_capacity.into(),
+        memory_type: mem_details.memory_type.into(),
+        speed: mem_details.speed.into(),
+        channels: mem_details.channels as i32,
+        module_count: mem_details.module_count as i32,
+    });
+
+    // Detailed Storage Info
+    let storage_details = collector::lock(&monitor).get_storage_detailed_info();
+    let storage_details_slint: Vec<StorageDetailedInfo> = storage_details
+        .into_iter()
+        .map(|d| StorageDetailedInfo {
+            smart_trend: collector::lock(&monitor)
+                .get_smart_trend(&d.serial_number)
+                .map(|t| t.describe())
+                .unwrap_or_default()
+                .into(),
+            device_name: d.device_name.into(),
+            model: d.model.into(),
+            capacity: format!("{:.2} GB", d.capacity_bytes as f64 / 1_073_741_824.0).into(),
+            interface_type: d.interface_type.into(),
+            is_ssd: d.is_ssd,
+            serial_number: d.serial_number.into(),
+            firmware_version: d.firmware_version.into(),
+            health_status: d.health_status.into(),
+        })
+        .collect();
+    ui.set_sys_storage_detailed_info(slint::ModelRc::from(std::rc::Rc::new(
+        slint::VecModel::from(storage_details_slint),
+    )));
+
+    // Detailed GPU Info
+    let gpu_detail_model = Rc::new(slint::VecModel::from(
+        collector::lock(&monitor)
+            .get_gpu_detailed_info()
+            .into_iter()
+            .map(gpu_detail_to_slint)
+            .collect::<Vec<GpuDetailedInfo>>(),
+    ));
+    ui.set_sys_gpu_detailed_info(slint::ModelRc::from(gpu_detail_model.clone()));
+    ui.set_allow_gpu_power_control(settings.allow_gpu_power_control);
+    ui.set_poll_suspended_gpu(settings.poll_suspended_gpu);
+    ui.set_allow_fan_control(settings.allow_fan_control);
+    ui.set_show_network_namespaces(settings.show_network_namespaces);
+    ui.set_totals_since_install(settings.totals_since_install);
+    ui.set_collect_gpu(settings.collectors.gpu);
+    ui.set_collect_smart(settings.collectors.smart);
+    ui.set_collect_network(settings.collectors.network);
+    ui.set_network_scale_auto(settings.chart_scales.network_auto);
+    ui.set_network_scale_max_mbps(settings.chart_scales.network_max_mbps as i32);
+    ui.set_disk_scale_auto(settings.chart_scales.disk_auto);
+    ui.set_disk_await_max_ms(settings.chart_scales.disk_await_max_ms as i32);
+    ui.set_disk_queue_max(settings.chart_scales.disk_queue_max as i32);
+    ui.set_enable_packet_capture(settings.enable_packet_capture);
+    ui.set_enable_dns_probe(settings.enable_dns_probe);
+    ui.set_forward_alerts_to_syslog(settings.forward_alerts_to_syslog);
+    ui.set_cert_targets(settings.certificates.targets.join(", ").into());
+    ui.set_cert_warn_days(settings.certificates.warn_days as i32);
+    let cert_status_model = Rc::new(slint::VecModel::default());
+    ui.set_cert_status(slint::ModelRc::from(cert_status_model.clone()));
+    ui.set_backup_jobs(settings.backups.jobs.join("; ").into());
+    ui.set_backup_max_age_hours(settings.backups.max_age_hours as i32);
+    let backup_status_model = Rc::new(slint::VecModel::default());
+    ui.set_backup_status(slint::ModelRc::from(backup_status_model.clone()));
+    ui.set_webhook_enabled(settings.webhook.enabled);
+    ui.set_webhook_url(settings.webhook.url.clone().into());
+    ui.set_webhook_interval_secs(settings.webhook.interval_secs as i32);
+    ui.set_csv_logging(settings.csv.enabled);
+    ui.set_csv_metrics(settings.csv.metrics.join(", ").into());
+    ui.set_csv_interval_secs(settings.csv.interval_secs as i32);
+    ui.set_csv_retention_days(settings.csv.retention_days as i32);
+    ui.set_prometheus_enabled(settings.prometheus.enabled);
+    ui.set_prometheus_bind_address(settings.prometheus.bind_address.clone().into());
+    ui.set_prometheus_port(settings.prometheus.port as i32);
+    ui.set_overlay_enabled(settings.overlay.enabled);
+    ui.set_overlay_bind_address(settings.overlay.bind_address.clone().into());
+    ui.set_overlay_port(settings.overlay.port as i32);
+    ui.set_grpc_enabled(settings.grpc.enabled);
+    ui.set_grpc_bind_address(settings.grpc.bind_address.clone().into());
+    ui.set_grpc_port(settings.grpc.port as i32);
+    ui.on_validate_webhook_url(|url| {
+        WebhookSettings::validate_url(&url)
+            .err()
+            .unwrap_or_default()
+            .into()
+    });
+    ui.on_validate_csv_metrics(|metrics| {
+        CsvSettings::parse_metrics(&metrics)
+            .err()
+            .unwrap_or_default()
+            .into()
+    });
+    ui.on_validate_bind_address(|address| {
+        validate_bind_address(&address)
+            .err()
+            .unwrap_or_default()
+            .into()
+    });
+
+    // Detailed Network Info
+    let net_details = collector::lock(&monitor).get_network_detailed_info();
+    let net_details_slint: Vec<NetworkDetailedInfo> = net_details
+        .into_iter()
+        .map(|d| NetworkDetailedInfo {
+            name: d.name.into(),
+            mac_address: d.mac_address.into(),
+            rx_bytes: format!("{:.2} MB", d.rx_bytes as f64 / 1_048_576.0).into(),
+            tx_bytes: format!("{:.2} MB", d.tx_bytes as f64 / 1_048_576.0).into(),
+            rx_packets: d.rx_packets.to_string().into(),
+            tx_packets: d.tx_packets.to_string().into(),
+            ip_v4: d.ip_v4.into(),
+            ip_v6: d.ip_v6.into(),
+            link_speed: d.link_speed.into(),
+        })
+        .collect();
+    ui.set_sys_network_detailed_info(slint::ModelRc::from(std::rc::Rc::new(
+        slint::VecModel::from(net_details_slint),
+    )));
+
+    // Callbacks
+    // --- Detached Panels ---
+    let panels: Rc<RefCell<Vec<PanelWindow>>> = Rc::new(RefCell::new(Vec::new()));
+    for layout in &settings.detached_panels {
+        open_panel(&ui.as_weak(), &panels, layout.tab, Some(layout));
+    }
+
+    let detach_handle = ui.as_weak();
+    let detach_panels = panels.clone();
+    ui.on_detach_panel(move |tab| {
+        open_panel(&detach_handle, &detach_panels, tab, None);
+        save_panel_layout(&detach_panels.borrow());
+    });
+
+    // Screenshot and recording of the window (see `screenshot.rs`)
+    let screenshot_handle = ui.as_weak();
+    ui.on_take_screenshot(move || {
+        let ui = screenshot_handle.unwrap();
+        let result = ui
+            .window()
+            .take_snapshot()
+            .map_err(|e| format!("Failed to capture the window: {}", e))
+            .and_then(|frame| screenshot::save_png(&frame));
+        ui.set_capture_status(capture_status(result).into());
+    });
+
+    let record_handle = ui.as_weak();
+    let record_timer = Rc::new(Timer::default());
+    ui.on_record_window(move |secs| {
+        let ui = record_handle.unwrap();
+        if record_timer.running() {
+            return;
+        }
+        let done_handle = ui.as_weak();
+        let recording = ui
+            .window()
+            .take_snapshot()
+            .map_err(|e| format!("Failed to capture the window: {}", e))
+            .and_then(|first| {
+                screenshot::Recording::start(first, secs.max(1) as u32, move |result| {
+                    let _ = done_handle.upgrade_in_event_loop(move |ui| {
+                        ui.set_recording(false);
+                        ui.set_capture_status(capture_status(result).into());
+                    });
+                })
+            });
+        let mut recording = match recording {
+            Ok(recording) => recording,
+            Err(e) => {
+                ui.set_capture_status(capture_status(Err(e)).into());
+                return;
+            }
+        };
+        ui.set_recording(true);
+        ui.set_capture_status(
+            format!("Recording {} s to {}", secs, recording.path.display()).into(),
+        );
+
+        let frame_handle = ui.as_weak();
+        let timer = Rc::downgrade(&record_timer);
+        record_timer.start(
+            TimerMode::Repeated,
+            std::time::Duration::from_millis(1000 / screenshot::RECORD_FPS as u64),
+            move || {
+                let frame = frame_handle
+                    .upgrade()
+                    .and_then(|ui| ui.window().take_snapshot().ok());
+                // Stop once every frame is queued, or when the window is gone
+                if !frame.is_some_and(|frame| recording.push(frame)) {
+                    if let Some(timer) = timer.upgrade() {
+                        timer.stop();
+                    }
+                }
+            },
+        );
+    });
+
+    // Closing the main window closes the panels too, remembering where they were
+    let close_panels = panels.clone();
+    ui.window().on_close_requested(move || {
+        save_panel_layout(&close_panels.borrow());
+        let _ = slint::quit_event_loop();
+        slint::CloseRequestResponse::HideWindow
+    });
+
+    let quit_panels = panels.clone();
+    ui.on_quit(move || {
+        save_panel_layout(&quit_panels.borrow());
+        slint::quit_event_loop().unwrap();
+    });
+
+    ui.on_set_gpu_power_limit(move |index, watts| {
+        info!("Requesting GPU {} power limit of {:.0} W", index, watts);
+        std::thread::spawn(move || {
+            let index_arg = index.to_string();
+            let watts_arg = format!("{:.0}", watts);
+            match worker::run_helper(&["--set-gpu-power-limit", &index_arg, &watts_arg]) {
+                Ok(_) => info!("GPU {} power limit set to {:.0} W", index, watts),
+                Err(e) => error!("Failed to set GPU power limit: {}", e),
+            }
+        });
+    });
+
+    ui.on_set_fan_speed(move |chip, index, percent| {
+        // Negative values hand the fan back to automatic control
+        let value = if percent < 0 {
+            "auto".to_string()
+        } else {
+            ((percent.min(100) as f32 / 100.0 * 255.0).round() as u8).to_string()
+        };
+        info!("Requesting {} fan {} speed: {}", chip, index, value);
+        std::thread::spawn(move || {
+            let index_arg = index.to_string();
+            match worker::run_helper(&["--set-fan-pwm", chip.as_str(), &index_arg, &value]) {
+                Ok(_) => info!("{} fan {} set to {}", chip, index, value),
+                Err(e) => error!("Failed to set fan speed: {}", e),
+            }
+        });
+    });
+
+    let routes_handle = ui.as_weak();
+    ui.on_refresh_routes(move || {
+        let ui = routes_handle.unwrap();
+        let mut errors = Vec::new();
+
+        match routes::get_routes() {
+            Ok(list) => route_model.set_vec(
+                list.into_iter()
+                    .map(|r| RouteData {
+                        destination: r.destination.into(),
+                        gateway: r.gateway.into(),
+                        device: r.device.into(),
+                        protocol: r.protocol.into(),
+                        metric: r.metric.map(|m| m.to_string()).unwrap_or_default().into(),
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            Err(e) => errors.push(e),
+        }
+
+        match routes::get_neighbors() {
+            Ok(list) => neighbor_model.set_vec(
+                list.into_iter()
+                    .map(|n| NeighborData {
+                        address: n.address.into(),
+                        lladdr: n.lladdr.into(),
+                        device: n.device.into(),
+                        state: n.state.into(),
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            Err(e) => errors.push(e),
+        }
+
+        if errors.is_empty() {
+            ui.set_sys_routes_status(
+                format!(
+                    "{} routes, {} neighbors",
+                    route_model.row_count(),
+                    neighbor_model.row_count()
+                )
+                .into(),
+            );
+        } else {
+            error!("Failed to read routes: {}", errors.join("; "));
+            ui.set_sys_routes_status(errors.join("; ").into());
+        }
+    });
+
+    let display_handle = ui.as_weak();
+    ui.on_refresh_display(move || {
+        update_display_info(&display_handle.unwrap(), &monitor_model);
+    });
+
+    let iommu_handle = ui.as_weak();
+    ui.on_refresh_iommu(move || {
+        update_iommu_info(&iommu_handle.unwrap());
+    });
+
+    // Share snapshot: the static info is read once, the metrics at the time of the click
+    let static_info = Rc::new(static_info);
+    let shared_snapshot = {
+        let monitor = monitor.clone();
+        let static_info = static_info.clone();
+        move || share::SharedSnapshot {
+            system: (*static_info).clone(),
+            metrics: collector::lock(&monitor).snapshot(),
+        }
+    };
+
+    let copy_handle = ui.as_weak();
+    let copy_snapshot = shared_snapshot.clone();
+    ui.on_copy_snapshot(move || {
+        let text = share::format_summary(&copy_snapshot());
+        let status = match share::copy_to_clipboard(&text) {
+            Ok(_) => "Snapshot copied to the clipboard".to_string(),
+            Err(e) => {
+                error!("Failed to copy snapshot: {}", e);
+                e
+            }
+        };
+        copy_handle.unwrap().set_share_status(status.into());
+    });
+
+    let save_snapshot_handle = ui.as_weak();
+    ui.on_save_snapshot(move || {
+        let status = match share::write_json(&shared_snapshot()) {
+            Ok(path) => format!("Snapshot saved to {}", path.display()),
+            Err(e) => {
+                error!("Failed to save snapshot: {}", e);
+                format!("Failed to save snapshot: {}", e)
+            }
+        };
+        save_snapshot_handle
+            .unwrap()
+            .set_share_status(status.into());
+    });
+
+    let wifi_handle = ui.as_weak();
+    ui.on_scan_wifi(move || {
+        wifi_handle
+            .unwrap()
+            .set_sys_wifi_status("Scanning...".into());
+        let wifi_handle = wifi_handle.clone();
+        std::thread::spawn(move || {
+            let result = worker::run_helper(&["--wifi-scan"]).and_then(|output| {
+                worker::parse_helper_list::<wifi::WifiNetwork>(&output)
+                    .map_err(|e| format!("Invalid scan output: {}", e))
+            });
+
+            let _ = wifi_handle.upgrade_in_event_loop(move |ui| match result {
+                Ok(networks) => {
+                    ui.set_sys_wifi_status(format!("{} networks found", networks.len()).into());
+                    let rows: Vec<WifiNetworkData> = networks
+                        .into_iter()
+                        .map(|n| WifiNetworkData {
+                            ssid: if n.ssid.is_empty() {
+                                "(hidden)".into()
+                            } else {
+                                n.ssid.into()
+                            },
+                            bssid: n.bssid.into(),
+                            channel: format!(
+                                "{} ({:.1} GHz)",
+                                n.channel,
+                                n.frequency_mhz as f32 / 1000.0
+                            )
+                            .into(),
+                            signal: format!("{:.0} dBm", n.signal_dbm).into(),
+                            // Map -90 dBm (unusable) .. -30 dBm (excellent) onto 0..1
+                            signal_percent: ((n.signal_dbm + 90.0) / 60.0).clamp(0.0, 1.0),
+                            security: n.security.into(),
+                            connected: n.connected,
+                        })
+                        .collect();
+                    ui.set_sys_wifi_networks(slint::ModelRc::from(Rc::new(slint::VecModel::from(
+                        rows,
+                    ))));
+                }
+                Err(e) => {
+                    error!("Wi-Fi scan failed: {}", e);
+                    ui.set_sys_wifi_status(format!("Scan failed: {}", e).into());
+                }
+            });
+        });
+    });
+
+    ui.on_cpu_view_changed(move |view| {
+        let mut settings = AppSettings::load();
+        settings.cpu_view = view;
+        settings.save();
+    });
+
+    let marker_monitor = monitor.clone();
+    ui.on_add_marker(move |label| {
+        collector::lock(&marker_monitor).add_marker(&label);
+        info!("Marker added: {}", label);
+    });
+
+    let idle_monitor = monitor.clone();
+    ui.on_cancel_idle_action(move || {
+        collector::lock(&idle_monitor).idle_action.cancel();
+        info!("Idle action cancelled");
+    });
+
+    let session_monitor = monitor.clone();
+    ui.on_start_gpu_session(move |label| {
+        collector::lock(&session_monitor).start_gpu_session(&label);
+        info!("GPU session capture started: {}", label);
+    });
+
+    let session_monitor = monitor.clone();
+    ui.on_stop_gpu_session(move || {
+        let mut monitor = collector::lock(&session_monitor);
+        monitor.stop_gpu_session();
+        info!(
+            "GPU session capture stopped: {}",
+            monitor.gpu_session_status()
+        );
+    });
+
+    // Pinning changes are saved right away, like markers
+    let pin_monitor = monitor.clone();
+    ui.on_pin_series(move |id| {
+        let mut monitor = collector::lock(&pin_monitor);
+        monitor.pin_series(&id);
+        let mut settings = AppSettings::load();
+        settings.pinned_series = monitor.pins.pinned_ids();
+        settings.save();
+    });
+
+    let unpin_monitor = monitor.clone();
+    ui.on_unpin_series(move |id| {
+        let mut monitor = collector::lock(&unpin_monitor);
+        monitor.unpin_series(&id);
+        let mut settings = AppSettings::load();
+        settings.pinned_series = monitor.pins.pinned_ids();
+        settings.save();
+    });
+
+    let overlay_monitor = monitor.clone();
+    ui.on_toggle_chart_overlay(move |chart, kind| {
+        let mut monitor = collector::lock(&overlay_monitor);
+        let mut overlays = monitor.chart_overlays.clone();
+        overlays.toggle(&chart, &kind);
+        monitor.set_chart_overlays(overlays.clone());
+        let mut settings = AppSettings::load();
+        settings.chart_overlays = overlays;
+        settings.save();
+    });
+
+    let ui_handle = ui.as_weak();
+
+    // --- Timer Logic ---
+    // Collection runs on its own thread; the timer only redraws after each refresh
+    let collector = Rc::new(Collector::spawn(monitor.clone(), settings.refresh_rate_ms));
+    let timer = Timer::default();
+
+    // State captured by tick closure
+    let tick_monitor = monitor.clone();
+    let tick_collector = collector.clone();
+    let tick_ui = ui_handle.clone();
+    let tick_cpu_model = cpu_model.clone();
+    let tick_cpu_overlays = cpu_overlay_model.clone();
+    let tick_cpu_stacked = cpu_stacked_model.clone();
+    let tick_cpu_heat = cpu_heat_model.clone();
+    let tick_gpu_comp = gpu_compute_model.clone();
+    let tick_gpu_mem = gpu_memory_model.clone();
+    let tick_gpu_processes = gpu_process_model.clone();
+    let tick_net = network_model.clone();
+    let tick_disk = disk_model.clone();
+    let tick_gpu_detail = gpu_detail_model.clone();
+    let tick_slices = slice_model.clone();
+    let tick_sandbox = sandbox_model.clone();
+    let tick_fans = fan_model.clone();
+    let tick_sensors = sensor_model.clone();
+    let tick_batteries = battery_model.clone();
+    let tick_game_sessions = game_session_model.clone();
+    let tick_long_term_selection = long_term_selection.clone();
+    let tick_long_term_comparison = long_term_comparison.clone();
+    let mut long_term_updated = std::time::Instant::now();
+    let tick_drive_temps = drive_temp_model.clone();
+    let tick_disk_latency = disk_latency_model.clone();
+    let tick_disk_io = disk_io_model.clone();
+    let tick_processes = process_model.clone();
+    let tick_remote = remote_model.clone();
+    let tick_vms = vm_model.clone();
+    let tick_cert_status = cert_status_model.clone();
+    let tick_backup_status = backup_status_model.clone();
+    let tick_panels = panels.clone();
+    let tick_pinned = pinned_model.clone();
+    let tick_health = health_model.clone();
+    let tick_pinnable_ids = pinnable_ids_model.clone();
+    let tick_pinnable_labels = pinnable_labels_model.clone();
+    let tick_markers = marker_model.clone();
+    let tick_alerts = alert_model.clone();
+    let tick_netns = netns_model.clone();
+    let tick_kernel_log = kernel_log_model.clone();
+    let tick_suggestions = suggestion_model.clone();
+    let tick_protocols = protocol_model.clone();
+    let tick_dns = dns_model.clone();
+    let tick_custom_metrics = custom_metric_model.clone();
+    let tick_script_badges = script_badge_model.clone();
+    let mut pending_snapshot = None;
+
+    // Reusable tick closure
+    let tick = move || {
+        if let Some(snapshot) = tick_collector.drain() {
+            pending_snapshot = Some(snapshot);
+        }
+        if pending_snapshot.is_none() {
+            return;
+        }
+        // The collector holds the monitor while it refreshes; redraw on a later tick
+        let Some(mut monitor) = collector::try_lock(&tick_monitor) else {
+            return;
+        };
+        let Some(snapshot) = pending_snapshot.take() else {
+            return;
+        };
+        let ui = tick_ui.unwrap();
+        let window_secs = monitor.history_limits.window_secs;
+
+        // --- Application Profiles ---
+        if let Some(tab) = monitor.profile_tab(ui.get_usage_tab()) {
+            ui.set_usage_tab(tab);
+        }
+
+        // --- Update CPU ---
+        for i in 0..monitor.get_cpu_count() {
+            if i >= tick_cpu_model.row_count() {
+                continue;
+            }
+
+            let hist = monitor.get_cpu_history(i);
+            if let Some(usage) = hist.latest() {
+                let mut data = tick_cpu_model.row_data(i).unwrap();
+                data.usage_str = format!("{:.1}%", usage).into();
+                data.path_commands = generate_path(hist, 100.0, window_secs);
+                tick_cpu_model.set_row_data(i, data);
+            }
+        }
+        let cpu_overlays: Vec<ChartOverlay> = (0..monitor.get_cpu_count())
+            .map(|i| {
+                let hist = monitor.get_cpu_history(i);
+                overlay_paths(&monitor.get_chart_overlay("cpu", hist), 100.0, window_secs)
+            })
+            .collect();
+        tick_cpu_overlays.set_vec(cpu_overlays);
+
+        // Stacked areas and the heatmap are only filled while their view is selected
+        if ui.get_cpu_view() == 1 {
+            let cores = monitor.get_cpu_count().min(tick_cpu_model.row_count());
+            let histories: Vec<&History> = (0..cores).map(|i| monitor.get_cpu_history(i)).collect();
+            let total: f32 = histories.iter().filter_map(|h| h.latest()).sum();
+            let paths =
+                generate_stacked_paths(&histories, 100.0 * cores.max(1) as f32, window_secs);
+            let layers: Vec<CpuData> = paths
+                .into_iter()
+                .enumerate()
+                .map(|(i, path)| CpuData {
+                    usage_str: "".into(),
+                    path_commands: path,
+                    color: tick_cpu_model.row_data(i).unwrap().color,
+                })
+                .collect();
+            tick_cpu_stacked.set_vec(layers);
+            ui.set_cpu_total_label(format!("Total: {:.1}%", total / cores.max(1) as f32).into());
+        }
+        if ui.get_cpu_view() == 2 {
+            let heat: Vec<f32> = (0..monitor.get_cpu_count())
+                .map(|i| monitor.get_cpu_history(i).latest().unwrap_or(0.0))
+                .collect();
+            tick_cpu_heat.set_vec(heat);
+        }
+
+        ui.set_session_stats(monitor.session_stats_label().into());
+
+        // --- Update Memory ---
+        let memory = &snapshot.memory;
+        ui.set_memory_label(format!("{:.1} / {:.1} GB", memory.used_gb, memory.total_gb).into());
+        ui.set_memory_path(generate_path(
+            monitor.get_memory_history(),
+            100.0,
+            window_secs,
+        ));
+        ui.set_memory_overlay(overlay_paths(
+            &monitor.get_chart_overlay("memory", monitor.get_memory_history()),
+            100.0,
+            window_secs,
+        ));
+        if memory.swap_total_gb > 0.0 {
+            ui.set_swap_label(
+                format!(
+                    "{:.1} / {:.1} GB",
+                    memory.swap_used_gb, memory.swap_total_gb
+                )
+                .into(),
+            );
+            ui.set_swap_path(generate_path(
+                monitor.get_swap_history(),
+                100.0,
+                window_secs,
+            ));
+        } else {
+            ui.set_swap_label("".into());
+        }
+
+        // --- Update GPU ---
+        let gpu_data = monitor.get_gpu_data();
+        if gpu_data.len() != tick_gpu_comp.row_count() {
+            // GPU hot-add/removal: rebuild the rows, the loop below fills them in
+            tick_gpu_comp.set_vec(
+                gpu_data
+                    .iter()
+                    .map(|_| CpuData {
+                        usage_str: "".into(),
+                        path_commands: "".into(),
+                        color: slint::Color::from_rgb_u8(200, 50, 200).into(),
+                    })
+                    .collect::<Vec<_>>(),
+            );
+            tick_gpu_mem.set_vec(
+                gpu_data
+                    .iter()
+                    .map(|_| CpuData {
+                        usage_str: "".into(),
+                        path_commands: "".into(),
+                        color: slint::Color::from_rgb_u8(50, 200, 200).into(),
+                    })
+                    .collect::<Vec<_>>(),
+            );
+        }
+        for (i, g) in gpu_data.iter().enumerate() {
+            if i < tick_gpu_comp.row_count() {
+                let mut data = tick_gpu_comp.row_data(i).unwrap();
+                data.usage_str = if g.suspended {
+                    format!("{}: powered down", g.name)
+                } else {
+                    format!("{}: {:.0}%", g.name, g.util)
+                }
+                .into();
+                data.path_commands = generate_path(&g.util_history, 100.0, window_secs);
+                tick_gpu_comp.set_row_data(i, data);
+            }
+            if i < tick_gpu_mem.row_count() {
+                let mut data = tick_gpu_mem.row_data(i).unwrap();
+                data.usage_str = if g.suspended {
+                    format!("{}: powered down", g.name)
+                } else {
+                    format!(
+                        "{}: {:.0} / {:.0} MB",
+                        g.name, g.mem_used_mb, g.mem_total_mb
+                    )
+                }
+                .into();
+                data.path_commands = generate_path(&g.mem_history, 100.0, window_secs);
+                tick_gpu_mem.set_row_data(i, data);
+            }
+        }
+
+        // --- Update GPU Processes ---
+        let gpu_processes: Vec<GpuProcessData> = monitor
+            .get_gpu_process_data()
+            .into_iter()
+            .map(|p| {
+                let kind = match (p.compute, p.graphics) {
+                    (true, true) => "Compute + Graphics",
+                    (true, false) => "Compute",
+                    _ => "Graphics",
+                };
+                let gpu = if gpu_data.len() > 1 {
+                    format!("GPU {} · {}", p.gpu, kind)
+                } else {
+                    kind.to_string()
+                };
+                let mut usage = match p.sm_util {
+                    Some(sm) => format!("{}%", sm),
+                    None => "N/A".to_string(),
+                };
+                for (label, util) in [("enc", p.enc_util), ("dec", p.dec_util)] {
+                    if let Some(util) = util.filter(|u| *u > 0) {
+                        usage += &format!(" · {} {}%", label, util);
+                    }
+                }
+                GpuProcessData {
+                    pid: p.pid as i32,
+                    name: p.name.into(),
+                    gpu_str: gpu.into(),
+                    vram_str: match p.vram_mb {
+                        Some(mb) if mb >= 1024.0 => format!("{:.2} GB", mb / 1024.0),
+                        Some(mb) => format!("{:.0} MB", mb),
+                        None => "N/A".to_string(),
+                    }
+                    .into(),
+                    util_str: usage.into(),
+                }
+            })
+            .collect();
+        tick_gpu_processes.set_vec(gpu_processes);
+
+        // --- Update GPU Details (clocks, power) ---
+        let gpu_details = monitor.get_gpu_detailed_info();
+        if gpu_details.len() == tick_gpu_detail.row_count() {
+            for (i, d) in gpu_details.into_iter().enumerate() {
+                tick_gpu_detail.set_row_data(i, gpu_detail_to_slint(d));
+            }
+        } else {
+            tick_gpu_detail.set_vec(
+                gpu_details
+                    .into_iter()
+                    .map(gpu_detail_to_slint)
+                    .collect::<Vec<GpuDetailedInfo>>(),
+            );
+        }
+
+        // --- Update Network ---
+        let net_data = monitor.get_network_data();
+        for (i, net) in net_data.iter().enumerate() {
+            if i < tick_net.row_count() {
+                // Formatting
+                let fmt_rate = |val: u64| -> String {
+                    if val > 1024 * 1024 {
+                        format!("{:.1} MB/s", val as f32 / 1024.0 / 1024.0)
+                    } else {
+                        format!("{:.0} KB/s", val as f32 / 1024.0)
+                    }
+                };
+                let fmt_total = |val: u64| -> String {
+                    if val > 1024 * 1024 * 1024 * 1024 {
+                        format!("{:.2} TB", val as f64 / 1024.0 / 1024.0 / 1024.0 / 1024.0)
+                    } else if val > 1024 * 1024 * 1024 {
+                        format!("{:.1} GB", val as f32 / 1024.0 / 1024.0 / 1024.0)
+                    } else {
+                        format!("{:.0} MB", val as f32 / 1024.0 / 1024.0)
+                    }
+                };
+
+                let gw_icon = if net.is_default { "🌐 " } else { "" };
+
+                let mut lines = Vec::new();
+                lines.push(format!("{}{}", gw_icon, net.name));
+                if !net.ips_v4.is_empty() {
+                    lines.push(format!("IPv4: {}", net.ips_v4.join(", ")));
+                }
+
+                lines.push(format!(
+                    "⬇{} ⬆{}",
+                    fmt_rate(net.rx_bytes),
+                    fmt_rate(net.tx_bytes)
+                ));
+                lines.push(format!(
+                    "TOT: ⬇{} ⬆{}",
+                    fmt_total(net.total_rx_bytes),
+                    fmt_total(net.total_tx_bytes)
+                ));
+
+                // Download and upload share the scale, so their lines compare directly
+                let max_val = monitor
+                    .network_chart_max(&net.history)
+                    .max(monitor.network_chart_max(&net.tx_history));
+
+                let mut data = tick_net.row_data(i).unwrap();
+                data.usage_str = lines.join("\n").into();
+                data.rx_path = generate_path(&net.history, max_val, window_secs);
+                data.tx_path = generate_path(&net.tx_history, max_val, window_secs);
+                data.overlay = overlay_paths(
+                    &monitor.get_chart_overlay(&format!("net.{}", net.name), &net.history),
+                    max_val,
+                    window_secs,
+                );
+                tick_net.set_row_data(i, data);
+            }
+        }
+
+        // --- Update Disk ---
+        let disks = monitor.get_disk_data();
+        if disks.len() != tick_disk.row_count() {
+            // Rebuild
+            let vec_data: Vec<DiskData> = disks
+                .iter()
+                .map(|d| {
+                    let total_gb = d.total_space_bytes as f32 / 1024.0 / 1024.0 / 1024.0;
+                    let used_gb = (d.total_space_bytes - d.available_space_bytes) as f32
+                        / 1024.0
+                        / 1024.0
+                        / 1024.0;
+                    let factor = if d.total_space_bytes > 0 {
+                        used_gb / total_gb
+                    } else {
+                        0.0
+                    };
+
+                    let bar_color = if factor > 0.9 {
+                        slint::Color::from_rgb_u8(231, 76, 60) // Red
+                    } else if factor > 0.75 {
+                        slint::Color::from_rgb_u8(241, 196, 15) // Yellow
+                    } else {
+                        slint::Color::from_rgb_u8(46, 204, 113) // Green
+                    };
+
+                    DiskData {
+                        name: d.name.clone().into(),
+                        mount_point: d.mount_point.clone().into(),
+                        total: format!("{:.1} GB", total_gb).into(),
+                        used: format!("{:.1} GB", used_gb).into(),
+                        usage_factor: factor,
+                        bar_color: bar_color.into(),
+                    }
+                })
+                .collect();
+            tick_disk.set_vec(vec_data);
+        } else {
+            // Update in place
+            for (i, d) in disks.iter().enumerate() {
+                let total_gb = d.total_space_bytes as f32 / 1024.0 / 1024.0 / 1024.0;
+                let used_gb = (d.total_space_bytes - d.available_space_bytes) as f32
+                    / 1024.0
+                    / 1024.0
+                    / 1024.0;
+                let factor = if d.total_space_bytes > 0 {
+                    used_gb / total_gb
+                } else {
+                    0.0
+                };
+
+                let bar_color = if factor > 0.9 {
+                    slint::Color::from_rgb_u8(231, 76, 60) // Red
+                } else if factor > 0.75 {
+                    slint::Color::from_rgb_u8(241, 196, 15) // Yellow
+                } else {
+                    slint::Color::from_rgb_u8(46, 204, 113) // Green
+                };
+
+                let mut data = tick_disk.row_data(i).unwrap();
+                data.name = d.name.clone().into();
+                data.used = format!("{:.1} GB", used_gb).into();
+                data.usage_factor = factor;
+                data.bar_color = bar_color.into();
+                tick_disk.set_row_data(i, data);
+            }
+        }
+
+        // --- Update Scheduler ---
+        let sched = monitor.get_sched_data();
+        // Scale the run queue so a queue twice the core count reads as saturated
+        let runnable_scale = (monitor.system.cpus().len().max(1) * 2) as f32;
+        ui.set_runnable_path(generate_path(
+            &sched.running_history,
+            runnable_scale,
+            window_secs,
+        ));
+        ui.set_runnable_label(
+            format!(
+                "Runnable: {}  Blocked: {}",
+                sched.procs_running, sched.procs_blocked
+            )
+            .into(),
+        );
+        ui.set_sched_wait_path(generate_path(
+            &sched.wait_history,
+            sched.wait_scale_us,
+            window_secs,
+        ));
+        ui.set_sched_wait_label(
+            match sched.avg_wait_us {
+                Some(wait) if wait >= 1000.0 => format!("Run-Queue Wait: {:.1} ms", wait / 1000.0),
+                Some(wait) => format!("Run-Queue Wait: {:.0} µs", wait),
+                None => "Run-Queue Wait: N/A".to_string(),
+            }
+            .into(),
+        );
+
+        // --- Update Slices ---
+        let slices: Vec<SliceData> = monitor
+            .get_cgroup_data()
+            .iter()
+            .map(|c| SliceData {
+                name: c.label.clone().into(),
+                cpu_str: format!("{:.1}%", c.cpu_percent).into(),
+                cpu_path: generate_path(&c.cpu_history, 100.0, window_secs),
+                mem_str: format!("{:.0} MB", c.memory_bytes as f32 / 1024.0 / 1024.0).into(),
+                mem_path: generate_path(&c.mem_history, 100.0, window_secs),
+                cpu_limit_str: match (c.limits.cpu_cores, c.cpu_of_limit) {
+                    (Some(cores), Some(pct)) => format!("{:.0}% of {:.1} cores", pct, cores),
+                    _ => String::new(),
+                }
+                .into(),
+                cpu_limit_factor: c.cpu_of_limit.unwrap_or(0.0) / 100.0,
+                mem_limit_str: match (c.limits.memory_bytes, c.mem_of_limit) {
+                    (Some(limit), Some(pct)) => format!(
+                        "{:.0} MB of {:.1} GB ({:.0}%)",
+                        c.memory_bytes as f64 / 1024.0 / 1024.0,
+                        limit as f64 / 1024.0 / 1024.0 / 1024.0,
+                        pct
+                    ),
+                    _ => String::new(),
+                }
+                .into(),
+                mem_limit_factor: c.mem_of_limit.unwrap_or(0.0) / 100.0,
+            })
+            .collect();
+        tick_slices.set_vec(slices);
+
+        let sandbox_apps: Vec<SandboxAppData> = monitor
+            .get_sandbox_app_data()
+            .iter()
+            .map(|a| SandboxAppData {
+                app_id: a.app_id.clone().into(),
+                kind: a.kind.to_string().into(),
+                processes: a.process_count as i32,
+                cpu: format!("{:.1}%", a.cpu_percent).into(),
+                memory: format!("{:.0} MB", a.memory_bytes as f32 / 1024.0 / 1024.0).into(),
+            })
+            .collect();
+        tick_sandbox.set_vec(sandbox_apps);
+
+        // --- Update Fans ---
+        let fans: Vec<FanData> = monitor
+            .get_fan_data()
+            .iter()
+            .map(|f| FanData {
+                chip: f.chip.clone().into(),
+                index: f.index as i32,
+                label: f.label.clone().into(),
+                rpm_str: format!("{} RPM", f.rpm).into(),
+                rpm_path: generate_path(&f.rpm_history, f.rpm_scale, window_secs),
+                temp_str: f
+                    .temperature
+                    .map(|t| format!("{:.1} °C", t))
+                    .unwrap_or_else(|| "N/A".to_string())
+                    .into(),
+                temp_path: generate_path(&f.temp_history, 100.0, window_secs),
+                controllable: f.controllable,
+                pwm_percent: f.pwm.map(|p| p as f32 / 255.0 * 100.0).unwrap_or(0.0),
+                mode: if f.pwm_auto { "Auto" } else { "Manual" }.into(),
+            })
+            .collect();
+        // Update rows in place so the speed sliders keep their state between ticks
+        if tick_fans.row_count() == fans.len() {
+            for (i, fan) in fans.into_iter().enumerate() {
+                tick_fans.set_row_data(i, fan);
+            }
+        } else {
+            tick_fans.set_vec(fans);
+        }
+
+        // --- Update Sensors ---
+        let mut chips: Vec<(String, Vec<SensorData>)> = Vec::new();
+        for s in monitor.get_sensor_data() {
+            let sensor = SensorData {
+                kind: s.kind as i32,
+                title: format!("{} {}", s.label, s.describe()).into(),
+                path: generate_path(&s.history, s.scale, window_secs),
+            };
+            let name = format!("{} ({})", s.driver, s.chip);
+            match chips.last_mut() {
+                Some((last, sensors)) if *last == name => sensors.push(sensor),
+                _ => chips.push((name, vec![sensor])),
+            }
+        }
+        let chips: Vec<SensorChipData> = chips
+            .into_iter()
+            .map(|(name, sensors)| SensorChipData {
+                name: name.into(),
+                sensors: slint::ModelRc::new(slint::VecModel::from(sensors)),
+            })
+            .collect();
+        // Update rows in place so the list keeps its scroll position between ticks
+        if tick_sensors.row_count() == chips.len() {
+            for (i, chip) in chips.into_iter().enumerate() {
+                tick_sensors.set_row_data(i, chip);
+            }
+        } else {
+            tick_sensors.set_vec(chips);
+        }
+
+        // --- Update Frame Times ---
+        ui.set_frame_time(match monitor.get_frame_time_data() {
+            Some(f) => FrameTimeData {
+                active: true,
+                app: f.app.clone().into(),
+                fps_str: format!("{:.0} FPS", f.fps).into(),
+                fps_path: generate_path(
+                    &f.fps_history,
+                    f.fps_history.max_value().max(60.0),
+                    window_secs,
+                ),
+                frame_time_str: format!(
+                    "{:.1} ms (worst {:.1} ms)",
+                    f.frame_time_ms, f.worst_frame_ms
+                )
+                .into(),
+                // 33 ms (30 FPS) keeps the usual frame times in the lower part of the chart
+                frame_time_path: generate_path(
+                    &f.frame_time_history,
+                    f.frame_time_history.max_value().max(33.3),
+                    window_secs,
+                ),
+            },
+            None => FrameTimeData::default(),
+        });
+
+        // --- Update Batteries ---
+        let batteries: Vec<BatteryData> = monitor
+            .get_battery_data()
+            .iter()
+            .map(|b| BatteryData {
+                name: if b.model.is_empty() {
+                    b.name.clone()
+                } else {
+                    format!("{} ({})", b.name, b.model)
+                }
+                .into(),
+                status: b.status.clone().into(),
+                charge_str: format!("{:.0}%", b.charge_percent).into(),
+                charge_path: generate_path(&b.charge_history, 100.0, window_secs),
+                power_str: format!("{:+.1} W", b.power_watts).into(),
+                power_path: generate_path(&b.power_history, b.power_scale, window_secs),
+                time_str: b
+                    .time_remaining_secs
+                    .map(|secs| {
+                        let minutes = secs / 60;
+                        let direction = if b.power_watts > 0.0 { "full" } else { "empty" };
+                        format!("{}h {:02}m to {}", minutes / 60, minutes % 60, direction)
+                    })
+                    .unwrap_or_default()
+                    .into(),
+                health_str: [
+                    b.health_percent().map(|h| format!("{:.0}% health", h)),
+                    b.cycle_count.map(|c| format!("{} cycles", c)),
+                ]
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+                .join(" · ")
+                .into(),
+            })
+            .collect();
+        if tick_batteries.row_count() == batteries.len() {
+            for (i, battery) in batteries.into_iter().enumerate() {
+                tick_batteries.set_row_data(i, battery);
+            }
+        } else {
+            tick_batteries.set_vec(batteries);
+        }
+
+        // --- Update Game Sessions ---
+        ui.set_games_playing(monitor.games.playing().join(", ").into());
+        // Sessions only change when a game exits; the newest one comes first
+        let sessions = monitor.get_game_sessions();
+        let newest = sessions.first().map(|s| s.time_label());
+        let shown = tick_game_sessions
+            .row_data(0)
+            .map(|r| r.time_str.to_string());
+        if tick_game_sessions.row_count() != sessions.len() || newest != shown {
+            let sessions: Vec<GameSessionData> = sessions
+                .iter()
+                .map(|s| GameSessionData {
+                    name: if s.proton {
+                        format!("{} (Proton)", s.name)
+                    } else {
+                        s.name.clone()
+                    }
+                    .into(),
+                    time_str: s.time_label().into(),
+                    stats_str: s.stats_label().into(),
+                })
+                .collect();
+            tick_game_sessions.set_vec(sessions);
+        }
+
+        // --- Update Long-Term History (a new minute is stored once a minute) ---
+        if long_term_updated.elapsed() >= std::time::Duration::from_secs(60) {
+            long_term_updated = std::time::Instant::now();
+            update_long_term(
+                &ui,
+                &monitor,
+                &tick_long_term_selection.borrow(),
+                &tick_long_term_comparison.borrow(),
+            );
+        }
+
+        // --- Update Drive Temperatures ---
+        let drive_temps: Vec<CpuData> = monitor
+            .get_drive_temp_data()
+            .iter()
+            .map(|d| CpuData {
+                usage_str: match (d.temperature, d.max) {
+                    (Some(t), Some(max)) => {
+                        format!("{}: {:.0} °C (throttles at {:.0} °C)", d.device, t, max)
+                    }
+                    (Some(t), None) => format!("{}: {:.0} °C", d.device, t),
+                    (None, _) => format!("{}: N/A", d.device),
+                }
+                .into(),
+                path_commands: generate_path(&d.history, 100.0, window_secs),
+                color: ui.get_cpu_chart_color(),
+            })
+            .collect();
+        tick_drive_temps.set_vec(drive_temps);
+
+        // --- Update Disk Latency ---
+        let disk_latency: Vec<DiskLatencyData> = monitor
+            .get_disk_latency_data()
+            .iter()
+            .map(|d| DiskLatencyData {
+                device: d.device.clone().into(),
+                await_str: match d.await_ms {
+                    Some(ms) => format!("{:.1} ms", ms),
+                    None => "idle".to_string(),
+                }
+                .into(),
+                await_path: generate_path(&d.await_history, d.await_scale_ms, window_secs),
+                queue_str: format!("{:.2}", d.queue_depth).into(),
+                written_str: if d.written_bytes > 1024 * 1024 * 1024 * 1024 {
+                    format!("{:.2} TB", d.written_bytes as f64 / 1024f64.powi(4))
+                } else {
+                    format!("{:.1} GB", d.written_bytes as f64 / 1024f64.powi(3))
+                }
+                .into(),
+                queue_path: generate_path(&d.queue_history, d.queue_scale, window_secs),
+            })
+            .collect();
+        tick_disk_latency.set_vec(disk_latency);
+
+        // --- Update Disk Throughput ---
+        let disk_io: Vec<DiskIoData> = monitor
+            .get_disk_io_data()
+            .iter()
+            .map(|d| DiskIoData {
+                device: d.device.clone().into(),
+                read_str: format!("{:.1} MB/s", d.read_mb_s).into(),
+                read_path: generate_path(&d.read_history, d.scale_mb_s, window_secs),
+                write_str: format!("{:.1} MB/s", d.write_mb_s).into(),
+                write_path: generate_path(&d.write_history, d.scale_mb_s, window_secs),
+            })
+            .collect();
+        tick_disk_io.set_vec(disk_io);
+
+        // --- Update Process List ---
+        let process_rows: Vec<ProcessData> = monitor
+            .get_process_data(
+                processes::ProcessSort::from_index(ui.get_process_sort()),
+                &ui.get_process_filter(),
+                processes::MAX_ROWS,
+            )
+            .into_iter()
+            .map(|p| ProcessData {
+                pid: p.pid as i32,
+                name: p.name.into(),
+                cpu_str: format!("{:.1}%", p.cpu_percent).into(),
+                mem_str: format_rss(p.rss_bytes).into(),
+                state: p.state.into(),
+                owner: p.owner.into(),
+                stuck: p.stuck,
+                nice_str: p
+                    .sched
+                    .as_ref()
+                    .map(|s| s.nice.to_string())
+                    .unwrap_or_default()
+                    .into(),
+                policy: p
+                    .sched
+                    .as_ref()
+                    .and_then(|s| s.policy)
+                    .map(|policy| policy.name())
+                    .unwrap_or_default()
+                    .into(),
+                affinity: p
+                    .sched
+                    .as_ref()
+                    .map(|s| s.affinity.clone())
+                    .unwrap_or_default()
+                    .into(),
+            })
+            .collect();
+        if tick_processes.row_count() == process_rows.len() {
+            for (i, row) in process_rows.into_iter().enumerate() {
+                tick_processes.set_row_data(i, row);
+            }
+        } else {
+            tick_processes.set_vec(process_rows);
+        }
+        if ui.get_selected_pid() >= 0 {
+            update_process_detail(&ui, &monitor);
+        }
+        let states = monitor.get_process_state_data();
+        let states_scale = states
+            .blocked_history
+            .max_value()
+            .max(states.zombie_history.max_value())
+            .max(5.0);
+        ui.set_blocked_tasks_path(generate_path(
+            &states.blocked_history,
+            states_scale,
+            window_secs,
+        ));
+        ui.set_zombies_path(generate_path(
+            &states.zombie_history,
+            states_scale,
+            window_secs,
+        ));
+        ui.set_process_states_label(
+            format!(
+                "Uninterruptible (D): {}  Zombies (Z): {}",
+                states.blocked, states.zombies
+            )
+            .into(),
+        );
+
+        // --- Update Remote Hosts ---
+        let remote_hosts: Vec<RemoteHostData> = monitor
+            .get_remote_host_data()
+            .into_iter()
+            .map(|h| {
+                let mut details: Vec<String> = h
+                    .temperature
+                    .map(|t| format!("Temperature: {:.1} °C", t))
+                    .into_iter()
+                    .collect();
+                details.extend(
+                    h.disks
+                        .iter()
+                        .map(|(device, health)| format!("{}: {}", device, health)),
+                );
+                RemoteHostData {
+                    title: if h.hostname.is_empty() || h.hostname == h.target {
+                        h.target.clone()
+                    } else {
+                        format!("{} ({})", h.target, h.hostname)
+                    }
+                    .into(),
+                    online: h.online,
+                    status: if h.online {
+                        format!(
+                            "up {}d {}h {}m, load {:.2} {:.2} {:.2}",
+                            h.uptime_secs / 86400,
+                            (h.uptime_secs % 86400) / 3600,
+                            (h.uptime_secs % 3600) / 60,
+                            h.load[0],
+                            h.load[1],
+                            h.load[2]
+                        )
+                    } else if h.error.is_empty() {
+                        "Connecting...".to_string()
+                    } else {
+                        h.error.clone()
+                    }
+                    .into(),
+                    cpu_str: format!("CPU {:.1}%", h.cpu_usage).into(),
+                    cpu_path: generate_path(&h.cpu_history, 100.0, window_secs),
+                    mem_str: format!("Memory: {:.1} / {:.1} GB", h.mem_used_gb, h.mem_total_gb)
+                        .into(),
+                    details: details.join("\n").into(),
+                }
+            })
+            .collect();
+        tick_remote.set_vec(remote_hosts);
+
+        // --- Update Virtual Machines ---
+        let fmt_rate = |rate: f64| -> String {
+            if rate > 1024.0 * 1024.0 {
+                format!("{:.1} MB/s", rate / 1024.0 / 1024.0)
+            } else {
+                format!("{:.0} KB/s", rate / 1024.0)
+            }
+        };
+        let vms: Vec<VmData> = monitor
+            .get_vm_data()
+            .into_iter()
+            .map(|vm| {
+                // Throughput histories are in MB/s; scale to the busiest sample, at least 1 MB/s
+                let disk_max = vm.disk_history.max_value().max(1.0);
+                let net_max = vm.net_history.max_value().max(1.0);
+                VmData {
+                    info: format!("{}, {} vCPU, {} MB", vm.state, vm.vcpus, vm.memory_mb).into(),
+                    running: vm.running,
+                    cpu_str: format!("vCPU {:.1}%", vm.cpu_usage).into(),
+                    cpu_path: generate_path(&vm.cpu_history, 100.0, window_secs),
+                    disk_str: format!("Disk {}", fmt_rate(vm.disk_bytes_per_sec)).into(),
+                    disk_path: generate_path(&vm.disk_history, disk_max, window_secs),
+                    net_str: format!("Network {}", fmt_rate(vm.net_bytes_per_sec)).into(),
+                    net_path: generate_path(&vm.net_history, net_max, window_secs),
+                    name: vm.name.into(),
+                }
+            })
+            .collect();
+        tick_vms.set_vec(vms);
+        ui.set_vm_status(monitor.vm_status().into());
+        ui.set_gpu_session_active(monitor.gpu_session.is_some());
+        ui.set_gpu_session_status(monitor.gpu_session_status().into());
+
+        // --- Update Markers ---
+        // Charts span the configured window (see `SystemMonitor::set_history_window`).
+        let markers: Vec<ChartMarker> = monitor
+            .markers
+            .in_window(window_secs as u64)
+            .into_iter()
+            .map(|(position, m)| ChartMarker {
+                label: m.label.clone().into(),
+                position,
+            })
+            .collect();
+        tick_markers.set_vec(markers);
+
+        // --- Update Kernel Limits ---
+        let limits = monitor.get_limits();
+        ui.set_sys_open_files(format!("{} / {}", limits.open_files, limits.file_max).into());
+        ui.set_sys_process_count(format!("{} / {}", limits.processes, limits.pid_max).into());
+        ui.set_sys_thread_count(format!("{} / {}", limits.threads, limits.threads_max).into());
+
+        // --- Update Entropy ---
+        let entropy = monitor.get_entropy();
+        ui.set_sys_entropy(entropy.summary().into());
+        ui.set_sys_entropy_warning(entropy.is_starved());
+
+        // --- Update SELinux/AppArmor ---
+        let mac = monitor.get_mac_status();
+        let denials = monitor.get_mac_denials();
+        let mut mac_status = mac.summary();
+        if let Some(d) = denials.as_ref().filter(|_| mac.system == "AppArmor") {
+            mac_status += &format!(
+                ", {} profiles enforced, {} complaining",
+                d.apparmor_enforce, d.apparmor_complain
+            );
+        }
+        ui.set_sys_mac_status(mac_status.into());
+        ui.set_sys_mac_warning(mac.is_permissive());
+        ui.set_sys_mac_denials(
+            match denials {
+                None => "Requires the privileged worker".to_string(),
+                Some(d) => match d.latest.filter(|_| d.recent > 0) {
+                    Some(latest) => {
+                        format!("{} from the {}, latest: {}", d.recent, d.source, latest)
+                    }
+                    None => format!("{} from the {}", d.recent, d.source),
+                },
+            }
+            .into(),
+        );
+
+        // --- Update Protocol Breakdown (opt-in) ---
+        let mut breakdown = monitor.get_protocol_breakdown();
+        // Keep the legend readable: fold everything past the top 7 into one segment
+        if breakdown.len() > 8 {
+            let rest: f64 = breakdown.drain(7..).map(|(_, rate)| rate).sum();
+            breakdown.push(("Rest".to_string(), rest));
+        }
+        let total: f64 = breakdown.iter().map(|(_, rate)| rate).sum();
+        let mut offset = 0.0;
+        let protocols: Vec<ProtocolData> = breakdown
+            .into_iter()
+            .enumerate()
+            .map(|(i, (protocol, rate))| {
+                let fraction = if total > 0.0 {
+                    (rate / total) as f32
+                } else {
+                    0.0
+                };
+                let data = ProtocolData {
+                    protocol: protocol.into(),
+                    rate: if rate > 1024.0 * 1024.0 {
+                        format!("{:.1} MB/s", rate / 1024.0 / 1024.0)
+                    } else {
+                        format!("{:.0} KB/s", rate / 1024.0)
+                    }
+                    .into(),
+                    offset,
+                    fraction,
+                    color: slint::Color::from_rgb_u8(
+                        (60 + (i * 70) % 196) as u8,
+                        (120 + (i * 45) % 136) as u8,
+                        (220 - (i * 30) % 160) as u8,
+                    )
+                    .into(),
+                };
+                offset += fraction;
+                data
+            })
+            .collect();
+        tick_protocols.set_vec(protocols);
+
+        // --- Update DNS Latency (opt-in) ---
+        let dns: Vec<CpuData> = monitor
+            .get_dns_latency_data()
+            .iter()
+            .map(|d| CpuData {
+                usage_str: match d.latency_ms {
+                    Some(ms) => format!("DNS {}: {:.0} ms", d.server, ms),
+                    None => format!("DNS {}: timeout", d.server),
+                }
+                .into(),
+                path_commands: generate_path(&d.history, d.scale_ms, window_secs),
+                color: ui.get_net_chart_color(),
+            })
+            .collect();
+        tick_dns.set_vec(dns);
+
+        // --- Update Custom Metrics ---
+        let custom_metrics: Vec<CpuData> = monitor
+            .get_custom_metric_data()
+            .iter()
+            .chain(&monitor.get_script_metric_data())
+            .map(|m| CpuData {
+                usage_str: m.describe().into(),
+                path_commands: generate_path(&m.history, m.scale, window_secs),
+                color: ui.get_cpu_chart_color(),
+            })
+            .collect();
+        tick_custom_metrics.set_vec(custom_metrics);
+        let badges: Vec<slint::SharedString> = monitor
+            .get_script_badges()
+            .into_iter()
+            .map(slint::SharedString::from)
+            .collect();
+        tick_script_badges.set_vec(badges);
+
+        // --- Update Network Namespaces (opt-in) ---
+        if ui.get_show_network_namespaces() {
+            let namespaces: Vec<NetNamespaceData> = monitor
+                .get_network_namespaces()
+                .iter()
+                .map(|ns| NetNamespaceData {
+                    title: format!(
+                        "{} ({}, {} processes)",
+                        ns.name
+                            .clone()
+                            .unwrap_or_else(|| format!("net:[{}]", ns.inode)),
+                        if ns.owner.is_empty() {
+                            "no processes"
+                        } else {
+                            &ns.owner
+                        },
+                        ns.process_count
+                    )
+                    .into(),
+                    interfaces: ns
+                        .interfaces
+                        .iter()
+                        .map(|i| {
+                            format!(
+                                "{}: Rx {:.2} MB / Tx {:.2} MB",
+                                i.name,
+                                i.rx_bytes as f64 / 1_048_576.0,
+                                i.tx_bytes as f64 / 1_048_576.0
+                            )
+                        })
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                        .into(),
+                })
+                .collect();
+            tick_netns.set_vec(namespaces);
+        }
+
+        // --- Update Kernel Log ---
+        let now = kmsg::uptime_secs();
+        let entries: Vec<KernelLogData> = monitor
+            .get_kernel_log()
+            .into_iter()
+            .map(|e| KernelLogData {
+                age: e.age(now).into(),
+                level: e.level_name().into(),
+                error: e.level <= 3,
+                message: e.message.into(),
+            })
+            .collect();
+        tick_kernel_log.set_vec(entries);
+
+        // --- Update Suggestions ---
+        let suggestions: Vec<SuggestionData> = monitor
+            .get_suggestions()
+            .into_iter()
+            .map(|s| SuggestionData {
+                title: s.title.into(),
+                detail: s.detail.into(),
+            })
+            .collect();
+        tick_suggestions.set_vec(suggestions);
+
+        // --- Update Overview (health score) ---
+        let health = monitor.get_health();
+        ui.set_health_score(health.score as i32);
+        ui.set_health_grade(health.grade().into());
+        let components: Vec<HealthComponentData> = health
+            .components
+            .iter()
+            .map(|c| HealthComponentData {
+                label: c.area.label().into(),
+                score: c.score as i32,
+                detail: if c.alerts.is_empty() {
+                    "OK".to_string()
+                } else {
+                    c.alerts.join("; ")
+                }
+                .into(),
+                tab: c.area.tab(),
+                critical: c.critical,
+            })
+            .collect();
+        // Update rows in place so the rows don't flicker every tick
+        if tick_health.row_count() == components.len() {
+            for (i, component) in components.into_iter().enumerate() {
+                tick_health.set_row_data(i, component);
+            }
+        } else {
+            tick_health.set_vec(components);
+        }
+
+        // --- Update Overview (pinned series) ---
+        let pinned: Vec<PinnedSeriesData> = monitor
+            .get_pinned_data()
+            .into_iter()
+            .map(|p| PinnedSeriesData {
+                title: format!("{}: {}", p.label, p.value_str).into(),
+                path: generate_path(&p.history, p.scale, window_secs),
+                id: p.id.into(),
+            })
+            .collect();
+        // Update rows in place so the Unpin buttons aren't recreated every tick
+        if tick_pinned.row_count() == pinned.len() {
+            for (i, series) in pinned.into_iter().enumerate() {
+                tick_pinned.set_row_data(i, series);
+            }
+        } else {
+            tick_pinned.set_vec(pinned);
+        }
+
+        // Only replace the picker entries when they change, to keep the selection
+        let pinnable = monitor.get_pinnable_series();
+        let pinnable_changed = pinnable.len() != tick_pinnable_ids.row_count()
+            || pinnable
+                .iter()
+                .zip(tick_pinnable_ids.iter())
+                .any(|(series, id)| series.id != id.as_str());
+        if pinnable_changed {
+            tick_pinnable_ids.set_vec(
+                pinnable
+                    .iter()
+                    .map(|s| s.id.clone().into())
+                    .collect::<Vec<slint::SharedString>>(),
+            );
+            tick_pinnable_labels.set_vec(
+                pinnable
+                    .into_iter()
+                    .map(|s| s.label.into())
+                    .collect::<Vec<slint::SharedString>>(),
+            );
+        }
+
+        // --- Update Detached Panels ---
+        for panel in tick_panels.borrow().iter() {
+            sync_panel(&ui, panel);
+        }
+
+        // --- Update Alerts ---
+        // Rules routed to the `none` channel stay out of the banner too
+        let alerts: Vec<AlertData> = monitor
+            .get_alerts()
+            .iter()
+            .filter(|a| {
+                monitor
+                    .notifier
+                    .as_ref()
+                    .is_none_or(|n| n.channel(&a.id) != NotifyChannel::None)
+            })
+            .map(|a| AlertData {
+                message: a.message.clone().into(),
+                critical: a.severity == alerts::AlertSeverity::Critical,
+            })
+            .collect();
+        tick_alerts.set_vec(alerts);
+
+        // --- Idle Action ---
+        ui.set_idle_action_countdown(
+            monitor
+                .idle_action
+                .countdown_text()
+                .unwrap_or_default()
+                .into(),
+        );
+        if let Some(action) = monitor.take_idle_action() {
+            std::thread::spawn(move || {
+                match worker::run_helper(&["--power-action", action.as_str()]) {
+                    Ok(_) => info!("Idle action {} executed", action.as_str()),
+                    Err(e) => error!("Failed to {}: {}", action.as_str(), e),
+                }
+            });
+        }
+
+        // --- Update Integration Status ---
+        ui.set_webhook_status(integration_status(
+            monitor.webhook.as_ref().map(|w| w.status()),
+        ));
+        ui.set_csv_status(integration_status(
+            monitor.csv_logger.as_ref().map(|c| c.status()),
+        ));
+        ui.set_syslog_status(match &monitor.syslog {
+            Some(syslog) => {
+                integration_status(Some(Ok(format!("Forwarding to {}", syslog.destination()))))
+            }
+            None if ui.get_forward_alerts_to_syslog() => {
+                integration_status(Some(Err("No log socket available".to_string())))
+            }
+            None => integration_status(None),
+        });
+        ui.set_prometheus_status(integration_status(monitor.prometheus_status.clone()));
+        ui.set_overlay_status(integration_status(monitor.overlay_status.clone()));
+        ui.set_grpc_status(integration_status(monitor.grpc_status.clone()));
+        let warn_days = ui.get_cert_warn_days() as i64;
+        let cert_status: Vec<IntegrationStatus> = monitor
+            .get_cert_data()
+            .iter()
+            .map(|c| IntegrationStatus {
+                state: match c.days_left() {
+                    Some(days) if days >= warn_days => 1,
+                    None if c.error.is_empty() => 0,
+                    _ => 2,
+                },
+                message: c.describe().into(),
+            })
+            .collect();
+        tick_cert_status.set_vec(cert_status);
+        let max_age = ui.get_backup_max_age_hours() as f64;
+        let backup_status: Vec<IntegrationStatus> = monitor
+            .get_backup_data()
+            .iter()
+            .map(|b| IntegrationStatus {
+                state: match b.age_hours() {
+                    Some(age) if age < max_age => 1,
+                    None if b.error.is_empty() => 0,
+                    _ => 2,
+                },
+                message: b.describe().into(),
+            })
+            .collect();
+        tick_backup_status.set_vec(backup_status);
+
+        // --- Update Uptime ---
+        let uptime_sec = snapshot.uptime_secs;
+        let days = uptime_sec / 86400;
+        let hours = (uptime_sec % 86400) / 3600;
+        let mins = (uptime_sec % 3600) / 60;
+        ui.set_sys_uptime(format!("{}d {}h {}m", days, hours, mins).into());
+    };
+
+    // Start Timer
+    timer.start(TimerMode::Repeated, collector::DRAIN_INTERVAL, tick);
+
+    let save_handle = ui_handle.clone();
+    let save_monitor = monitor.clone();
+    let save_collector = collector.clone();
+
+    ui.on_save_prefs(move || {
+        let ui = save_handle.unwrap();
+        let mut current_settings = AppSettings::load();
+
+        let old_refresh = current_settings.refresh_rate_ms;
+        let old_window = current_settings.history_window_secs;
+
+        current_settings.dark_mode = ui.get_dark_mode();
+        current_settings.use_uniform_cpu = ui.get_use_uniform_cpu();
+        current_settings.refresh_rate_ms = ui.get_refresh_rate_ms() as u64;
+        current_settings.history_window_secs = ui.get_history_window_secs() as u64;
+        current_settings.cpu_color = brush_to_hex(ui.get_cpu_chart_color());
+        current_settings.ram_color = brush_to_hex(ui.get_ram_chart_color());
+        current_settings.gpu_color = brush_to_hex(ui.get_gpu_chart_color());
+        current_settings.net_color = brush_to_hex(ui.get_net_chart_color());
+        current_settings.allow_gpu_power_control = ui.get_allow_gpu_power_control();
+        current_settings.poll_suspended_gpu = ui.get_poll_suspended_gpu();
+        current_settings.allow_fan_control = ui.get_allow_fan_control();
+        current_settings.show_network_namespaces = ui.get_show_network_namespaces();
+        current_settings.totals_since_install = ui.get_totals_since_install();
+        collector::lock(&save_monitor)
+            .set_totals_since_install(current_settings.totals_since_install);
+        current_settings.collectors = CollectorSettings {
+            gpu: ui.get_collect_gpu(),
+            smart: ui.get_collect_smart(),
+            network: ui.get_collect_network(),
+        };
+        current_settings.chart_scales = ChartScaleSettings {
+            network_auto: ui.get_network_scale_auto(),
+            network_max_mbps: ui.get_network_scale_max_mbps().max(1) as u64,
+            disk_auto: ui.get_disk_scale_auto(),
+            disk_await_max_ms: ui.get_disk_await_max_ms().max(1) as u64,
+            disk_queue_max: ui.get_disk_queue_max().max(1) as u64,
+        };
+        collector::lock(&save_monitor).set_chart_scales(current_settings.chart_scales.clone());
+        collector::lock(&save_monitor).set_certificates(&current_settings.certificates);
+        collector::lock(&save_monitor).set_backups(&current_settings.backups);
+        collector::lock(&save_monitor).set_custom_metrics(&current_settings.custom_metrics);
+        collector::lock(&save_monitor).set_long_term_history(&current_settings.long_term_history);
+        collector::lock(&save_monitor).set_scripting(&current_settings.scripting);
+        current_settings.enable_packet_capture = ui.get_enable_packet_capture();
+        current_settings.enable_dns_probe = ui.get_enable_dns_probe();
+        let old_webhook = std::mem::replace(
+            &mut current_settings.webhook,
+            WebhookSettings {
+                enabled: ui.get_webhook_enabled(),
+                url: ui.get_webhook_url().trim().to_string(),
+                interval_secs: ui.get_webhook_interval_secs().max(1) as u64,
+            },
+        );
+        let old_csv = current_settings.csv.clone();
+        current_settings.csv = CsvSettings {
+            enabled: ui.get_csv_logging(),
+            metrics: CsvSettings::parse_metrics(&ui.get_csv_metrics())
+                .unwrap_or_else(|_| old_csv.metrics.clone()),
+            interval_secs: ui.get_csv_interval_secs().max(1) as u64,
+            retention_days: ui.get_csv_retention_days().max(0) as u64,
+        };
+        current_settings.forward_alerts_to_syslog = ui.get_forward_alerts_to_syslog();
+        let old_prometheus = std::mem::replace(
+            &mut current_settings.prometheus,
+            PrometheusSettings {
+                enabled: ui.get_prometheus_enabled(),
+                bind_address: ui.get_prometheus_bind_address().trim().to_string(),
+                port: ui.get_prometheus_port().clamp(1, u16::MAX as i32) as u16,
+            },
+        );
+        let old_overlay = std::mem::replace(
+            &mut current_settings.overlay,
+            OverlaySettings {
+                enabled: ui.get_overlay_enabled(),
+                bind_address: ui.get_overlay_bind_address().trim().to_string(),
+                port: ui.get_overlay_port().clamp(1, u16::MAX as i32) as u16,
+            },
+        );
+        let old_grpc = std::mem::replace(
+            &mut current_settings.grpc,
+            GrpcSettings {
+                enabled: ui.get_grpc_enabled(),
+                bind_address: ui.get_grpc_bind_address().trim().to_string(),
+                port: ui.get_grpc_port().clamp(1, u16::MAX as i32) as u16,
+            },
+        );
+        current_settings.certificates = CertSettings {
+            targets: ui
+                .get_cert_targets()
+                .split(',')
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+                .collect(),
+            warn_days: ui.get_cert_warn_days().max(1) as u32,
+        };
+        // Commands may contain commas, so jobs are separated by semicolons
+        current_settings.backups = BackupSettings {
+            jobs: ui
+                .get_backup_jobs()
+                .split(';')
+                .map(|j| j.trim().to_string())
+                .filter(|j| !j.is_empty())
+                .collect(),
+            max_age_hours: ui.get_backup_max_age_hours().max(1) as u32,
+        };
+        current_settings.aliases = AppSettings::parse_aliases(&ui.get_aliases());
+        // Same `name=value` list format as the aliases
+        current_settings.app_profiles = AppSettings::parse_aliases(&ui.get_app_profiles());
+        current_settings.derived_metrics = AppSettings::parse_derived_metrics(
+            &ui.get_derived_metrics(),
+            &current_settings.derived_metrics,
+        );
+        current_settings.watched_processes = ui
+            .get_watched_processes()
+            .split(',')
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty())
+            .collect();
+        let old_remote_hosts = std::mem::replace(
+            &mut current_settings.remote_hosts,
+            ui.get_remote_host_list()
+                .split(',')
+                .map(|h| h.trim().to_string())
+                .filter(|h| !h.is_empty())
+                .collect(),
+        );
+        current_settings.save();
+        info!("Settings saved");
+
+        collector::lock(&save_monitor)
+            .set_watched_processes(current_settings.watched_processes.clone());
+        collector::lock(&save_monitor).set_aliases(current_settings.aliases.clone());
+        collector::lock(&save_monitor).set_app_profiles(&current_settings.app_profiles);
+        collector::lock(&save_monitor).set_derived_metrics(&current_settings.derived_metrics);
+        collector::lock(&save_monitor).set_poll_suspended_gpu(current_settings.poll_suspended_gpu);
+
+        if current_settings.remote_hosts != old_remote_hosts {
+            collector::lock(&save_monitor).set_remote_hosts(current_settings.remote_hosts.clone());
+        }
+
+        if current_settings.webhook != old_webhook {
+            collector::lock(&save_monitor).set_webhook(&current_settings.webhook);
+            collector::lock(&save_monitor).set_notifications(
+                &current_settings.notifications,
+                &current_settings.webhook.url,
+            );
+        }
+
+        if current_settings.csv != old_csv {
+            collector::lock(&save_monitor).set_csv_logging(&current_settings.csv);
+        }
+
+        // Restart the servers whose address changed (or stop them)
+        if current_settings.prometheus != old_prometheus {
+            collector::lock(&save_monitor).set_prometheus(&current_settings.prometheus);
+        }
+        if current_settings.overlay != old_overlay {
+            collector::lock(&save_monitor).set_overlay(&current_settings.overlay);
+        }
+        if current_settings.grpc != old_grpc {
+            collector::lock(&save_monitor).set_grpc(&current_settings.grpc);
+        }
+
+        let syslog_enabled = collector::lock(&save_monitor).syslog.is_some();
+        if current_settings.forward_alerts_to_syslog != syslog_enabled {
+            collector::lock(&save_monitor)
+                .set_syslog_forwarding(current_settings.forward_alerts_to_syslog);
+        }
+
+        // Start or stop the DNS probe thread when toggled
+        let dns_running = collector::lock(&save_monitor).dns.is_some();
+        if current_settings.enable_dns_probe != dns_running {
+            collector::lock(&save_monitor).set_dns_probe(
+                current_settings
+                    .enable_dns_probe
+                    .then(|| current_settings.dns_probe_host.clone()),
+            );
+        }
+
+        // Handle refresh rate change
+        if current_settings.refresh_rate_ms != old_refresh {
+            info!(
+                "Updating refresh rate to {}ms",
+                current_settings.refresh_rate_ms
+            );
+            collector::lock(&save_monitor).set_refresh_rate(current_settings.refresh_rate_ms);
+
+            save_collector.set_interval(current_settings.refresh_rate_ms);
+        }
+        if current_settings.history_window_secs != old_window {
+            collector::lock(&save_monitor).set_history_window(current_settings.history_window_secs);
+        }
+        ui.set_history_resolution(collector::lock(&save_monitor).history_resolution().into());
+    });
+
+    ui.run()
+}
+
+/// The overlay lines of `overlay` for a chart scaled to `max_val`.
+fn overlay_paths(
+    overlay: &history::HistoryOverlay,
+    max_val: f32,
+    window_secs: f64,
+) -> ChartOverlay {
+    ChartOverlay {
+        average_path: if overlay.average.is_empty() {
+            Default::default()
+        } else {
+            generate_path(&overlay.average, max_val, window_secs)
+        },
+        p95_path: generate_level_path(overlay.p95, max_val),
+    }
+}
+
+/// Resident memory of a process, e.g. "512 MB" or "1.25 GB".
+fn format_rss(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 * 1024 {
+        format!("{:.2} GB", bytes as f64 / 1024f64.powi(3))
+    } else {
+        format!("{:.0} MB", bytes as f64 / 1024f64.powi(2))
+    }
+}
+
+/// Copies the selected process into the detail pane, or closes the pane if it exited
+/// before it could be selected.
+fn update_process_detail(ui: &AppWindow, monitor: &SystemMonitor) {
+    let Some(detail) = monitor.get_process_detail() else {
+        ui.set_selected_pid(-1);
+        return;
+    };
+    let window_secs = monitor.history_limits.window_secs;
+    let process = &detail.process;
+    let sched = process.sched.as_ref();
+    let owner_str = match (process.owner.is_empty(), process.cgroup.is_empty()) {
+        (_, true) => "cgroup unknown".to_string(),
+        (true, false) => format!("cgroup {}", process.cgroup),
+        (false, false) => format!("{} · cgroup {}", process.owner, process.cgroup),
+    };
+    let threads = match detail.info.threads {
+        Some(threads) => format!("{} threads", threads),
+        None => "threads unknown".to_string(),
+    };
+    let open_files = match detail.info.open_files {
+        Some(files) => format!("{} open files", files),
+        None => "open files not readable".to_string(),
+    };
+    ui.set_process_detail(ProcessDetailData {
+        pid: process.pid as i32,
+        name: process.name.clone().into(),
+        cmdline: detail.info.cmdline.clone().into(),
+        owner_str: owner_str.into(),
+        counts_str: format!("{} · {}", threads, open_files).into(),
+        cpu_str: format!("{:.1}%", process.cpu_percent).into(),
+        mem_str: format_rss(process.rss_bytes).into(),
+        // CPU is per core, so a busy multi-threaded process goes beyond 100%
+        cpu_path: generate_path(
+            &detail.cpu_history,
+            detail.cpu_history.max_value().max(100.0),
+            window_secs,
+        ),
+        mem_path: generate_path(
+            &detail.mem_history,
+            detail.mem_history.max_value().max(1.0),
+            window_secs,
+        ),
+        running: detail.running,
+        sched_known: sched.is_some(),
+        nice: sched.map(|s| s.nice).unwrap_or(0),
+        policy_index: sched
+            .and_then(|s| s.policy)
+            .and_then(|policy| SchedPolicy::EDITABLE.iter().position(|p| *p == policy))
+            .map(|index| index as i32)
+            .unwrap_or(-1),
+        affinity: sched.map(|s| s.affinity.clone()).unwrap_or_default().into(),
+    });
+}
+
+/// Status line of a screenshot or recording: where it was saved, or the error.
+fn capture_status(result: Result<std::path::PathBuf, String>) -> String {
+    match result {
+        Ok(path) => format!("Saved {}", path.display()),
+        Err(e) => {
+            error!("{}", e);
+            e
+        }
+    }
+}
+
+/// Refreshes the long-term history series list and the chart of `selection` (the first
+/// series over the first range when nothing was picked yet), and the `comparison` of two
+/// spans of the same series.
+fn update_long_term(
+    ui: &AppWindow,
+    monitor: &SystemMonitor,
+    selection: &Option<(String, usize)>,
+    comparison: &Option<(String, String, usize)>,
+) {
+    let clear = |summary: &str| {
+        ui.set_long_term_path("".into());
+        ui.set_long_term_max_path("".into());
+        ui.set_long_term_summary(summary.into());
+    };
+    if monitor.long_term.is_none() {
+        clear("Long-term history is off. Enable \"long_term_history\" in the config file to keep per-minute statistics.");
+        update_long_term_comparison(ui, monitor, "", &None);
+        return;
+    }
+
+    let series = monitor.get_long_term_series();
+    let ids: Vec<slint::SharedString> = series.iter().map(|s| s.id.as_str().into()).collect();
+    // Only replace the picker models when the series changed, keeping the selection
+    if ui.get_long_term_ids().iter().ne(ids.iter().cloned()) {
+        let labels: Vec<slint::SharedString> =
+            series.iter().map(|s| s.label.as_str().into()).collect();
+        ui.set_long_term_labels(slint::ModelRc::new(slint::VecModel::from(labels)));
+        ui.set_long_term_ids(slint::ModelRc::new(slint::VecModel::from(ids)));
+    }
+
+    let (id, range) = match selection {
+        Some((id, range)) => (id.clone(), *range),
+        None => match series.first() {
+            Some(first) => (first.id.clone(), 0),
+            None => {
+                clear("No statistics stored yet. Each minute is stored once it is over.");
+                update_long_term_comparison(ui, monitor, "", &None);
+                return;
+            }
+        },
+    };
+    let (range_label, range_secs) = longterm::RANGES[range.min(longterm::RANGES.len() - 1)];
+    let label = series
+        .iter()
+        .find(|s| s.id == id)
+        .map_or(id.as_str(), |s| s.label.as_str());
+    update_long_term_comparison(ui, monitor, &id, comparison);
+    match monitor.get_long_term_history(&id, range_secs) {
+        Ok(points) if !points.is_empty() => {
+            let max = points.iter().map(|p| p.max).fold(0.0, f32::max);
+            let min = points.iter().map(|p| p.min).fold(f32::INFINITY, f32::min);
+            let avg = points.iter().map(|p| p.avg).sum::<f32>() / points.len() as f32;
+            let scale = max.max(1.0);
+            let max_gap = longterm::bucket_secs(range_secs) as f64 * 2.0;
+            let avg_points: Vec<(f64, f32)> = points.iter().map(|p| (p.time, p.avg)).collect();
+            let max_points: Vec<(f64, f32)> = points.iter().map(|p| (p.time, p.max)).collect();
+            ui.set_long_term_path(generate_points_path(
+                &avg_points,
+                scale,
+                range_secs as f64,
+                max_gap,
+            ));
+            ui.set_long_term_max_path(generate_points_path(
+                &max_points,
+                scale,
+                range_secs as f64,
+                max_gap,
+            ));
+            ui.set_long_term_summary(
+                format!(
+                    "{}, {}: average {:.1}, min {:.1}, max {:.1} (maxima in the second color)",
+                    label,
+                    range_label.to_lowercase(),
+                    avg,
+                    min,
+                    max
+                )
+                .into(),
+            );
+        }
+        Ok(_) => clear(&format!(
+            "{}: nothing stored in the {}.",
+            label,
+            range_label.to_lowercase()
+        )),
+        Err(e) => {
+            error!("{}", e);
+            clear(&e);
+        }
+    }
+}
+
+/// Draws the spans of series `id` starting at the two times of `comparison` over each
+/// other, the first in the primary and the second in the secondary color.
+fn update_long_term_comparison(
+    ui: &AppWindow,
+    monitor: &SystemMonitor,
+    id: &str,
+    comparison: &Option<(String, String, usize)>,
+) {
+    let show = |first: &str, second: &str, summary: &str| {
+        ui.set_long_term_compare_path(first.into());
+        ui.set_long_term_compare_other_path(second.into());
+        ui.set_long_term_compare_summary(summary.into());
+    };
+    let Some((first, second, length)) = comparison else {
+        show("", "", "");
+        return;
+    };
+    let (length_label, length_secs) =
+        longterm::COMPARE_LENGTHS[(*length).min(longterm::COMPARE_LENGTHS.len() - 1)];
+    let now = markers::now_secs();
+    let spans = [first, second].map(|start| {
+        let start = longterm::parse_time(start, now)?;
+        let points = monitor.get_long_term_span(id, start, start + length_secs)?;
+        Ok::<_, String>((start, points))
+    });
+    let [first_span, second_span] = match spans {
+        [Ok(first), Ok(second)] => [first, second],
+        [Err(e), _] | [_, Err(e)] => {
+            show("", "", &e);
+            return;
+        }
+    };
+
+    let scale = first_span
+        .1
+        .iter()
+        .chain(&second_span.1)
+        .map(|p| p.avg)
+        .fold(1.0, f32::max);
+    let max_gap = longterm::bucket_secs(length_secs) as f64 * 2.0;
+    let path = |(start, points): &(u64, Vec<longterm::LongTermPoint>)| {
+        let points: Vec<(f64, f32)> = points.iter().map(|p| (p.time, p.avg)).collect();
+        generate_span_path(&points, scale, *start as f64, length_secs as f64, max_gap)
+    };
+    let describe = |name: &str, (start, points): &(u64, Vec<longterm::LongTermPoint>)| {
+        let from = longterm::format_local(*start);
+        if points.is_empty() {
+            return format!("{} ({}): nothing stored", name, from);
+        }
+        let avg = points.iter().map(|p| p.avg).sum::<f32>() / points.len() as f32;
+        let max = points.iter().map(|p| p.max).fold(0.0, f32::max);
+        format!("{} ({}): average {:.1}, max {:.1}", name, from, avg, max)
+    };
+    let summary = format!(
+        "{} from each start. {}; {} (second color)",
+        length_label,
+        describe("First", &first_span),
+        describe("second", &second_span)
+    );
+    ui.set_long_term_compare_path(path(&first_span));
+    ui.set_long_term_compare_other_path(path(&second_span));
+    ui.set_long_term_compare_summary(summary.into());
+}
+
+/// Copies the usage view inputs of the main window to a detached panel. Models are
+/// shared (`ModelRc` clones), so only the plain properties actually change.
+fn sync_panel(ui: &AppWindow, panel: &PanelWindow) {
+    panel.set_dark_mode(ui.get_dark_mode());
+    panel.set_cpus(ui.get_cpus());
+    panel.set_cpu_stacked(ui.get_cpu_stacked());
+    panel.set_cpu_total_label(ui.get_cpu_total_label());
+    panel.set_cpu_heat(ui.get_cpu_heat());
+    panel.set_session_stats(ui.get_session_stats());
+    panel.set_cpu_view(ui.get_cpu_view());
+    panel.set_memory_path(ui.get_memory_path());
+    panel.set_memory_overlay(ui.get_memory_overlay());
+    panel.set_cpu_overlays(ui.get_cpu_overlays());
+    panel.set_memory_label(ui.get_memory_label());
+    panel.set_swap_path(ui.get_swap_path());
+    panel.set_swap_label(ui.get_swap_label());
+    panel.set_runnable_path(ui.get_runnable_path());
+    panel.set_runnable_label(ui.get_runnable_label());
+    panel.set_sched_wait_path(ui.get_sched_wait_path());
+    panel.set_sched_wait_label(ui.get_sched_wait_label());
+    panel.set_gpu_compute(ui.get_gpu_compute());
+    panel.set_gpu_memory(ui.get_gpu_memory());
+    panel.set_gpu_processes(ui.get_gpu_processes());
+    panel.set_networks(ui.get_networks());
+    panel.set_disks(ui.get_disks());
+    panel.set_slices(ui.get_slices());
+    panel.set_sandbox_apps(ui.get_sandbox_apps());
+    panel.set_fans(ui.get_fans());
+    panel.set_sensor_chips(ui.get_sensor_chips());
+    panel.set_batteries(ui.get_batteries());
+    panel.set_game_sessions(ui.get_game_sessions());
+    panel.set_long_term_labels(ui.get_long_term_labels());
+    panel.set_long_term_ids(ui.get_long_term_ids());
+    panel.set_long_term_ranges(ui.get_long_term_ranges());
+    panel.set_long_term_path(ui.get_long_term_path());
+    panel.set_long_term_max_path(ui.get_long_term_max_path());
+    panel.set_long_term_summary(ui.get_long_term_summary());
+    panel.set_long_term_compare_lengths(ui.get_long_term_compare_lengths());
+    panel.set_long_term_compare_path(ui.get_long_term_compare_path());
+    panel.set_long_term_compare_other_path(ui.get_long_term_compare_other_path());
+    panel.set_long_term_compare_summary(ui.get_long_term_compare_summary());
+    panel.set_games_playing(ui.get_games_playing());
+    panel.set_frame_time(ui.get_frame_time());
+    panel.set_drive_temps(ui.get_drive_temps());
+    panel.set_disk_latency(ui.get_disk_latency());
+    panel.set_disk_io(ui.get_disk_io());
+    panel.set_processes(ui.get_processes());
+    panel.set_process_sort(ui.get_process_sort());
+    panel.set_selected_pid(ui.get_selected_pid());
+    panel.set_process_detail(ui.get_process_detail());
+    panel.set_blocked_tasks_path(ui.get_blocked_tasks_path());
+    panel.set_zombies_path(ui.get_zombies_path());
+    panel.set_process_states_label(ui.get_process_states_label());
+    // Only on change, so the cursor of the panel's filter box isn't reset while typing
+    if panel.get_process_filter() != ui.get_process_filter() {
+        panel.set_process_filter(ui.get_process_filter());
+    }
+    panel.set_protocols(ui.get_protocols());
+    panel.set_dns_latency(ui.get_dns_latency());
+    panel.set_remote_hosts(ui.get_remote_hosts());
+    panel.set_vms(ui.get_vms());
+    panel.set_vm_status(ui.get_vm_status());
+    panel.set_gpu_session_active(ui.get_gpu_session_active());
+    panel.set_gpu_session_status(ui.get_gpu_session_status());
+    panel.set_pinned(ui.get_pinned_series());
+    panel.set_custom_metrics(ui.get_custom_metrics());
+    panel.set_script_badges(ui.get_script_badges());
+    panel.set_pinnable_labels(ui.get_pinnable_labels());
+    panel.set_pinnable_ids(ui.get_pinnable_ids());
+    panel.set_health_score(ui.get_health_score());
+    panel.set_health_grade(ui.get_health_grade());
+    panel.set_health_components(ui.get_health_components());
+    panel.set_allow_fan_control(ui.get_allow_fan_control());
+    panel.set_markers(ui.get_chart_markers());
+    panel.set_cpu_color(ui.get_cpu_chart_color());
+    panel.set_use_uniform_cpu(ui.get_use_uniform_cpu());
+    panel.set_ram_color(ui.get_ram_chart_color());
+    panel.set_gpu_color(ui.get_gpu_chart_color());
+    panel.set_net_color(ui.get_net_chart_color());
+}
+
+/// Opens `tab` in its own window, or brings an existing window for it to the front.
+/// `layout` restores a saved position and size.
+fn open_panel(
+    ui_handle: &slint::Weak<AppWindow>,
+    panels: &Rc<RefCell<Vec<PanelWindow>>>,
+    tab: i32,
+    layout: Option<&DetachedPanel>,
+) {
+    let Some(ui) = ui_handle.upgrade() else {
+        return;
+    };
+    if let Some(panel) = panels.borrow().iter().find(|p| p.get_tab() == tab) {
+        let _ = panel.show();
+        return;
+    }
+    let panel = match PanelWindow::new() {
+        Ok(panel) => panel,
+        Err(e) => {
+            error!("Failed to open panel window: {}", e);
+            return;
+        }
+    };
+    panel.set_tab(tab);
+    sync_panel(&ui, &panel);
+
+    let handle = ui_handle.clone();
+    panel.on_add_marker(move |label| handle.unwrap().invoke_add_marker(label));
+    let handle = ui_handle.clone();
+    panel.on_start_gpu_session(move |label| handle.unwrap().invoke_start_gpu_session(label));
+    let handle = ui_handle.clone();
+    panel.on_stop_gpu_session(move || handle.unwrap().invoke_stop_gpu_session());
+    let handle = ui_handle.clone();
+    panel.on_cpu_view_changed(move |view| {
+        let ui = handle.unwrap();
+        ui.set_cpu_view(view);
+        ui.invoke_cpu_view_changed(view);
+    });
+    let handle = ui_handle.clone();
+    panel.on_process_sort_changed(move |sort| handle.unwrap().set_process_sort(sort));
+    let handle = ui_handle.clone();
+    panel.on_process_filter_changed(move |filter| handle.unwrap().set_process_filter(filter));
+    let handle = ui_handle.clone();
+    panel.on_process_selected(move |pid| handle.unwrap().invoke_process_selected(pid));
+    let handle = ui_handle.clone();
+    panel.on_set_process_scheduling(move |pid, nice, policy, cpus| {
+        handle
+            .unwrap()
+            .invoke_set_process_scheduling(pid, nice, policy, cpus)
+    });
+    let handle = ui_handle.clone();
+    panel.on_set_fan_speed(move |chip, index, percent| {
+        handle.unwrap().invoke_set_fan_speed(chip, index, percent)
+    });
+    let handle = ui_handle.clone();
+    panel.on_toggle_chart_overlay(move |chart, kind| {
+        handle.unwrap().invoke_toggle_chart_overlay(chart, kind)
+    });
+    let handle = ui_handle.clone();
+    panel.on_pin_series(move |id| handle.unwrap().invoke_pin_series(id));
+    let handle = ui_handle.clone();
+    panel.on_unpin_series(move |id| handle.unwrap().invoke_unpin_series(id));
+    let handle = ui_handle.clone();
+    panel.on_show_long_term(move |id, range| handle.unwrap().invoke_show_long_term(id, range));
+    let handle = ui_handle.clone();
+    panel.on_compare_long_term(move |first, second, length| {
+        handle
+            .unwrap()
+            .invoke_compare_long_term(first, second, length)
+    });
+
+    // Closing a panel forgets it, so it isn't reopened on the next start
+    let close_panels = Rc::downgrade(panels);
+    panel.window().on_close_requested(move || {
+        if let Some(panels) = close_panels.upgrade() {
+            panels.borrow_mut().retain(|p| p.get_tab() != tab);
+            save_panel_layout(&panels.borrow());
+        }
+        slint::CloseRequestResponse::HideWindow
+    });
+
+    if let Some(layout) = layout {
+        panel
+            .window()
+            .set_position(slint::PhysicalPosition::new(layout.x, layout.y));
+        if layout.width > 0 && layout.height > 0 {
+            panel
+                .window()
+                .set_size(slint::PhysicalSize::new(layout.width, layout.height));
+        }
+    }
+    if let Err(e) = panel.show() {
+        error!("Failed to show panel window: {}", e);
+        return;
+    }
+    panels.borrow_mut().push(panel);
+}
+
+/// Stores the open panels and their window geometry in the settings.
+fn save_panel_layout(panels: &[PanelWindow]) {
+    let mut settings = AppSettings::load();
+    settings.detached_panels = panels
+        .iter()
+        .map(|p| {
+            let position = p.window().position();
+            let size = p.window().size();
+            DetachedPanel {
+                tab: p.get_tab(),
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+            }
+        })
+        .collect();
+    settings.save();
+}
+
+/// Reads the display session and monitor layout into the System Info Display tab.
+fn update_display_info(ui: &AppWindow, monitor_model: &slint::VecModel<MonitorData>) {
+    let session = display::get_session_info();
+    ui.set_sys_display_session(DisplaySessionData {
+        session_type: session.session_type.into(),
+        display_server: session.display_server.into(),
+        desktop: session.desktop.into(),
+        compositor: session.compositor.into(),
+        renderer: session.renderer.into(),
+        drivers: session.drivers.into(),
+    });
+    monitor_model.set_vec(
+        display::get_monitors()
+            .into_iter()
+            .map(|m| MonitorData {
+                name: m.name.into(),
+                mode: m.mode.into(),
+                refresh: m.refresh.into(),
+                position: m.position.into(),
+                primary: m.primary,
+            })
+            .collect::<Vec<_>>(),
+    );
+}
+
+/// Reads the IOMMU groups into the Hardware > IOMMU tab.
+fn update_iommu_info(ui: &AppWindow) {
+    let groups = iommu::read_groups();
+    let gpus: Vec<String> = groups
+        .iter()
+        .flat_map(|g| &g.devices)
+        .filter(|d| d.is_gpu)
+        .map(|d| {
+            format!(
+                "{} → {}",
+                d.address,
+                d.driver.as_deref().unwrap_or("no driver")
+            )
+        })
+        .collect();
+    let status = if groups.is_empty() {
+        "No IOMMU groups found. Enable the IOMMU in the firmware (VT-d / AMD-Vi) and, on \
+         Intel, boot with intel_iommu=on."
+            .to_string()
+    } else if gpus.is_empty() {
+        format!("{} groups.", groups.len())
+    } else {
+        format!("{} groups. GPUs: {}", groups.len(), gpus.join(", "))
+    };
+    let rows: Vec<IommuGroupData> = groups
+        .iter()
+        .map(|g| {
+            let summary = if !g.has_gpu() {
+                String::new()
+            } else if g.ready_for_passthrough() {
+                "GPU, ready for passthrough".to_string()
+            } else {
+                let pending = g
+                    .devices
+                    .iter()
+                    .filter(|d| d.class != "PCI bridge" && !d.is_vfio())
+                    .count();
+                format!("GPU, {} device(s) not bound to vfio-pci", pending)
+            };
+            let devices: Vec<IommuDeviceData> = g
+                .devices
+                .iter()
+                .map(|d| IommuDeviceData {
+                    address: d.address.clone().into(),
+                    name: d.name.clone().into(),
+                    class: d.class.clone().into(),
+                    driver: d.driver.clone().unwrap_or("none".to_string()).into(),
+                    gpu: d.is_gpu,
+                    vfio: d.is_vfio(),
+                })
+                .collect();
+            IommuGroupData {
+                title: format!("Group {}", g.id).into(),
+                summary: summary.into(),
+                has_gpu: g.has_gpu(),
+                devices: slint::ModelRc::from(Rc::new(slint::VecModel::from(devices))),
+            }
+        })
+        .collect();
+    ui.set_sys_iommu_status(status.into());
+    ui.set_sys_iommu_groups(slint::ModelRc::from(Rc::new(slint::VecModel::from(rows))));
+}
+
+/// Maps an integration's status (`None` when disabled) to its Slint indicator.
+fn integration_status(status: Option<Result<String, String>>) -> IntegrationStatus {
+    let (state, message) = match status {
+        None => (0, "Disabled".to_string()),
+        Some(Ok(message)) => (1, message),
+        Some(Err(message)) => (2, message),
+    };
+    IntegrationStatus {
+        state,
+        message: message.into(),
+    }
+}
+
+/// Converts monitor GPU details into the formatted Slint representation.
+fn gpu_detail_to_slint(d: monitor::GpuDetailedInfo) -> GpuDetailedInfo {
+    let fmt_watts = |w: Option<f32>| {
+        w.map(|w| format!("{:.0} W", w))
+            .unwrap_or("N/A".to_string())
+    };
+    let fmt_clocks = |cur: Option<u32>, max: Option<u32>| match (cur, max) {
+        (Some(c), Some(m)) => format!("{} / {} MHz", c, m),
+        (Some(c), None) => format!("{} MHz", c),
+        _ => "N/A".to_string(),
+    };
+
+    GpuDetailedInfo {
+        name: d.name.into(),
+        vram_total: format!("{:.1} GB", d.vram_total as f64 / 1024.0 / 1024.0 / 1024.0).into(),
+        vram_used: format!("{:.1} GB", d.vram_used as f64 / 1024.0 / 1024.0 / 1024.0).into(),
+        driver_version: d.driver_version.into(),
+        temperature: d
+            .temperature
+            .map(|t| format!("{}°C", t))
+            .unwrap_or("N/A".to_string())
+            .into(),
+        power_draw: d
+            .power_draw
+            .map(|p| format!("{:.2} W", p))
+            .unwrap_or("N/A".to_string())
+            .into(),
+        power_limit: d
+            .power_limit
+            .map(|p| format!("{:.2} W", p))
+            .unwrap_or("N/A".to_string())
+            .into(),
+        power_limit_range: format!(
+            "{} - {} (default {})",
+            fmt_watts(d.power_limit_min),
+            fmt_watts(d.power_limit_max),
+            fmt_watts(d.power_limit_default)
+        )
+        .into(),
+        power_limit_watts: d.power_limit.unwrap_or(0.0),
+        power_limit_min: d.power_limit_min.unwrap_or(0.0),
+        power_limit_max: d.power_limit_max.unwrap_or(0.0),
+        fan_speed: d
+            .fan_speed
+            .map(|f| format!("{}%", f))
+            .unwrap_or("N/A".to_string())
+            .into(),
+        gpu_utilization: d
+            .gpu_utilization
+            .map(|u| format!("{}%", u))
+            .unwrap_or("N/A".to_string())
+            .into(),
+        memory_utilization: d
+            .memory_utilization
+            .map(|u| format!("{}%", u))
+            .unwrap_or("N/A".to_string())
+            .into(),
+        graphics_clock: fmt_clocks(d.clock_graphics, d.clock_graphics_max).into(),
+        memory_clock: fmt_clocks(d.clock_memory, d.clock_memory_max).into(),
+        cuda_version: d.cuda_version.unwrap_or("N/A".to_string()).into(),
+        compute_capability: d.compute_capability.unwrap_or("N/A".to_string()).into(),
+        architecture: d.architecture.unwrap_or("N/A".to_string()).into(),
+        memory_bus: match (d.memory_bus_width, d.memory_bandwidth) {
+            (Some(bits), Some(bandwidth)) => format!("{}-bit, {:.0} GB/s", bits, bandwidth),
+            (Some(bits), None) => format!("{}-bit", bits),
+            _ => "N/A".to_string(),
+        }
+        .into(),
+        pcie_link: d.pcie_link.unwrap_or("N/A".to_string()).into(),
+        power_state: d.power_state.unwrap_or("N/A".to_string()).into(),
+    }
+}
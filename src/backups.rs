@@ -0,0 +1,208 @@
+//! # Backup Freshness Module
+//!
+//! This module checks when backup jobs last succeeded, so a silently failing backup is
+//! noticed before the backup is needed. Each job is either:
+//! - A path whose modification time marks the last backup: a file (e.g. a stamp file
+//!   touched by the job) or a repository directory, which counts as modified when any of
+//!   its direct entries is (borg updates `index.N`, restic adds to `snapshots/`).
+//! - A command, prefixed with `!`, that prints the last success time as UNIX seconds or
+//!   an ISO 8601 date-time, e.g.
+//!   `!restic -r /srv/restic snapshots --latest 1 --json | jq -r '.[0].time'`.
+//!
+//! Jobs are checked in a background thread; `SystemMonitor` raises alerts for stale ones.
+//! A command is killed after `BACKUP_COMMAND_TIMEOUT_SECS` (e.g. a repository on an
+//! unreachable host), so one hung job can't stop the others from being checked.
+
+use crate::shell;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+/// Seconds between two checks of all jobs.
+const BACKUP_POLL_INTERVAL_SECS: u64 = 10 * 60;
+/// Longest a `!command` job may run.
+const BACKUP_COMMAND_TIMEOUT_SECS: u64 = 2 * 60;
+
+/// Latest check of one backup job.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct BackupStatus {
+    /// Path or `!command`, as configured.
+    pub target: String,
+    /// Last success in seconds since the UNIX epoch; `None` until checked or on error.
+    pub last_success: Option<f64>,
+    /// Why the time could not be determined.
+    pub error: String,
+}
+
+impl BackupStatus {
+    /// Hours since the last success.
+    pub fn age_hours(&self) -> Option<f64> {
+        self.last_success
+            .map(|t| ((crate::history::now() - t) / 3600.0).max(0.0))
+    }
+
+    /// e.g. "/srv/borg: 5 h ago" or the error.
+    pub fn describe(&self) -> String {
+        match self.last_success {
+            Some(t) => format!(
+                "{}: last backup {} ago",
+                self.target,
+                crate::suspend::format_gap(Duration::from_secs_f64(
+                    (crate::history::now() - t).max(0.0)
+                ))
+            ),
+            None if self.error.is_empty() => format!("{}: checking...", self.target),
+            None => format!("{}: {}", self.target, self.error),
+        }
+    }
+}
+
+/// Parses UNIX seconds or an ISO 8601 date-time such as "2026-05-01T03:00:12.5+02:00"
+/// (UTC when no offset is given).
+fn parse_timestamp(text: &str) -> Option<f64> {
+    let text = text.trim();
+    if let Ok(secs) = text.parse::<f64>() {
+        return Some(secs);
+    }
+    let (date, time) = text.split_once(['T', ' '])?;
+    let mut date_fields = date.split('-');
+    let year: i64 = date_fields.next()?.parse().ok()?;
+    let month: u32 = date_fields.next()?.parse().ok()?;
+    let day: u32 = date_fields.next()?.parse().ok()?;
+
+    // Split off the UTC offset: "Z", "+02:00" or "-0500"
+    let (clock, offset_secs) = match time.find(['Z', '+', '-']) {
+        Some(pos) => {
+            let offset = &time[pos..];
+            let secs = if offset == "Z" {
+                0
+            } else {
+                let digits: String = offset[1..].chars().filter(char::is_ascii_digit).collect();
+                let hours: i64 = digits.get(..2)?.parse().ok()?;
+                let minutes: i64 = digits.get(2..4).unwrap_or("0").parse().ok()?;
+                let secs = hours * 3600 + minutes * 60;
+                if offset.starts_with('-') {
+                    -secs
+                } else {
+                    secs
+                }
+            };
+            (&time[..pos], secs)
+        }
+        None => (time, 0),
+    };
+    let mut clock_fields = clock.split(':');
+    let h: f64 = clock_fields.next()?.parse().ok()?;
+    let m: f64 = clock_fields.next()?.parse().ok()?;
+    let s: f64 = clock_fields.next().unwrap_or("0").parse().ok()?;
+    let days = crate::certs::days_from_civil(year, month, day);
+    Some((days * 86400 - offset_secs) as f64 + h * 3600.0 + m * 60.0 + s)
+}
+
+fn modified_secs(path: &Path) -> Option<f64> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_secs_f64())
+}
+
+/// Newest modification time of `path` and, for a directory, its direct entries.
+fn newest_modification(path: &Path) -> Result<f64, String> {
+    let own = modified_secs(path).ok_or_else(|| format!("{} not found", path.display()))?;
+    let entries = std::fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| modified_secs(&e.path()))
+                .fold(0.0, f64::max)
+        })
+        .unwrap_or(0.0);
+    Ok(own.max(entries))
+}
+
+/// Runs a job's command (see `shell::run`) and parses the last line it prints. The
+/// command is killed after `BACKUP_COMMAND_TIMEOUT_SECS`, or once `stop` is set.
+fn run_command(command: &str, stop: &AtomicBool) -> Result<f64, String> {
+    let timeout = Duration::from_secs(BACKUP_COMMAND_TIMEOUT_SECS);
+    let stdout = shell::run(command, timeout, stop)?;
+    let last = stdout.lines().rev().find(|l| !l.trim().is_empty());
+    last.and_then(parse_timestamp)
+        .ok_or_else(|| "Command printed no timestamp".to_string())
+}
+
+fn check(target: &str, stop: &AtomicBool) -> BackupStatus {
+    let result = match target.strip_prefix('!') {
+        Some(command) => run_command(command, stop),
+        None => {
+            let path = match target.strip_prefix("~/") {
+                Some(rest) => std::env::var("HOME").unwrap_or_default() + "/" + rest,
+                None => target.to_string(),
+            };
+            newest_modification(Path::new(&path))
+        }
+    };
+    let mut status = BackupStatus {
+        target: target.to_string(),
+        ..Default::default()
+    };
+    match result {
+        Ok(time) => status.last_success = Some(time),
+        Err(error) => status.error = error,
+    }
+    status
+}
+
+/// Checks the configured backup jobs in a background thread.
+pub struct BackupTracker {
+    latest: Arc<Mutex<Vec<BackupStatus>>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl BackupTracker {
+    /// Starts checking `targets` now and every `BACKUP_POLL_INTERVAL_SECS`.
+    pub fn start(targets: Vec<String>) -> Self {
+        let latest = Arc::new(Mutex::new(
+            targets
+                .iter()
+                .map(|t| BackupStatus {
+                    target: t.clone(),
+                    ..Default::default()
+                })
+                .collect(),
+        ));
+        let stop = Arc::new(AtomicBool::new(false));
+        if !targets.is_empty() {
+            let thread_latest = latest.clone();
+            let thread_stop = stop.clone();
+            std::thread::spawn(move || {
+                while !thread_stop.load(Ordering::Relaxed) {
+                    let statuses: Vec<BackupStatus> =
+                        targets.iter().map(|t| check(t, &thread_stop)).collect();
+                    if let Ok(mut guard) = thread_latest.lock() {
+                        *guard = statuses;
+                    }
+                    // Sleep in short steps so a replaced tracker's thread ends promptly
+                    let next = Instant::now() + Duration::from_secs(BACKUP_POLL_INTERVAL_SECS);
+                    while Instant::now() < next && !thread_stop.load(Ordering::Relaxed) {
+                        std::thread::sleep(Duration::from_secs(1));
+                    }
+                }
+            });
+        }
+
+        Self { latest, stop }
+    }
+
+    pub fn get_data(&self) -> Vec<BackupStatus> {
+        self.latest
+            .lock()
+            .map(|guard| guard.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for BackupTracker {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
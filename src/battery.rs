@@ -0,0 +1,253 @@
+//! # Battery Module
+//!
+//! This module reads the laptop batteries exposed by the kernel's power supply class
+//! under `/sys/class/power_supply`: charge, charge/discharge power, time remaining, cycle
+//! count and health (full capacity relative to the design capacity).
+//!
+//! Drivers report either energy (`energy_*`, µWh, with `power_now` in µW) or charge
+//! (`charge_*`, µAh, with `current_now` in µA); charge is converted with the design
+//! voltage. Time remaining is derived from the current power, like `upower` does.
+//! Batteries of peripherals (`scope` = `Device`, e.g. wireless mice) are left out.
+
+use crate::history::{History, HistoryLimits};
+use crate::monitor::SysPaths;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Directory containing one entry per power supply, under the `/sys` root.
+const POWER_SUPPLY_DIR: &str = "class/power_supply";
+
+/// Lower bound of the power chart scale (W), so a trickle charge doesn't fill the graph.
+const MIN_POWER_SCALE: f32 = 10.0;
+
+/// Holds data for a single battery for external consumers
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BatteryData {
+    /// Power supply name (e.g., "BAT0").
+    pub name: String,
+    /// Manufacturer and model, if reported.
+    pub model: String,
+    /// "Charging", "Discharging", "Full", "Not charging" or "Unknown".
+    pub status: String,
+    pub charge_percent: f32,
+    /// Charging (positive) or discharging (negative) power in watts.
+    pub power_watts: f32,
+    /// Seconds until full while charging or until empty while discharging.
+    pub time_remaining_secs: Option<u64>,
+    pub cycle_count: Option<u32>,
+    /// Energy when full and by design, in Wh.
+    pub energy_full_wh: Option<f32>,
+    pub energy_design_wh: Option<f32>,
+    pub charge_history: History,
+    /// Absolute power in watts.
+    pub power_history: History,
+    /// Highest power seen so far (at least 10 W), used to scale the power chart.
+    pub power_scale: f32,
+}
+
+impl BatteryData {
+    /// Full capacity in percent of the design capacity.
+    pub fn health_percent(&self) -> Option<f32> {
+        match (self.energy_full_wh, self.energy_design_wh) {
+            (Some(full), Some(design)) if design > 0.0 => Some(full / design * 100.0),
+            _ => None,
+        }
+    }
+}
+
+/// One reading of a battery's sysfs attributes.
+#[derive(Debug, Clone, Default)]
+struct BatteryReading {
+    model: String,
+    status: String,
+    charge_percent: f32,
+    power_watts: f32,
+    energy_now_wh: Option<f32>,
+    energy_full_wh: Option<f32>,
+    energy_design_wh: Option<f32>,
+    cycle_count: Option<u32>,
+}
+
+impl BatteryReading {
+    fn read(dir: &Path) -> Self {
+        let text = |name: &str| {
+            std::fs::read_to_string(dir.join(name))
+                .map(|s| s.trim().to_string())
+                .unwrap_or_default()
+        };
+        let value = |name: &str| text(name).parse::<f64>().ok();
+
+        // Charge-based drivers: µAh × design voltage (µV) → Wh
+        let voltage = value("voltage_min_design").or_else(|| value("voltage_now"));
+        let energy = |kind: &str| {
+            value(&format!("energy_{}", kind))
+                .map(|uwh| (uwh / 1e6) as f32)
+                .or_else(|| {
+                    let uah = value(&format!("charge_{}", kind))?;
+                    Some((uah * voltage? / 1e12) as f32)
+                })
+        };
+        let power = value("power_now")
+            .map(|uw| uw / 1e6)
+            .or_else(|| Some(value("current_now")? * value("voltage_now")? / 1e12))
+            .unwrap_or(0.0)
+            .abs() as f32;
+
+        let status = match text("status") {
+            s if s.is_empty() => "Unknown".to_string(),
+            s => s,
+        };
+        let energy_now_wh = energy("now");
+        let energy_full_wh = energy("full");
+        let charge_percent = value("capacity").map(|c| c as f32).unwrap_or_else(|| {
+            match (energy_now_wh, energy_full_wh) {
+                (Some(now), Some(full)) if full > 0.0 => now / full * 100.0,
+                _ => 0.0,
+            }
+        });
+
+        Self {
+            model: [text("manufacturer"), text("model_name")]
+                .into_iter()
+                .filter(|s| !s.is_empty())
+                .collect::<Vec<_>>()
+                .join(" "),
+            power_watts: if status == "Discharging" {
+                -power
+            } else {
+                power
+            },
+            status,
+            charge_percent,
+            energy_now_wh,
+            energy_full_wh,
+            energy_design_wh: energy("full_design"),
+            // Some firmware reports 0 when it doesn't count cycles
+            cycle_count: value("cycle_count").map(|c| c as u32).filter(|c| *c > 0),
+        }
+    }
+
+    /// Time to full or empty at the current power.
+    fn time_remaining_secs(&self) -> Option<u64> {
+        let now = self.energy_now_wh?;
+        let remaining_wh = match self.status.as_str() {
+            "Discharging" => now,
+            "Charging" => (self.energy_full_wh? - now).max(0.0),
+            _ => return None,
+        };
+        let watts = self.power_watts.abs();
+        (watts > 0.1).then(|| (remaining_wh / watts * 3600.0) as u64)
+    }
+}
+
+struct BatteryEntry {
+    name: String,
+    reading: BatteryReading,
+    power_scale: f32,
+    charge_history: History,
+    power_history: History,
+}
+
+/// Samples every battery on each refresh and keeps charge/power history.
+pub struct BatteryTracker {
+    root: PathBuf,
+    entries: Vec<BatteryEntry>,
+}
+
+impl BatteryTracker {
+    /// Reads the batteries under the `/sys` root of `paths`.
+    pub fn new(paths: &SysPaths) -> Self {
+        Self {
+            root: paths.sys.join(POWER_SUPPLY_DIR),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Lists the system batteries, sorted by name.
+    fn discover(root: &Path) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(root)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter(|e| {
+                        let read = |name: &str| {
+                            std::fs::read_to_string(e.path().join(name))
+                                .map(|s| s.trim().to_string())
+                                .unwrap_or_default()
+                        };
+                        read("type") == "Battery" && read("scope") != "Device"
+                    })
+                    .map(|e| e.file_name().to_string_lossy().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        names.sort();
+        names
+    }
+
+    /// Polls every battery and appends one sample to each history buffer.
+    pub fn refresh(&mut self, limits: HistoryLimits) {
+        // Keep the tracked set in sync with swapped or hot-plugged batteries
+        let names = Self::discover(&self.root);
+        self.entries.retain(|e| names.contains(&e.name));
+        for name in names {
+            if !self.entries.iter().any(|e| e.name == name) {
+                self.entries.push(BatteryEntry {
+                    name,
+                    reading: BatteryReading::default(),
+                    power_scale: MIN_POWER_SCALE,
+                    charge_history: History::with_limits(limits),
+                    power_history: History::with_limits(limits),
+                });
+            }
+        }
+        self.entries.sort_by(|a, b| a.name.cmp(&b.name));
+
+        for entry in &mut self.entries {
+            entry.reading = BatteryReading::read(&self.root.join(&entry.name));
+            let watts = entry.reading.power_watts.abs();
+            entry.power_scale = entry.power_scale.max(watts);
+            entry.charge_history.push(entry.reading.charge_percent);
+            entry.power_history.push(watts);
+        }
+    }
+
+    /// Applies new `limits` to every history buffer.
+    pub fn resize_history(&mut self, limits: HistoryLimits) {
+        for entry in &mut self.entries {
+            entry.charge_history.set_limits(limits);
+            entry.power_history.set_limits(limits);
+        }
+    }
+
+    /// Number of history buffers, for the monitor's sample budget.
+    pub fn history_count(&self) -> usize {
+        self.entries.len() * 2
+    }
+
+    pub fn get_data(&self) -> Vec<BatteryData> {
+        self.entries
+            .iter()
+            .map(|e| BatteryData {
+                name: e.name.clone(),
+                model: e.reading.model.clone(),
+                status: e.reading.status.clone(),
+                charge_percent: e.reading.charge_percent,
+                power_watts: e.reading.power_watts,
+                time_remaining_secs: e.reading.time_remaining_secs(),
+                cycle_count: e.reading.cycle_count,
+                energy_full_wh: e.reading.energy_full_wh,
+                energy_design_wh: e.reading.energy_design_wh,
+                charge_history: e.charge_history.clone(),
+                power_history: e.power_history.clone(),
+                power_scale: e.power_scale,
+            })
+            .collect()
+    }
+}
+
+impl Default for BatteryTracker {
+    fn default() -> Self {
+        Self::new(&SysPaths::default())
+    }
+}
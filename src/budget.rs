@@ -0,0 +1,150 @@
+//! # Data Budget Module
+//!
+//! This module compares the network traffic of the current billing period, taken from
+//! the persisted totals (see `totals.rs`), with the monthly cap of the `data_budget`
+//! settings section, for metered connections and capped plans.
+//!
+//! A warning is raised at each configured fraction of the cap and a critical alert once
+//! the cap is reached; each step is a new alert, so it is announced again. Once a day of
+//! the period has passed, the usage is projected linearly to the end of the period, and a
+//! projection above the cap raises a warning before any fraction is reached.
+//!
+//! Billing periods start at midnight local time on `reset_day`. Only the main window
+//! keeps the totals, so the budget is checked while it runs.
+
+use crate::alerts::AlertSeverity;
+use crate::settings::DataBudgetSettings;
+
+/// Bytes per GB, as providers count.
+const GB: f64 = 1e9;
+/// Time into the period before usage is projected.
+const MIN_PROJECTION_SECS: i64 = 86400;
+
+/// The billing period containing a point in time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BillingPeriod {
+    /// Month the period starts in, e.g. "2026-10".
+    pub key: String,
+    /// UNIX timestamps of the first and the first following second.
+    pub start: i64,
+    pub end: i64,
+}
+
+/// Local midnight of `day` in `month` (0-based, may overflow) of `year` (since 1900).
+fn local_midnight(year: i32, month: i32, day: i32) -> i64 {
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    tm.tm_year = year;
+    tm.tm_mon = month;
+    tm.tm_mday = day;
+    tm.tm_isdst = -1;
+    unsafe { libc::mktime(&mut tm) as i64 }
+}
+
+/// The billing period, starting on `reset_day` (clamped to 1-28), that contains `now`.
+pub fn current_period(reset_day: u32, now: i64) -> Option<BillingPeriod> {
+    let day = reset_day.clamp(1, 28) as i32;
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    let time = now as libc::time_t;
+    if unsafe { libc::localtime_r(&time, &mut tm) }.is_null() {
+        return None;
+    }
+    let (mut year, mut month) = (tm.tm_year, tm.tm_mon);
+    if tm.tm_mday < day {
+        month -= 1;
+        if month < 0 {
+            month = 11;
+            year -= 1;
+        }
+    }
+    Some(BillingPeriod {
+        key: format!("{}-{:02}", year + 1900, month + 1),
+        start: local_midnight(year, month, day),
+        end: local_midnight(year, month + 1, day),
+    })
+}
+
+/// Whether traffic of `interface` counts towards the budget.
+pub fn counts(settings: &DataBudgetSettings, interface: &str) -> bool {
+    if settings.interfaces.is_empty() {
+        interface != "lo"
+    } else {
+        settings.interfaces.iter().any(|i| i == interface)
+    }
+}
+
+/// Usage at the end of `period` if it continues at the rate so far, once a day has passed.
+pub fn project(used_bytes: u64, period: &BillingPeriod, now: i64) -> Option<u64> {
+    let elapsed = now - period.start;
+    if elapsed < MIN_PROJECTION_SECS {
+        return None;
+    }
+    let length = (period.end - period.start).max(elapsed);
+    Some((used_bytes as f64 * length as f64 / elapsed as f64) as u64)
+}
+
+/// The budget alert for `used_bytes` at `now`, as (id suffix, severity, message): the
+/// highest fraction reached ("80"), "100" for the cap or "projected".
+pub fn evaluate(
+    settings: &DataBudgetSettings,
+    used_bytes: u64,
+    period: &BillingPeriod,
+    now: i64,
+) -> Option<(String, AlertSeverity, String)> {
+    let cap = settings.monthly_cap_gb * GB;
+    if cap <= 0.0 {
+        return None;
+    }
+    let fraction = used_bytes as f64 / cap;
+    let projected = project(used_bytes, period, now);
+    let days_left = ((period.end - now).max(0) + 86399) / 86400;
+    let projection = projected
+        .map(|p| {
+            format!(
+                ", projected {:.0} GB by the end of the period ({} days left)",
+                p as f64 / GB,
+                days_left
+            )
+        })
+        .unwrap_or_default();
+    let message = |what: &str| {
+        format!(
+            "Data budget {}: {:.1} of {:.0} GB used ({:.0}%){}",
+            what,
+            used_bytes as f64 / GB,
+            settings.monthly_cap_gb,
+            fraction * 100.0,
+            projection
+        )
+    };
+
+    if fraction >= 1.0 {
+        return Some((
+            "100".to_string(),
+            AlertSeverity::Critical,
+            message("exceeded"),
+        ));
+    }
+    let reached = settings
+        .warn_fractions
+        .iter()
+        .copied()
+        .filter(|f| *f > 0.0 && fraction >= *f)
+        .fold(None, |max: Option<f64>, f| {
+            Some(max.map_or(f, |m| m.max(f)))
+        });
+    if let Some(step) = reached {
+        return Some((
+            format!("{:.0}", step * 100.0),
+            AlertSeverity::Warning,
+            message(&format!("at {:.0}%", step * 100.0)),
+        ));
+    }
+    if projected.is_some_and(|p| p as f64 > cap) {
+        return Some((
+            "projected".to_string(),
+            AlertSeverity::Warning,
+            message("on track to be exceeded"),
+        ));
+    }
+    None
+}
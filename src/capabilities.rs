@@ -0,0 +1,213 @@
+//! # Capabilities Module
+//!
+//! This module detects, once at startup, which optional libraries, tools and kernel
+//! interfaces are available. Features that depend on a missing one degrade gracefully
+//! (sections stay empty or show "Unknown"); the `Capabilities` report explains which
+//! features are affected and how to enable them.
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+/// One entry of the capability report.
+#[derive(Debug, Clone)]
+pub struct CapabilityInfo {
+    pub name: &'static str,
+    pub available: bool,
+    /// Features that depend on this capability.
+    pub enables: &'static str,
+    /// How to make it available when it is missing.
+    pub hint: &'static str,
+}
+
+/// Optional dependencies detected at startup.
+#[derive(Debug, Clone, Default)]
+pub struct Capabilities {
+    /// NVIDIA Management Library loaded; set by `SystemMonitor` after initializing it.
+    pub nvml: bool,
+    /// At least one hwmon chip exposes sensors.
+    pub hwmon: bool,
+    /// Unified cgroup v2 hierarchy mounted at `/sys/fs/cgroup`.
+    pub cgroup_v2: bool,
+    pub pkexec: bool,
+    pub smartctl: bool,
+    pub nvme_cli: bool,
+    pub dmidecode: bool,
+    pub ip: bool,
+    pub iw: bool,
+    pub tcpdump: bool,
+    pub ssh: bool,
+    pub curl: bool,
+    pub openssl: bool,
+    pub xrandr: bool,
+    pub glxinfo: bool,
+    pub vulkaninfo: bool,
+    pub xprintidle: bool,
+    pub virsh: bool,
+}
+
+/// Whether an executable named `name` exists in one of the `PATH` directories.
+/// Also searches the sbin directories, which are often missing from a user's `PATH`
+/// but used by the privileged worker.
+pub fn find_program(name: &str) -> bool {
+    let path = std::env::var("PATH").unwrap_or_default();
+    let found = path
+        .split(':')
+        .chain(["/usr/sbin", "/sbin", "/usr/local/sbin"])
+        .filter(|dir| !dir.is_empty())
+        .map(|dir| Path::new(dir).join(name))
+        .any(|p| {
+            p.metadata()
+                .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        });
+    found
+}
+
+impl Capabilities {
+    /// Probes everything except NVML, which `SystemMonitor` initializes itself.
+    pub fn detect() -> Self {
+        let hwmon = std::fs::read_dir("/sys/class/hwmon")
+            .map(|entries| entries.count() > 0)
+            .unwrap_or(false);
+
+        Self {
+            nvml: false,
+            hwmon,
+            cgroup_v2: Path::new("/sys/fs/cgroup/cgroup.controllers").exists(),
+            pkexec: find_program("pkexec"),
+            smartctl: find_program("smartctl"),
+            nvme_cli: find_program("nvme"),
+            dmidecode: find_program("dmidecode"),
+            ip: find_program("ip"),
+            iw: find_program("iw"),
+            tcpdump: find_program("tcpdump"),
+            ssh: find_program("ssh"),
+            curl: find_program("curl"),
+            openssl: find_program("openssl"),
+            xrandr: find_program("xrandr"),
+            glxinfo: find_program("glxinfo"),
+            vulkaninfo: find_program("vulkaninfo"),
+            xprintidle: find_program("xprintidle"),
+            virsh: find_program("virsh"),
+        }
+    }
+
+    /// Number of missing capabilities.
+    pub fn missing_count(&self) -> usize {
+        self.report().iter().filter(|c| !c.available).count()
+    }
+
+    /// Describes each capability, what it enables and how to install it.
+    pub fn report(&self) -> Vec<CapabilityInfo> {
+        vec![
+            CapabilityInfo {
+                name: "NVIDIA NVML",
+                available: self.nvml,
+                enables: "NVIDIA GPU usage, memory, clocks and power limits",
+                hint: "Install the proprietary NVIDIA driver (provides libnvidia-ml.so)",
+            },
+            CapabilityInfo {
+                name: "hwmon sensors",
+                available: self.hwmon,
+                enables: "Fan speeds, chip temperatures and fan control",
+                hint: "Load the sensor driver for your board (run `sudo sensors-detect`)",
+            },
+            CapabilityInfo {
+                name: "cgroup v2",
+                available: self.cgroup_v2,
+                enables: "Per-slice and per-scope usage in the Slices section",
+                hint: "Boot with `systemd.unified_cgroup_hierarchy=1`",
+            },
+            CapabilityInfo {
+                name: "pkexec",
+                available: self.pkexec,
+                enables:
+                    "Privileged worker: disk health, network namespaces, packet capture, Wi-Fi scan",
+                hint: "sudo apt install pkexec (polkit)",
+            },
+            CapabilityInfo {
+                name: "smartctl",
+                available: self.smartctl,
+                enables: "Disk health, serial and firmware details",
+                hint: "sudo apt install smartmontools",
+            },
+            CapabilityInfo {
+                name: "nvme-cli",
+                available: self.nvme_cli,
+                enables: "NVMe health when smartctl is not installed",
+                hint: "sudo apt install nvme-cli",
+            },
+            CapabilityInfo {
+                name: "dmidecode",
+                available: self.dmidecode,
+                enables: "Memory type, speed and module count",
+                hint: "sudo apt install dmidecode",
+            },
+            CapabilityInfo {
+                name: "ip",
+                available: self.ip,
+                enables: "Routing tables and neighbor cache",
+                hint: "sudo apt install iproute2",
+            },
+            CapabilityInfo {
+                name: "iw",
+                available: self.iw,
+                enables: "Wi-Fi network scan",
+                hint: "sudo apt install iw",
+            },
+            CapabilityInfo {
+                name: "tcpdump",
+                available: self.tcpdump,
+                enables: "Protocol breakdown (Packet Capture)",
+                hint: "sudo apt install tcpdump",
+            },
+            CapabilityInfo {
+                name: "ssh",
+                available: self.ssh,
+                enables: "Remote hosts",
+                hint: "sudo apt install openssh-client",
+            },
+            CapabilityInfo {
+                name: "curl",
+                available: self.curl,
+                enables: "Webhook sink",
+                hint: "sudo apt install curl",
+            },
+            CapabilityInfo {
+                name: "openssl",
+                available: self.openssl,
+                enables: "Certificate expiry checks",
+                hint: "sudo apt install openssl",
+            },
+            CapabilityInfo {
+                name: "xrandr",
+                available: self.xrandr,
+                enables: "Monitor refresh rates and layout (Display info)",
+                hint: "sudo apt install x11-xserver-utils",
+            },
+            CapabilityInfo {
+                name: "glxinfo",
+                available: self.glxinfo,
+                enables: "OpenGL renderer and driver version (Display and GPU info)",
+                hint: "sudo apt install mesa-utils",
+            },
+            CapabilityInfo {
+                name: "vulkaninfo",
+                available: self.vulkaninfo,
+                enables: "Vulkan devices and driver versions (GPU info)",
+                hint: "sudo apt install vulkan-tools",
+            },
+            CapabilityInfo {
+                name: "xprintidle",
+                available: self.xprintidle,
+                enables: "User idle time on X11 outside GNOME (idle/active session statistics)",
+                hint: "sudo apt install xprintidle",
+            },
+            CapabilityInfo {
+                name: "virsh",
+                available: self.virsh,
+                enables: "libvirt/QEMU virtual machine list and usage (VMs tab)",
+                hint: "sudo apt install libvirt-clients",
+            },
+        ]
+    }
+}
@@ -0,0 +1,163 @@
+//! # Packet Capture Module
+//!
+//! This module attributes network traffic to application protocols (HTTP, DNS, SSH, ...)
+//! by their well-known ports. It is opt-in and only runs inside the privileged worker,
+//! since capturing packets requires `CAP_NET_RAW`.
+//!
+//! Packets are captured with libpcap through `tcpdump` (quiet, numeric, line-buffered
+//! output), so no capture library has to be linked into the GUI binary. Only packet
+//! headers are parsed; payloads are never looked at.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+
+/// Traffic attributed to one protocol during a worker interval.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProtocolStats {
+    pub protocol: String,
+    pub bytes: u64,
+    pub packets: u64,
+}
+
+/// Well-known ports and the protocol they identify.
+const SERVICES: &[(u16, &str)] = &[
+    (20, "FTP"),
+    (21, "FTP"),
+    (22, "SSH"),
+    (25, "SMTP"),
+    (53, "DNS"),
+    (67, "DHCP"),
+    (68, "DHCP"),
+    (80, "HTTP"),
+    (110, "POP3"),
+    (123, "NTP"),
+    (143, "IMAP"),
+    (443, "HTTPS"),
+    (465, "SMTP"),
+    (587, "SMTP"),
+    (853, "DNS"),
+    (993, "IMAP"),
+    (995, "POP3"),
+    (1900, "SSDP"),
+    (3389, "RDP"),
+    (5353, "mDNS"),
+    (8080, "HTTP"),
+    (8443, "HTTPS"),
+];
+
+fn service_name(port: u16) -> Option<&'static str> {
+    SERVICES.iter().find(|(p, _)| *p == port).map(|(_, n)| *n)
+}
+
+/// Splits the port off a tcpdump endpoint ("192.168.1.5.443" or "fe80::1.546").
+fn endpoint_port(endpoint: &str) -> Option<u16> {
+    endpoint
+        .trim_end_matches(':')
+        .rsplit_once('.')
+        .and_then(|(_, port)| port.parse().ok())
+}
+
+/// Parses one line of `tcpdump -nn -q -t` output into `(protocol, payload bytes)`.
+///
+/// Example lines:
+/// - `IP 10.0.0.2.51234 > 140.82.112.3.443: tcp 1380`
+/// - `eth0  Out IP6 fe80::1.5353 > ff02::fb.5353: UDP, length 45`
+/// - `IP 10.0.0.1 > 10.0.0.2: ICMP echo request, id 1, seq 1, length 64`
+fn parse_tcpdump_line(line: &str) -> Option<(String, u64)> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let ip = tokens.iter().position(|t| *t == "IP" || *t == "IP6")?;
+    let src = tokens.get(ip + 1)?;
+    let dst = tokens.get(ip + 3)?;
+    let rest = tokens.get(ip + 4..)?;
+
+    let transport = rest.first()?.trim_end_matches(',');
+    let bytes = rest
+        .iter()
+        .rev()
+        .find_map(|t| t.trim_end_matches(',').parse::<u64>().ok())
+        .unwrap_or(0);
+
+    let protocol = match transport {
+        "tcp" | "UDP" => {
+            // IPv4 endpoints without a port have exactly three dots
+            let has_ports = tokens[ip] == "IP6" || src.matches('.').count() == 4;
+            let ports = if has_ports {
+                [endpoint_port(src), endpoint_port(dst)]
+            } else {
+                [None, None]
+            };
+            // Prefer the lower (server-side) port when both are well known
+            let mut known: Vec<(u16, &str)> = ports
+                .iter()
+                .flatten()
+                .filter_map(|p| service_name(*p).map(|n| (*p, n)))
+                .collect();
+            known.sort();
+            match known.first() {
+                Some((_, name)) => name.to_string(),
+                None if transport == "tcp" => "Other TCP".to_string(),
+                None => "Other UDP".to_string(),
+            }
+        }
+        t if t.starts_with("ICMP") => "ICMP".to_string(),
+        _ => "Other".to_string(),
+    };
+    Some((protocol, bytes))
+}
+
+/// Runs `tcpdump` in a background thread and accumulates per-protocol counters.
+pub struct CaptureCollector {
+    counters: Arc<Mutex<HashMap<String, (u64, u64)>>>,
+}
+
+impl CaptureCollector {
+    /// Starts capturing on all interfaces. Returns an error if `tcpdump` can't be spawned.
+    pub fn start() -> Result<Self, String> {
+        let mut child = crate::sandbox::command("tcpdump")
+            .args(["-i", "any", "-nn", "-q", "-t", "-l"])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Failed to start tcpdump: {}", e))?;
+
+        let counters = Arc::new(Mutex::new(HashMap::new()));
+        let thread_counters = counters.clone();
+        let stdout = child.stdout.take().ok_or("tcpdump has no stdout")?;
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if let Some((protocol, bytes)) = parse_tcpdump_line(&line) {
+                    if let Ok(mut map) = thread_counters.lock() {
+                        let entry: &mut (u64, u64) = map.entry(protocol).or_default();
+                        entry.0 += bytes;
+                        entry.1 += 1;
+                    }
+                }
+            }
+            let _ = child.wait();
+        });
+
+        Ok(Self { counters })
+    }
+
+    /// Returns the traffic seen since the previous call, largest first, and resets the counters.
+    pub fn take(&self) -> Vec<ProtocolStats> {
+        let drained: HashMap<String, (u64, u64)> = match self.counters.lock() {
+            Ok(mut map) => std::mem::take(&mut *map),
+            Err(_) => return Vec::new(),
+        };
+
+        let mut stats: Vec<ProtocolStats> = drained
+            .into_iter()
+            .map(|(protocol, (bytes, packets))| ProtocolStats {
+                protocol,
+                bytes,
+                packets,
+            })
+            .collect();
+        stats.sort_by_key(|s| std::cmp::Reverse(s.bytes));
+        stats
+    }
+}
@@ -0,0 +1,236 @@
+//! # Certificate Expiry Module
+//!
+//! This module watches TLS certificates for approaching expiry: local certificate files
+//! (PEM) and `host:port` endpoints, whose certificate is fetched with a TLS handshake.
+//! Both are read with the `openssl` command line tool in a background thread, a few
+//! times a day; `SystemMonitor` turns certificates close to expiry into alerts.
+
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Seconds between two checks of all certificates.
+const CERT_POLL_INTERVAL_SECS: u64 = 6 * 3600;
+/// Longest wait for an endpoint's TLS handshake.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Port used for endpoints given without one.
+const DEFAULT_PORT: u16 = 443;
+/// Days before expiry below which the alert becomes critical.
+pub const CERT_CRITICAL_DAYS: i64 = 3;
+
+/// Latest check of one certificate.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CertStatus {
+    /// File path or `host:port`, as configured.
+    pub target: String,
+    /// Subject of the certificate, e.g. "CN = example.com".
+    pub subject: String,
+    /// Expiry time in seconds since the UNIX epoch; `None` until checked or on error.
+    pub not_after: Option<i64>,
+    /// Why the certificate could not be read.
+    pub error: String,
+}
+
+impl CertStatus {
+    /// Whole days until expiry (negative once expired).
+    pub fn days_left(&self) -> Option<i64> {
+        let now = crate::history::now() as i64;
+        self.not_after.map(|t| (t - now).div_euclid(86400))
+    }
+
+    /// e.g. "example.com:443: expires in 40 days" or the error.
+    pub fn describe(&self) -> String {
+        match self.days_left() {
+            Some(days) if days < 0 => format!("{}: expired {} days ago", self.target, -days),
+            Some(days) => format!("{}: expires in {} days", self.target, days),
+            None if self.error.is_empty() => format!("{}: checking...", self.target),
+            None => format!("{}: {}", self.target, self.error),
+        }
+    }
+}
+
+/// Days since the UNIX epoch of a civil date (Howard Hinnant's days-from-civil).
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let m = month as i64;
+    let doy = (153 * (if m > 2 { m - 3 } else { m + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Parses an OpenSSL date such as "Jan  1 00:00:00 2027 GMT" into UNIX seconds.
+fn parse_openssl_date(text: &str) -> Option<i64> {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let mut fields = text.split_whitespace();
+    let month_name = fields.next()?;
+    let month = MONTHS.iter().position(|m| *m == month_name)? as u32 + 1;
+    let day: u32 = fields.next()?.parse().ok()?;
+    let mut time = fields.next()?.split(':').map(|f| f.parse::<i64>().ok());
+    let (h, m, s) = (time.next()??, time.next()??, time.next()??);
+    let year: i64 = fields.next()?.parse().ok()?;
+    Some(days_from_civil(year, month, day) * 86400 + h * 3600 + m * 60 + s)
+}
+
+/// Reads `notAfter` and `subject` from `openssl x509` output into `status`.
+fn parse_x509_output(output: &str, status: &mut CertStatus) {
+    for line in output.lines() {
+        if let Some(date) = line.strip_prefix("notAfter=") {
+            status.not_after = parse_openssl_date(date);
+        } else if let Some(subject) = line.strip_prefix("subject=") {
+            status.subject = subject.trim().to_string();
+        }
+    }
+    if status.not_after.is_none() {
+        status.error = "No certificate found".to_string();
+    }
+}
+
+/// Runs `openssl x509` on PEM `input` (or on the file `path`).
+fn x509(path: Option<&str>, input: &[u8]) -> Result<String, String> {
+    let mut command = Command::new("openssl");
+    command.args(["x509", "-noout", "-enddate", "-subject"]);
+    if let Some(path) = path {
+        command.args(["-in", path]);
+    }
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(input);
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(stderr
+            .lines()
+            .next()
+            .unwrap_or("openssl failed")
+            .to_string());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Fetches the certificate an endpoint presents, as PEM.
+fn fetch_endpoint(host: &str, port: u16) -> Result<Vec<u8>, String> {
+    let mut child = Command::new("openssl")
+        .args([
+            "s_client",
+            "-connect",
+            &format!("{}:{}", host, port),
+            "-servername",
+            host,
+        ])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    let started = Instant::now();
+    while child.try_wait().map_err(|e| e.to_string())?.is_none() {
+        if started.elapsed() >= CONNECT_TIMEOUT {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err("Connection timed out".to_string());
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if output.stdout.is_empty() {
+        return Err("Connection failed".to_string());
+    }
+    Ok(output.stdout)
+}
+
+/// Whether `target` is a certificate file rather than an endpoint.
+fn is_file(target: &str) -> bool {
+    target.starts_with('/') || target.starts_with('~') || std::path::Path::new(target).exists()
+}
+
+fn check(target: &str) -> CertStatus {
+    let mut status = CertStatus {
+        target: target.to_string(),
+        ..Default::default()
+    };
+    let result = if is_file(target) {
+        let path = match target.strip_prefix("~/") {
+            Some(rest) => std::env::var("HOME").unwrap_or_default() + "/" + rest,
+            None => target.to_string(),
+        };
+        x509(Some(&path), &[])
+    } else {
+        let (host, port) = match target.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().unwrap_or(DEFAULT_PORT)),
+            None => (target, DEFAULT_PORT),
+        };
+        fetch_endpoint(host, port).and_then(|pem| x509(None, &pem))
+    };
+    match result {
+        Ok(output) => parse_x509_output(&output, &mut status),
+        Err(error) => status.error = error,
+    }
+    status
+}
+
+/// Checks the configured certificates in a background thread.
+pub struct CertTracker {
+    latest: Arc<Mutex<Vec<CertStatus>>>,
+    stop: Arc<AtomicBool>,
+}
+
+impl CertTracker {
+    /// Starts checking `targets` now and every `CERT_POLL_INTERVAL_SECS`.
+    pub fn start(targets: Vec<String>) -> Self {
+        let latest = Arc::new(Mutex::new(
+            targets
+                .iter()
+                .map(|t| CertStatus {
+                    target: t.clone(),
+                    ..Default::default()
+                })
+                .collect(),
+        ));
+        let stop = Arc::new(AtomicBool::new(false));
+        if !targets.is_empty() {
+            let thread_latest = latest.clone();
+            let thread_stop = stop.clone();
+            std::thread::spawn(move || {
+                while !thread_stop.load(Ordering::Relaxed) {
+                    let statuses: Vec<CertStatus> = targets.iter().map(|t| check(t)).collect();
+                    if let Ok(mut guard) = thread_latest.lock() {
+                        *guard = statuses;
+                    }
+                    // Sleep in short steps so a replaced tracker's thread ends promptly
+                    let next = Instant::now() + Duration::from_secs(CERT_POLL_INTERVAL_SECS);
+                    while Instant::now() < next && !thread_stop.load(Ordering::Relaxed) {
+                        std::thread::sleep(Duration::from_secs(1));
+                    }
+                }
+            });
+        }
+
+        Self { latest, stop }
+    }
+
+    pub fn get_data(&self) -> Vec<CertStatus> {
+        self.latest
+            .lock()
+            .map(|guard| guard.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for CertTracker {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,458 @@
+//! # Control Group Module
+//!
+//! This module tracks resource usage of the top-level systemd slices and scopes
+//! (`user.slice`, `system.slice`, `init.scope`, `machine.slice`, ...) using the
+//! unified cgroup v2 hierarchy mounted at `/sys/fs/cgroup`.
+//!
+//! CPU usage is derived from the `usage_usec` counter in `cpu.stat` and expressed as a
+//! percentage of total machine capacity; memory usage comes from `memory.current`.
+//! Both are kept in sliding-window history buffers like the rest of `SystemMonitor`.
+//!
+//! Below the top level, systemd units and containers (Docker, Podman, nspawn machines)
+//! are tracked too when they have a CPU quota (`cpu.max`) or memory limit (`memory.max`)
+//! configured, so their usage can be shown as a fraction of the limit, or when a scoped
+//! alert rule names them (see `scopedrules.rs`).
+//!
+//! It also attributes processes to Flatpak apps and Snaps by their systemd scope, since
+//! sandboxed apps spawn many confusingly-named helper processes.
+
+use crate::history::{History, HistoryLimits};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+/// Default mount point of the unified cgroup v2 hierarchy.
+pub const CGROUP_ROOT: &str = "/sys/fs/cgroup";
+/// How deep below the root to look for limited units and containers. Rootless Podman
+/// containers live at `user.slice/user-1000.slice/user@1000.service/user.slice/...`.
+const MAX_LIMITED_DEPTH: usize = 5;
+
+/// Configured resource limits of a cgroup.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct CgroupLimits {
+    /// CPU quota in cores (`cpu.max` quota / period), `None` when unlimited.
+    pub cpu_cores: Option<f32>,
+    /// Hard memory limit in bytes (`memory.max`), `None` when unlimited.
+    pub memory_bytes: Option<u64>,
+}
+
+impl CgroupLimits {
+    fn read(dir: &Path) -> Self {
+        // "max 100000" or "<quota> <period>"
+        let cpu_cores = std::fs::read_to_string(dir.join("cpu.max"))
+            .ok()
+            .and_then(|s| {
+                let mut fields = s.split_whitespace();
+                let quota: f32 = fields.next()?.parse().ok()?;
+                let period: f32 = fields.next()?.parse().ok()?;
+                (period > 0.0).then(|| quota / period)
+            });
+        let memory_bytes = std::fs::read_to_string(dir.join("memory.max"))
+            .ok()
+            .and_then(|s| s.trim().parse().ok());
+        Self {
+            cpu_cores,
+            memory_bytes,
+        }
+    }
+
+    pub fn is_limited(&self) -> bool {
+        self.cpu_cores.is_some() || self.memory_bytes.is_some()
+    }
+}
+
+/// A unit or container tracked because an alert rule targets it, limited or not.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CgroupTarget {
+    /// Unit name, with or without its suffix ("postgresql.service" or "postgresql").
+    Unit(String),
+    /// Docker or Podman container ID prefix, or nspawn machine name.
+    Container(String),
+}
+
+impl CgroupTarget {
+    /// Whether the cgroup directory `dir_name` (last path component) is the target.
+    pub fn matches(&self, dir_name: &str) -> bool {
+        match self {
+            CgroupTarget::Unit(unit) => {
+                dir_name == unit
+                    || dir_name
+                        .rsplit_once('.')
+                        .is_some_and(|(stem, _)| stem == unit)
+            }
+            CgroupTarget::Container(id) => {
+                let container = ["docker-", "libpod-"].iter().any(|prefix| {
+                    dir_name
+                        .strip_prefix(prefix)
+                        .and_then(|rest| rest.strip_suffix(".scope"))
+                        .is_some_and(|full_id| !id.is_empty() && full_id.starts_with(id.as_str()))
+                });
+                container || dir_name == format!("machine-{}.scope", id)
+            }
+        }
+    }
+}
+
+/// Holds data for a single slice/scope for external consumers
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CgroupData {
+    /// Path relative to the cgroup root, e.g. "system.slice/nginx.service".
+    pub name: String,
+    /// Short name for display, e.g. "nginx.service" or "docker-3f2a9c1b7d4e".
+    pub label: String,
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+    pub limits: CgroupLimits,
+    /// CPU usage as a percentage of the CPU quota.
+    pub cpu_of_limit: Option<f32>,
+    /// Memory usage as a percentage of the memory limit.
+    pub mem_of_limit: Option<f32>,
+    pub cpu_history: History,
+    /// Memory usage as a percentage of total system memory.
+    pub mem_history: History,
+}
+
+struct CgroupEntry {
+    name: String,
+    last_usage_usec: Option<u64>,
+    cpu_percent: f32,
+    memory_bytes: u64,
+    limits: CgroupLimits,
+    cpu_history: History,
+    mem_history: History,
+}
+
+/// Samples the top-level cgroups, and units and containers with limits, on every refresh
+/// and keeps their history.
+pub struct CgroupTracker {
+    root: PathBuf,
+    entries: Vec<CgroupEntry>,
+    last_sample: Option<Instant>,
+    cpu_count: usize,
+    /// Units and containers tracked regardless of limits.
+    targets: Vec<CgroupTarget>,
+}
+
+/// Display name of the cgroup at relative path `name`: its last component, with the
+/// 64-character container IDs of Docker and Podman scopes cut to 12.
+pub fn short_label(name: &str) -> String {
+    let last = name.rsplit('/').next().unwrap_or(name);
+    for prefix in ["docker-", "libpod-"] {
+        if let Some(id) = last
+            .strip_prefix(prefix)
+            .and_then(|rest| rest.strip_suffix(".scope"))
+        {
+            if id.len() > 12 && id.chars().all(|c| c.is_ascii_hexdigit()) {
+                return format!("{}{}", prefix, &id[..12]);
+            }
+        }
+    }
+    last.to_string()
+}
+
+fn is_unit_dir(name: &str) -> bool {
+    name.ends_with(".slice") || name.ends_with(".scope") || name.ends_with(".service")
+}
+
+impl CgroupTracker {
+    pub fn new() -> Self {
+        Self {
+            root: PathBuf::from(CGROUP_ROOT),
+            entries: Vec::new(),
+            last_sample: None,
+            cpu_count: 1,
+            targets: Vec::new(),
+        }
+    }
+
+    /// Sets the units and containers to track even without limits.
+    pub fn set_targets(&mut self, targets: Vec<CgroupTarget>) {
+        self.targets = targets;
+    }
+
+    /// Adds the units and containers below `dir` that have a CPU or memory limit or are
+    /// one of `targets`.
+    fn discover_limited(
+        root: &Path,
+        dir: &Path,
+        depth: usize,
+        targets: &[CgroupTarget],
+        names: &mut Vec<String>,
+    ) {
+        if depth > MAX_LIMITED_DEPTH {
+            return;
+        }
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if !is_unit_dir(&file_name) || !path.is_dir() {
+                continue;
+            }
+            let targeted = targets.iter().any(|t| t.matches(&file_name));
+            if depth > 1 && (targeted || CgroupLimits::read(&path).is_limited()) {
+                if let Ok(rel) = path.strip_prefix(root) {
+                    names.push(rel.to_string_lossy().to_string());
+                }
+            }
+            Self::discover_limited(root, &path, depth + 1, targets, names);
+        }
+    }
+
+    /// Lists the top-level `*.slice` and `*.scope` groups, plus the limited or targeted
+    /// units and containers below them, sorted by path.
+    /// Returns an empty list on cgroup v1 systems (no `cgroup.controllers` file).
+    fn discover(root: &Path, targets: &[CgroupTarget]) -> Vec<String> {
+        if !root.join("cgroup.controllers").exists() {
+            return Vec::new();
+        }
+
+        let mut names: Vec<String> = std::fs::read_dir(root)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .filter(|e| e.path().is_dir())
+                    .map(|e| e.file_name().to_string_lossy().to_string())
+                    .filter(|n| n.ends_with(".slice") || n.ends_with(".scope"))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self::discover_limited(root, root, 1, targets, &mut names);
+        names.sort();
+        names
+    }
+
+    /// Forgets the previous CPU time samples, so the next refresh only sets a new
+    /// baseline (e.g. after a suspend).
+    pub fn reset_baseline(&mut self) {
+        self.last_sample = None;
+        for entry in &mut self.entries {
+            entry.last_usage_usec = None;
+        }
+    }
+
+    /// Polls every tracked cgroup and appends one sample to each history buffer.
+    pub fn refresh(&mut self, cpu_count: usize, total_memory: u64, limits: HistoryLimits) {
+        let now = Instant::now();
+        let elapsed_usec = self
+            .last_sample
+            .map(|t| now.duration_since(t).as_micros() as f64)
+            .unwrap_or(0.0);
+        self.last_sample = Some(now);
+        self.cpu_count = cpu_count.max(1);
+
+        // Keep the tracked set in sync with slices appearing/disappearing
+        let names = Self::discover(&self.root, &self.targets);
+        self.entries.retain(|e| names.contains(&e.name));
+        for name in names {
+            if !self.entries.iter().any(|e| e.name == name) {
+                self.entries.push(CgroupEntry {
+                    name,
+                    last_usage_usec: None,
+                    cpu_percent: 0.0,
+                    memory_bytes: 0,
+                    limits: CgroupLimits::default(),
+                    cpu_history: History::with_limits(limits),
+                    mem_history: History::with_limits(limits),
+                });
+            }
+        }
+        self.entries.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let capacity_usec = elapsed_usec * cpu_count.max(1) as f64;
+        for entry in &mut self.entries {
+            let dir = self.root.join(&entry.name);
+            entry.limits = CgroupLimits::read(&dir);
+
+            let usage_usec = std::fs::read_to_string(dir.join("cpu.stat"))
+                .ok()
+                .and_then(|s| {
+                    s.lines()
+                        .find(|l| l.starts_with("usage_usec"))
+                        .and_then(|l| l.split_whitespace().nth(1))
+                        .and_then(|v| v.parse::<u64>().ok())
+                });
+
+            entry.cpu_percent = match (usage_usec, entry.last_usage_usec) {
+                (Some(cur), Some(prev)) if capacity_usec > 0.0 => {
+                    (cur.saturating_sub(prev) as f64 / capacity_usec * 100.0).min(100.0) as f32
+                }
+                _ => 0.0,
+            };
+            entry.last_usage_usec = usage_usec;
+
+            entry.memory_bytes = std::fs::read_to_string(dir.join("memory.current"))
+                .ok()
+                .and_then(|s| s.trim().parse::<u64>().ok())
+                .unwrap_or(0);
+            let mem_pct = if total_memory > 0 {
+                entry.memory_bytes as f32 / total_memory as f32 * 100.0
+            } else {
+                0.0
+            };
+
+            entry.cpu_history.push(entry.cpu_percent);
+            entry.mem_history.push(mem_pct);
+        }
+    }
+
+    /// Applies new `limits` to every history buffer.
+    pub fn resize_history(&mut self, limits: HistoryLimits) {
+        for entry in &mut self.entries {
+            entry.cpu_history.set_limits(limits);
+            entry.mem_history.set_limits(limits);
+        }
+    }
+
+    /// Number of history buffers, for the monitor's sample budget.
+    pub fn history_count(&self) -> usize {
+        self.entries.len() * 2
+    }
+
+    pub fn get_data(&self) -> Vec<CgroupData> {
+        self.entries
+            .iter()
+            .map(|e| CgroupData {
+                name: e.name.clone(),
+                label: short_label(&e.name),
+                cpu_percent: e.cpu_percent,
+                memory_bytes: e.memory_bytes,
+                limits: e.limits,
+                // `cpu_percent` is relative to all cores
+                cpu_of_limit: e
+                    .limits
+                    .cpu_cores
+                    .filter(|c| *c > 0.0)
+                    .map(|cores| e.cpu_percent / 100.0 * self.cpu_count as f32 / cores * 100.0),
+                mem_of_limit: e
+                    .limits
+                    .memory_bytes
+                    .filter(|l| *l > 0)
+                    .map(|limit| e.memory_bytes as f32 / limit as f32 * 100.0),
+                cpu_history: e.cpu_history.clone(),
+                mem_history: e.mem_history.clone(),
+            })
+            .collect()
+    }
+}
+
+impl Default for CgroupTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Sandboxing technology that spawned a process.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SandboxKind {
+    Flatpak,
+    Snap,
+}
+
+impl std::fmt::Display for SandboxKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SandboxKind::Flatpak => write!(f, "Flatpak"),
+            SandboxKind::Snap => write!(f, "Snap"),
+        }
+    }
+}
+
+/// Aggregated usage of all processes belonging to one Flatpak app or Snap.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SandboxAppData {
+    pub app_id: String,
+    pub kind: SandboxKind,
+    pub process_count: usize,
+    /// CPU usage as a percentage of total machine capacity.
+    pub cpu_percent: f32,
+    pub memory_bytes: u64,
+}
+
+/// Extracts the Flatpak app ID or Snap name from the contents of `/proc/<pid>/cgroup`.
+///
+/// systemd places sandboxed apps in transient scopes named
+/// `app-flatpak-<app id>-<n>.scope` and `snap.<name>.<app>-<uuid>.scope`.
+pub fn parse_sandbox_app(cgroup: &str) -> Option<(SandboxKind, String)> {
+    let path = cgroup.lines().find_map(|l| l.strip_prefix("0::"))?;
+    for segment in path.split('/').rev() {
+        if let Some(rest) = segment.strip_prefix("app-flatpak-") {
+            let rest = rest.strip_suffix(".scope").unwrap_or(rest);
+            // Drop the trailing "-<instance>" suffix
+            let app_id = match rest.rsplit_once('-') {
+                Some((id, instance)) if instance.chars().all(|c| c.is_ascii_digit()) => id,
+                _ => rest,
+            };
+            return Some((SandboxKind::Flatpak, app_id.to_string()));
+        }
+        if let Some(rest) = segment.strip_prefix("snap.") {
+            if let Some(name) = rest.split('.').next().filter(|n| !n.is_empty()) {
+                return Some((SandboxKind::Snap, name.to_string()));
+            }
+        }
+    }
+    None
+}
+
+/// Attributes processes to sandboxed apps, caching the (stable) cgroup lookup per PID.
+#[derive(Default)]
+pub struct SandboxAttribution {
+    cache: HashMap<u32, Option<(SandboxKind, String)>>,
+}
+
+impl SandboxAttribution {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Groups the given processes by sandboxed app and sums their usage.
+    /// The result is sorted by CPU usage, highest first.
+    pub fn collect(&mut self, system: &sysinfo::System) -> Vec<SandboxAppData> {
+        let processes = system.processes();
+        self.cache
+            .retain(|pid, _| processes.contains_key(&sysinfo::Pid::from_u32(*pid)));
+
+        let cpu_count = system.cpus().len().max(1) as f32;
+        let mut apps: Vec<SandboxAppData> = Vec::new();
+        for (pid, process) in processes {
+            // Threads show up as processes too; count only thread group leaders
+            if process.thread_kind().is_some() {
+                continue;
+            }
+
+            let attribution = self.cache.entry(pid.as_u32()).or_insert_with(|| {
+                std::fs::read_to_string(format!("/proc/{}/cgroup", pid.as_u32()))
+                    .ok()
+                    .and_then(|c| parse_sandbox_app(&c))
+            });
+
+            if let Some((kind, app_id)) = attribution {
+                let idx = match apps
+                    .iter()
+                    .position(|a| a.kind == *kind && a.app_id == *app_id)
+                {
+                    Some(i) => i,
+                    None => {
+                        apps.push(SandboxAppData {
+                            app_id: app_id.clone(),
+                            kind: *kind,
+                            process_count: 0,
+                            cpu_percent: 0.0,
+                            memory_bytes: 0,
+                        });
+                        apps.len() - 1
+                    }
+                };
+                apps[idx].process_count += 1;
+                apps[idx].cpu_percent += process.cpu_usage() / cpu_count;
+                apps[idx].memory_bytes += process.memory();
+            }
+        }
+
+        apps.sort_by(|a, b| b.cpu_percent.total_cmp(&a.cpu_percent));
+        apps
+    }
+}
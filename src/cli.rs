@@ -0,0 +1,597 @@
+//! # Command Line Module
+//!
+//! This module implements the non-GUI subcommands, which print data and exit:
+//!
+//! - `gjallarhorn metrics [--format=influx|zabbix|json] [--key=<zabbix key>]` collects one
+//!   `MetricsSnapshot` and prints it, for telegraf's `exec` input (`influx`) or Zabbix
+//!   (`zabbix` prints `zabbix_sender` input; `--key` prints a single value for a
+//!   `UserParameter`).
+//! - `gjallarhorn info [--format=text|json]` prints a neofetch-style summary of the
+//!   machine (OS, kernel, CPU, GPU, memory, disks, uptime) from `get_static_info()`.
+//! - `gjallarhorn top [--interval=2s]` redraws a table of CPU, memory, GPU and network
+//!   usage in the terminal until interrupted.
+//! - `gjallarhorn stream [--interval=1s] [--format=json|influx]` prints a snapshot every
+//!   interval until interrupted: one compact JSON object per line (for `jq` and scripts),
+//!   or the InfluxDB lines of each snapshot.
+//! - `gjallarhorn --headless [--format=table|json] [--interval=<rate>]` runs the monitor
+//!   without the window at the configured refresh rate, with the settings file's
+//!   exporters (webhook, CSV log, Prometheus, gRPC, overlay, syslog, notifications), and
+//!   prints each refresh as the `top` table or one JSON line, for servers without a
+//!   display.
+//! - `gjallarhorn query --metric=<series id> [--from="2h ago"] [--to=now] [--format=csv|json]`
+//!   prints the per-minute statistics of one series from the long-term history database
+//!   (see `longterm.rs`); without `--metric` it lists the stored series.
+//!
+//! Subcommands never start the privileged worker, so they don't trigger a `pkexec` prompt.
+
+use crate::csvlog::format_timestamp;
+use crate::longterm::{parse_time, LongTermStore};
+use crate::markers::now_secs;
+use crate::monitor::SystemMonitor;
+use crate::settings::AppSettings;
+use crate::share::format_rate;
+use crate::snapshot::MetricsSnapshot;
+use std::io::Write;
+use std::time::Duration;
+
+/// Time between the two samples needed for CPU usage and network rates.
+const SAMPLE_INTERVAL_MS: u64 = 1000;
+
+/// Output formats of the `metrics` subcommand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricsFormat {
+    Influx,
+    Zabbix,
+    Json,
+}
+
+/// Returns the value of `--name=value` or `--name value` in `args`.
+fn flag_value<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    let prefix = format!("--{}=", name);
+    args.iter().enumerate().find_map(|(i, arg)| {
+        if let Some(value) = arg.strip_prefix(&prefix) {
+            Some(value)
+        } else if *arg == format!("--{}", name) {
+            args.get(i + 1).map(String::as_str)
+        } else {
+            None
+        }
+    })
+}
+
+/// Collects a snapshot from two samples taken `SAMPLE_INTERVAL_MS` apart.
+pub fn collect_snapshot() -> MetricsSnapshot {
+    let settings = AppSettings {
+        refresh_rate_ms: SAMPLE_INTERVAL_MS,
+        ..AppSettings::default()
+    };
+    let mut monitor = SystemMonitor::new_headless(&settings);
+    std::thread::sleep(std::time::Duration::from_millis(SAMPLE_INTERVAL_MS));
+    monitor.refresh();
+    monitor.snapshot()
+}
+
+/// Parses an interval such as "2s", "500ms" or "1.5" (seconds).
+fn parse_interval(value: &str) -> Result<Duration, String> {
+    let (number, scale) = if let Some(ms) = value.strip_suffix("ms") {
+        (ms, 0.001)
+    } else if let Some(s) = value.strip_suffix('s') {
+        (s, 1.0)
+    } else {
+        (value, 1.0)
+    };
+    match number.trim().parse::<f64>() {
+        Ok(n) if n * scale >= 0.1 => Ok(Duration::from_secs_f64(n * scale)),
+        Ok(_) => Err(format!("Interval '{}' is below 100ms", value)),
+        Err(_) => Err(format!("Invalid interval '{}' (e.g. 2s or 500ms)", value)),
+    }
+}
+
+/// Escapes a tag key/value in the InfluxDB line protocol.
+fn influx_tag(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(',', "\\,")
+        .replace('=', "\\=")
+        .replace(' ', "\\ ")
+}
+
+/// Formats `snapshot` in the InfluxDB line protocol (nanosecond timestamps).
+pub fn format_influx(snapshot: &MetricsSnapshot) -> String {
+    let host = influx_tag(&snapshot.hostname);
+    let ts = snapshot.timestamp as u128 * 1_000_000_000;
+    let mut lines = vec![
+        format!(
+            "cpu,host={} usage_percent={} {}",
+            host, snapshot.cpu.usage_percent, ts
+        ),
+        format!(
+            "memory,host={} used_gb={},total_gb={} {}",
+            host, snapshot.memory.used_gb, snapshot.memory.total_gb, ts
+        ),
+        format!(
+            "system,host={} uptime_secs={}i,active_alerts={}i {}",
+            host,
+            snapshot.uptime_secs,
+            snapshot.alerts.len(),
+            ts
+        ),
+    ];
+    for (i, usage) in snapshot.cpu.cores.iter().enumerate() {
+        lines.push(format!(
+            "cpu_core,host={},core={} usage_percent={} {}",
+            host, i, usage, ts
+        ));
+    }
+    for (i, gpu) in snapshot.gpus.iter().enumerate() {
+        lines.push(format!(
+            "gpu,host={},index={},name={} util_percent={},mem_used_mb={},mem_total_mb={} {}",
+            host,
+            i,
+            influx_tag(&gpu.name),
+            gpu.util_percent,
+            gpu.mem_used_mb,
+            gpu.mem_total_mb,
+            ts
+        ));
+    }
+    for net in &snapshot.networks {
+        lines.push(format!(
+            "net,host={},interface={} rx_bytes_per_sec={},tx_bytes_per_sec={},total_rx_bytes={}i,total_tx_bytes={}i {}",
+            host,
+            influx_tag(&net.interface),
+            net.rx_bytes_per_sec,
+            net.tx_bytes_per_sec,
+            net.total_rx_bytes,
+            net.total_tx_bytes,
+            ts
+        ));
+    }
+    for disk in &snapshot.disks {
+        lines.push(format!(
+            "disk,host={},mount_point={} total_bytes={}i,available_bytes={}i {}",
+            host,
+            influx_tag(&disk.mount_point),
+            disk.total_bytes,
+            disk.available_bytes,
+            ts
+        ));
+    }
+    for fan in &snapshot.fans {
+        let temperature = fan
+            .temperature
+            .map(|t| format!(",temperature={}", t))
+            .unwrap_or_default();
+        lines.push(format!(
+            "fan,host={},label={} rpm={}i{} {}",
+            host,
+            influx_tag(&fan.label),
+            fan.rpm,
+            temperature,
+            ts
+        ));
+    }
+    for metric in &snapshot.derived {
+        lines.push(format!(
+            "derived,host={},name={} value={} {}",
+            host,
+            influx_tag(&metric.name),
+            metric.value,
+            ts
+        ));
+    }
+    lines.join("\n")
+}
+
+/// Quotes a Zabbix item key parameter when it contains special characters.
+fn zabbix_param(value: &str) -> String {
+    if value.contains([',', '[', ']', '"', ' ']) {
+        format!("\"{}\"", value.replace('"', "\\\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Quotes a `zabbix_sender` input field that contains spaces or quotes.
+fn sender_field(value: &str) -> String {
+    if value.contains([' ', '"']) {
+        format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Flattens `snapshot` into Zabbix item keys and values.
+pub fn zabbix_items(snapshot: &MetricsSnapshot) -> Vec<(String, String)> {
+    let mut items = vec![
+        (
+            "gjallarhorn.cpu.usage".to_string(),
+            format!("{:.1}", snapshot.cpu.usage_percent),
+        ),
+        (
+            "gjallarhorn.memory.used_gb".to_string(),
+            format!("{:.2}", snapshot.memory.used_gb),
+        ),
+        (
+            "gjallarhorn.memory.total_gb".to_string(),
+            format!("{:.2}", snapshot.memory.total_gb),
+        ),
+        (
+            "gjallarhorn.uptime".to_string(),
+            snapshot.uptime_secs.to_string(),
+        ),
+        (
+            "gjallarhorn.alerts.active".to_string(),
+            snapshot.alerts.len().to_string(),
+        ),
+    ];
+    for (i, usage) in snapshot.cpu.cores.iter().enumerate() {
+        items.push((
+            format!("gjallarhorn.cpu.core[{}]", i),
+            format!("{:.1}", usage),
+        ));
+    }
+    for (i, gpu) in snapshot.gpus.iter().enumerate() {
+        items.push((
+            format!("gjallarhorn.gpu.util[{}]", i),
+            format!("{:.1}", gpu.util_percent),
+        ));
+        items.push((
+            format!("gjallarhorn.gpu.mem_used_mb[{}]", i),
+            format!("{:.0}", gpu.mem_used_mb),
+        ));
+    }
+    for net in &snapshot.networks {
+        let param = zabbix_param(&net.interface);
+        items.push((
+            format!("gjallarhorn.net.rx[{}]", param),
+            format!("{:.0}", net.rx_bytes_per_sec),
+        ));
+        items.push((
+            format!("gjallarhorn.net.tx[{}]", param),
+            format!("{:.0}", net.tx_bytes_per_sec),
+        ));
+    }
+    for disk in &snapshot.disks {
+        let used = disk.total_bytes.saturating_sub(disk.available_bytes);
+        let percent = if disk.total_bytes > 0 {
+            used as f64 / disk.total_bytes as f64 * 100.0
+        } else {
+            0.0
+        };
+        items.push((
+            format!(
+                "gjallarhorn.disk.used_percent[{}]",
+                zabbix_param(&disk.mount_point)
+            ),
+            format!("{:.1}", percent),
+        ));
+    }
+    for fan in &snapshot.fans {
+        items.push((
+            format!("gjallarhorn.fan.rpm[{}]", zabbix_param(&fan.label)),
+            fan.rpm.to_string(),
+        ));
+    }
+    for metric in &snapshot.derived {
+        items.push((
+            format!("gjallarhorn.derived[{}]", zabbix_param(&metric.name)),
+            format!("{:.2}", metric.value),
+        ));
+    }
+    items
+}
+
+/// Runs `gjallarhorn info <args>` and returns the text to print. Needs a single sample,
+/// so it returns without waiting for `SAMPLE_INTERVAL_MS`.
+pub fn info(args: &[String]) -> Result<String, String> {
+    let json = match flag_value(args, "format").unwrap_or("text") {
+        "text" => false,
+        "json" => true,
+        other => {
+            return Err(format!(
+                "Unknown format '{}' (expected text or json)",
+                other
+            ))
+        }
+    };
+
+    let monitor = SystemMonitor::new_headless(&AppSettings::default());
+    let static_info = monitor.get_static_info();
+    let uptime_secs = monitor.get_uptime();
+    if json {
+        let mut value = serde_json::to_value(&static_info).map_err(|e| e.to_string())?;
+        value["uptime_secs"] = uptime_secs.into();
+        return serde_json::to_string_pretty(&value).map_err(|e| e.to_string());
+    }
+
+    let mut lines = crate::share::format_system(&static_info, uptime_secs);
+    let (used_gb, total_gb) = monitor.get_memory_info();
+    if let Some(line) = lines.iter_mut().find(|l| l.starts_with("Memory: ")) {
+        *line = format!("Memory: {:.1} / {:.1} GB", used_gb, total_gb);
+    }
+    Ok(lines.join("\n"))
+}
+
+/// Usage bar such as "[#####...............]" for `percent` (0-100).
+fn usage_bar(percent: f32) -> String {
+    const WIDTH: usize = 20;
+    let filled = ((percent.clamp(0.0, 100.0) / 100.0) * WIDTH as f32).round() as usize;
+    format!("[{}{}]", "#".repeat(filled), ".".repeat(WIDTH - filled))
+}
+
+/// One screen of `gjallarhorn top`.
+pub fn format_top(snapshot: &MetricsSnapshot) -> String {
+    let mut lines = vec![
+        format!(
+            "{}  up {}  {}  (Ctrl+C to quit)",
+            snapshot.hostname,
+            crate::share::format_uptime(snapshot.uptime_secs),
+            crate::csvlog::format_timestamp(snapshot.timestamp)
+        ),
+        String::new(),
+        format!(
+            "CPU  {} {:5.1}%",
+            usage_bar(snapshot.cpu.usage_percent),
+            snapshot.cpu.usage_percent
+        ),
+    ];
+    for (row, cores) in snapshot.cpu.cores.chunks(8).enumerate() {
+        let cells: Vec<String> = cores.iter().map(|u| format!("{:5.1}", u)).collect();
+        lines.push(format!("  {:>3}: {}", row * 8, cells.join(" ")));
+    }
+    let memory_percent = if snapshot.memory.total_gb > 0.0 {
+        snapshot.memory.used_gb / snapshot.memory.total_gb * 100.0
+    } else {
+        0.0
+    };
+    lines.push(format!(
+        "MEM  {} {:5.1}%  {:.1} / {:.1} GB",
+        usage_bar(memory_percent),
+        memory_percent,
+        snapshot.memory.used_gb,
+        snapshot.memory.total_gb
+    ));
+
+    if !snapshot.gpus.is_empty() {
+        lines.push(String::new());
+        lines.push(format!(
+            "{:<3} {:<28} {:>6} {:>17} {:>6}",
+            "GPU", "NAME", "UTIL", "MEMORY", "TEMP"
+        ));
+        for (i, gpu) in snapshot.gpus.iter().enumerate() {
+            lines.push(format!(
+                "{:<3} {:<28} {:>5.0}% {:>17} {:>6}",
+                i,
+                gpu.name.chars().take(28).collect::<String>(),
+                gpu.util_percent,
+                format!(
+                    "{:.1} / {:.1} GB",
+                    gpu.mem_used_mb / 1024.0,
+                    gpu.mem_total_mb / 1024.0
+                ),
+                gpu.temperature
+                    .map(|t| format!("{:.0} °C", t))
+                    .unwrap_or_else(|| "-".to_string())
+            ));
+        }
+    }
+
+    if !snapshot.networks.is_empty() {
+        lines.push(String::new());
+        lines.push(format!("{:<16} {:>12} {:>12}", "INTERFACE", "RX", "TX"));
+        for net in &snapshot.networks {
+            lines.push(format!(
+                "{:<16} {:>12} {:>12}",
+                net.interface,
+                format_rate(net.rx_bytes_per_sec),
+                format_rate(net.tx_bytes_per_sec)
+            ));
+        }
+    }
+
+    if !snapshot.alerts.is_empty() {
+        lines.push(String::new());
+        for alert in &snapshot.alerts {
+            lines.push(format!("{}: {}", alert.severity, alert.message));
+        }
+    }
+    lines.join("\n")
+}
+
+/// Runs `gjallarhorn top <args>`: redraws `format_top` every interval until interrupted
+/// or stdout is closed.
+pub fn top(args: &[String]) -> Result<(), String> {
+    let interval = parse_interval(flag_value(args, "interval").unwrap_or("2s"))?;
+    let settings = AppSettings {
+        refresh_rate_ms: interval.as_millis() as u64,
+        ..AppSettings::default()
+    };
+    let mut monitor = SystemMonitor::new_headless(&settings);
+    let mut stdout = std::io::stdout();
+    loop {
+        std::thread::sleep(interval);
+        monitor.refresh();
+        let screen = format_top(&monitor.snapshot());
+        // Clear the screen and move the cursor home before each redraw
+        if writeln!(stdout, "\x1b[2J\x1b[H{}", screen)
+            .and_then(|()| stdout.flush())
+            .is_err()
+        {
+            return Ok(());
+        }
+    }
+}
+
+/// Runs `gjallarhorn stream <args>`: prints a snapshot every interval until interrupted
+/// or stdout is closed.
+pub fn stream(args: &[String]) -> Result<(), String> {
+    let format = match flag_value(args, "format").unwrap_or("json") {
+        "json" => MetricsFormat::Json,
+        "influx" => MetricsFormat::Influx,
+        other => {
+            return Err(format!(
+                "Unknown format '{}' (expected json or influx)",
+                other
+            ))
+        }
+    };
+    let interval = parse_interval(flag_value(args, "interval").unwrap_or("1s"))?;
+    let settings = AppSettings {
+        refresh_rate_ms: interval.as_millis() as u64,
+        ..AppSettings::default()
+    };
+    let mut monitor = SystemMonitor::new_headless(&settings);
+    let mut stdout = std::io::stdout();
+    loop {
+        std::thread::sleep(interval);
+        monitor.refresh();
+        let snapshot = monitor.snapshot();
+        let output = match format {
+            MetricsFormat::Influx => format_influx(&snapshot),
+            _ => serde_json::to_string(&snapshot).map_err(|e| e.to_string())?,
+        };
+        // Flushed per snapshot so consumers see each line as soon as it is sampled
+        if writeln!(stdout, "{}", output)
+            .and_then(|()| stdout.flush())
+            .is_err()
+        {
+            return Ok(());
+        }
+    }
+}
+
+/// Runs `gjallarhorn --headless <args>`: refreshes a monitor built from the settings file
+/// at its refresh rate and prints every refresh until interrupted or stdout is closed.
+pub fn headless(args: &[String]) -> Result<(), String> {
+    let json = match flag_value(args, "format").unwrap_or("table") {
+        "table" => false,
+        "json" => true,
+        other => {
+            return Err(format!(
+                "Unknown format '{}' (expected table or json)",
+                other
+            ))
+        }
+    };
+    let mut settings = AppSettings::load();
+    if let Some(interval) = flag_value(args, "interval") {
+        settings.refresh_rate_ms = parse_interval(interval)?.as_millis() as u64;
+    }
+    let interval = Duration::from_millis(settings.refresh_rate_ms);
+
+    let mut monitor = SystemMonitor::new_headless(&settings);
+    monitor.set_watched_processes(settings.watched_processes.clone());
+    monitor.set_prometheus(&settings.prometheus);
+    monitor.set_overlay(&settings.overlay);
+    monitor.set_grpc(&settings.grpc);
+    monitor.set_scripting(&settings.scripting);
+    monitor.set_notifications(&settings.notifications, &settings.webhook.url);
+    let mut stdout = std::io::stdout();
+    loop {
+        std::thread::sleep(interval);
+        monitor.refresh();
+        let snapshot = monitor.snapshot();
+        let output = if json {
+            serde_json::to_string(&snapshot).map_err(|e| e.to_string())?
+        } else {
+            // A blank line between tables keeps the log readable when redirected
+            format!("{}\n", format_top(&snapshot))
+        };
+        if writeln!(stdout, "{}", output)
+            .and_then(|()| stdout.flush())
+            .is_err()
+        {
+            return Ok(());
+        }
+    }
+}
+
+/// Runs `gjallarhorn metrics <args>` and returns the text to print.
+pub fn metrics(args: &[String]) -> Result<String, String> {
+    let format = match flag_value(args, "format").unwrap_or("influx") {
+        "influx" => MetricsFormat::Influx,
+        "zabbix" => MetricsFormat::Zabbix,
+        "json" => MetricsFormat::Json,
+        other => {
+            return Err(format!(
+                "Unknown format '{}' (expected influx, zabbix or json)",
+                other
+            ))
+        }
+    };
+    let key = flag_value(args, "key");
+
+    let snapshot = collect_snapshot();
+    match format {
+        MetricsFormat::Influx => Ok(format_influx(&snapshot)),
+        MetricsFormat::Json => serde_json::to_string_pretty(&snapshot).map_err(|e| e.to_string()),
+        MetricsFormat::Zabbix => {
+            let items = zabbix_items(&snapshot);
+            match key {
+                Some(key) => items
+                    .into_iter()
+                    .find(|(k, _)| k == key)
+                    .map(|(_, v)| v)
+                    .ok_or_else(|| format!("Unknown key '{}'", key)),
+                // zabbix_sender input: "<host> <key> <value>", "-" uses the agent's hostname
+                None => Ok(items
+                    .iter()
+                    .map(|(k, v)| format!("- {} {}", sender_field(k), v))
+                    .collect::<Vec<_>>()
+                    .join("\n")),
+            }
+        }
+    }
+}
+
+/// Runs `gjallarhorn query <args>` and returns the text to print: the stored minutes of
+/// one series as CSV (`timestamp,avg,min,max`, the start of each minute in UTC) or as a
+/// JSON array, or the stored series when no `--metric` is given.
+pub fn query(args: &[String]) -> Result<String, String> {
+    let json = match flag_value(args, "format").unwrap_or("csv") {
+        "csv" => false,
+        "json" => true,
+        other => return Err(format!("Unknown format '{}' (expected csv or json)", other)),
+    };
+    let store = LongTermStore::open_read_only()?;
+    let series = store.series();
+    let Some(metric) = flag_value(args, "metric") else {
+        return Ok(series
+            .iter()
+            .map(|s| format!("{}\t{}", s.id, s.label))
+            .collect::<Vec<_>>()
+            .join("\n"));
+    };
+    if !series.iter().any(|s| s.id == metric) {
+        return Err(format!(
+            "No long-term history for '{}' (run `gjallarhorn query` without --metric to list the series)",
+            metric
+        ));
+    }
+
+    let now = now_secs();
+    let from = parse_time(flag_value(args, "from").unwrap_or("24h ago"), now)?;
+    let to = parse_time(flag_value(args, "to").unwrap_or("now"), now)?;
+    if from >= to {
+        return Err("--from must be before --to".to_string());
+    }
+    let points = store.minutes(metric, from, to)?;
+
+    if json {
+        return serde_json::to_string_pretty(&points).map_err(|e| e.to_string());
+    }
+    let rows = points.iter().map(|p| {
+        format!(
+            "{},{},{},{}",
+            format_timestamp(p.time as u64 / 60 * 60),
+            p.avg,
+            p.min,
+            p.max
+        )
+    });
+    Ok(std::iter::once("timestamp,avg,min,max".to_string())
+        .chain(rows)
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
@@ -0,0 +1,105 @@
+//! # Background Collector Module
+//!
+//! This module runs `SystemMonitor::refresh()` on a dedicated thread, so slow collectors
+//! (many cores, NVML calls, spawned tools) never stall the UI thread.
+//!
+//! After every refresh the collector publishes a `MetricsSnapshot` on a channel. The UI
+//! timer drains the channel and only redraws when a new snapshot arrived; the monitor is
+//! shared behind a mutex, so the timer reads the histories between two refreshes. The
+//! timer only ever `try_lock`s it and redraws on a later tick while a refresh is running,
+//! and the collector pauses at least `MIN_SLEEP` between refreshes, so a refresh slower
+//! than the interval can't starve the UI.
+
+use crate::monitor::SystemMonitor;
+use crate::snapshot::MetricsSnapshot;
+use log::warn;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Mutex, MutexGuard, TryLockError};
+use std::time::{Duration, Instant};
+
+/// Interval at which the UI timer polls for new snapshots.
+pub const DRAIN_INTERVAL: Duration = Duration::from_millis(50);
+/// Shortest pause between two refreshes; longer than `DRAIN_INTERVAL`, so the UI timer
+/// gets the monitor at least once in between.
+const MIN_SLEEP: Duration = Duration::from_millis(120);
+
+/// Locks the shared monitor. A panic while it was held (a collector bug) leaves the
+/// histories usable, so the poisoning is logged and ignored.
+pub fn lock(monitor: &Mutex<SystemMonitor>) -> MutexGuard<'_, SystemMonitor> {
+    monitor.lock().unwrap_or_else(|poisoned| {
+        warn!("System monitor lock poisoned by a panic, continuing");
+        poisoned.into_inner()
+    })
+}
+
+/// Like `lock`, but returns `None` instead of waiting while the monitor is in use.
+pub fn try_lock(monitor: &Mutex<SystemMonitor>) -> Option<MutexGuard<'_, SystemMonitor>> {
+    match monitor.try_lock() {
+        Ok(guard) => Some(guard),
+        Err(TryLockError::WouldBlock) => None,
+        Err(TryLockError::Poisoned(poisoned)) => {
+            warn!("System monitor lock poisoned by a panic, continuing");
+            Some(poisoned.into_inner())
+        }
+    }
+}
+
+/// Runs the collection thread. The thread stops when the collector is dropped.
+pub struct Collector {
+    receiver: Receiver<MetricsSnapshot>,
+    interval_ms: Arc<AtomicU64>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Collector {
+    /// Starts refreshing `monitor` every `interval_ms` milliseconds.
+    pub fn spawn(monitor: Arc<Mutex<SystemMonitor>>, interval_ms: u64) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let interval_ms = Arc::new(AtomicU64::new(interval_ms.max(1)));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let thread_interval = interval_ms.clone();
+        let thread_stop = stop.clone();
+        std::thread::spawn(move || {
+            while !thread_stop.load(Ordering::Relaxed) {
+                let started = Instant::now();
+                let snapshot = {
+                    let mut monitor = lock(&monitor);
+                    monitor.refresh();
+                    monitor.snapshot()
+                };
+                if sender.send(snapshot).is_err() {
+                    break;
+                }
+
+                // Keep the cadence: time spent refreshing counts towards the interval
+                let interval = Duration::from_millis(thread_interval.load(Ordering::Relaxed));
+                std::thread::sleep(interval.saturating_sub(started.elapsed()).max(MIN_SLEEP));
+            }
+        });
+
+        Self {
+            receiver,
+            interval_ms,
+            stop,
+        }
+    }
+
+    /// Changes the refresh interval, effective after the current sleep.
+    pub fn set_interval(&self, ms: u64) {
+        self.interval_ms.store(ms.max(1), Ordering::Relaxed);
+    }
+
+    /// Returns the newest snapshot published since the last call, dropping older ones
+    /// (a busy UI thread skips frames instead of falling behind).
+    pub fn drain(&self) -> Option<MetricsSnapshot> {
+        self.receiver.try_iter().last()
+    }
+}
+
+impl Drop for Collector {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,307 @@
+//! # CSV Logger Module
+//!
+//! This module appends selected metrics from each `MetricsSnapshot` to daily CSV files
+//! in `<data dir>/csv/`, giving spreadsheet users long-term data without a database.
+//!
+//! Files are named `gjallarhorn-YYYY-MM-DD.csv` and rotate at midnight UTC. Because the
+//! set of columns can change (an interface or GPU appears), a file is only appended to
+//! while its header still matches; otherwise the next free `-N` suffix is used. Files
+//! older than the retention period are deleted on rotation.
+//!
+//! The `markers` column lists the labels of the timeline markers placed since the
+//! previous row, separated by "; ".
+
+use crate::settings::CsvSettings;
+use crate::snapshot::MetricsSnapshot;
+use directories::ProjectDirs;
+use log::warn;
+use std::fs::{self, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Metric groups that can be selected for logging.
+pub const CSV_METRICS: &[&str] = &[
+    "cpu", "memory", "gpu", "network", "disk", "fans", "derived", "markers",
+];
+
+/// Appends snapshots to daily-rotated CSV files.
+pub struct CsvLogger {
+    dir: PathBuf,
+    metrics: Vec<String>,
+    interval: Duration,
+    retention_days: u64,
+    last_write: Option<Instant>,
+    /// Newest marker seen so far, in seconds since the UNIX epoch.
+    last_marker: u64,
+    /// Labels of the markers placed since the last row.
+    pending_markers: Vec<String>,
+    /// File currently appended to, with the day (since the epoch) and header it was opened for.
+    current: Option<(u64, String, PathBuf)>,
+    /// Error of the last write; `None` after a successful write.
+    last_error: Option<String>,
+}
+
+/// Converts days since the UNIX epoch to a (year, month, day) civil date.
+fn civil_from_days(days: u64) -> (i64, u32, u32) {
+    // Howard Hinnant's days-from-civil inverse
+    let z = days as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Formats a UNIX timestamp as an ISO 8601 UTC date-time ("2024-05-01T13:37:00Z").
+pub fn format_timestamp(secs: u64) -> String {
+    let (y, m, d) = civil_from_days(secs / 86400);
+    let t = secs % 86400;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        y,
+        m,
+        d,
+        t / 3600,
+        (t % 3600) / 60,
+        t % 60
+    )
+}
+
+/// Escapes a field that may contain commas or quotes (interface/fan labels, markers).
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Flattens the selected metric groups of `snapshot` and the labels of the new `markers`
+/// into (column, value) pairs.
+fn columns(
+    snapshot: &MetricsSnapshot,
+    metrics: &[String],
+    markers: &[String],
+) -> Vec<(String, String)> {
+    let selected = |name: &str| metrics.iter().any(|m| m == name);
+    let mut cols = Vec::new();
+
+    if selected("cpu") {
+        cols.push((
+            "cpu_percent".to_string(),
+            format!("{:.1}", snapshot.cpu.usage_percent),
+        ));
+    }
+    if selected("memory") {
+        cols.push((
+            "memory_used_gb".to_string(),
+            format!("{:.2}", snapshot.memory.used_gb),
+        ));
+        cols.push((
+            "memory_total_gb".to_string(),
+            format!("{:.2}", snapshot.memory.total_gb),
+        ));
+    }
+    if selected("gpu") {
+        for (i, gpu) in snapshot.gpus.iter().enumerate() {
+            cols.push((
+                format!("gpu{}_percent", i),
+                format!("{:.1}", gpu.util_percent),
+            ));
+            cols.push((
+                format!("gpu{}_mem_used_mb", i),
+                format!("{:.0}", gpu.mem_used_mb),
+            ));
+        }
+    }
+    if selected("network") {
+        for net in &snapshot.networks {
+            cols.push((
+                format!("{}_rx_bytes_per_sec", net.interface),
+                format!("{:.0}", net.rx_bytes_per_sec),
+            ));
+            cols.push((
+                format!("{}_tx_bytes_per_sec", net.interface),
+                format!("{:.0}", net.tx_bytes_per_sec),
+            ));
+        }
+    }
+    if selected("disk") {
+        for disk in &snapshot.disks {
+            let used = disk.total_bytes.saturating_sub(disk.available_bytes);
+            let percent = if disk.total_bytes > 0 {
+                used as f64 / disk.total_bytes as f64 * 100.0
+            } else {
+                0.0
+            };
+            cols.push((
+                format!("{}_used_percent", disk.mount_point),
+                format!("{:.1}", percent),
+            ));
+        }
+    }
+    if selected("fans") {
+        for fan in &snapshot.fans {
+            cols.push((format!("{}_rpm", fan.label), fan.rpm.to_string()));
+        }
+    }
+    if selected("derived") {
+        for metric in &snapshot.derived {
+            cols.push((metric.name.clone(), format!("{:.2}", metric.value)));
+        }
+    }
+    if selected("markers") {
+        cols.push(("markers".to_string(), escape(&markers.join("; "))));
+    }
+    cols
+}
+
+impl CsvLogger {
+    /// Creates a logger for the metric groups, interval and retention in `settings`.
+    pub fn new(settings: &CsvSettings) -> Self {
+        let dir = ProjectDirs::from("com", "gjallarhorn", "gjallarhorn")
+            .map(|p| p.data_dir().join("csv"))
+            .unwrap_or_else(|| PathBuf::from("csv"));
+        Self {
+            dir,
+            metrics: settings.metrics.clone(),
+            interval: Duration::from_secs(settings.interval_secs.max(1)),
+            retention_days: settings.retention_days,
+            last_write: None,
+            last_marker: crate::markers::now_secs(),
+            pending_markers: Vec::new(),
+            current: None,
+            last_error: None,
+        }
+    }
+
+    /// The file currently written to, or the error of the last write.
+    pub fn status(&self) -> Result<String, String> {
+        if let Some(e) = &self.last_error {
+            return Err(e.clone());
+        }
+        Ok(match &self.current {
+            Some((_, _, path)) => format!("Writing {}", path.display()),
+            None => "Waiting for first row".to_string(),
+        })
+    }
+
+    /// Whether the interval has elapsed since the last row was written.
+    pub fn is_due(&self) -> bool {
+        self.last_write.is_none_or(|t| t.elapsed() >= self.interval)
+    }
+
+    /// Remembers the markers of `snapshot` placed since the previous call, for the next
+    /// row. Called on every refresh, so markers are kept even when rows are further apart
+    /// than the chart window.
+    pub fn collect_markers(&mut self, snapshot: &MetricsSnapshot) {
+        for marker in &snapshot.markers {
+            if marker.timestamp > self.last_marker {
+                self.pending_markers.push(marker.label.clone());
+            }
+        }
+        if let Some(newest) = snapshot.markers.iter().map(|m| m.timestamp).max() {
+            self.last_marker = self.last_marker.max(newest);
+        }
+    }
+
+    /// Picks the file for `day` whose header matches, or the first unused name.
+    fn file_for(&self, day: u64, header: &str) -> PathBuf {
+        let (y, m, d) = civil_from_days(day);
+        let base = format!("gjallarhorn-{:04}-{:02}-{:02}", y, m, d);
+        for n in 1.. {
+            let name = if n == 1 {
+                format!("{}.csv", base)
+            } else {
+                format!("{}-{}.csv", base, n)
+            };
+            let path = self.dir.join(name);
+            let first_line = fs::File::open(&path)
+                .ok()
+                .and_then(|f| BufReader::new(f).lines().next())
+                .and_then(Result::ok);
+            match first_line {
+                None => return path,
+                Some(line) if line == header => return path,
+                Some(_) => continue,
+            }
+        }
+        unreachable!()
+    }
+
+    /// Deletes CSV files older than the retention period.
+    fn prune(&self) {
+        if self.retention_days == 0 {
+            return;
+        }
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+        let max_age = Duration::from_secs(self.retention_days * 86400);
+        for entry in entries.flatten() {
+            let is_csv = entry.path().extension().is_some_and(|e| e == "csv");
+            let age = entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|t| t.elapsed().ok());
+            if is_csv && age.is_some_and(|a| a > max_age) {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    }
+
+    /// Appends one row for `snapshot`, rotating the file when the day or columns change.
+    pub fn write(&mut self, snapshot: &MetricsSnapshot) {
+        self.last_write = Some(Instant::now());
+        let markers = std::mem::take(&mut self.pending_markers);
+        let cols = columns(snapshot, &self.metrics, &markers);
+        let header = std::iter::once("timestamp".to_string())
+            .chain(cols.iter().map(|(name, _)| escape(name)))
+            .collect::<Vec<_>>()
+            .join(",");
+        let row = std::iter::once(format_timestamp(snapshot.timestamp))
+            .chain(cols.into_iter().map(|(_, value)| value))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let day = snapshot.timestamp / 86400;
+        let path = match &self.current {
+            Some((d, h, path)) if *d == day && *h == header => path.clone(),
+            _ => {
+                if let Err(e) = fs::create_dir_all(&self.dir) {
+                    let message = format!("Failed to create {}: {}", self.dir.display(), e);
+                    warn!("{}", message);
+                    self.last_error = Some(message);
+                    return;
+                }
+                self.prune();
+                let path = self.file_for(day, &header);
+                self.current = Some((day, header.clone(), path.clone()));
+                path
+            }
+        };
+
+        let is_new = !path.exists();
+        let result = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| {
+                if is_new {
+                    writeln!(file, "{}", header)?;
+                }
+                writeln!(file, "{}", row)
+            });
+        self.last_error = result.err().map(|e| {
+            let message = format!("Failed to write {}: {}", path.display(), e);
+            warn!("{}", message);
+            message
+        });
+    }
+}
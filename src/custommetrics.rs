@@ -0,0 +1,215 @@
+//! # Custom Metrics Module
+//!
+//! This module charts user-defined metrics: each `custom_metrics` entry names a shell
+//! command that is run every few seconds, and the first number it prints becomes the
+//! value, e.g. `cat /sys/class/power_supply/BAT0/power_now` or
+//! `nvidia-smi --query-gpu=fan.speed --format=csv,noheader`. This covers niche sensors
+//! and tools without code changes.
+//!
+//! Every metric runs its command in its own background thread, so a slow command only
+//! delays its own chart. A command still running after its interval is killed, so a hung
+//! tool can't keep its thread alive; its output is read while it runs, so a command that
+//! prints a lot before its number doesn't stall. A new reading is added to the history when it
+//! arrives; failed runs keep the error for display.
+
+use crate::history::{History, HistoryLimits};
+use crate::settings::CustomMetric;
+use crate::shell;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Latest run of one metric's command.
+#[derive(Debug, Clone, Default)]
+struct Reading {
+    value: Option<f32>,
+    /// Time of the reading (`history::now()`); 0 until the command ran once.
+    time: f64,
+    error: String,
+}
+
+/// e.g. "12.5 MB/s": up to two decimals, without trailing zeros.
+pub fn format_value(value: f32, unit: &str) -> String {
+    let value = format!("{:.2}", value);
+    let value = value.trim_end_matches('0').trim_end_matches('.');
+    format!("{} {}", value, unit).trim_end().to_string()
+}
+
+/// Holds the history of one custom metric for external consumers
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CustomMetricData {
+    pub name: String,
+    pub unit: String,
+    /// Latest value; `None` until the command printed a number, or if its last run failed.
+    pub value: Option<f32>,
+    /// Why the last run failed; empty on success.
+    pub error: String,
+    pub history: History,
+    /// The configured maximum, or the highest value in the history.
+    pub scale: f32,
+}
+
+impl CustomMetricData {
+    /// e.g. "Fan speed: 1450 RPM", "Fan speed: waiting..." or the error.
+    pub fn describe(&self) -> String {
+        match self.value {
+            Some(value) => format!("{}: {}", self.name, format_value(value, &self.unit)),
+            None if self.error.is_empty() => format!("{}: waiting...", self.name),
+            None => format!("{}: {}", self.name, self.error),
+        }
+    }
+}
+
+/// The first number in `text`, e.g. 45.5 in "temp1: +45.5°C". A leading `+` or bracket
+/// and a trailing unit are ignored.
+fn parse_number(text: &str) -> Option<f32> {
+    text.split(|c: char| c.is_whitespace() || c == ',' || c == ';' || c == '=')
+        .map(|token| {
+            token
+                .trim_start_matches(['+', '(', '[', '"', '\''])
+                .trim_end_matches(|c: char| !c.is_ascii_digit())
+        })
+        .find_map(|token| token.parse::<f32>().ok().filter(|v| v.is_finite()))
+}
+
+/// Runs `command` (see `shell::run`) and parses the first number it prints. The command
+/// (and everything it started) is killed after `timeout`, or once `stop` is set.
+fn run_command(command: &str, timeout: Duration, stop: &AtomicBool) -> Result<f32, String> {
+    let stdout = shell::run(command, timeout, stop)?;
+    parse_number(&stdout).ok_or_else(|| "Command printed no number".to_string())
+}
+
+struct MetricEntry {
+    metric: CustomMetric,
+    history: History,
+    /// Time of the last reading added to `history`.
+    recorded: f64,
+}
+
+/// Runs the configured commands in background threads and keeps their history.
+/// The threads stop when the tracker is dropped.
+pub struct CustomMetricTracker {
+    /// Latest reading per metric, in configuration order, written by the threads.
+    latest: Arc<Mutex<Vec<Reading>>>,
+    stop: Arc<AtomicBool>,
+    entries: Vec<MetricEntry>,
+}
+
+impl CustomMetricTracker {
+    /// Starts running the command of every metric in `metrics` now and every
+    /// `interval_secs` (at least one second). Entries without a command are skipped.
+    pub fn start(metrics: &[CustomMetric], limits: HistoryLimits) -> Self {
+        let metrics: Vec<CustomMetric> = metrics
+            .iter()
+            .filter(|m| !m.command.trim().is_empty())
+            .cloned()
+            .collect();
+        let latest = Arc::new(Mutex::new(vec![Reading::default(); metrics.len()]));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        for (index, metric) in metrics.iter().enumerate() {
+            let command = metric.command.clone();
+            let interval = Duration::from_secs(metric.interval_secs.max(1));
+            let thread_latest = latest.clone();
+            let thread_stop = stop.clone();
+            std::thread::spawn(move || {
+                while !thread_stop.load(Ordering::Relaxed) {
+                    let next = Instant::now() + interval;
+                    let reading = match run_command(&command, interval, &thread_stop) {
+                        Ok(value) => Reading {
+                            value: Some(value),
+                            time: crate::history::now(),
+                            error: String::new(),
+                        },
+                        Err(error) => Reading {
+                            value: None,
+                            time: crate::history::now(),
+                            error,
+                        },
+                    };
+                    if let Ok(mut guard) = thread_latest.lock() {
+                        guard[index] = reading;
+                    }
+                    // Sleep in short steps so a replaced tracker's thread ends promptly
+                    while Instant::now() < next && !thread_stop.load(Ordering::Relaxed) {
+                        std::thread::sleep(Duration::from_millis(250).min(interval));
+                    }
+                }
+            });
+        }
+
+        Self {
+            latest,
+            stop,
+            entries: metrics
+                .into_iter()
+                .map(|metric| MetricEntry {
+                    metric,
+                    history: History::with_limits(limits),
+                    recorded: 0.0,
+                })
+                .collect(),
+        }
+    }
+
+    /// The metrics being tracked.
+    pub fn metrics(&self) -> Vec<CustomMetric> {
+        self.entries.iter().map(|e| e.metric.clone()).collect()
+    }
+
+    /// Adds readings that arrived since the last call to the histories, at the time
+    /// they were taken.
+    pub fn refresh(&mut self) {
+        let latest = match self.latest.lock() {
+            Ok(guard) => guard.clone(),
+            Err(_) => return,
+        };
+        for (entry, reading) in self.entries.iter_mut().zip(latest) {
+            if reading.time > entry.recorded {
+                entry.recorded = reading.time;
+                if let Some(value) = reading.value {
+                    entry.history.push_at(reading.time, value);
+                }
+            }
+        }
+    }
+
+    /// Applies new `limits` to every history buffer.
+    pub fn resize_history(&mut self, limits: HistoryLimits) {
+        for entry in &mut self.entries {
+            entry.history.set_limits(limits);
+        }
+    }
+
+    /// Number of history buffers, for the monitor's sample budget.
+    pub fn history_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn get_data(&self) -> Vec<CustomMetricData> {
+        let latest = self.latest.lock().map(|g| g.clone()).unwrap_or_default();
+        self.entries
+            .iter()
+            .zip(latest)
+            .map(|(entry, reading)| CustomMetricData {
+                name: entry.metric.name.clone(),
+                unit: entry.metric.unit.clone(),
+                value: reading.value,
+                error: reading.error,
+                history: entry.history.clone(),
+                scale: entry
+                    .metric
+                    .max
+                    .filter(|m| *m > 0.0)
+                    .unwrap_or_else(|| entry.history.max_value().max(1.0)),
+            })
+            .collect()
+    }
+}
+
+impl Drop for CustomMetricTracker {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
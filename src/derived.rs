@@ -0,0 +1,356 @@
+//! # Derived Metrics Module
+//!
+//! This module computes the `derived_metrics` entries: series defined as arithmetic over
+//! the Overview series (see `pins.rs`), such as "total network" =
+//! `net.eth0.rx + net.wlan0.rx` or "hottest" = `max(gpu.0.temperature, fan.k10temp.temperature)`.
+//!
+//! Expressions support numbers, series ids, `+ - * /`, parentheses and the functions
+//! `min`, `max` and `abs`. Ids containing other characters than letters, digits, `_` and
+//! `.` (e.g. `disk./home.used`) are written in brackets: `[disk./home.used]`.
+//!
+//! Values are computed when the snapshot is captured and added to it, so derived metrics
+//! are pinned, charted and exported like the native series. A metric whose expression
+//! references a series missing from the snapshot (an interface that is down, a GPU that
+//! is asleep) is left out of that snapshot.
+
+use crate::pins;
+use crate::settings::DerivedMetric;
+use crate::snapshot::{DerivedSnapshot, MetricsSnapshot};
+use log::warn;
+use std::collections::HashMap;
+
+/// A parsed expression.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    Number(f32),
+    Series(String),
+    Negate(Box<Expression>),
+    /// Operator (`+`, `-`, `*` or `/`) and operands.
+    Binary(char, Box<Expression>, Box<Expression>),
+    /// `min`, `max` or `abs` and the arguments.
+    Call(String, Vec<Expression>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f32),
+    Name(String),
+    Operator(char),
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut number = String::new();
+            while let Some(&d) = chars.peek().filter(|d| d.is_ascii_digit() || **d == '.') {
+                number.push(d);
+                chars.next();
+            }
+            let value = number
+                .parse()
+                .map_err(|_| format!("Invalid number '{}'", number))?;
+            tokens.push(Token::Number(value));
+        } else if c.is_alphabetic() || c == '_' {
+            let mut name = String::new();
+            while let Some(&d) = chars
+                .peek()
+                .filter(|d| d.is_alphanumeric() || **d == '_' || **d == '.')
+            {
+                name.push(d);
+                chars.next();
+            }
+            tokens.push(Token::Name(name));
+        } else if c == '[' {
+            chars.next();
+            let name: String = chars.by_ref().take_while(|d| *d != ']').collect();
+            if name.trim().is_empty() {
+                return Err("Empty series id in brackets".to_string());
+            }
+            tokens.push(Token::Name(name.trim().to_string()));
+        } else if "+-*/(),".contains(c) {
+            tokens.push(Token::Operator(c));
+            chars.next();
+        } else {
+            return Err(format!("Unexpected '{}'", c));
+        }
+    }
+    Ok(tokens)
+}
+
+/// Recursive descent parser over the tokens of one expression.
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    /// Consumes `operator` if it is next.
+    fn accept(&mut self, operator: char) -> bool {
+        if self.peek() == Some(&Token::Operator(operator)) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, operator: char) -> Result<(), String> {
+        if self.accept(operator) {
+            Ok(())
+        } else {
+            Err(format!("Expected '{}'", operator))
+        }
+    }
+
+    /// `term (('+' | '-') term)*`
+    fn sum(&mut self) -> Result<Expression, String> {
+        let mut left = self.product()?;
+        loop {
+            let operator = match self.peek() {
+                Some(Token::Operator(op @ ('+' | '-'))) => *op,
+                _ => return Ok(left),
+            };
+            self.position += 1;
+            left = Expression::Binary(operator, Box::new(left), Box::new(self.product()?));
+        }
+    }
+
+    /// `factor (('*' | '/') factor)*`
+    fn product(&mut self) -> Result<Expression, String> {
+        let mut left = self.factor()?;
+        loop {
+            let operator = match self.peek() {
+                Some(Token::Operator(op @ ('*' | '/'))) => *op,
+                _ => return Ok(left),
+            };
+            self.position += 1;
+            left = Expression::Binary(operator, Box::new(left), Box::new(self.factor()?));
+        }
+    }
+
+    /// A number, series id, function call, negation or parenthesized expression.
+    fn factor(&mut self) -> Result<Expression, String> {
+        match self.next() {
+            Some(Token::Number(value)) => Ok(Expression::Number(value)),
+            Some(Token::Operator('-')) => Ok(Expression::Negate(Box::new(self.factor()?))),
+            Some(Token::Operator('(')) => {
+                let inner = self.sum()?;
+                self.expect(')')?;
+                Ok(inner)
+            }
+            Some(Token::Name(name)) if self.accept('(') => {
+                if !matches!(name.as_str(), "min" | "max" | "abs") {
+                    return Err(format!("Unknown function '{}'", name));
+                }
+                let mut arguments = vec![self.sum()?];
+                while self.accept(',') {
+                    arguments.push(self.sum()?);
+                }
+                self.expect(')')?;
+                if name == "abs" && arguments.len() != 1 {
+                    return Err("abs() takes one argument".to_string());
+                }
+                Ok(Expression::Call(name, arguments))
+            }
+            Some(Token::Name(name)) => Ok(Expression::Series(name)),
+            Some(Token::Operator(c)) => Err(format!("Unexpected '{}'", c)),
+            None => Err("Unexpected end of expression".to_string()),
+        }
+    }
+}
+
+impl Expression {
+    /// Parses `text`, e.g. `(net.eth0.rx + net.wlan0.rx) / 1048576`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut parser = Parser {
+            tokens: tokenize(text)?,
+            position: 0,
+        };
+        let expression = parser.sum()?;
+        match parser.peek() {
+            None => Ok(expression),
+            Some(Token::Operator(c)) => Err(format!("Unexpected '{}'", c)),
+            Some(_) => Err("Missing operator".to_string()),
+        }
+    }
+
+    /// The value with the series looked up in `values`; `None` if one is missing.
+    pub fn evaluate(&self, values: &HashMap<String, f32>) -> Option<f32> {
+        Some(match self {
+            Expression::Number(value) => *value,
+            Expression::Series(id) => *values.get(id)?,
+            Expression::Negate(inner) => -inner.evaluate(values)?,
+            Expression::Binary(operator, left, right) => {
+                let (left, right) = (left.evaluate(values)?, right.evaluate(values)?);
+                match operator {
+                    '+' => left + right,
+                    '-' => left - right,
+                    '*' => left * right,
+                    _ => left / right,
+                }
+            }
+            Expression::Call(name, arguments) => {
+                let values = arguments
+                    .iter()
+                    .map(|a| a.evaluate(values))
+                    .collect::<Option<Vec<f32>>>()?;
+                match name.as_str() {
+                    "min" => values.into_iter().reduce(f32::min)?,
+                    "max" => values.into_iter().reduce(f32::max)?,
+                    _ => values[0].abs(),
+                }
+            }
+        })
+    }
+}
+
+/// The configured derived metrics with their parsed expressions.
+#[derive(Default)]
+pub struct DerivedMetrics {
+    metrics: Vec<(DerivedMetric, Expression)>,
+}
+
+impl DerivedMetrics {
+    /// Parses every metric in `metrics`; entries that do not parse are logged and skipped.
+    pub fn new(metrics: &[DerivedMetric]) -> Self {
+        let metrics = metrics
+            .iter()
+            .filter(|m| !m.name.trim().is_empty())
+            .filter_map(|m| match Expression::parse(&m.expression) {
+                Ok(expression) => Some((m.clone(), expression)),
+                Err(e) => {
+                    warn!("Derived metric '{}': {}", m.name, e);
+                    None
+                }
+            })
+            .collect();
+        Self { metrics }
+    }
+
+    /// The values of the metrics in `snapshot`, in configuration order.
+    pub fn evaluate(&self, snapshot: &MetricsSnapshot) -> Vec<DerivedSnapshot> {
+        if self.metrics.is_empty() {
+            return Vec::new();
+        }
+        let values: HashMap<String, f32> = pins::snapshot_series(snapshot)
+            .into_iter()
+            .map(|(info, value)| (info.id, value))
+            .collect();
+        self.metrics
+            .iter()
+            .filter_map(|(metric, expression)| {
+                let value = expression.evaluate(&values).filter(|v| v.is_finite())?;
+                Some(DerivedSnapshot {
+                    name: metric.name.clone(),
+                    unit: metric.unit.clone(),
+                    value,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::{CpuSnapshot, MemorySnapshot};
+
+    fn eval(text: &str) -> Option<f32> {
+        let values = HashMap::from([
+            ("net.eth0.rx".to_string(), 300.0),
+            ("net.wlan0.rx".to_string(), 100.0),
+            ("disk./home.used".to_string(), 40.0),
+        ]);
+        Expression::parse(text).unwrap().evaluate(&values)
+    }
+
+    #[test]
+    fn products_bind_tighter_than_sums() {
+        assert_eq!(eval("1 + 2 * 3"), Some(7.0));
+        assert_eq!(eval("(1 + 2) * 3"), Some(9.0));
+        assert_eq!(eval("8 - 4 - 2"), Some(2.0));
+        assert_eq!(eval("8 / 4 / 2"), Some(1.0));
+    }
+
+    #[test]
+    fn unary_minus_applies_to_the_next_factor() {
+        assert_eq!(eval("-2 * 3"), Some(-6.0));
+        assert_eq!(eval("4 - -2"), Some(6.0));
+        assert_eq!(eval("-(1 + 2)"), Some(-3.0));
+    }
+
+    #[test]
+    fn series_ids_are_looked_up() {
+        assert_eq!(eval("net.eth0.rx + net.wlan0.rx"), Some(400.0));
+        assert_eq!(eval("[disk./home.used] / 4"), Some(10.0));
+        assert_eq!(eval("[ disk./home.used ]"), Some(40.0));
+    }
+
+    #[test]
+    fn functions_take_their_arguments() {
+        assert_eq!(eval("max(net.eth0.rx, net.wlan0.rx, 50)"), Some(300.0));
+        assert_eq!(eval("min(net.eth0.rx, net.wlan0.rx)"), Some(100.0));
+        assert_eq!(eval("abs(net.wlan0.rx - net.eth0.rx)"), Some(200.0));
+        assert!(Expression::parse("abs(1, 2)").is_err());
+        assert!(Expression::parse("sqrt(4)").is_err());
+    }
+
+    #[test]
+    fn missing_series_gives_no_value() {
+        assert_eq!(eval("net.eth1.rx + 1"), None);
+        assert_eq!(eval("max(1, net.eth1.rx)"), None);
+    }
+
+    #[test]
+    fn malformed_expressions_are_rejected() {
+        for text in ["", "1 +", "(1 + 2", "1 2", "1 + )", "[]", "2 $ 3", "1..2"] {
+            assert!(Expression::parse(text).is_err(), "{:?} parsed", text);
+        }
+    }
+
+    #[test]
+    fn metrics_skip_missing_series_and_division_by_zero() {
+        let metric = |name: &str, expression: &str| DerivedMetric {
+            name: name.to_string(),
+            expression: expression.to_string(),
+            unit: "%".to_string(),
+        };
+        let metrics = DerivedMetrics::new(&[
+            metric("free", "100 - memory"),
+            metric("ratio", "cpu / (memory - 25)"),
+            metric("gone", "gpu.0.util"),
+            metric("broken", "cpu +"),
+            metric("", "cpu"),
+        ]);
+        let snapshot = MetricsSnapshot {
+            cpu: CpuSnapshot {
+                usage_percent: 50.0,
+                cores: Vec::new(),
+            },
+            memory: MemorySnapshot {
+                used_gb: 4.0,
+                total_gb: 16.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let values = metrics.evaluate(&snapshot);
+        assert_eq!(values.len(), 1);
+        assert_eq!(values[0].name, "free");
+        assert_eq!(values[0].value, 75.0);
+    }
+}
@@ -0,0 +1,278 @@
+//! # Disk Latency Module
+//!
+//! This module derives per-drive request latency and queue depth from `/proc/diskstats`,
+//! the same figures `iostat -x` reports as `r_await`/`w_await` and `aqu-sz`. A drive that
+//! "feels slow" often moves little data while requests sit in its queue for a long time,
+//! which throughput graphs alone do not show, so both are kept: read and write MB/s
+//! (`rMB/s`/`wMB/s`) come from the same samples.
+//!
+//! Only whole drives (entries of `/sys/block`) are tracked; partitions share their drive's
+//! queue. Loop and RAM devices are skipped.
+
+use crate::history::{History, HistoryLimits};
+use crate::monitor::SysPaths;
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+/// Lower bound of the latency chart scale (in milliseconds).
+const MIN_AWAIT_SCALE_MS: f32 = 10.0;
+/// Lower bound of the queue depth chart scale.
+const MIN_QUEUE_SCALE: f32 = 1.0;
+/// Lower bound of the throughput chart scale (in MB/s).
+const MIN_IO_SCALE_MB_S: f32 = 1.0;
+const SECTOR_BYTES: u64 = 512;
+
+/// Latency history of one drive.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DiskLatencyData {
+    pub device: String,
+    /// Average time a request completed during the last interval took (queue wait plus
+    /// service), in milliseconds. `None` if no request completed.
+    pub await_ms: Option<f32>,
+    /// Average number of requests in flight during the last interval.
+    pub queue_depth: f32,
+    pub await_history: History,
+    pub queue_history: History,
+    /// Highest latency seen so far (at least 10 ms), used to scale the latency chart.
+    pub await_scale_ms: f32,
+    /// Highest queue depth seen so far (at least 1), used to scale the queue chart.
+    pub queue_scale: f32,
+    /// Bytes written since boot, or since install when the monitor's totals are set to.
+    #[serde(default)]
+    pub written_bytes: u64,
+}
+
+/// Throughput history of one drive.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DiskIoData {
+    pub device: String,
+    /// Read and written MB/s during the last interval.
+    pub read_mb_s: f32,
+    pub write_mb_s: f32,
+    pub read_history: History,
+    pub write_history: History,
+    /// Highest read or write rate seen so far (at least 1 MB/s), used to scale both
+    /// charts of the drive.
+    pub scale_mb_s: f32,
+}
+
+/// Cumulative counters of one `/proc/diskstats` line.
+#[derive(Debug, Clone, Copy)]
+struct DiskCounters {
+    /// Reads and writes completed.
+    ios: u64,
+    /// Milliseconds spent on reads and writes.
+    io_ms: u64,
+    /// Milliseconds spent doing I/O, weighted by the number of requests in flight.
+    weighted_ms: u64,
+    /// 512-byte sectors read.
+    sectors_read: u64,
+    /// 512-byte sectors written.
+    sectors_written: u64,
+}
+
+struct DiskEntry {
+    device: String,
+    last: DiskCounters,
+    await_ms: Option<f32>,
+    queue_depth: f32,
+    await_scale_ms: f32,
+    queue_scale: f32,
+    await_history: History,
+    queue_history: History,
+    read_mb_s: f32,
+    write_mb_s: f32,
+    io_scale_mb_s: f32,
+    read_history: History,
+    write_history: History,
+}
+
+/// Parses `/proc/diskstats` into `(device, counters)` pairs.
+fn parse_diskstats(content: &str) -> Vec<(String, DiskCounters)> {
+    content
+        .lines()
+        .filter_map(|line| {
+            // major minor name reads merged sectors read_ms writes merged sectors write_ms
+            //   in_flight io_ms weighted_ms [discard and flush fields on newer kernels]
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 14 {
+                return None;
+            }
+            let value = |i: usize| fields[i].parse::<u64>().ok();
+            Some((
+                fields[2].to_string(),
+                DiskCounters {
+                    ios: value(3)? + value(7)?,
+                    io_ms: value(6)? + value(10)?,
+                    weighted_ms: value(13)?,
+                    sectors_read: value(5)?,
+                    sectors_written: value(9)?,
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Whether `device` is a whole drive worth charting.
+fn is_tracked(paths: &SysPaths, device: &str) -> bool {
+    !device.starts_with("loop")
+        && !device.starts_with("ram")
+        && paths.sys.join("block").join(device).exists()
+}
+
+/// Samples `/proc/diskstats` on every refresh and keeps per-drive latency history.
+pub struct DiskLatencyTracker {
+    paths: SysPaths,
+    entries: Vec<DiskEntry>,
+    last_sample: Option<Instant>,
+}
+
+impl Default for DiskLatencyTracker {
+    fn default() -> Self {
+        Self::new(&SysPaths::default())
+    }
+}
+
+impl DiskLatencyTracker {
+    /// Reads `diskstats` and the drives under the roots of `paths`.
+    pub fn new(paths: &SysPaths) -> Self {
+        Self {
+            paths: paths.clone(),
+            entries: Vec::new(),
+            last_sample: None,
+        }
+    }
+
+    /// Reads `/proc/diskstats` and appends one sample per drive. Drives appear once they
+    /// have completed at least one request.
+    pub fn refresh(&mut self, limits: HistoryLimits) {
+        let Ok(content) = std::fs::read_to_string(self.paths.proc.join("diskstats")) else {
+            return;
+        };
+        let now = Instant::now();
+        let elapsed_ms = self
+            .last_sample
+            .map(|t| now.duration_since(t).as_secs_f32() * 1000.0);
+        self.last_sample = Some(now);
+
+        for (device, counters) in parse_diskstats(&content) {
+            let known = self.entries.iter().any(|e| e.device == device);
+            if !known {
+                if counters.ios == 0 || !is_tracked(&self.paths, &device) {
+                    continue;
+                }
+                // The first sample only sets the baseline
+                self.entries.push(DiskEntry {
+                    device,
+                    last: counters,
+                    await_ms: None,
+                    queue_depth: 0.0,
+                    await_scale_ms: MIN_AWAIT_SCALE_MS,
+                    queue_scale: MIN_QUEUE_SCALE,
+                    await_history: History::with_limits(limits),
+                    queue_history: History::with_limits(limits),
+                    read_mb_s: 0.0,
+                    write_mb_s: 0.0,
+                    io_scale_mb_s: MIN_IO_SCALE_MB_S,
+                    read_history: History::with_limits(limits),
+                    write_history: History::with_limits(limits),
+                });
+                continue;
+            }
+            let Some(entry) = self.entries.iter_mut().find(|e| e.device == device) else {
+                continue;
+            };
+
+            let ios = counters.ios.saturating_sub(entry.last.ios);
+            let io_ms = counters.io_ms.saturating_sub(entry.last.io_ms);
+            let weighted_ms = counters.weighted_ms.saturating_sub(entry.last.weighted_ms);
+            let read_bytes = counters
+                .sectors_read
+                .saturating_sub(entry.last.sectors_read)
+                * SECTOR_BYTES;
+            let written_bytes = counters
+                .sectors_written
+                .saturating_sub(entry.last.sectors_written)
+                * SECTOR_BYTES;
+            entry.last = counters;
+
+            // Without a previous sample time the deltas span an unknown period
+            (entry.await_ms, entry.queue_depth) = match elapsed_ms {
+                Some(ms) if ms > 0.0 => (
+                    (ios > 0).then(|| io_ms as f32 / ios as f32),
+                    weighted_ms as f32 / ms,
+                ),
+                _ => (None, 0.0),
+            };
+            (entry.read_mb_s, entry.write_mb_s) = match elapsed_ms {
+                Some(ms) if ms > 0.0 => {
+                    let mb_s = |bytes: u64| bytes as f32 / 1024.0 / 1024.0 / (ms / 1000.0);
+                    (mb_s(read_bytes), mb_s(written_bytes))
+                }
+                _ => (0.0, 0.0),
+            };
+            if let Some(await_ms) = entry.await_ms {
+                entry.await_scale_ms = entry.await_scale_ms.max(await_ms);
+            }
+            entry.queue_scale = entry.queue_scale.max(entry.queue_depth);
+            entry.io_scale_mb_s = entry
+                .io_scale_mb_s
+                .max(entry.read_mb_s)
+                .max(entry.write_mb_s);
+
+            entry.await_history.push(entry.await_ms.unwrap_or(0.0));
+            entry.queue_history.push(entry.queue_depth);
+            entry.read_history.push(entry.read_mb_s);
+            entry.write_history.push(entry.write_mb_s);
+        }
+    }
+
+    /// Makes the next refresh only set a new baseline (e.g. after a suspend).
+    pub fn reset_baseline(&mut self) {
+        self.last_sample = None;
+    }
+
+    pub fn resize_history(&mut self, limits: HistoryLimits) {
+        for entry in &mut self.entries {
+            entry.await_history.set_limits(limits);
+            entry.queue_history.set_limits(limits);
+            entry.read_history.set_limits(limits);
+            entry.write_history.set_limits(limits);
+        }
+    }
+
+    /// Number of history buffers, for the monitor's sample budget.
+    pub fn history_count(&self) -> usize {
+        self.entries.len() * 4
+    }
+
+    pub fn get_data(&self) -> Vec<DiskLatencyData> {
+        self.entries
+            .iter()
+            .map(|e| DiskLatencyData {
+                device: e.device.clone(),
+                await_ms: e.await_ms,
+                queue_depth: e.queue_depth,
+                await_history: e.await_history.clone(),
+                queue_history: e.queue_history.clone(),
+                await_scale_ms: e.await_scale_ms,
+                queue_scale: e.queue_scale,
+                written_bytes: e.last.sectors_written * SECTOR_BYTES,
+            })
+            .collect()
+    }
+
+    pub fn get_io_data(&self) -> Vec<DiskIoData> {
+        self.entries
+            .iter()
+            .map(|e| DiskIoData {
+                device: e.device.clone(),
+                read_mb_s: e.read_mb_s,
+                write_mb_s: e.write_mb_s,
+                read_history: e.read_history.clone(),
+                write_history: e.write_history.clone(),
+                scale_mb_s: e.io_scale_mb_s,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,291 @@
+//! # Display Session Module
+//!
+//! This module describes the graphical session for triaging graphics issues: session
+//! type (Wayland, X11 or a text console), display server, desktop and compositor, the
+//! OpenGL renderer and kernel DRM drivers, and the connected monitors.
+//!
+//! Everything comes from the environment, `/proc` and `/sys/class/drm`, plus `glxinfo`
+//! and `xrandr` when they are installed. Under Wayland, `xrandr` sees the outputs through
+//! XWayland, so connector names may differ from the compositor's. The information is
+//! collected at startup and on demand, not on every refresh tick.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::process::Command;
+
+/// Compositors and window managers recognized by process name (`/proc/<pid>/comm`).
+const COMPOSITORS: &[(&str, &str)] = &[
+    ("gnome-shell", "GNOME Shell (Mutter)"),
+    ("kwin_wayland", "KWin (Wayland)"),
+    ("kwin_x11", "KWin (X11)"),
+    ("cinnamon", "Cinnamon (Muffin)"),
+    ("cosmic-comp", "COSMIC"),
+    ("Hyprland", "Hyprland"),
+    ("sway", "Sway"),
+    ("niri", "niri"),
+    ("river", "river"),
+    ("wayfire", "Wayfire"),
+    ("labwc", "labwc"),
+    ("weston", "Weston"),
+    ("xfwm4", "Xfwm4"),
+    ("marco", "Marco"),
+    ("picom", "picom"),
+    ("compton", "compton"),
+    ("i3", "i3"),
+    ("openbox", "Openbox"),
+];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SessionInfo {
+    /// "Wayland", "X11" or "TTY".
+    pub session_type: String,
+    /// Display server and the display it is reached on, e.g. "Xorg (:0)".
+    pub display_server: String,
+    pub desktop: String,
+    pub compositor: String,
+    /// OpenGL renderer and version as reported by `glxinfo`.
+    pub renderer: String,
+    /// Kernel DRM driver per card, e.g. "card0: amdgpu (boot)".
+    pub drivers: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct MonitorInfo {
+    pub name: String,
+    /// Current mode, e.g. "2560x1440".
+    pub mode: String,
+    /// Current refresh rate, e.g. "143.91 Hz"; empty if unknown.
+    pub refresh: String,
+    /// Offset in the virtual screen, e.g. "+1920+0"; empty if unknown.
+    pub position: String,
+    pub primary: bool,
+}
+
+fn env(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.is_empty())
+}
+
+/// Names of all running processes.
+fn process_names() -> HashSet<String> {
+    let mut names = HashSet::new();
+    if let Ok(entries) = std::fs::read_dir("/proc") {
+        for entry in entries.flatten() {
+            if let Ok(comm) = std::fs::read_to_string(entry.path().join("comm")) {
+                names.insert(comm.trim().to_string());
+            }
+        }
+    }
+    names
+}
+
+fn session_type() -> String {
+    let kind = env("XDG_SESSION_TYPE").unwrap_or_default().to_lowercase();
+    match kind.as_str() {
+        "wayland" => "Wayland".to_string(),
+        "x11" => "X11".to_string(),
+        "tty" => "TTY".to_string(),
+        _ if env("WAYLAND_DISPLAY").is_some() => "Wayland".to_string(),
+        _ if env("DISPLAY").is_some() => "X11".to_string(),
+        _ => "TTY".to_string(),
+    }
+}
+
+/// OpenGL implementation reported by `glxinfo -B`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct GlInfo {
+    pub vendor: String,
+    pub renderer: String,
+    /// Core profile version when available, e.g. "4.6 (Core Profile) Mesa 24.0.5".
+    pub version: String,
+}
+
+/// Queries the OpenGL vendor, renderer and version. Returns `None` if `glxinfo` is not
+/// installed or no X display (or XWayland) is reachable.
+pub fn glx_info() -> Option<GlInfo> {
+    let output = Command::new("glxinfo").arg("-B").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&output.stdout);
+    let field = |key: &str| {
+        text.lines()
+            .find_map(|l| l.trim().strip_prefix(key))
+            .map(|v| v.trim().to_string())
+    };
+    Some(GlInfo {
+        vendor: field("OpenGL vendor string:").unwrap_or_default(),
+        renderer: field("OpenGL renderer string:")?,
+        version: field("OpenGL core profile version string:")
+            .or_else(|| field("OpenGL version string:"))
+            .unwrap_or_default(),
+    })
+}
+
+/// Kernel driver bound to each DRM card; the firmware's boot display is marked.
+fn drm_drivers() -> String {
+    let mut cards: Vec<String> = Vec::new();
+    if let Ok(entries) = std::fs::read_dir("/sys/class/drm") {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            // Connectors are named like card0-HDMI-A-1
+            if !name.starts_with("card") || name.contains('-') {
+                continue;
+            }
+            let device = entry.path().join("device");
+            let Some(driver) = std::fs::read_link(device.join("driver"))
+                .ok()
+                .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+            else {
+                continue;
+            };
+            let boot =
+                std::fs::read_to_string(device.join("boot_vga")).is_ok_and(|v| v.trim() == "1");
+            cards.push(format!(
+                "{}: {}{}",
+                name,
+                driver,
+                if boot { " (boot)" } else { "" }
+            ));
+        }
+    }
+    cards.sort();
+    if cards.is_empty() {
+        "Unknown".to_string()
+    } else {
+        cards.join(", ")
+    }
+}
+
+/// Describes the current graphical session.
+pub fn get_session_info() -> SessionInfo {
+    let processes = process_names();
+    let session_type = session_type();
+    let xwayland = processes.contains("Xwayland");
+
+    let display_server = match session_type.as_str() {
+        "Wayland" => format!(
+            "Wayland ({}){}",
+            env("WAYLAND_DISPLAY").unwrap_or_else(|| "wayland-0".to_string()),
+            if xwayland { " + XWayland" } else { "" }
+        ),
+        "X11" => {
+            let server = if processes.contains("Xorg") || processes.contains("X") {
+                "Xorg"
+            } else if xwayland {
+                "XWayland"
+            } else {
+                "X11"
+            };
+            format!("{} ({})", server, env("DISPLAY").unwrap_or_default())
+        }
+        _ => "None (text console)".to_string(),
+    };
+
+    let compositor = COMPOSITORS
+        .iter()
+        .filter(|(process, _)| processes.contains(*process))
+        .map(|(_, name)| *name)
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    SessionInfo {
+        session_type,
+        display_server,
+        desktop: env("XDG_CURRENT_DESKTOP")
+            .map(|d| d.replace(':', " / "))
+            .or_else(|| env("DESKTOP_SESSION"))
+            .unwrap_or_else(|| "Unknown".to_string()),
+        compositor: if compositor.is_empty() {
+            "Unknown".to_string()
+        } else {
+            compositor
+        },
+        renderer: glx_info()
+            .map(|gl| format!("{} (OpenGL {})", gl.renderer, gl.version))
+            .unwrap_or_else(|| "Unknown (requires glxinfo)".to_string()),
+        drivers: drm_drivers(),
+    }
+}
+
+/// Parses `xrandr --query` output into the connected, active outputs.
+fn parse_xrandr(text: &str) -> Vec<MonitorInfo> {
+    let mut monitors: Vec<MonitorInfo> = Vec::new();
+    let mut current: Option<usize> = None;
+
+    for line in text.lines() {
+        if !line.starts_with(' ') {
+            current = None;
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            if tokens.get(1) != Some(&"connected") {
+                continue;
+            }
+            let primary = tokens.get(2) == Some(&"primary");
+            // Geometry "WxH+X+Y" is missing for connected but disabled outputs
+            let Some(geometry) = tokens.iter().find(|t| t.contains('x') && t.contains('+')) else {
+                continue;
+            };
+            let (mode, position) = geometry.split_at(geometry.find('+').unwrap_or(0));
+            monitors.push(MonitorInfo {
+                name: tokens[0].to_string(),
+                mode: mode.to_string(),
+                refresh: String::new(),
+                position: position.to_string(),
+                primary,
+            });
+            current = Some(monitors.len() - 1);
+        } else if let Some(index) = current {
+            // Mode lines: "   2560x1440     59.95 + 143.91*"
+            if let Some(rate) = line.split_whitespace().skip(1).find(|t| t.contains('*')) {
+                monitors[index].refresh = format!("{} Hz", rate.trim_end_matches(['*', '+']));
+            }
+        }
+    }
+    monitors
+}
+
+/// Connected connectors from `/sys/class/drm`, with their preferred mode. Used when
+/// `xrandr` is unavailable; refresh rate and position are unknown.
+fn drm_monitors() -> Vec<MonitorInfo> {
+    let mut monitors = Vec::new();
+    if let Ok(entries) = std::fs::read_dir("/sys/class/drm") {
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let Some((_, connector)) = name.split_once('-') else {
+                continue;
+            };
+            let path = entry.path();
+            let connected =
+                std::fs::read_to_string(path.join("status")).is_ok_and(|s| s.trim() == "connected");
+            if !connected {
+                continue;
+            }
+            let mode = std::fs::read_to_string(path.join("modes"))
+                .ok()
+                .and_then(|m| m.lines().next().map(str::to_string))
+                .unwrap_or_default();
+            monitors.push(MonitorInfo {
+                name: connector.to_string(),
+                mode,
+                ..Default::default()
+            });
+        }
+    }
+    monitors.sort_by(|a, b| a.name.cmp(&b.name));
+    monitors
+}
+
+/// Returns the connected monitors, from `xrandr` when a display is reachable, otherwise
+/// from the kernel's connector list.
+pub fn get_monitors() -> Vec<MonitorInfo> {
+    if env("DISPLAY").is_some() {
+        if let Ok(output) = Command::new("xrandr").arg("--query").output() {
+            if output.status.success() {
+                let monitors = parse_xrandr(&String::from_utf8_lossy(&output.stdout));
+                if !monitors.is_empty() {
+                    return monitors;
+                }
+            }
+        }
+    }
+    drm_monitors()
+}
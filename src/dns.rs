@@ -0,0 +1,212 @@
+//! # DNS Latency Module
+//!
+//! This module periodically times DNS lookups against the configured resolvers, catching
+//! the common "internet feels slow but bandwidth is fine" case.
+//!
+//! Resolvers are taken from `/etc/resolv.conf`, plus the upstream servers of
+//! systemd-resolved (`/run/systemd/resolve/resolv.conf`) when the local stub is in use.
+//! Each probe sends a minimal A query over UDP from a background thread, so a hanging
+//! resolver never blocks the UI.
+
+use crate::history::{History, HistoryLimits};
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, SocketAddr, UdpSocket};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Seconds between two probe rounds.
+pub const DNS_PROBE_INTERVAL_SECS: u64 = 5;
+/// Probes slower than this count as timed out.
+pub const DNS_TIMEOUT: Duration = Duration::from_secs(2);
+/// Lower bound of the latency chart scale (in milliseconds).
+const MIN_LATENCY_SCALE_MS: f32 = 100.0;
+
+/// Holds latency data for one resolver for external consumers
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DnsLatencyData {
+    pub server: String,
+    /// Latest round-trip time in milliseconds; `None` if the last probe timed out.
+    pub latency_ms: Option<f32>,
+    pub history: History,
+    /// Highest latency in the visible history (at least 100 ms), used to scale the chart.
+    pub scale_ms: f32,
+}
+
+/// Latest round-trip time per resolver (`None` = timed out).
+type ProbeResults = Vec<(IpAddr, Option<f32>)>;
+
+struct ResolverEntry {
+    server: IpAddr,
+    history: History,
+}
+
+/// Runs the probe thread and keeps per-resolver latency history.
+/// The probe thread stops when the tracker is dropped.
+pub struct DnsTracker {
+    /// Latest probe result per resolver, written by the probe thread.
+    latest: Arc<Mutex<ProbeResults>>,
+    stop: Arc<AtomicBool>,
+    entries: Vec<ResolverEntry>,
+}
+
+fn parse_nameservers(content: &str) -> Vec<IpAddr> {
+    content
+        .lines()
+        .filter_map(|l| l.trim().strip_prefix("nameserver"))
+        .filter_map(|v| v.trim().split('%').next()?.parse().ok())
+        .collect()
+}
+
+/// Lists the configured resolvers, including systemd-resolved's upstream servers.
+pub fn configured_resolvers() -> Vec<IpAddr> {
+    let mut servers = Vec::new();
+    for path in ["/etc/resolv.conf", "/run/systemd/resolve/resolv.conf"] {
+        if let Ok(content) = std::fs::read_to_string(path) {
+            for server in parse_nameservers(&content) {
+                if !servers.contains(&server) {
+                    servers.push(server);
+                }
+            }
+        }
+    }
+    servers
+}
+
+/// Builds a DNS query packet for the A record of `host`.
+fn build_query(id: u16, host: &str) -> Vec<u8> {
+    let mut packet = Vec::with_capacity(17 + host.len());
+    packet.extend_from_slice(&id.to_be_bytes());
+    packet.extend_from_slice(&[0x01, 0x00]); // Standard query, recursion desired
+    packet.extend_from_slice(&[0, 1, 0, 0, 0, 0, 0, 0]); // 1 question
+    for label in host.trim_end_matches('.').split('.') {
+        packet.push(label.len() as u8);
+        packet.extend_from_slice(label.as_bytes());
+    }
+    packet.push(0);
+    packet.extend_from_slice(&[0, 1, 0, 1]); // QTYPE A, QCLASS IN
+    packet
+}
+
+/// Times one lookup of `host` against `server`. Returns `None` on timeout or error.
+pub fn probe(server: IpAddr, host: &str) -> Option<Duration> {
+    let bind: SocketAddr = if server.is_ipv4() {
+        "0.0.0.0:0".parse().ok()?
+    } else {
+        "[::]:0".parse().ok()?
+    };
+    let socket = UdpSocket::bind(bind).ok()?;
+    socket.set_read_timeout(Some(DNS_TIMEOUT)).ok()?;
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    let id = (nanos ^ std::process::id()) as u16;
+    let start = Instant::now();
+    socket
+        .send_to(&build_query(id, host), SocketAddr::new(server, 53))
+        .ok()?;
+
+    let mut buf = [0u8; 512];
+    loop {
+        let (len, from) = socket.recv_from(&mut buf).ok()?;
+        // Ignore stray datagrams; only our resolver's answer to our query counts
+        if from.ip() == server && len >= 2 && buf[..2] == id.to_be_bytes() {
+            return Some(start.elapsed());
+        }
+        if start.elapsed() > DNS_TIMEOUT {
+            return None;
+        }
+    }
+}
+
+impl DnsTracker {
+    /// Starts probing the configured resolvers for `host` every `DNS_PROBE_INTERVAL_SECS`.
+    pub fn start(host: String) -> Self {
+        let latest = Arc::new(Mutex::new(Vec::new()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_latest = latest.clone();
+        let thread_stop = stop.clone();
+        std::thread::spawn(move || {
+            while !thread_stop.load(Ordering::Relaxed) {
+                let results: ProbeResults = configured_resolvers()
+                    .into_iter()
+                    .map(|server| {
+                        let latency = probe(server, &host).map(|d| d.as_secs_f32() * 1000.0);
+                        (server, latency)
+                    })
+                    .collect();
+                if let Ok(mut guard) = thread_latest.lock() {
+                    *guard = results;
+                }
+                std::thread::sleep(Duration::from_secs(DNS_PROBE_INTERVAL_SECS));
+            }
+        });
+
+        Self {
+            latest,
+            stop,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Appends the latest probe result of every resolver to its history
+    /// (sample-and-hold between probe rounds). Timeouts are recorded at the timeout value.
+    pub fn refresh(&mut self, limits: HistoryLimits) {
+        let latest = match self.latest.lock() {
+            Ok(guard) => guard.clone(),
+            Err(_) => return,
+        };
+
+        self.entries
+            .retain(|e| latest.iter().any(|(server, _)| *server == e.server));
+        for (server, latency) in latest {
+            let value = latency.unwrap_or(DNS_TIMEOUT.as_secs_f32() * 1000.0);
+            match self.entries.iter_mut().find(|e| e.server == server) {
+                Some(entry) => {
+                    entry.history.push(value);
+                }
+                None => {
+                    let mut history = History::with_limits(limits);
+                    history.push(value);
+                    self.entries.push(ResolverEntry { server, history });
+                }
+            }
+        }
+    }
+
+    /// Applies new `limits` to every history buffer.
+    pub fn resize_history(&mut self, limits: HistoryLimits) {
+        for entry in &mut self.entries {
+            entry.history.set_limits(limits);
+        }
+    }
+
+    /// Number of history buffers, for the monitor's sample budget.
+    pub fn history_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn get_data(&self) -> Vec<DnsLatencyData> {
+        let latest = self.latest.lock().map(|g| g.clone()).unwrap_or_default();
+        self.entries
+            .iter()
+            .map(|e| DnsLatencyData {
+                server: e.server.to_string(),
+                latency_ms: latest
+                    .iter()
+                    .find(|(server, _)| *server == e.server)
+                    .and_then(|(_, latency)| *latency),
+                history: e.history.clone(),
+                scale_ms: e.history.max_value().max(MIN_LATENCY_SCALE_MS),
+            })
+            .collect()
+    }
+}
+
+impl Drop for DnsTracker {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,160 @@
+//! # Drive Temperature Module
+//!
+//! This module keeps a temperature history per physical drive, since sustained NVMe
+//! thermal throttling is easy to miss when only throughput is graphed.
+//!
+//! Temperatures are read from the drive's hwmon sensor when the kernel exposes one
+//! (NVMe always, SATA with the `drivetemp` module), which needs no privileges. Other
+//! drives use the `smartctl` reading reported by the privileged worker.
+
+use crate::history::{History, HistoryLimits};
+use crate::monitor::{StorageDetailedInfo, SysPaths};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Temperature history of one drive.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DriveTempData {
+    pub device: String,
+    /// Latest reading in °C; `None` if the last reading failed.
+    pub temperature: Option<f32>,
+    /// Temperature at which the drive starts throttling, when reported.
+    pub max: Option<f32>,
+    pub history: History,
+}
+
+struct DriveEntry {
+    device: String,
+    temperature: Option<f32>,
+    max: Option<f32>,
+    history: History,
+}
+
+/// hwmon directories of a block device: NVMe registers them on the controller
+/// (`device/hwmonN`), SCSI disks under `device/hwmon/hwmonN`.
+fn hwmon_dirs(block_root: &Path, device: &str) -> Vec<PathBuf> {
+    let base = block_root.join(device).join("device");
+    let mut dirs = Vec::new();
+    for parent in [base.clone(), base.join("hwmon")] {
+        if let Ok(entries) = std::fs::read_dir(&parent) {
+            dirs.extend(
+                entries
+                    .flatten()
+                    .filter(|e| e.file_name().to_string_lossy().starts_with("hwmon"))
+                    .map(|e| e.path())
+                    .filter(|p| p.join("temp1_input").exists()),
+            );
+        }
+    }
+    dirs
+}
+
+/// Reads a millidegree hwmon file as °C.
+fn read_millidegrees(path: &Path) -> Option<f32> {
+    std::fs::read_to_string(path)
+        .ok()?
+        .trim()
+        .parse::<f32>()
+        .ok()
+        .map(|m| m / 1000.0)
+}
+
+/// Current temperature and throttling threshold of `device` (an entry of `block_root`,
+/// i.e. `/sys/block`) from hwmon.
+pub fn read_sysfs(block_root: &Path, device: &str) -> (Option<f32>, Option<f32>) {
+    match hwmon_dirs(block_root, device).first() {
+        Some(dir) => (
+            read_millidegrees(&dir.join("temp1_input")),
+            read_millidegrees(&dir.join("temp1_max"))
+                .or_else(|| read_millidegrees(&dir.join("temp1_crit"))),
+        ),
+        None => (None, None),
+    }
+}
+
+/// Keeps the temperature history of each drive that reports one.
+pub struct DriveTempTracker {
+    block_root: PathBuf,
+    entries: Vec<DriveEntry>,
+}
+
+impl Default for DriveTempTracker {
+    fn default() -> Self {
+        Self::new(&SysPaths::default())
+    }
+}
+
+impl DriveTempTracker {
+    /// Reads the drives under the `/sys` root of `paths`.
+    pub fn new(paths: &SysPaths) -> Self {
+        Self {
+            block_root: paths.sys.join("block"),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Appends the current temperature of every drive. `worker` holds the worker's
+    /// smartctl readings, used for drives without a hwmon sensor.
+    pub fn refresh(&mut self, worker: &[StorageDetailedInfo], limits: HistoryLimits) {
+        let mut devices: Vec<String> = std::fs::read_dir(&self.block_root)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|e| e.file_name().to_string_lossy().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        devices.sort();
+
+        for device in devices {
+            let (sysfs, max) = read_sysfs(&self.block_root, &device);
+            let temperature = sysfs.or_else(|| {
+                worker
+                    .iter()
+                    .find(|d| d.device_name == device)
+                    .and_then(|d| d.temperature)
+            });
+            let known = self.entries.iter().any(|e| e.device == device);
+            // Only drives that have reported a temperature at least once are charted
+            if temperature.is_none() && !known {
+                continue;
+            }
+            if !known {
+                self.entries.push(DriveEntry {
+                    device: device.clone(),
+                    temperature: None,
+                    max: None,
+                    history: History::with_limits(limits),
+                });
+            }
+            if let Some(entry) = self.entries.iter_mut().find(|e| e.device == device) {
+                entry.temperature = temperature;
+                entry.max = max.or(entry.max);
+                entry.history.push(temperature.unwrap_or(0.0));
+            }
+        }
+    }
+
+    pub fn resize_history(&mut self, limits: HistoryLimits) {
+        for entry in &mut self.entries {
+            entry.history.set_limits(limits);
+        }
+    }
+
+    /// Number of history buffers, for the monitor's sample budget.
+    pub fn history_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn get_data(&self) -> Vec<DriveTempData> {
+        self.entries
+            .iter()
+            .map(|e| DriveTempData {
+                device: e.device.clone(),
+                temperature: e.temperature,
+                max: e.max,
+                history: e.history.clone(),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,105 @@
+//! # Entropy Module
+//!
+//! This module reports the state of the kernel random number generator:
+//! - Available entropy vs pool size (`/proc/sys/kernel/random`).
+//! - The active hardware RNG, if any (`/sys/class/misc/hw_random`).
+//! - Whether an entropy daemon (`rngd`, `haveged`, `jitterentropy-rngd`) is running.
+//!
+//! On kernels before 5.18, headless machines and VMs without a virtio-rng device can run
+//! low on entropy, making `/dev/random` and early `getrandom()` calls block. Newer kernels
+//! always report a full pool, so the warning state only triggers where it matters.
+
+use serde::{Deserialize, Serialize};
+use sysinfo::System;
+
+/// Entropy (in bits) below which the pool is considered low.
+const LOW_ENTROPY_BITS: u32 = 256;
+
+/// Process names of common entropy-feeding daemons.
+const RNG_DAEMONS: &[&str] = &["rngd", "haveged", "jitterentropy-rngd"];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct EntropyStatus {
+    /// Available entropy in bits.
+    pub available: u32,
+    /// Size of the entropy pool in bits.
+    pub pool_size: u32,
+    /// Name of the active hardware RNG (e.g. "tpm-rng-0", "virtio_rng.0").
+    pub hw_rng: Option<String>,
+    /// Name of the running entropy daemon, if any.
+    pub rng_daemon: Option<String>,
+    /// Running under a hypervisor (`hypervisor` CPU flag).
+    pub virtualized: bool,
+}
+
+fn read_trimmed(path: &str) -> Option<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty() && s != "none")
+}
+
+/// Whether we run under a hypervisor (`hypervisor` CPU flag). Doesn't change at runtime,
+/// so callers should detect it once.
+pub fn detect_virtualization() -> bool {
+    std::fs::read_to_string("/proc/cpuinfo")
+        .map(|c| {
+            c.lines()
+                .filter(|l| l.starts_with("flags"))
+                .any(|l| l.split_whitespace().any(|f| f == "hypervisor"))
+        })
+        .unwrap_or(false)
+}
+
+impl EntropyStatus {
+    /// Reads the current status; `system` must have a refreshed process list.
+    pub fn read(system: &System, virtualized: bool) -> Self {
+        let read_bits = |path| read_trimmed(path).and_then(|s| s.parse().ok()).unwrap_or(0);
+
+        let rng_daemon = system.processes().values().find_map(|p| {
+            let name = p.name().to_string_lossy();
+            RNG_DAEMONS
+                .iter()
+                .find(|d| name == **d)
+                .map(|d| d.to_string())
+        });
+
+        Self {
+            available: read_bits("/proc/sys/kernel/random/entropy_avail"),
+            pool_size: read_bits("/proc/sys/kernel/random/poolsize"),
+            hw_rng: read_trimmed("/sys/class/misc/hw_random/rng_current"),
+            rng_daemon,
+            virtualized,
+        }
+    }
+
+    /// Whether crypto operations may block: the pool is low and nothing refills it.
+    pub fn is_starved(&self) -> bool {
+        self.pool_size > 0
+            && self.available < LOW_ENTROPY_BITS
+            && self.hw_rng.is_none()
+            && self.rng_daemon.is_none()
+    }
+
+    /// One-line summary for the UI, e.g. "256 / 256 bits, HW RNG: tpm-rng-0".
+    pub fn summary(&self) -> String {
+        let mut parts = vec![format!("{} / {} bits", self.available, self.pool_size)];
+        parts.push(match &self.hw_rng {
+            Some(rng) => format!("HW RNG: {}", rng),
+            None => "no HW RNG".to_string(),
+        });
+        if let Some(daemon) = &self.rng_daemon {
+            parts.push(format!("{} running", daemon));
+        }
+        parts.join(", ")
+    }
+
+    /// Suggested fix shown with the low-entropy alert.
+    pub fn hint(&self) -> &'static str {
+        if self.virtualized {
+            "add a virtio-rng device to the VM or run rngd/haveged"
+        } else {
+            "run rngd or haveged"
+        }
+    }
+}
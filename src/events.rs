@@ -0,0 +1,115 @@
+//! # Metric Events Module
+//!
+//! This module lets library consumers follow a `SystemMonitor` without polling its
+//! getters: `SystemMonitor::subscribe` returns a channel `Receiver` that gets
+//! `MetricEvent`s after every `refresh()`. CPU, memory, GPU, network and fan samples are
+//! sent on every tick; disks only when one is mounted, unmounted or its usage changes;
+//! alerts on every transition.
+//!
+//! Channels are unbounded, so a subscriber should drain its receiver at least as often as
+//! the monitor refreshes. Dropping the receiver unsubscribes.
+
+use crate::alerts::AlertEvent;
+use crate::snapshot::{
+    CpuSnapshot, DiskSnapshot, FanSnapshot, GpuSnapshot, MemorySnapshot, MetricsSnapshot,
+    NetworkSnapshot,
+};
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+/// One change reported to subscribers.
+#[derive(Debug, Clone)]
+pub enum MetricEvent {
+    /// Sent first on every tick; the events up to the next `Tick` belong to it.
+    Tick {
+        /// Seconds since the UNIX epoch at which the values were sampled.
+        timestamp: u64,
+    },
+    Cpu(CpuSnapshot),
+    Memory(MemorySnapshot),
+    Gpu {
+        index: usize,
+        sample: GpuSnapshot,
+    },
+    Network(NetworkSnapshot),
+    Fan(FanSnapshot),
+    /// A filesystem was mounted or its usage changed.
+    DiskChanged(DiskSnapshot),
+    DiskRemoved {
+        mount_point: String,
+    },
+    Alert(AlertEvent),
+}
+
+/// Subscribers and the state needed to turn snapshots into deltas.
+#[derive(Default)]
+pub struct EventBus {
+    subscribers: Vec<Sender<MetricEvent>>,
+    /// Disks as last reported, by mount point.
+    disks: HashMap<String, DiskSnapshot>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a new subscriber. The disks known so far are sent right away, so it
+    /// starts with a complete picture.
+    pub fn subscribe(&mut self) -> Receiver<MetricEvent> {
+        let (sender, receiver) = mpsc::channel();
+        for disk in self.disks.values() {
+            let _ = sender.send(MetricEvent::DiskChanged(disk.clone()));
+        }
+        self.subscribers.push(sender);
+        receiver
+    }
+
+    /// Sends the events of one tick to every subscriber and drops those that hung up.
+    pub fn publish(&mut self, snapshot: &MetricsSnapshot, alerts: &[AlertEvent]) {
+        if self.subscribers.is_empty() {
+            return;
+        }
+
+        let mut events = vec![
+            MetricEvent::Tick {
+                timestamp: snapshot.timestamp,
+            },
+            MetricEvent::Cpu(snapshot.cpu.clone()),
+            MetricEvent::Memory(snapshot.memory.clone()),
+        ];
+        events.extend(
+            snapshot
+                .gpus
+                .iter()
+                .enumerate()
+                .map(|(index, gpu)| MetricEvent::Gpu {
+                    index,
+                    sample: gpu.clone(),
+                }),
+        );
+        events.extend(snapshot.networks.iter().cloned().map(MetricEvent::Network));
+        events.extend(snapshot.fans.iter().cloned().map(MetricEvent::Fan));
+
+        for disk in &snapshot.disks {
+            if self.disks.get(&disk.mount_point) != Some(disk) {
+                self.disks.insert(disk.mount_point.clone(), disk.clone());
+                events.push(MetricEvent::DiskChanged(disk.clone()));
+            }
+        }
+        let removed: Vec<String> = self
+            .disks
+            .keys()
+            .filter(|mount| !snapshot.disks.iter().any(|d| d.mount_point == **mount))
+            .cloned()
+            .collect();
+        for mount_point in removed {
+            self.disks.remove(&mount_point);
+            events.push(MetricEvent::DiskRemoved { mount_point });
+        }
+        events.extend(alerts.iter().cloned().map(MetricEvent::Alert));
+
+        self.subscribers
+            .retain(|s| events.iter().all(|e| s.send(e.clone()).is_ok()));
+    }
+}
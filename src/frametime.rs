@@ -0,0 +1,200 @@
+//! # Frame Time Module
+//!
+//! This module shows the frame pacing of a running game next to the GPU utilization, by
+//! following the CSV log MangoHud writes while logging (`output_folder` plus
+//! `autostart_log=1` or the logging hotkey in `MangoHud.conf`). PresentMon is Windows
+//! only; MangoHud covers Vulkan and OpenGL games on Linux, including Proton ones.
+//!
+//! The newest log in the configured folder counts as live while it was written to in the
+//! last few seconds. Each refresh reads the rows appended since the previous one and
+//! records their average FPS and frame time, plus the slowest frame (stutter).
+//! Log names are `<executable>_<date>_<time>.csv`; the executable names the game.
+
+use crate::history::{History, HistoryLimits};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// A log not written to for this long belongs to a game that has exited.
+const STALE_AFTER: Duration = Duration::from_secs(5);
+
+/// Frame pacing of the running game for external consumers
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FrameTimeData {
+    /// Executable of the game, from the log name (e.g., "Cyberpunk2077.exe").
+    pub app: String,
+    /// Averages over the last refresh interval.
+    pub fps: f32,
+    pub frame_time_ms: f32,
+    /// Slowest frame of the last refresh interval.
+    pub worst_frame_ms: f32,
+    pub fps_history: History,
+    pub frame_time_history: History,
+}
+
+/// The game name of a MangoHud log file, without the date and time suffix.
+fn app_name(path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut parts = stem.rsplitn(3, '_');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(_time), Some(_date), Some(app)) => app.to_string(),
+        _ => stem,
+    }
+}
+
+/// The most recently modified log in `dir`, if it is still being written.
+fn live_log(dir: &Path) -> Option<PathBuf> {
+    let (modified, path) = std::fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|e| {
+            let name = e.file_name().to_string_lossy().to_string();
+            // Summaries are written once logging stops
+            name.ends_with(".csv") && !name.ends_with("_summary.csv")
+        })
+        .filter_map(|e| Some((e.metadata().ok()?.modified().ok()?, e.path())))
+        .max()?;
+    let age = SystemTime::now()
+        .duration_since(modified)
+        .unwrap_or_default();
+    (age < STALE_AFTER).then_some(path)
+}
+
+/// Follows the MangoHud logs in one folder.
+pub struct FrameTimeTracker {
+    dir: PathBuf,
+    /// Log being followed and the number of bytes read from it.
+    file: Option<PathBuf>,
+    offset: u64,
+    /// Incomplete last line of the previous read.
+    partial: String,
+    /// Positions of the `fps` and `frametime` columns, once the header was read.
+    columns: Option<(usize, usize)>,
+    live: bool,
+    app: String,
+    fps: f32,
+    frame_time_ms: f32,
+    worst_frame_ms: f32,
+    fps_history: History,
+    frame_time_history: History,
+}
+
+impl FrameTimeTracker {
+    /// Follows the logs in `log_dir` ("~/" is the home directory).
+    pub fn new(log_dir: &str, limits: HistoryLimits) -> Self {
+        let dir = match log_dir.strip_prefix("~/") {
+            Some(rest) => std::env::var("HOME").unwrap_or_default() + "/" + rest,
+            None => log_dir.to_string(),
+        };
+        Self {
+            dir: PathBuf::from(dir),
+            file: None,
+            offset: 0,
+            partial: String::new(),
+            columns: None,
+            live: false,
+            app: String::new(),
+            fps: 0.0,
+            frame_time_ms: 0.0,
+            worst_frame_ms: 0.0,
+            fps_history: History::with_limits(limits),
+            frame_time_history: History::with_limits(limits),
+        }
+    }
+
+    /// Reads the rows appended to the live log and records one sample.
+    pub fn refresh(&mut self) {
+        let Some(path) = live_log(&self.dir) else {
+            self.live = false;
+            return;
+        };
+        if self.file.as_ref() != Some(&path) {
+            // A new game (or a new recording)
+            self.app = app_name(&path);
+            self.file = Some(path.clone());
+            self.offset = 0;
+            self.partial.clear();
+            self.columns = None;
+            self.fps_history.clear();
+            self.frame_time_history.clear();
+        }
+
+        let mut text = String::new();
+        let read = std::fs::File::open(&path).and_then(|mut file| {
+            if file.metadata()?.len() < self.offset {
+                // Truncated and rewritten
+                self.offset = 0;
+                self.columns = None;
+            }
+            file.seek(SeekFrom::Start(self.offset))?;
+            file.read_to_string(&mut text)
+        });
+        let Ok(read) = read else {
+            self.live = false;
+            return;
+        };
+        self.offset += read as u64;
+        self.live = true;
+
+        self.partial.push_str(&text);
+        let complete = match self.partial.rfind('\n') {
+            Some(end) => self.partial.drain(..=end).collect::<String>(),
+            None => return,
+        };
+
+        let mut frames = Vec::new();
+        for line in complete.lines() {
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            // The system info lines come first, then the column header
+            if fields.first() == Some(&"fps") {
+                let frametime = fields.iter().position(|f| *f == "frametime");
+                self.columns = frametime.map(|frametime| (0, frametime));
+                continue;
+            }
+            let Some((fps, frametime)) = self.columns else {
+                continue;
+            };
+            let value = |i: usize| fields.get(i).and_then(|v| v.parse::<f32>().ok());
+            if let (Some(fps), Some(frametime)) = (value(fps), value(frametime)) {
+                frames.push((fps, frametime));
+            }
+        }
+        if frames.is_empty() {
+            return;
+        }
+
+        let count = frames.len() as f32;
+        self.fps = frames.iter().map(|(fps, _)| fps).sum::<f32>() / count;
+        self.frame_time_ms = frames.iter().map(|(_, ft)| ft).sum::<f32>() / count;
+        self.worst_frame_ms = frames.iter().map(|(_, ft)| *ft).fold(0.0, f32::max);
+        self.fps_history.push(self.fps);
+        self.frame_time_history.push(self.frame_time_ms);
+    }
+
+    /// Applies new `limits` to both history buffers.
+    pub fn resize_history(&mut self, limits: HistoryLimits) {
+        self.fps_history.set_limits(limits);
+        self.frame_time_history.set_limits(limits);
+    }
+
+    /// Number of history buffers, for the monitor's sample budget.
+    pub fn history_count(&self) -> usize {
+        2
+    }
+
+    /// The running game's frame pacing, or `None` when no log is being written.
+    pub fn get_data(&self) -> Option<FrameTimeData> {
+        (self.live && !self.fps_history.is_empty()).then(|| FrameTimeData {
+            app: self.app.clone(),
+            fps: self.fps,
+            frame_time_ms: self.frame_time_ms,
+            worst_frame_ms: self.worst_frame_ms,
+            fps_history: self.fps_history.clone(),
+            frame_time_history: self.frame_time_history.clone(),
+        })
+    }
+}
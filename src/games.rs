@@ -0,0 +1,367 @@
+//! # Game Sessions Module
+//!
+//! This module detects running Steam games and records a summary of each play session:
+//! length, average and peak GPU and CPU usage, and peak GPU and chip temperatures.
+//! Sessions are persisted to `game-sessions.json` in the standard data directory and
+//! listed in the History tab.
+//!
+//! Steam launches every game with `SteamAppId` (or `SteamGameId`) in its environment,
+//! inherited by all processes of the game, so the app ID is read from
+//! `/proc/<pid>/environ` once per new process. Proton games also get
+//! `STEAM_COMPAT_DATA_PATH`. Only processes of the current user are readable, which
+//! covers games. The game name is taken from the `appmanifest_<appid>.acf` in one of the
+//! Steam libraries, falling back to the process name.
+
+use crate::gpujobs::format_duration;
+use crate::markers::now_secs;
+use crate::snapshot::MetricsSnapshot;
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use sysinfo::System;
+
+/// Upper bound on stored sessions; the oldest ones are dropped first.
+const MAX_SESSIONS: usize = 500;
+/// Sessions shorter than this (e.g. a crash on launch) are not recorded.
+const MIN_SESSION_SECS: u64 = 30;
+
+/// Summary of one play session, as persisted and shown in the History tab.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GameSession {
+    pub appid: u32,
+    pub name: String,
+    /// Run through Proton (Windows game).
+    pub proton: bool,
+    /// Seconds since the UNIX epoch.
+    pub start: u64,
+    pub end: u64,
+    /// Utilization of the busiest GPU, in percent.
+    pub avg_gpu_percent: f32,
+    pub peak_gpu_percent: f32,
+    /// Average usage across cores, in percent.
+    pub avg_cpu_percent: f32,
+    pub peak_cpu_percent: f32,
+    /// Hottest GPU, in °C.
+    pub peak_gpu_temp: Option<f32>,
+    /// Hottest hwmon chip (CPU package on most machines), in °C.
+    pub peak_chip_temp: Option<f32>,
+}
+
+impl GameSession {
+    pub fn duration_secs(&self) -> u64 {
+        self.end.saturating_sub(self.start)
+    }
+
+    /// Local start time and length, e.g. "2026-03-14 20:05 · 2h 13m".
+    pub fn time_label(&self) -> String {
+        let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+        let time = self.start as libc::time_t;
+        let start = if unsafe { libc::localtime_r(&time, &mut tm) }.is_null() {
+            crate::csvlog::format_timestamp(self.start)
+        } else {
+            format!(
+                "{}-{:02}-{:02} {:02}:{:02}",
+                tm.tm_year + 1900,
+                tm.tm_mon + 1,
+                tm.tm_mday,
+                tm.tm_hour,
+                tm.tm_min
+            )
+        };
+        let length = format_duration(Duration::from_secs(self.duration_secs()));
+        format!("{} · {}", start, length)
+    }
+
+    /// Averages and peaks, e.g. "GPU avg 87% / peak 99% · CPU avg 31% / peak 64% · GPU 74 °C".
+    pub fn stats_label(&self) -> String {
+        let mut parts = vec![
+            format!(
+                "GPU avg {:.0}% / peak {:.0}%",
+                self.avg_gpu_percent, self.peak_gpu_percent
+            ),
+            format!(
+                "CPU avg {:.0}% / peak {:.0}%",
+                self.avg_cpu_percent, self.peak_cpu_percent
+            ),
+        ];
+        if let Some(temp) = self.peak_gpu_temp {
+            parts.push(format!("GPU {:.0} °C", temp));
+        }
+        if let Some(temp) = self.peak_chip_temp {
+            parts.push(format!("Chip {:.0} °C", temp));
+        }
+        parts.join(" · ")
+    }
+}
+
+/// Steam app ID and Proton flag of a process environment (`NUL`-separated).
+fn parse_environ(environ: &[u8]) -> Option<(u32, bool)> {
+    let mut appid = None;
+    let mut proton = false;
+    for var in environ.split(|b| *b == 0) {
+        let var = String::from_utf8_lossy(var);
+        if let Some((key, value)) = var.split_once('=') {
+            match key {
+                "SteamAppId" | "SteamGameId" if appid.is_none() => {
+                    // 0 is the Steam client itself
+                    appid = value.parse::<u32>().ok().filter(|id| *id != 0);
+                }
+                "STEAM_COMPAT_DATA_PATH" => proton = true,
+                _ => {}
+            }
+        }
+    }
+    appid.map(|id| (id, proton))
+}
+
+/// Roots of the Steam installation (native, legacy symlink and Flatpak).
+fn steam_roots() -> Vec<PathBuf> {
+    let Some(home) = std::env::var_os("HOME").map(PathBuf::from) else {
+        return Vec::new();
+    };
+    [
+        ".local/share/Steam",
+        ".steam/steam",
+        ".var/app/com.valvesoftware.Steam/.local/share/Steam",
+    ]
+    .iter()
+    .map(|p| home.join(p))
+    .filter(|p| p.is_dir())
+    .collect()
+}
+
+/// Values of the `"key" "value"` lines for `key` in a Valve KeyValues file.
+fn vdf_values<'a>(content: &'a str, key: &'a str) -> impl Iterator<Item = String> + 'a {
+    content.lines().filter_map(move |line| {
+        let mut quoted = line.split('"').skip(1).step_by(2);
+        match (quoted.next(), quoted.next()) {
+            (Some(k), Some(v)) if k.eq_ignore_ascii_case(key) => Some(v.replace("\\\\", "\\")),
+            _ => None,
+        }
+    })
+}
+
+/// Name of the Steam app `appid` from its manifest in one of the libraries.
+fn app_name(roots: &[PathBuf], appid: u32) -> Option<String> {
+    let manifest = format!("appmanifest_{}.acf", appid);
+    let mut libraries: Vec<PathBuf> = Vec::new();
+    for root in roots {
+        libraries.push(root.join("steamapps"));
+        let folders = fs::read_to_string(root.join("steamapps/libraryfolders.vdf"));
+        if let Ok(folders) = folders {
+            libraries.extend(vdf_values(&folders, "path").map(|p| Path::new(&p).join("steamapps")));
+        }
+    }
+    libraries.iter().find_map(|library| {
+        let content = fs::read_to_string(library.join(&manifest)).ok()?;
+        let name = vdf_values(&content, "name").next();
+        name
+    })
+}
+
+/// Running aggregates of a session in progress.
+#[derive(Debug, Clone)]
+struct ActiveSession {
+    name: String,
+    proton: bool,
+    start: u64,
+    samples: u64,
+    gpu_sum: f64,
+    cpu_sum: f64,
+    peak_gpu: f32,
+    peak_cpu: f32,
+    peak_gpu_temp: Option<f32>,
+    peak_chip_temp: Option<f32>,
+}
+
+impl ActiveSession {
+    fn add(&mut self, snapshot: &MetricsSnapshot) {
+        let gpu = snapshot
+            .gpus
+            .iter()
+            .map(|g| g.util_percent)
+            .fold(0.0, f32::max);
+        let cpu = snapshot.cpu.usage_percent;
+        self.samples += 1;
+        self.gpu_sum += gpu as f64;
+        self.cpu_sum += cpu as f64;
+        self.peak_gpu = self.peak_gpu.max(gpu);
+        self.peak_cpu = self.peak_cpu.max(cpu);
+        let max_opt = |peak: Option<f32>, value: Option<f32>| match (peak, value) {
+            (Some(p), Some(v)) => Some(p.max(v)),
+            (p, v) => p.or(v),
+        };
+        let gpu_temp = snapshot
+            .gpus
+            .iter()
+            .filter_map(|g| g.temperature)
+            .reduce(f32::max);
+        let chip_temp = snapshot
+            .fans
+            .iter()
+            .filter_map(|f| f.temperature)
+            .reduce(f32::max);
+        self.peak_gpu_temp = max_opt(self.peak_gpu_temp, gpu_temp);
+        self.peak_chip_temp = max_opt(self.peak_chip_temp, chip_temp);
+    }
+
+    fn finish(self, appid: u32, end: u64) -> GameSession {
+        let samples = self.samples.max(1) as f64;
+        GameSession {
+            appid,
+            name: self.name,
+            proton: self.proton,
+            start: self.start,
+            end,
+            avg_gpu_percent: (self.gpu_sum / samples) as f32,
+            peak_gpu_percent: self.peak_gpu,
+            avg_cpu_percent: (self.cpu_sum / samples) as f32,
+            peak_cpu_percent: self.peak_cpu,
+            peak_gpu_temp: self.peak_gpu_temp,
+            peak_chip_temp: self.peak_chip_temp,
+        }
+    }
+}
+
+/// Persistent list of finished sessions, oldest first.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+struct SessionStore {
+    sessions: Vec<GameSession>,
+}
+
+impl SessionStore {
+    fn get_path() -> PathBuf {
+        if let Some(proj_dirs) = ProjectDirs::from("com", "gjallarhorn", "gjallarhorn") {
+            let data_dir = proj_dirs.data_dir();
+            if !data_dir.exists() {
+                let _ = fs::create_dir_all(data_dir);
+            }
+            data_dir.join("game-sessions.json")
+        } else {
+            PathBuf::from("game-sessions.json")
+        }
+    }
+
+    fn load() -> Self {
+        fs::read_to_string(Self::get_path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self) {
+        if let Ok(json) = serde_json::to_string_pretty(self) {
+            let _ = fs::write(Self::get_path(), json);
+        }
+    }
+}
+
+/// Detects running Steam games and records their sessions.
+pub struct GameTracker {
+    steam_roots: Vec<PathBuf>,
+    /// Steam app ID and Proton flag per PID, `None` for other processes. Each process's
+    /// environment is read once.
+    known: HashMap<u32, Option<(u32, bool)>>,
+    active: BTreeMap<u32, ActiveSession>,
+    store: SessionStore,
+}
+
+impl GameTracker {
+    pub fn new() -> Self {
+        Self {
+            steam_roots: steam_roots(),
+            known: HashMap::new(),
+            active: BTreeMap::new(),
+            store: SessionStore::load(),
+        }
+    }
+
+    /// Looks for running games in the process table and adds `snapshot` to the session
+    /// of each. Sessions of games that exited are saved.
+    pub fn update(&mut self, system: &System, snapshot: &MetricsSnapshot) {
+        let processes = system.processes();
+        self.known
+            .retain(|pid, _| processes.contains_key(&sysinfo::Pid::from_u32(*pid)));
+
+        // App ID → name of a process (fallback name) and Proton flag
+        let mut running: BTreeMap<u32, (String, bool)> = BTreeMap::new();
+        for (pid, process) in processes {
+            if process.thread_kind().is_some() {
+                continue;
+            }
+            let game = *self.known.entry(pid.as_u32()).or_insert_with(|| {
+                fs::read(format!("/proc/{}/environ", pid.as_u32()))
+                    .ok()
+                    .and_then(|environ| parse_environ(&environ))
+            });
+            if let Some((appid, proton)) = game {
+                let entry = running
+                    .entry(appid)
+                    .or_insert_with(|| (process.name().to_string_lossy().to_string(), false));
+                entry.1 |= proton;
+            }
+        }
+
+        let now = now_secs();
+        let exited: Vec<u32> = self
+            .active
+            .keys()
+            .filter(|appid| !running.contains_key(appid))
+            .copied()
+            .collect();
+        for appid in exited {
+            if let Some(session) = self.active.remove(&appid) {
+                self.record(session.finish(appid, now));
+            }
+        }
+
+        for (appid, (process_name, proton)) in running {
+            let roots = &self.steam_roots;
+            let session = self.active.entry(appid).or_insert_with(|| ActiveSession {
+                name: app_name(roots, appid).unwrap_or(process_name),
+                proton,
+                start: now,
+                samples: 0,
+                gpu_sum: 0.0,
+                cpu_sum: 0.0,
+                peak_gpu: 0.0,
+                peak_cpu: 0.0,
+                peak_gpu_temp: None,
+                peak_chip_temp: None,
+            });
+            session.proton |= proton;
+            session.add(snapshot);
+        }
+    }
+
+    fn record(&mut self, session: GameSession) {
+        if session.duration_secs() < MIN_SESSION_SECS {
+            return;
+        }
+        self.store.sessions.push(session);
+        if self.store.sessions.len() > MAX_SESSIONS {
+            let excess = self.store.sessions.len() - MAX_SESSIONS;
+            self.store.sessions.drain(..excess);
+        }
+        self.store.save();
+    }
+
+    /// Names of the games being played.
+    pub fn playing(&self) -> Vec<String> {
+        self.active.values().map(|s| s.name.clone()).collect()
+    }
+
+    /// Finished sessions, newest first.
+    pub fn sessions(&self) -> Vec<GameSession> {
+        self.store.sessions.iter().rev().cloned().collect()
+    }
+}
+
+impl Default for GameTracker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
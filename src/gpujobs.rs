@@ -0,0 +1,120 @@
+//! # GPU Job Completion Module
+//!
+//! This module notices when a GPU goes quiet after a long busy stretch, which usually
+//! means a training run, render or benchmark has finished (or crashed), so nobody has to
+//! keep checking the utilization chart.
+//!
+//! A GPU whose utilization stays at or above `busy_percent` for `busy_minutes` is running
+//! a job. Once it then stays below `idle_percent` for `idle_minutes`, the job is reported
+//! finished, with how long it ran. Short dips (data loading, checkpoints) don't count.
+//!
+//! `SystemMonitor` raises the report as a `gpujob.<index>` alert, which the default
+//! notification rules send to the desktop. It clears when the GPU gets busy again or
+//! after `FINISHED_ALERT` has passed.
+
+use crate::settings::GpuJobSettings;
+use std::time::{Duration, Instant};
+
+/// Time a "job finished" alert stays up when the GPU stays idle.
+pub const FINISHED_ALERT: Duration = Duration::from_secs(3600);
+
+/// Change reported by `GpuJobWatcher::update`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GpuJobEvent {
+    /// The GPU has been busy for `busy_minutes`.
+    Started,
+    /// The GPU has been idle for `idle_minutes` after a job; carries how long it was busy.
+    Finished(Duration),
+    /// The "job finished" alert is `FINISHED_ALERT` old.
+    Expired,
+}
+
+/// Formats a job length as "2h 13m" or "45m".
+pub fn format_duration(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    if minutes >= 60 {
+        format!("{}h {}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct JobState {
+    /// Start of the current stretch at or above `busy_percent`.
+    busy_since: Option<Instant>,
+    /// Start of the running job.
+    job_since: Option<Instant>,
+    /// Start of the current stretch below `idle_percent` during a job.
+    idle_since: Option<Instant>,
+    /// When the last job was reported finished, while its alert is up.
+    finished_at: Option<Instant>,
+}
+
+/// Tracks the jobs of every GPU.
+#[derive(Debug, Default)]
+pub struct GpuJobWatcher {
+    settings: GpuJobSettings,
+    states: Vec<JobState>,
+}
+
+impl GpuJobWatcher {
+    pub fn new(settings: &GpuJobSettings) -> Self {
+        Self {
+            settings: settings.clone(),
+            states: Vec::new(),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.settings.enabled
+    }
+
+    /// Feeds the latest utilization (%) of GPU `index`.
+    pub fn update(&mut self, index: usize, util: f32) -> Option<GpuJobEvent> {
+        if index >= self.states.len() {
+            self.states.resize(index + 1, JobState::default());
+        }
+        let settings = &self.settings;
+        let busy_for = Duration::from_secs(settings.busy_minutes as u64 * 60);
+        let idle_for = Duration::from_secs(settings.idle_minutes as u64 * 60);
+        let state = &mut self.states[index];
+        let now = Instant::now();
+
+        if util >= settings.busy_percent {
+            let busy_since = *state.busy_since.get_or_insert(now);
+            state.idle_since = None;
+            if state.job_since.is_none() && now.duration_since(busy_since) >= busy_for {
+                state.job_since = Some(busy_since);
+                state.finished_at = None;
+                return Some(GpuJobEvent::Started);
+            }
+        } else {
+            state.busy_since = None;
+        }
+
+        if let Some(job_since) = state.job_since {
+            if util < settings.idle_percent {
+                let idle_since = *state.idle_since.get_or_insert(now);
+                if now.duration_since(idle_since) >= idle_for {
+                    *state = JobState {
+                        finished_at: Some(now),
+                        ..JobState::default()
+                    };
+                    return Some(GpuJobEvent::Finished(idle_since.duration_since(job_since)));
+                }
+            } else {
+                state.idle_since = None;
+            }
+        }
+
+        if state
+            .finished_at
+            .is_some_and(|at| now.duration_since(at) >= FINISHED_ALERT)
+        {
+            state.finished_at = None;
+            return Some(GpuJobEvent::Expired);
+        }
+        None
+    }
+}
@@ -0,0 +1,89 @@
+//! # GPU Power State Module
+//!
+//! This module reads the runtime power management state of the display controllers
+//! (`/sys/bus/pci/devices/<addr>/power/`). On hybrid-graphics laptops the discrete GPU
+//! is powered down while idle (PCI runtime PM, NVIDIA's dynamic power management);
+//! every NVML query wakes it up again and costs battery.
+//!
+//! `SystemMonitor` skips NVML polling of suspended NVIDIA GPUs unless the
+//! `poll_suspended_gpu` setting is enabled, and the GPU views show the power state.
+
+use std::path::Path;
+
+const PCI_ROOT: &str = "/sys/bus/pci/devices";
+/// PCI vendor ID of NVIDIA.
+const NVIDIA_VENDOR: &str = "0x10de";
+
+/// Power state of one display controller.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GpuPowerState {
+    /// PCI address, e.g. "0000:01:00.0".
+    pub address: String,
+    pub nvidia: bool,
+    /// Runtime PM status: "active", "suspended", "suspending", "resuming" or
+    /// "unsupported".
+    pub runtime_status: String,
+    /// PCI power state (D0, D3hot, D3cold), on kernels that expose it.
+    pub power_state: Option<String>,
+}
+
+impl GpuPowerState {
+    /// Whether the device is powered down (or on its way there).
+    pub fn is_suspended(&self) -> bool {
+        matches!(self.runtime_status.as_str(), "suspended" | "suspending")
+    }
+
+    /// Short description, e.g. "Active" or "Suspended (D3cold)".
+    pub fn describe(&self) -> String {
+        let status = match self.runtime_status.as_str() {
+            "active" => "Active",
+            "suspended" => "Suspended",
+            "suspending" => "Suspending",
+            "resuming" => "Resuming",
+            _ => "Always on",
+        };
+        match &self.power_state {
+            Some(state) if self.runtime_status != "unsupported" => {
+                format!("{} ({})", status, state)
+            }
+            _ => status.to_string(),
+        }
+    }
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|s| s.trim().to_string())
+}
+
+/// Reads the power state of every display controller (PCI class 0x03), sorted by
+/// address. NVML enumerates NVIDIA GPUs in the same order.
+///
+/// Reading these files does not wake the device.
+pub fn read_power_states() -> Vec<GpuPowerState> {
+    let Ok(entries) = std::fs::read_dir(PCI_ROOT) else {
+        return Vec::new();
+    };
+    let mut states: Vec<GpuPowerState> = entries
+        .flatten()
+        .filter(|e| read_trimmed(&e.path().join("class")).is_some_and(|c| c.starts_with("0x03")))
+        .map(|e| {
+            let dir = e.path();
+            GpuPowerState {
+                address: e.file_name().to_string_lossy().to_string(),
+                nvidia: read_trimmed(&dir.join("vendor")).as_deref() == Some(NVIDIA_VENDOR),
+                runtime_status: read_trimmed(&dir.join("power/runtime_status"))
+                    .unwrap_or_else(|| "unsupported".to_string()),
+                power_state: read_trimmed(&dir.join("power_state")),
+            }
+        })
+        .collect();
+    states.sort_by(|a, b| a.address.cmp(&b.address));
+    states
+}
+
+/// Power state of the NVML device `index`, i.e. the `index`-th NVIDIA controller.
+pub fn nvidia_state(states: &[GpuPowerState], index: usize) -> Option<&GpuPowerState> {
+    states.iter().filter(|s| s.nvidia).nth(index)
+}
@@ -0,0 +1,288 @@
+//! # GPU Session Capture Module
+//!
+//! This module records GPU utilization, VRAM and power draw at a high rate for a labeled
+//! span of time, such as an ML training run, for inclusion in experiment logs. Samples
+//! are taken every `SAMPLE_INTERVAL` in a background thread with its own NVML handle,
+//! independent of the refresh rate.
+//!
+//! Each capture writes two files to `<data dir>/gpu-sessions/`, named after the label
+//! and start time: a CSV with one row per GPU and sample, and a JSON document with the
+//! label, span, GPU names, samples and per-GPU summary. Both are streamed to disk while
+//! recording, so a run of several days does not accumulate in memory, and the CSV stays
+//! usable even if the application is killed before the capture is stopped.
+
+use directories::ProjectDirs;
+use nvml_wrapper::Nvml;
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Time between two samples.
+pub const SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+
+/// One reading of one GPU, as written to the JSON file.
+#[derive(Serialize, Debug, Clone, Copy)]
+struct GpuSample {
+    /// Seconds since the start of the capture.
+    elapsed_secs: f64,
+    gpu: u32,
+    util_percent: u32,
+    mem_used_mb: f64,
+    /// `None` when the GPU does not report its power draw.
+    power_watts: Option<f64>,
+}
+
+/// Per-GPU aggregates over the whole capture.
+#[derive(Serialize, Debug, Clone, Default)]
+struct GpuSummary {
+    gpu: u32,
+    name: String,
+    samples: u64,
+    avg_util_percent: f64,
+    peak_util_percent: u32,
+    peak_mem_used_mb: f64,
+    avg_power_watts: Option<f64>,
+    energy_wh: f64,
+    #[serde(skip)]
+    util_sum: f64,
+    #[serde(skip)]
+    power_sum: f64,
+    #[serde(skip)]
+    power_samples: u64,
+}
+
+impl GpuSummary {
+    fn add(&mut self, sample: &GpuSample) {
+        self.samples += 1;
+        self.util_sum += sample.util_percent as f64;
+        self.avg_util_percent = self.util_sum / self.samples as f64;
+        self.peak_util_percent = self.peak_util_percent.max(sample.util_percent);
+        self.peak_mem_used_mb = self.peak_mem_used_mb.max(sample.mem_used_mb);
+        if let Some(watts) = sample.power_watts {
+            self.power_samples += 1;
+            self.power_sum += watts;
+            self.avg_power_watts = Some(self.power_sum / self.power_samples as f64);
+            self.energy_wh += watts * SAMPLE_INTERVAL.as_secs_f64() / 3600.0;
+        }
+    }
+}
+
+/// Keeps the characters that are safe in a file name, e.g. "resnet50 run #3" ->
+/// "resnet50_run__3".
+fn file_stem(label: &str) -> String {
+    let stem: String = label
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if stem.is_empty() {
+        "session".to_string()
+    } else {
+        stem
+    }
+}
+
+/// Samples every GPU once.
+fn sample(nvml: &Nvml, elapsed_secs: f64) -> Vec<GpuSample> {
+    let count = nvml.device_count().unwrap_or(0);
+    (0..count)
+        .filter_map(|i| {
+            let dev = nvml.device_by_index(i).ok()?;
+            Some(GpuSample {
+                elapsed_secs,
+                gpu: i,
+                util_percent: dev.utilization_rates().map(|u| u.gpu).unwrap_or(0),
+                mem_used_mb: dev
+                    .memory_info()
+                    .map(|m| m.used as f64 / 1024.0 / 1024.0)
+                    .unwrap_or(0.0),
+                power_watts: dev.power_usage().ok().map(|mw| mw as f64 / 1000.0),
+            })
+        })
+        .collect()
+}
+
+/// Writes the CSV header and opens the JSON document up to its samples array.
+fn write_headers(
+    csv: &mut impl Write,
+    json: &mut impl Write,
+    label: &str,
+    started: f64,
+) -> std::io::Result<()> {
+    writeln!(
+        csv,
+        "timestamp,elapsed_secs,gpu,util_percent,mem_used_mb,power_watts"
+    )?;
+    writeln!(
+        json,
+        "{{\n\"label\": {},\n\"started\": {:.3},\n\"interval_ms\": {},\n\"samples\": [",
+        serde_json::to_string(label)?,
+        started,
+        SAMPLE_INTERVAL.as_millis()
+    )
+}
+
+/// The sampling loop: appends CSV rows and JSON samples until `stop` is set, then
+/// closes the JSON document with the end time and summaries.
+fn record(
+    nvml: Nvml,
+    mut csv: BufWriter<File>,
+    mut json: BufWriter<File>,
+    started: f64,
+    stop: Arc<AtomicBool>,
+    count: Arc<AtomicU64>,
+) -> std::io::Result<()> {
+    let mut summaries: Vec<GpuSummary> = (0..nvml.device_count().unwrap_or(0))
+        .map(|i| GpuSummary {
+            gpu: i,
+            name: nvml
+                .device_by_index(i)
+                .and_then(|d| d.name())
+                .unwrap_or_else(|_| format!("GPU {}", i)),
+            ..Default::default()
+        })
+        .collect();
+    let clock = Instant::now();
+    let mut next = clock;
+    let mut first = true;
+    while !stop.load(Ordering::Relaxed) {
+        let elapsed = clock.elapsed().as_secs_f64();
+        for s in sample(&nvml, elapsed) {
+            writeln!(
+                csv,
+                "{:.3},{:.3},{},{},{:.1},{}",
+                started + elapsed,
+                elapsed,
+                s.gpu,
+                s.util_percent,
+                s.mem_used_mb,
+                s.power_watts
+                    .map(|w| format!("{:.2}", w))
+                    .unwrap_or_default()
+            )?;
+            let separator = if first { "" } else { "," };
+            writeln!(json, "{}{}", separator, serde_json::to_string(&s)?)?;
+            first = false;
+            if let Some(summary) = summaries.iter_mut().find(|g| g.gpu == s.gpu) {
+                summary.add(&s);
+            }
+        }
+        let rounds = count.fetch_add(1, Ordering::Relaxed) + 1;
+        // Flush about once a second, so the CSV is current for anyone tailing it
+        if rounds.is_multiple_of(10) {
+            csv.flush()?;
+        }
+        next += SAMPLE_INTERVAL;
+        std::thread::sleep(next.saturating_duration_since(Instant::now()));
+    }
+
+    writeln!(
+        json,
+        "],\n\"ended\": {:.3},\n\"summary\": {}\n}}",
+        started + clock.elapsed().as_secs_f64(),
+        serde_json::to_string_pretty(&summaries)?
+    )?;
+    csv.flush()?;
+    json.flush()
+}
+
+/// A running capture. Dropping it stops the capture and completes the files.
+pub struct GpuSessionRecorder {
+    label: String,
+    csv_path: PathBuf,
+    json_path: PathBuf,
+    started: Instant,
+    /// Sampling rounds taken so far.
+    count: Arc<AtomicU64>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<std::io::Result<()>>>,
+}
+
+impl GpuSessionRecorder {
+    /// Creates the output files and starts sampling.
+    pub fn start(label: &str) -> Result<Self, String> {
+        let nvml = Nvml::init().map_err(|e| format!("NVML unavailable: {}", e))?;
+        let dir = ProjectDirs::from("com", "gjallarhorn", "gjallarhorn")
+            .map(|p| p.data_dir().join("gpu-sessions"))
+            .unwrap_or_else(|| PathBuf::from("gpu-sessions"));
+        fs::create_dir_all(&dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+        let now = crate::history::now();
+        let stamp = crate::csvlog::format_timestamp(now as u64).replace(':', "");
+        let base = format!("{}-{}", file_stem(label), stamp);
+        let csv_path = dir.join(format!("{}.csv", base));
+        let json_path = dir.join(format!("{}.json", base));
+        let create = |path: &PathBuf| {
+            File::create(path)
+                .map(BufWriter::new)
+                .map_err(|e| format!("Failed to create {}: {}", path.display(), e))
+        };
+        let mut csv = create(&csv_path)?;
+        let mut json = create(&json_path)?;
+        write_headers(&mut csv, &mut json, label.trim(), now)
+            .map_err(|e| format!("Failed to write {}: {}", base, e))?;
+
+        let count = Arc::new(AtomicU64::new(0));
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_count = count.clone();
+        let thread_stop = stop.clone();
+        let thread =
+            std::thread::spawn(move || record(nvml, csv, json, now, thread_stop, thread_count));
+
+        Ok(Self {
+            label: label.trim().to_string(),
+            csv_path,
+            json_path,
+            started: Instant::now(),
+            count,
+            stop,
+            thread: Some(thread),
+        })
+    }
+
+    /// e.g. "Recording "resnet50": 2 h 5 min, 75000 samples".
+    pub fn status(&self) -> String {
+        format!(
+            "Recording \"{}\": {}, {} samples",
+            self.label,
+            crate::suspend::format_gap(self.started.elapsed()),
+            self.count.load(Ordering::Relaxed)
+        )
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    /// Stops sampling, completes both files and returns where they were written.
+    pub fn finish(mut self) -> Result<String, String> {
+        self.stop.store(true, Ordering::Relaxed);
+        match self.thread.take().map(JoinHandle::join) {
+            Some(Ok(Ok(()))) => Ok(format!(
+                "Saved {} and {}",
+                self.csv_path.display(),
+                self.json_path.display()
+            )),
+            Some(Ok(Err(e))) => Err(format!("Failed to write capture: {}", e)),
+            _ => Err("Capture thread failed".to_string()),
+        }
+    }
+}
+
+impl Drop for GpuSessionRecorder {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,100 @@
+//! # Graphics API Module
+//!
+//! This module reports the graphics APIs the installed drivers expose, for the GPU
+//! capability view: every Vulkan physical device with its API and driver version, and
+//! the active OpenGL implementation.
+//!
+//! Vulkan data comes from `vulkaninfo --summary` (vulkan-tools) and OpenGL data from
+//! `glxinfo` (see `display.rs`), so it covers every vendor, not just NVML devices.
+//! Both tools take a moment to start, so the report is built once in the background.
+
+use crate::display::{self, GlInfo};
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct VulkanDevice {
+    pub name: String,
+    /// "discrete gpu", "integrated gpu", "cpu", ...
+    pub device_type: String,
+    pub api_version: String,
+    /// Driver name and version, e.g. "radv (Mesa 24.0.5)".
+    pub driver: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct GraphicsApiInfo {
+    /// Version of the Vulkan loader; `None` if `vulkaninfo` is unavailable.
+    pub vulkan_instance: Option<String>,
+    pub vulkan_devices: Vec<VulkanDevice>,
+    pub opengl: Option<GlInfo>,
+}
+
+/// Older `vulkaninfo` versions print encoded versions as "4206830 (1.3.238)".
+fn version_value(value: &str) -> String {
+    match (value.find('('), value.rfind(')')) {
+        (Some(start), Some(end)) if start < end => value[start + 1..end].to_string(),
+        _ => value.to_string(),
+    }
+}
+
+/// Parses `vulkaninfo --summary` into the instance version and the physical devices.
+fn parse_vulkan_summary(text: &str) -> (Option<String>, Vec<VulkanDevice>) {
+    let mut instance = None;
+    let mut devices: Vec<VulkanDevice> = Vec::new();
+    let mut driver_name = String::new();
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if let Some(version) = trimmed.strip_prefix("Vulkan Instance Version:") {
+            instance = Some(version.trim().to_string());
+            continue;
+        }
+        // Device blocks start with "GPU0:", "GPU1:", ...
+        if trimmed.starts_with("GPU")
+            && trimmed.ends_with(':')
+            && !line.starts_with(char::is_whitespace)
+        {
+            devices.push(VulkanDevice::default());
+            driver_name.clear();
+            continue;
+        }
+        let (Some(device), Some((key, value))) = (devices.last_mut(), trimmed.split_once('='))
+        else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "deviceName" => device.name = value.to_string(),
+            "apiVersion" => device.api_version = version_value(value),
+            "deviceType" => {
+                device.device_type = value
+                    .trim_start_matches("PHYSICAL_DEVICE_TYPE_")
+                    .replace('_', " ")
+                    .to_lowercase()
+            }
+            "driverName" => driver_name = value.to_string(),
+            // driverInfo follows driverName in the summary
+            "driverInfo" => device.driver = format!("{} ({})", driver_name, value),
+            _ => {}
+        }
+    }
+    (instance, devices)
+}
+
+/// Collects the Vulkan and OpenGL report. Missing tools leave the matching fields empty.
+pub fn get_graphics_api_info() -> GraphicsApiInfo {
+    let (vulkan_instance, vulkan_devices) =
+        match Command::new("vulkaninfo").arg("--summary").output() {
+            Ok(output) if output.status.success() => {
+                parse_vulkan_summary(&String::from_utf8_lossy(&output.stdout))
+            }
+            _ => (None, Vec::new()),
+        };
+
+    GraphicsApiInfo {
+        vulkan_instance,
+        vulkan_devices,
+        opengl: display::glx_info(),
+    }
+}
@@ -0,0 +1,497 @@
+//! # gRPC Service Module
+//!
+//! This module serves the `gjallarhorn.v1.Metrics` service of `proto/gjallarhorn.proto`,
+//! for tools that integrate Gjallarhorn over gRPC instead of scraping or polling HTTP.
+//! `StreamSnapshots` sends the latest `MetricsSnapshot` and then each new one after
+//! every refresh, and `ListSeries` and `QueryHistory` read the long-term history database
+//! like `gjallarhorn query` (see `cli.rs`). It is compiled only with the `grpc` cargo
+//! feature and started when the `grpc` settings section enables it.
+//!
+//! The messages and the request routing are written out here rather than generated by
+//! `tonic-build`, so building doesn't need `protoc`; they must be kept in sync with the
+//! `.proto` file, which clients generate their stubs from. The server runs on a
+//! single-threaded Tokio runtime in its own thread; history queries run on its blocking
+//! pool, so a slow query never holds up the streams. It binds to localhost by default.
+
+use crate::longterm::{parse_time, LongTermStore};
+use crate::markers::now_secs;
+use crate::settings::GrpcSettings;
+use crate::snapshot::MetricsSnapshot;
+use log::{info, warn};
+use std::convert::Infallible;
+use std::pin::Pin;
+use tokio::sync::{oneshot, watch};
+use tokio_stream::wrappers::{TcpListenerStream, WatchStream};
+use tokio_stream::{Stream, StreamExt};
+use tonic::codegen::{http, Body, BoxFuture, Context, Poll, Service, StdError};
+use tonic::server::{Grpc, ServerStreamingService, UnaryService};
+use tonic::Status;
+use tonic_prost::ProstCodec;
+
+/// Fully qualified service name, the prefix of every method path.
+pub const SERVICE_NAME: &str = "gjallarhorn.v1.Metrics";
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct StreamRequest {}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Snapshot {
+    #[prost(uint32, tag = "1")]
+    pub schema_version: u32,
+    #[prost(uint64, tag = "2")]
+    pub timestamp: u64,
+    #[prost(string, tag = "3")]
+    pub hostname: String,
+    #[prost(uint64, tag = "4")]
+    pub uptime_secs: u64,
+    #[prost(float, tag = "5")]
+    pub cpu_usage_percent: f32,
+    #[prost(float, repeated, tag = "6")]
+    pub cpu_cores_percent: Vec<f32>,
+    #[prost(float, tag = "7")]
+    pub memory_used_gb: f32,
+    #[prost(float, tag = "8")]
+    pub memory_total_gb: f32,
+    #[prost(float, tag = "9")]
+    pub swap_used_gb: f32,
+    #[prost(float, tag = "10")]
+    pub swap_total_gb: f32,
+    #[prost(message, repeated, tag = "11")]
+    pub gpus: Vec<Gpu>,
+    #[prost(message, repeated, tag = "12")]
+    pub networks: Vec<Network>,
+    #[prost(message, repeated, tag = "13")]
+    pub disks: Vec<Disk>,
+    #[prost(message, repeated, tag = "14")]
+    pub fans: Vec<Fan>,
+    #[prost(message, repeated, tag = "15")]
+    pub alerts: Vec<Alert>,
+    #[prost(message, repeated, tag = "16")]
+    pub derived: Vec<Derived>,
+    /// The whole `MetricsSnapshot` as JSON, including the fields without a message type.
+    #[prost(string, tag = "17")]
+    pub json: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Gpu {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(float, tag = "2")]
+    pub util_percent: f32,
+    #[prost(float, tag = "3")]
+    pub mem_used_mb: f32,
+    #[prost(float, tag = "4")]
+    pub mem_total_mb: f32,
+    #[prost(float, optional, tag = "5")]
+    pub temperature: Option<f32>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Network {
+    #[prost(string, tag = "1")]
+    pub interface: String,
+    #[prost(double, tag = "2")]
+    pub rx_bytes_per_sec: f64,
+    #[prost(double, tag = "3")]
+    pub tx_bytes_per_sec: f64,
+    #[prost(uint64, tag = "4")]
+    pub total_rx_bytes: u64,
+    #[prost(uint64, tag = "5")]
+    pub total_tx_bytes: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Disk {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(string, tag = "2")]
+    pub mount_point: String,
+    #[prost(uint64, tag = "3")]
+    pub total_bytes: u64,
+    #[prost(uint64, tag = "4")]
+    pub available_bytes: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Fan {
+    #[prost(string, tag = "1")]
+    pub label: String,
+    #[prost(uint32, tag = "2")]
+    pub rpm: u32,
+    #[prost(float, optional, tag = "3")]
+    pub temperature: Option<f32>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Alert {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(string, tag = "2")]
+    pub severity: String,
+    #[prost(string, tag = "3")]
+    pub message: String,
+    #[prost(uint64, tag = "4")]
+    pub since: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Derived {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(string, tag = "2")]
+    pub unit: String,
+    #[prost(float, tag = "3")]
+    pub value: f32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ListSeriesRequest {}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ListSeriesResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub series: Vec<Series>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Series {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(string, tag = "2")]
+    pub label: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryRequest {
+    #[prost(string, tag = "1")]
+    pub metric: String,
+    /// Start time as accepted by `parse_time`; empty for 24 hours ago.
+    #[prost(string, tag = "2")]
+    pub from: String,
+    /// End time as accepted by `parse_time`; empty for now.
+    #[prost(string, tag = "3")]
+    pub to: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct QueryResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub points: Vec<Point>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Point {
+    #[prost(double, tag = "1")]
+    pub time: f64,
+    #[prost(float, tag = "2")]
+    pub avg: f32,
+    #[prost(float, tag = "3")]
+    pub min: f32,
+    #[prost(float, tag = "4")]
+    pub max: f32,
+}
+
+impl From<&MetricsSnapshot> for Snapshot {
+    fn from(snapshot: &MetricsSnapshot) -> Self {
+        Self {
+            schema_version: snapshot.schema_version,
+            timestamp: snapshot.timestamp,
+            hostname: snapshot.hostname.clone(),
+            uptime_secs: snapshot.uptime_secs,
+            cpu_usage_percent: snapshot.cpu.usage_percent,
+            cpu_cores_percent: snapshot.cpu.cores.clone(),
+            memory_used_gb: snapshot.memory.used_gb,
+            memory_total_gb: snapshot.memory.total_gb,
+            swap_used_gb: snapshot.memory.swap_used_gb,
+            swap_total_gb: snapshot.memory.swap_total_gb,
+            gpus: snapshot
+                .gpus
+                .iter()
+                .map(|g| Gpu {
+                    name: g.name.clone(),
+                    util_percent: g.util_percent,
+                    mem_used_mb: g.mem_used_mb,
+                    mem_total_mb: g.mem_total_mb,
+                    temperature: g.temperature,
+                })
+                .collect(),
+            networks: snapshot
+                .networks
+                .iter()
+                .map(|n| Network {
+                    interface: n.interface.clone(),
+                    rx_bytes_per_sec: n.rx_bytes_per_sec,
+                    tx_bytes_per_sec: n.tx_bytes_per_sec,
+                    total_rx_bytes: n.total_rx_bytes,
+                    total_tx_bytes: n.total_tx_bytes,
+                })
+                .collect(),
+            disks: snapshot
+                .disks
+                .iter()
+                .map(|d| Disk {
+                    name: d.name.clone(),
+                    mount_point: d.mount_point.clone(),
+                    total_bytes: d.total_bytes,
+                    available_bytes: d.available_bytes,
+                })
+                .collect(),
+            fans: snapshot
+                .fans
+                .iter()
+                .map(|f| Fan {
+                    label: f.label.clone(),
+                    rpm: f.rpm,
+                    temperature: f.temperature,
+                })
+                .collect(),
+            alerts: snapshot
+                .alerts
+                .iter()
+                .map(|a| Alert {
+                    id: a.id.clone(),
+                    severity: a.severity.clone(),
+                    message: a.message.clone(),
+                    since: a.since,
+                })
+                .collect(),
+            derived: snapshot
+                .derived
+                .iter()
+                .map(|d| Derived {
+                    name: d.name.clone(),
+                    unit: d.unit.clone(),
+                    value: d.value,
+                })
+                .collect(),
+            json: serde_json::to_string(snapshot).unwrap_or_default(),
+        }
+    }
+}
+
+/// `ListSeries`: the series of the long-term history database.
+fn list_series(_request: ListSeriesRequest) -> Result<ListSeriesResponse, Status> {
+    let store = LongTermStore::open_read_only().map_err(Status::unavailable)?;
+    Ok(ListSeriesResponse {
+        series: store
+            .series()
+            .into_iter()
+            .map(|s| Series {
+                id: s.id,
+                label: s.label,
+            })
+            .collect(),
+    })
+}
+
+/// `QueryHistory`: the stored minutes of one series, like `gjallarhorn query`.
+fn query_history(request: QueryRequest) -> Result<QueryResponse, Status> {
+    let now = now_secs();
+    let time = |value: &str, default: &str| {
+        parse_time(if value.is_empty() { default } else { value }, now)
+            .map_err(Status::invalid_argument)
+    };
+    let from = time(&request.from, "24h ago")?;
+    let to = time(&request.to, "now")?;
+    if from >= to {
+        return Err(Status::invalid_argument("from must be before to"));
+    }
+
+    let store = LongTermStore::open_read_only().map_err(Status::unavailable)?;
+    if !store.series().iter().any(|s| s.id == request.metric) {
+        return Err(Status::not_found(format!(
+            "No long-term history for '{}'",
+            request.metric
+        )));
+    }
+    let points = store
+        .minutes(&request.metric, from, to)
+        .map_err(Status::internal)?;
+    Ok(QueryResponse {
+        points: points
+            .into_iter()
+            .map(|p| Point {
+                time: p.time,
+                avg: p.avg,
+                min: p.min,
+                max: p.max,
+            })
+            .collect(),
+    })
+}
+
+type SnapshotStream = Pin<Box<dyn Stream<Item = Result<Snapshot, Status>> + Send>>;
+
+/// `StreamSnapshots`: follows the snapshots published by `GrpcServer::update`.
+struct StreamSnapshots(watch::Receiver<Option<Snapshot>>);
+
+impl ServerStreamingService<StreamRequest> for StreamSnapshots {
+    type Response = Snapshot;
+    type ResponseStream = SnapshotStream;
+    type Future = BoxFuture<tonic::Response<SnapshotStream>, Status>;
+
+    fn call(&mut self, _request: tonic::Request<StreamRequest>) -> Self::Future {
+        // Starts with the latest snapshot; nothing before the first refresh
+        let stream = WatchStream::new(self.0.clone()).filter_map(|s| s.map(Ok));
+        Box::pin(async move { Ok(tonic::Response::new(Box::pin(stream) as SnapshotStream)) })
+    }
+}
+
+/// A unary method that reads the database, run on the blocking pool.
+struct Blocking<F>(F);
+
+impl<Req, Resp, F> UnaryService<Req> for Blocking<F>
+where
+    F: Fn(Req) -> Result<Resp, Status> + Copy + Send + 'static,
+    Req: Send + 'static,
+    Resp: Send + 'static,
+{
+    type Response = Resp;
+    type Future = BoxFuture<tonic::Response<Resp>, Status>;
+
+    fn call(&mut self, request: tonic::Request<Req>) -> Self::Future {
+        let method = self.0;
+        Box::pin(async move {
+            let request = request.into_inner();
+            tokio::task::spawn_blocking(move || method(request))
+                .await
+                .map_err(|e| Status::internal(e.to_string()))?
+                .map(tonic::Response::new)
+        })
+    }
+}
+
+/// Routes the requests to the methods of `SERVICE_NAME`; it is the only service served.
+#[derive(Clone)]
+struct MetricsService {
+    latest: watch::Receiver<Option<Snapshot>>,
+}
+
+impl<B> Service<http::Request<B>> for MetricsService
+where
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    type Response = http::Response<tonic::body::Body>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let latest = self.latest.clone();
+        Box::pin(async move {
+            Ok(match request.uri().path() {
+                "/gjallarhorn.v1.Metrics/StreamSnapshots" => {
+                    let mut grpc = Grpc::new(ProstCodec::default());
+                    grpc.server_streaming(StreamSnapshots(latest), request)
+                        .await
+                }
+                "/gjallarhorn.v1.Metrics/ListSeries" => {
+                    let mut grpc = Grpc::new(ProstCodec::default());
+                    grpc.unary(Blocking(list_series), request).await
+                }
+                "/gjallarhorn.v1.Metrics/QueryHistory" => {
+                    let mut grpc = Grpc::new(ProstCodec::default());
+                    grpc.unary(Blocking(query_history), request).await
+                }
+                _ => Status::unimplemented(format!("Unknown method {}", request.uri().path()))
+                    .into_http(),
+            })
+        })
+    }
+}
+
+/// Serves the gRPC service until dropped.
+pub struct GrpcServer {
+    latest: watch::Sender<Option<Snapshot>>,
+    /// Dropping it stops the server.
+    _shutdown: oneshot::Sender<()>,
+}
+
+impl GrpcServer {
+    /// Binds the listener and starts the server thread.
+    pub fn start(settings: &GrpcSettings) -> Result<Self, String> {
+        let address = format!("{}:{}", settings.bind_address, settings.port);
+        let listener = std::net::TcpListener::bind(&address)
+            .map_err(|e| format!("Failed to bind {}: {}", address, e))?;
+        listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| format!("Failed to start the gRPC runtime: {}", e))?;
+
+        let (latest, receiver) = watch::channel(None);
+        let (shutdown, stopped) = oneshot::channel::<()>();
+        std::thread::spawn(move || {
+            runtime.block_on(async move {
+                let listener = match tokio::net::TcpListener::from_std(listener) {
+                    Ok(listener) => listener,
+                    Err(e) => {
+                        warn!("gRPC listener error: {}", e);
+                        return;
+                    }
+                };
+                let result = tonic::transport::Server::builder()
+                    .serve_with_incoming_shutdown(
+                        MetricsService { latest: receiver },
+                        TcpListenerStream::new(listener),
+                        async {
+                            let _ = stopped.await;
+                        },
+                    )
+                    .await;
+                if let Err(e) = result {
+                    warn!("gRPC server failed: {}", e);
+                }
+            });
+        });
+        info!("Serving gRPC ({}) on {}", SERVICE_NAME, address);
+
+        Ok(Self {
+            latest,
+            _shutdown: shutdown,
+        })
+    }
+
+    /// Sends `snapshot` to every open `StreamSnapshots` call.
+    pub fn update(&self, snapshot: &MetricsSnapshot) {
+        self.latest.send_replace(Some(Snapshot::from(snapshot)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::{CpuSnapshot, GpuSnapshot};
+    use prost::Message;
+
+    #[test]
+    fn snapshots_survive_the_wire_format() {
+        let snapshot = MetricsSnapshot {
+            timestamp: 1_700_000_000,
+            hostname: "host".to_string(),
+            cpu: CpuSnapshot {
+                usage_percent: 25.0,
+                cores: vec![10.0, 40.0],
+            },
+            gpus: vec![GpuSnapshot {
+                name: "GPU 0".to_string(),
+                util_percent: 99.0,
+                temperature: None,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let message = Snapshot::from(&snapshot);
+        let decoded = Snapshot::decode(message.encode_to_vec().as_slice()).unwrap();
+        assert_eq!(decoded, message);
+        assert_eq!(decoded.cpu_cores_percent, [10.0, 40.0]);
+        assert_eq!(decoded.gpus[0].temperature, None);
+        let json: MetricsSnapshot = serde_json::from_str(&decoded.json).unwrap();
+        assert_eq!(json.hostname, "host");
+    }
+}
@@ -0,0 +1,344 @@
+//! # Health Score Module
+//!
+//! This module condenses the machine's state into one number for the Overview tab. It
+//! adds alert rules for the conditions that matter most for a machine's health —
+//! temperatures, drive SMART status, memory pressure and full file systems — and then
+//! scores the active alerts (from these rules and every other collector) per area, so
+//! the card can point at what pulled the score down.
+//!
+//! The thresholds are the `alert_rules` settings section (`AlertRuleSettings`), which can
+//! be shared between machines as a rule pack (see `rulepack.rs`); the constants below
+//! are its defaults.
+//!
+//! Each area starts at 100 and loses `WARNING_PENALTY` per warning and
+//! `CRITICAL_PENALTY` per critical alert; the overall score is the lowest area score.
+//! Alerts outside the four main areas (limits, certificates, backups, ...) count at a
+//! third of the penalty.
+
+use crate::alerts::{Alert, AlertCenter, AlertSeverity};
+use crate::settings::AlertRuleSettings;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// GPU core temperatures (°C) at which a warning and a critical alert are raised.
+pub const GPU_TEMP_WARN: f32 = 85.0;
+pub const GPU_TEMP_CRITICAL: f32 = 95.0;
+/// hwmon chip temperatures (°C), e.g. CPU package sensors next to fan headers.
+pub const CHIP_TEMP_WARN: f32 = 90.0;
+pub const CHIP_TEMP_CRITICAL: f32 = 100.0;
+/// Drive temperature (°C) used when the drive reports no maximum.
+pub const DRIVE_TEMP_WARN: f32 = 60.0;
+/// Degrees above the warning temperature at which drive alerts become critical.
+pub const DRIVE_TEMP_CRITICAL_MARGIN: f32 = 10.0;
+/// Share of the last 10 s (in percent) in which some tasks stalled on memory, from
+/// `/proc/pressure/memory`, for a warning; all tasks stalling for a critical alert.
+pub const MEMORY_PRESSURE_WARN: f32 = 20.0;
+pub const MEMORY_PRESSURE_CRITICAL: f32 = 10.0;
+
+const WARNING_PENALTY: u32 = 20;
+const CRITICAL_PENALTY: u32 = 50;
+const MEMORY_PRESSURE_PATH: &str = "/proc/pressure/memory";
+
+/// File systems that are full by design.
+const ALWAYS_FULL_FS: [&str; 4] = ["squashfs", "iso9660", "erofs", "udf"];
+
+/// Part of the machine an alert belongs to.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthArea {
+    Thermals,
+    DriveHealth,
+    Memory,
+    DiskSpace,
+    Other,
+}
+
+impl HealthArea {
+    const ALL: [HealthArea; 5] = [
+        HealthArea::Thermals,
+        HealthArea::DriveHealth,
+        HealthArea::Memory,
+        HealthArea::DiskSpace,
+        HealthArea::Other,
+    ];
+
+    /// Area of an alert, from its id.
+    pub fn of(alert_id: &str) -> Self {
+        if alert_id.starts_with("thermal.") {
+            HealthArea::Thermals
+        } else if alert_id.starts_with("smart.") || alert_id.starts_with("mount.") {
+            HealthArea::DriveHealth
+        } else if alert_id.starts_with("memory.") || alert_id.ends_with(".vram") {
+            HealthArea::Memory
+        } else if alert_id.starts_with("disk.") && alert_id.ends_with(".full") {
+            HealthArea::DiskSpace
+        } else {
+            HealthArea::Other
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            HealthArea::Thermals => "Thermals",
+            HealthArea::DriveHealth => "Drive health",
+            HealthArea::Memory => "Memory",
+            HealthArea::DiskSpace => "Disk space",
+            HealthArea::Other => "Other",
+        }
+    }
+
+    /// Usage view tab with the details of the area (see `usage_view.slint`).
+    pub fn tab(&self) -> i32 {
+        match self {
+            HealthArea::Thermals => 6,
+            HealthArea::DriveHealth | HealthArea::DiskSpace => 4,
+            HealthArea::Memory => 1,
+            HealthArea::Other => 8,
+        }
+    }
+}
+
+/// Score of one area and the alerts that lowered it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HealthComponent {
+    pub area: HealthArea,
+    pub score: u8,
+    /// Messages of the area's active alerts, most severe first.
+    pub alerts: Vec<String>,
+    pub critical: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HealthReport {
+    /// 0-100; the lowest area score.
+    pub score: u8,
+    /// One entry per area, in `HealthArea::ALL` order.
+    pub components: Vec<HealthComponent>,
+}
+
+impl HealthReport {
+    /// "Good", "Fair" or "Poor".
+    pub fn grade(&self) -> &'static str {
+        match self.score {
+            90.. => "Good",
+            60.. => "Fair",
+            _ => "Poor",
+        }
+    }
+}
+
+/// Scores the active alerts.
+pub fn assess(alerts: &[Alert]) -> HealthReport {
+    let components: Vec<HealthComponent> = HealthArea::ALL
+        .iter()
+        .map(|&area| {
+            let matching: Vec<&Alert> = alerts
+                .iter()
+                .filter(|a| HealthArea::of(&a.id) == area)
+                .collect();
+            let divisor = if area == HealthArea::Other { 3 } else { 1 };
+            let penalty: u32 = matching
+                .iter()
+                .map(|a| match a.severity {
+                    AlertSeverity::Warning => WARNING_PENALTY,
+                    AlertSeverity::Critical => CRITICAL_PENALTY,
+                } / divisor)
+                .sum();
+            HealthComponent {
+                area,
+                score: 100u32.saturating_sub(penalty) as u8,
+                alerts: matching.iter().map(|a| a.message.clone()).collect(),
+                critical: matching
+                    .iter()
+                    .any(|a| a.severity == AlertSeverity::Critical),
+            }
+        })
+        .collect();
+    HealthReport {
+        score: components.iter().map(|c| c.score).min().unwrap_or(100),
+        components,
+    }
+}
+
+/// `some` and `full` `avg10` of `/proc/pressure/memory` (kernels 4.20+ with PSI).
+pub fn read_memory_pressure() -> Option<(f32, f32)> {
+    let content = std::fs::read_to_string(MEMORY_PRESSURE_PATH).ok()?;
+    let avg10 = |kind: &str| {
+        content
+            .lines()
+            .find(|l| l.starts_with(kind))?
+            .split_whitespace()
+            .find_map(|f| f.strip_prefix("avg10="))?
+            .parse::<f32>()
+            .ok()
+    };
+    Some((avg10("some")?, avg10("full")?))
+}
+
+/// Readings the health rules look at, gathered by `SystemMonitor` on each refresh.
+#[derive(Debug, Clone, Default)]
+pub struct HealthInputs {
+    /// (index, display name, °C) per awake GPU.
+    pub gpu_temps: Vec<(usize, String, f32)>,
+    /// (display name, °C) per hwmon chip.
+    pub chip_temps: Vec<(String, f32)>,
+    /// (device, display name, °C, reported maximum) per drive.
+    pub drive_temps: Vec<(String, String, f32, Option<f32>)>,
+    /// (device, display name, health status from the worker) per drive.
+    pub drive_health: Vec<(String, String, String)>,
+    pub memory_used: u64,
+    pub memory_total: u64,
+    /// See `read_memory_pressure`.
+    pub memory_pressure: Option<(f32, f32)>,
+    /// (mount point, file system, used bytes, total bytes) per mounted file system.
+    pub disks: Vec<(String, String, u64, u64)>,
+}
+
+/// Raises and clears the health rules' alerts.
+#[derive(Debug, Default)]
+pub struct HealthRules {
+    thresholds: AlertRuleSettings,
+    /// Alerts raised on the previous evaluation.
+    raised: HashSet<String>,
+}
+
+impl HealthRules {
+    pub fn new(thresholds: AlertRuleSettings) -> Self {
+        Self {
+            thresholds,
+            raised: HashSet::new(),
+        }
+    }
+
+    /// Evaluates the rules and updates `alerts`: findings are raised, and alerts raised
+    /// before whose condition has recovered are cleared.
+    pub fn apply(&mut self, inputs: &HealthInputs, alerts: &mut AlertCenter) {
+        let mut raised = HashSet::new();
+        for (id, severity, message) in evaluate(inputs, &self.thresholds) {
+            alerts.raise(&id, severity, message);
+            raised.insert(id);
+        }
+        for id in self.raised.difference(&raised) {
+            alerts.clear(id);
+        }
+        self.raised = raised;
+    }
+}
+
+/// Severity of a reading against warning and critical thresholds.
+fn level(value: f32, warn: f32, critical: f32) -> Option<AlertSeverity> {
+    if value >= critical {
+        Some(AlertSeverity::Critical)
+    } else if value >= warn {
+        Some(AlertSeverity::Warning)
+    } else {
+        None
+    }
+}
+
+/// The alerts the readings call for, as (id, severity, message).
+fn evaluate(
+    inputs: &HealthInputs,
+    rules: &AlertRuleSettings,
+) -> Vec<(String, AlertSeverity, String)> {
+    let mut findings = Vec::new();
+
+    for (i, name, temp) in &inputs.gpu_temps {
+        if let Some(severity) = level(*temp, rules.gpu_temp_warn, rules.gpu_temp_critical) {
+            findings.push((
+                format!("thermal.gpu.{}", i),
+                severity,
+                format!("{} at {:.0} °C", name, temp),
+            ));
+        }
+    }
+    for (name, temp) in &inputs.chip_temps {
+        if let Some(severity) = level(*temp, rules.chip_temp_warn, rules.chip_temp_critical) {
+            findings.push((
+                format!("thermal.chip.{}", name),
+                severity,
+                format!("{} at {:.0} °C", name, temp),
+            ));
+        }
+    }
+    for (device, name, temp, max) in &inputs.drive_temps {
+        let warn = max.unwrap_or(rules.drive_temp_warn);
+        if let Some(severity) = level(*temp, warn, warn + rules.drive_temp_critical_margin) {
+            findings.push((
+                format!("thermal.drive.{}", device),
+                severity,
+                format!("Drive {} at {:.0} °C", name, temp),
+            ));
+        }
+    }
+
+    for (device, name, status) in &inputs.drive_health {
+        let severity = match status.as_str() {
+            "Failed" => AlertSeverity::Critical,
+            "Warning" => AlertSeverity::Warning,
+            _ => continue,
+        };
+        findings.push((
+            format!("smart.status.{}", device),
+            severity,
+            format!("Drive {} SMART status: {}", name, status),
+        ));
+    }
+
+    if inputs.memory_total > 0 {
+        let fraction = inputs.memory_used as f32 / inputs.memory_total as f32;
+        if let Some(severity) = level(
+            fraction * 100.0,
+            rules.memory_warn_percent,
+            rules.memory_critical_percent,
+        ) {
+            findings.push((
+                "memory.used".to_string(),
+                severity,
+                format!("Memory {:.0}% used", fraction * 100.0),
+            ));
+        }
+    }
+    if let Some((some, full)) = inputs.memory_pressure {
+        let severity = if full >= rules.memory_pressure_critical {
+            Some(AlertSeverity::Critical)
+        } else if some >= rules.memory_pressure_warn {
+            Some(AlertSeverity::Warning)
+        } else {
+            None
+        };
+        if let Some(severity) = severity {
+            findings.push((
+                "memory.pressure".to_string(),
+                severity,
+                format!(
+                    "Memory pressure: tasks stalled {:.0}% of the last 10 s (all tasks {:.0}%)",
+                    some, full
+                ),
+            ));
+        }
+    }
+
+    for (mount, fs, used, total) in &inputs.disks {
+        if *total == 0 || ALWAYS_FULL_FS.contains(&fs.as_str()) {
+            continue;
+        }
+        let fraction = *used as f32 / *total as f32;
+        if let Some(severity) = level(
+            fraction * 100.0,
+            rules.disk_warn_percent,
+            rules.disk_critical_percent,
+        ) {
+            findings.push((
+                format!("disk.{}.full", mount),
+                severity,
+                format!(
+                    "{} {:.0}% full ({:.1} GB free)",
+                    mount,
+                    fraction * 100.0,
+                    (total - used) as f64 / 1e9
+                ),
+            ));
+        }
+    }
+    findings
+}
@@ -0,0 +1,318 @@
+//! # History Module
+//!
+//! This module provides `History`, the ring buffer behind every chart. Each sample keeps
+//! the wall-clock time it was taken at, so charts are drawn against real time
+//! (`utils::generate_path`): irregular refresh intervals, suspend gaps and refresh-rate
+//! changes no longer stretch or shift the time axis, and exported samples carry the time
+//! they were measured.
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Most samples a single history keeps, whatever the refresh rate and window. Beyond
+/// that, older samples are thinned out (see `History::push_at`).
+pub const MAX_SAMPLES: usize = 3600;
+
+/// Most samples all histories of a `SystemMonitor` keep together (16 bytes each). With
+/// many series (cores, cgroups, sensors, VMs) each gets an equal share of it.
+pub const SAMPLE_BUDGET: usize = 1 << 20;
+
+/// Size and time span of the history buffers, the same for every series of a monitor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HistoryLimits {
+    /// Samples kept per history.
+    pub capacity: usize,
+    /// Time span shown by the charts, in seconds (`history_window_secs` setting).
+    pub window_secs: f64,
+}
+
+impl HistoryLimits {
+    /// Limits for `histories` buffers refreshed every `refresh_rate_ms`: one window of
+    /// samples plus some slack for timer jitter, capped at `MAX_SAMPLES` and at an equal
+    /// share of `SAMPLE_BUDGET`. The count is rounded up to a power of two, so the
+    /// capacity only changes when the number of series doubles or halves.
+    pub fn new(window_secs: u64, refresh_rate_ms: u64, histories: usize) -> Self {
+        let window_secs = window_secs.max(1) as f64;
+        let share = SAMPLE_BUDGET / histories.max(1).next_power_of_two();
+        Self {
+            capacity: (samples_per_window(window_secs, refresh_rate_ms) * 11 / 10 + 1)
+                .min(MAX_SAMPLES)
+                .min(share)
+                .max(2),
+            window_secs,
+        }
+    }
+}
+
+/// Samples one window of `window_secs` holds at `refresh_rate_ms`.
+pub fn samples_per_window(window_secs: f64, refresh_rate_ms: u64) -> usize {
+    (window_secs * 1000.0 / refresh_rate_ms.max(1) as f64).ceil() as usize
+}
+
+/// One measurement.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    /// Seconds since the UNIX epoch.
+    pub time: f64,
+    pub value: f32,
+}
+
+/// Current wall-clock time in (fractional) seconds since the UNIX epoch.
+pub fn now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0)
+}
+
+/// Statistics drawn over a chart's own line (see `History::overlay`).
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct HistoryOverlay {
+    /// Rolling average; empty when the average overlay is off.
+    pub average: History,
+    /// 95th percentile of the samples in the window; `None` when the overlay is off.
+    pub p95: Option<f32>,
+}
+
+/// Timestamped samples, oldest first, holding at most `capacity` samples from the last
+/// `window_secs` seconds.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct History {
+    samples: VecDeque<Sample>,
+    capacity: usize,
+    /// 0 keeps samples of any age.
+    #[serde(default)]
+    window_secs: f64,
+}
+
+impl History {
+    /// Creates an empty history of `capacity` samples of any age.
+    pub const fn new(capacity: usize) -> Self {
+        Self {
+            samples: VecDeque::new(),
+            capacity: if capacity > 1 { capacity } else { 1 },
+            window_secs: 0.0,
+        }
+    }
+
+    /// Creates an empty history holding one chart window (see `HistoryLimits`).
+    pub fn with_limits(limits: HistoryLimits) -> Self {
+        let mut history = Self::new(limits.capacity);
+        history.window_secs = limits.window_secs;
+        history
+    }
+
+    /// Appends `value` stamped with the current time.
+    pub fn push(&mut self, value: f32) {
+        self.push_at(now(), value);
+    }
+
+    /// Appends a sample taken at `time`. Samples that left the window are dropped; if the
+    /// window still holds more samples than fit (a very short refresh interval or a long
+    /// window), every other sample of the older half is dropped instead, so older data
+    /// is kept at a progressively lower resolution.
+    pub fn push_at(&mut self, time: f64, value: f32) {
+        if self.window_secs > 0.0 {
+            let start = time - self.window_secs;
+            while self.samples.front().is_some_and(|s| s.time < start) {
+                self.samples.pop_front();
+            }
+        }
+        if self.samples.len() >= self.capacity {
+            let half = self.samples.len() / 2;
+            let mut index = 0;
+            self.samples.retain(|_| {
+                let keep = index >= half || index % 2 == 0;
+                index += 1;
+                keep
+            });
+        }
+        self.samples.push_back(Sample { time, value });
+    }
+
+    /// Applies new limits (after a refresh-rate or window change); see `resize`. Samples
+    /// that left a shorter window are dropped with the next `push`.
+    pub fn set_limits(&mut self, limits: HistoryLimits) {
+        self.window_secs = limits.window_secs;
+        self.resize(limits.capacity);
+    }
+
+    /// Changes the capacity. When it shrinks, the buffer is thinned out evenly instead of
+    /// truncated, so the whole window stays visible at a lower resolution.
+    pub fn resize(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        let len = self.samples.len();
+        if len <= self.capacity {
+            return;
+        }
+        // Keep every `step`-th sample counting back from the newest one
+        let step = len as f64 / self.capacity as f64;
+        let keep: Vec<usize> = (0..self.capacity)
+            .map(|i| len - 1 - (i as f64 * step) as usize)
+            .collect();
+        self.samples = keep.into_iter().rev().map(|i| self.samples[i]).collect();
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// Drops every sample, keeping the limits.
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Most recent value.
+    pub fn latest(&self) -> Option<f32> {
+        self.samples.back().map(|s| s.value)
+    }
+
+    pub fn latest_sample(&self) -> Option<Sample> {
+        self.samples.back().copied()
+    }
+
+    /// Largest value in the buffer (0 when empty).
+    pub fn max_value(&self) -> f32 {
+        self.values().fold(0.0, f32::max)
+    }
+
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &Sample> + '_ {
+        self.samples.iter()
+    }
+
+    pub fn values(&self) -> impl ExactSizeIterator<Item = f32> + '_ {
+        self.samples.iter().map(|s| s.value)
+    }
+
+    /// The mean of the samples in the `window_secs` up to each sample, at the time of
+    /// that sample. NaN samples stay gaps and are left out of the means.
+    pub fn rolling_average(&self, window_secs: f64) -> History {
+        let mut average = History {
+            samples: VecDeque::with_capacity(self.samples.len()),
+            capacity: self.capacity,
+            window_secs: self.window_secs,
+        };
+        let (mut sum, mut count) = (0.0f64, 0usize);
+        let mut first = 0;
+        for sample in &self.samples {
+            if !sample.value.is_nan() {
+                sum += sample.value as f64;
+                count += 1;
+            }
+            while self.samples[first].time < sample.time - window_secs {
+                let value = self.samples[first].value;
+                if !value.is_nan() {
+                    sum -= value as f64;
+                    count -= 1;
+                }
+                first += 1;
+            }
+            let value = if sample.value.is_nan() || count == 0 {
+                f32::NAN
+            } else {
+                (sum / count as f64) as f32
+            };
+            average.samples.push_back(Sample {
+                time: sample.time,
+                value,
+            });
+        }
+        average
+    }
+
+    /// The `percentile` (0-100, nearest rank) of the samples taken since `since`;
+    /// `None` if there are none.
+    pub fn percentile(&self, percentile: f32, since: f64) -> Option<f32> {
+        let mut values: Vec<f32> = self
+            .samples
+            .iter()
+            .filter(|s| s.time >= since && !s.value.is_nan())
+            .map(|s| s.value)
+            .collect();
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_by(f32::total_cmp);
+        let rank = (percentile.clamp(0.0, 100.0) / 100.0 * values.len() as f32).ceil() as usize;
+        Some(values[rank.clamp(1, values.len()) - 1])
+    }
+
+    /// The rolling average over `average_secs` and the 95th percentile of the chart
+    /// window, each only when requested.
+    pub fn overlay(&self, average_secs: Option<f64>, p95: bool) -> HistoryOverlay {
+        let window_start = if self.window_secs > 0.0 {
+            now() - self.window_secs
+        } else {
+            f64::NEG_INFINITY
+        };
+        HistoryOverlay {
+            average: average_secs
+                .map(|secs| self.rolling_average(secs))
+                .unwrap_or_default(),
+            p95: p95.then(|| self.percentile(95.0, window_start)).flatten(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capacity_covers_one_window() {
+        let limits = HistoryLimits::new(60, 500, 1);
+        assert_eq!(limits.capacity, 120 * 11 / 10 + 1);
+        assert_eq!(limits.window_secs, 60.0);
+        assert_eq!(HistoryLimits::new(3600, 1, 1).capacity, MAX_SAMPLES);
+    }
+
+    #[test]
+    fn budget_is_shared_by_all_histories() {
+        // 1 ms refresh over 30 minutes on a large machine
+        let histories = 3000;
+        let limits = HistoryLimits::new(1800, 1, histories);
+        assert!(limits.capacity * histories <= SAMPLE_BUDGET);
+        // Rounded up to a power of two, so small changes keep the capacity
+        assert_eq!(limits, HistoryLimits::new(1800, 1, 2500));
+    }
+
+    #[test]
+    fn samples_outside_the_window_are_dropped() {
+        let mut history = History::with_limits(HistoryLimits {
+            capacity: 100,
+            window_secs: 10.0,
+        });
+        for time in 0..30 {
+            history.push_at(time as f64, time as f32);
+        }
+        assert_eq!(history.len(), 11);
+        assert_eq!(history.iter().next().map(|s| s.time), Some(19.0));
+
+        history.set_limits(HistoryLimits {
+            capacity: 4,
+            window_secs: 10.0,
+        });
+        assert_eq!(history.len(), 4);
+        assert_eq!(history.latest(), Some(29.0));
+    }
+
+    #[test]
+    fn full_history_thins_out_older_samples() {
+        let mut history = History::new(8);
+        for time in 0..100 {
+            history.push_at(time as f64, time as f32);
+        }
+        assert!(history.len() <= 8);
+        assert_eq!(history.latest(), Some(99.0));
+        assert_eq!(history.iter().next().map(|s| s.time), Some(0.0));
+    }
+}
@@ -0,0 +1,109 @@
+//! # HTTP Server Module
+//!
+//! This module is the small HTTP server behind the Prometheus exporter and the streaming
+//! overlay: one background thread on a non-blocking `TcpListener` that answers `GET`
+//! requests one at a time and closes each connection. Only the request line is read, with
+//! a timeout and a size limit, so a slow or hostile client can't hold the thread or its
+//! memory.
+
+use log::warn;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Time between two checks for new connections and the stop flag.
+const ACCEPT_INTERVAL: Duration = Duration::from_millis(100);
+/// Time a client gets to send its request line.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+/// Longest request line read; longer requests are answered with 400.
+const MAX_REQUEST_LINE: u64 = 8192;
+
+/// Body of a successful response.
+pub struct Response {
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+/// The path of a `GET` request line, without the query string.
+fn request_path(request_line: &str) -> Option<&str> {
+    let mut parts = request_line.split_whitespace();
+    match (parts.next(), parts.next()) {
+        (Some("GET"), Some(path)) => path.split('?').next(),
+        _ => None,
+    }
+}
+
+/// Answers one request with `route`; unknown paths get 404.
+fn handle(stream: TcpStream, route: &dyn Fn(&str) -> Option<Response>) -> std::io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    let mut request_line = String::new();
+    BufReader::new(&stream)
+        .take(MAX_REQUEST_LINE)
+        .read_line(&mut request_line)?;
+    let response = if !request_line.ends_with('\n') {
+        "HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".to_string()
+    } else {
+        match request_path(&request_line).and_then(route) {
+            Some(response) => format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n{}",
+                response.content_type,
+                response.body.len(),
+                response.body
+            ),
+            None => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                .to_string(),
+        }
+    };
+    (&stream).write_all(response.as_bytes())
+}
+
+/// Serves requests until dropped.
+pub struct HttpServer {
+    stop: Arc<AtomicBool>,
+}
+
+impl HttpServer {
+    /// Binds `address` and starts the server thread, which answers each `GET` with
+    /// `route(path)`. `name` prefixes the log messages, e.g. "Prometheus".
+    pub fn start(
+        name: &'static str,
+        address: &str,
+        route: impl Fn(&str) -> Option<Response> + Send + 'static,
+    ) -> Result<Self, String> {
+        let listener =
+            TcpListener::bind(address).map_err(|e| format!("Failed to bind {}: {}", address, e))?;
+        listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = stop.clone();
+        std::thread::spawn(move || {
+            while !thread_stop.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        if let Err(e) = handle(stream, &route) {
+                            warn!("{} request failed: {}", name, e);
+                        }
+                    }
+                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                        std::thread::sleep(ACCEPT_INTERVAL);
+                    }
+                    Err(e) => {
+                        warn!("{} listener error: {}", name, e);
+                        std::thread::sleep(ACCEPT_INTERVAL);
+                    }
+                }
+            }
+        });
+
+        Ok(Self { stop })
+    }
+}
+
+impl Drop for HttpServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,343 @@
+//! # Hardware Monitoring Module
+//!
+//! This module finds the temperature, fan and voltage inputs the kernel's hwmon drivers
+//! expose under `/sys/class/hwmon`, and reads fan PWM duty cycles.
+//!
+//! `HwmonScanner` lists the inputs of every chip (with their labels) and only walks the
+//! chip directories again when chips come or go. The readings and their history are
+//! kept by `sensors::SensorTracker`; `FanTracker` adds the PWM state of each fan, so the
+//! UI can plot fan RPM against the first temperature sensor of the same chip. Writing
+//! PWM values requires root and is therefore only done by the privileged helper (see
+//! `worker::set_fan_pwm`).
+
+use crate::history::History;
+use crate::monitor::SysPaths;
+use crate::sensors::SensorTracker;
+use serde::{Deserialize, Serialize};
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+/// Sysfs directory containing one `hwmonN` entry per sensor chip.
+pub const HWMON_ROOT: &str = "/sys/class/hwmon";
+/// `HWMON_ROOT` relative to the `/sys` root, for trackers reading a `SysPaths` tree.
+pub const HWMON_DIR: &str = "class/hwmon";
+
+/// Kind of a hwmon input, from the prefix of its file name.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SensorKind {
+    Temperature,
+    Fan,
+    Voltage,
+}
+
+impl SensorKind {
+    const ALL: [SensorKind; 3] = [
+        SensorKind::Temperature,
+        SensorKind::Fan,
+        SensorKind::Voltage,
+    ];
+
+    /// Sysfs file prefix, e.g. "temp" for `temp1_input`.
+    pub fn file_prefix(self) -> &'static str {
+        match self {
+            SensorKind::Temperature => "temp",
+            SensorKind::Fan => "fan",
+            SensorKind::Voltage => "in",
+        }
+    }
+
+    /// A raw sysfs value in `unit()`: temperatures are in millidegrees Celsius and
+    /// voltages in millivolts, fan speeds already in RPM.
+    pub fn scale(self, raw: i64) -> f32 {
+        match self {
+            SensorKind::Temperature | SensorKind::Voltage => raw as f32 / 1000.0,
+            SensorKind::Fan => raw as f32,
+        }
+    }
+
+    pub fn unit(self) -> &'static str {
+        match self {
+            SensorKind::Temperature => "°C",
+            SensorKind::Fan => "RPM",
+            SensorKind::Voltage => "V",
+        }
+    }
+
+    /// e.g. "45.5 °C", "1450 RPM" or "1.215 V".
+    pub fn format(self, value: f32) -> String {
+        match self {
+            SensorKind::Temperature => format!("{:.1} {}", value, self.unit()),
+            SensorKind::Fan => format!("{:.0} {}", value, self.unit()),
+            SensorKind::Voltage => format!("{:.3} {}", value, self.unit()),
+        }
+    }
+}
+
+/// Kind and channel of an input file name, e.g. `(Fan, 2)` for "fan2_input".
+pub fn parse_input(file_name: &str) -> Option<(SensorKind, u32)> {
+    let stem = file_name.strip_suffix("_input")?;
+    SensorKind::ALL.into_iter().find_map(|kind| {
+        let index = stem.strip_prefix(kind.file_prefix())?.parse().ok()?;
+        Some((kind, index))
+    })
+}
+
+/// Reads `<dir>/<prefix><index>_<suffix>` (e.g. `temp1_crit`) in the unit of `kind`.
+pub fn read_input(dir: &Path, kind: SensorKind, index: u32, suffix: &str) -> Option<f32> {
+    let file = format!("{}{}_{}", kind.file_prefix(), index, suffix);
+    read_value::<i64>(&dir.join(file)).map(|raw| kind.scale(raw))
+}
+
+/// One input of a chip, e.g. `temp1_input` of `hwmon2`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HwmonInput {
+    /// Chip directory name (e.g., "hwmon3").
+    pub chip: String,
+    /// Driver name from the chip's `name` file (e.g., "nct6798").
+    pub driver: String,
+    pub kind: SensorKind,
+    /// Channel number (`N` in `tempN_input`).
+    pub index: u32,
+    /// Contents of the `*_label` file, or the file prefix and channel (e.g. "in3").
+    pub label: String,
+}
+
+/// Lists the inputs of every chip, walking the chip directories only when chips come
+/// or go (hot-plugged USB controllers, eGPUs, drivers loaded late).
+pub struct HwmonScanner {
+    root: PathBuf,
+    /// Chip directory names of the last walk.
+    chips: Vec<String>,
+    inputs: Vec<HwmonInput>,
+}
+
+impl HwmonScanner {
+    /// Scans the chips in `root` (normally `/sys/class/hwmon`).
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            chips: Vec::new(),
+            inputs: Vec::new(),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// The inputs of all chips, sorted by chip, kind and channel. Only the list of chips
+    /// is read unless it changed since the last call.
+    pub fn scan(&mut self) -> &[HwmonInput] {
+        let mut chips: Vec<String> = std::fs::read_dir(&self.root)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|e| e.file_name().to_string_lossy().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        chips.sort();
+        if chips != self.chips {
+            self.inputs = chips
+                .iter()
+                .flat_map(|chip| Self::chip_inputs(&self.root, chip))
+                .collect();
+            self.chips = chips;
+        }
+        &self.inputs
+    }
+
+    fn chip_inputs(root: &Path, chip: &str) -> Vec<HwmonInput> {
+        let chip_dir = root.join(chip);
+        let driver = std::fs::read_to_string(chip_dir.join("name"))
+            .map(|s| s.trim().to_string())
+            .unwrap_or_else(|_| chip.to_string());
+        let Ok(files) = std::fs::read_dir(&chip_dir) else {
+            return Vec::new();
+        };
+
+        let mut inputs: Vec<HwmonInput> = files
+            .flatten()
+            .filter_map(|file| parse_input(&file.file_name().to_string_lossy()))
+            .map(|(kind, index)| {
+                let prefix = kind.file_prefix();
+                let label =
+                    std::fs::read_to_string(chip_dir.join(format!("{}{}_label", prefix, index)))
+                        .map(|s| s.trim().to_string())
+                        .unwrap_or_else(|_| format!("{}{}", prefix, index));
+                HwmonInput {
+                    chip: chip.to_string(),
+                    driver: driver.clone(),
+                    kind,
+                    index,
+                    label,
+                }
+            })
+            .collect();
+        inputs.sort_by_key(|i| (i.kind, i.index));
+        inputs
+    }
+}
+
+/// Holds data for a single fan for external consumers
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FanData {
+    /// Chip directory name (e.g., "hwmon3").
+    pub chip: String,
+    /// Fan channel number (`N` in `fanN_input`).
+    pub index: u32,
+    /// Display label, e.g. "nct6798: CPU Fan" or "nct6798: fan2".
+    pub label: String,
+    pub rpm: u32,
+    pub temperature: Option<f32>,
+    pub rpm_history: History,
+    /// Highest RPM seen so far (at least 1000), used to scale the RPM chart.
+    pub rpm_scale: f32,
+    pub temp_history: History,
+    /// Whether the chip exposes a writable `pwmN` file for this fan.
+    pub controllable: bool,
+    /// Current PWM duty cycle (0-255), if readable.
+    pub pwm: Option<u8>,
+    /// `true` when the fan is under automatic (firmware/driver) control.
+    pub pwm_auto: bool,
+}
+
+struct FanEntry {
+    chip: String,
+    index: u32,
+    pwm: Option<u8>,
+    pwm_auto: bool,
+}
+
+/// Reads the PWM state of every fan found by the `SensorTracker`, which keeps the RPM
+/// and temperature history.
+pub struct FanTracker {
+    root: PathBuf,
+    entries: Vec<FanEntry>,
+}
+
+fn read_value<T: std::str::FromStr>(path: &Path) -> Option<T> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|s| s.trim().parse::<T>().ok())
+}
+
+/// Returns `true` if `pwmN` exists and is owner-writable (i.e. settable by root).
+fn pwm_writable(chip_dir: &Path, index: u32) -> bool {
+    std::fs::metadata(chip_dir.join(format!("pwm{}", index)))
+        .map(|m| m.permissions().mode() & 0o200 != 0)
+        .unwrap_or(false)
+}
+
+impl FanTracker {
+    /// Reads the chips under the `/sys` root of `paths`.
+    pub fn new(paths: &SysPaths) -> Self {
+        Self {
+            root: paths.sys.join(HWMON_DIR),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Reads the PWM state of every fan input of `sensors` (refreshed first).
+    pub fn refresh(&mut self, sensors: &SensorTracker) {
+        self.entries = sensors
+            .sensors()
+            .iter()
+            .filter(|s| s.kind == SensorKind::Fan)
+            .map(|s| {
+                let dir = self.root.join(&s.chip);
+                FanEntry {
+                    chip: s.chip.clone(),
+                    index: s.index,
+                    pwm: read_value(&dir.join(format!("pwm{}", s.index))),
+                    // pwmN_enable: 0 = full speed, 1 = manual, 2+ = automatic modes
+                    pwm_auto: read_value::<u32>(&dir.join(format!("pwm{}_enable", s.index)))
+                        .map(|mode| mode != 1)
+                        .unwrap_or(true),
+                }
+            })
+            .collect();
+    }
+
+    /// Every fan with its RPM and the first temperature of its chip, from `sensors`.
+    pub fn get_data(&self, sensors: &SensorTracker) -> Vec<FanData> {
+        let sensors = sensors.sensors();
+        self.entries
+            .iter()
+            .filter_map(|e| {
+                let fan = sensors.iter().find(|s| {
+                    s.kind == SensorKind::Fan && s.chip == e.chip && s.index == e.index
+                })?;
+                let temp = sensors
+                    .iter()
+                    .find(|s| s.kind == SensorKind::Temperature && s.chip == e.chip);
+                Some(FanData {
+                    chip: e.chip.clone(),
+                    index: e.index,
+                    label: format!("{}: {}", fan.driver, fan.label),
+                    rpm: fan.value.unwrap_or(0.0) as u32,
+                    temperature: temp.and_then(|t| t.value),
+                    rpm_history: fan.history.clone(),
+                    rpm_scale: fan.scale,
+                    temp_history: temp
+                        .map(|t| t.history.clone())
+                        .unwrap_or_else(|| History::new(0)),
+                    controllable: pwm_writable(&self.root.join(&e.chip), e.index),
+                    pwm: e.pwm,
+                    pwm_auto: e.pwm_auto,
+                })
+            })
+            .collect()
+    }
+}
+
+impl Default for FanTracker {
+    fn default() -> Self {
+        Self::new(&SysPaths::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn input_files_are_parsed_by_prefix() {
+        assert_eq!(
+            parse_input("temp1_input"),
+            Some((SensorKind::Temperature, 1))
+        );
+        assert_eq!(parse_input("fan12_input"), Some((SensorKind::Fan, 12)));
+        assert_eq!(parse_input("in0_input"), Some((SensorKind::Voltage, 0)));
+    }
+
+    #[test]
+    fn other_files_are_not_inputs() {
+        for name in [
+            "temp1_label",
+            "temp1_crit",
+            "fan_input",
+            "tempX_input",
+            "intrusion0_input",
+            "power1_input",
+            "name",
+        ] {
+            assert_eq!(parse_input(name), None, "{}", name);
+        }
+    }
+
+    #[test]
+    fn raw_values_are_scaled_to_the_unit() {
+        assert_eq!(SensorKind::Temperature.scale(45_500), 45.5);
+        assert_eq!(SensorKind::Temperature.scale(-5_000), -5.0);
+        assert_eq!(SensorKind::Voltage.scale(1_215), 1.215);
+        assert_eq!(SensorKind::Fan.scale(1450), 1450.0);
+    }
+
+    #[test]
+    fn values_are_formatted_with_their_unit() {
+        assert_eq!(SensorKind::Temperature.format(45.54), "45.5 °C");
+        assert_eq!(SensorKind::Fan.format(1450.4), "1450 RPM");
+        assert_eq!(SensorKind::Voltage.format(1.2154), "1.215 V");
+    }
+}
@@ -0,0 +1,233 @@
+//! # User Idle Module
+//!
+//! This module tracks how long the user has been away from keyboard and mouse, and splits
+//! the session into active and idle time with the average CPU and GPU usage of each. Heavy
+//! CPU use while nobody is at the machine points at background work such as a crypto
+//! miner or a runaway file indexer.
+//!
+//! The idle time comes from, in order of preference:
+//! - GNOME's `org.gnome.Mutter.IdleMonitor` (via `gdbus`), which also works on Wayland.
+//! - `xprintidle` on X11 sessions.
+//! - The logind `IdleHint` of the current session (via `loginctl`), which most Wayland
+//!   compositors set through their idle manager.
+
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Seconds between two idle-time queries (each one spawns a process).
+const IDLE_POLL_INTERVAL_SECS: u64 = 5;
+/// Input inactivity after which the user counts as idle.
+pub const IDLE_THRESHOLD: Duration = Duration::from_secs(120);
+
+/// GNOME (Mutter) idle time in milliseconds, e.g. `(uint64 12345,)`.
+fn mutter_idle() -> Option<Duration> {
+    let output = Command::new("gdbus")
+        .args([
+            "call",
+            "--session",
+            "--dest",
+            "org.gnome.Mutter.IdleMonitor",
+            "--object-path",
+            "/org/gnome/Mutter/IdleMonitor/Core",
+            "--method",
+            "org.gnome.Mutter.IdleMonitor.GetIdletime",
+        ])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let ms = text
+        .trim()
+        .trim_start_matches("(uint64 ")
+        .trim_end_matches(",)")
+        .parse()
+        .ok()?;
+    Some(Duration::from_millis(ms))
+}
+
+/// X11 idle time in milliseconds.
+fn xprintidle() -> Option<Duration> {
+    std::env::var_os("DISPLAY")?;
+    let output = Command::new("xprintidle")
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    let ms = String::from_utf8_lossy(&output.stdout)
+        .trim()
+        .parse()
+        .ok()?;
+    Some(Duration::from_millis(ms))
+}
+
+/// logind session idle state: `IdleHint=yes` plus the monotonic time it was set at.
+fn logind_idle() -> Option<Duration> {
+    let session = std::env::var("XDG_SESSION_ID").ok()?;
+    let output = Command::new("loginctl")
+        .args([
+            "show-session",
+            &session,
+            "-p",
+            "IdleHint",
+            "-p",
+            "IdleSinceHintMonotonic",
+        ])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    let text = String::from_utf8_lossy(&output.stdout);
+    let mut idle = false;
+    let mut since_us: u64 = 0;
+    for line in text.lines() {
+        match line.split_once('=') {
+            Some(("IdleHint", value)) => idle = value == "yes",
+            Some(("IdleSinceHintMonotonic", value)) => since_us = value.parse().unwrap_or(0),
+            _ => {}
+        }
+    }
+    if !idle {
+        return Some(Duration::ZERO);
+    }
+    // CLOCK_MONOTONIC, the clock logind's timestamp is based on
+    let uptime = std::fs::read_to_string("/proc/uptime").ok()?;
+    let now_secs: f64 = uptime.split_whitespace().next()?.parse().ok()?;
+    let since = Duration::from_micros(since_us);
+    Some(Duration::from_secs_f64(now_secs).saturating_sub(since))
+}
+
+/// Current idle time from the first source that answers.
+pub fn idle_time() -> Option<Duration> {
+    mutter_idle().or_else(xprintidle).or_else(logind_idle)
+}
+
+/// Active and idle time of the session, with accumulated usage for the averages.
+#[derive(Debug, Clone, Default)]
+pub struct SessionStats {
+    pub active_secs: f64,
+    pub idle_secs: f64,
+    /// Usage percent × seconds while active and while idle.
+    cpu_active: f64,
+    cpu_idle: f64,
+    gpu_active: f64,
+    gpu_idle: f64,
+}
+
+impl SessionStats {
+    pub fn avg_cpu_active(&self) -> Option<f32> {
+        (self.active_secs > 0.0).then(|| (self.cpu_active / self.active_secs) as f32)
+    }
+
+    pub fn avg_cpu_idle(&self) -> Option<f32> {
+        (self.idle_secs > 0.0).then(|| (self.cpu_idle / self.idle_secs) as f32)
+    }
+
+    pub fn avg_gpu_active(&self) -> Option<f32> {
+        (self.active_secs > 0.0).then(|| (self.gpu_active / self.active_secs) as f32)
+    }
+
+    pub fn avg_gpu_idle(&self) -> Option<f32> {
+        (self.idle_secs > 0.0).then(|| (self.gpu_idle / self.idle_secs) as f32)
+    }
+}
+
+/// Formats a duration in seconds as e.g. "45 s", "12 min" or "3 h 5 min".
+fn format_secs(secs: f64) -> String {
+    crate::suspend::format_gap(Duration::from_secs_f64(secs.max(0.0)))
+}
+
+impl std::fmt::Display for SessionStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let avg = |v: Option<f32>| v.map_or("-".to_string(), |v| format!("{:.0}%", v));
+        write!(
+            f,
+            "Active {} (CPU {}, GPU {}) · Idle {} (CPU {}, GPU {})",
+            format_secs(self.active_secs),
+            avg(self.avg_cpu_active()),
+            avg(self.avg_gpu_active()),
+            format_secs(self.idle_secs),
+            avg(self.avg_cpu_idle()),
+            avg(self.avg_gpu_idle()),
+        )
+    }
+}
+
+/// Polls the idle time in a background thread and accumulates `SessionStats`.
+pub struct IdleTracker {
+    latest: Arc<Mutex<Option<Duration>>>,
+    stop: Arc<AtomicBool>,
+    last_record: Option<Instant>,
+    pub stats: SessionStats,
+}
+
+impl IdleTracker {
+    /// Starts querying the idle time every `IDLE_POLL_INTERVAL_SECS`.
+    pub fn start() -> Self {
+        let latest = Arc::new(Mutex::new(None));
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_latest = latest.clone();
+        let thread_stop = stop.clone();
+        std::thread::spawn(move || {
+            while !thread_stop.load(Ordering::Relaxed) {
+                let idle = idle_time();
+                if let Ok(mut guard) = thread_latest.lock() {
+                    *guard = idle;
+                }
+                std::thread::sleep(Duration::from_secs(IDLE_POLL_INTERVAL_SECS));
+            }
+        });
+
+        Self {
+            latest,
+            stop,
+            last_record: None,
+            stats: SessionStats::default(),
+        }
+    }
+
+    /// Latest idle time, or `None` when no source is available.
+    pub fn idle_time(&self) -> Option<Duration> {
+        self.latest.lock().ok().and_then(|guard| *guard)
+    }
+
+    /// Whether the user has been inactive for at least `IDLE_THRESHOLD`.
+    pub fn is_idle(&self) -> bool {
+        self.idle_time().is_some_and(|t| t >= IDLE_THRESHOLD)
+    }
+
+    /// Adds the time since the previous call to the active or idle total, weighted by the
+    /// current CPU and GPU usage. Nothing is recorded while the idle time is unknown.
+    pub fn record(&mut self, cpu_percent: f32, gpu_percent: f32) {
+        let now = Instant::now();
+        let elapsed = self
+            .last_record
+            .map(|last| now.duration_since(last).as_secs_f64());
+        self.last_record = Some(now);
+        let (Some(elapsed), Some(_)) = (elapsed, self.idle_time()) else {
+            return;
+        };
+        let (cpu, gpu) = (cpu_percent as f64 * elapsed, gpu_percent as f64 * elapsed);
+        let idle = self.is_idle();
+        let stats = &mut self.stats;
+        if idle {
+            stats.idle_secs += elapsed;
+            stats.cpu_idle += cpu;
+            stats.gpu_idle += gpu;
+        } else {
+            stats.active_secs += elapsed;
+            stats.cpu_active += cpu;
+            stats.gpu_active += gpu;
+        }
+    }
+
+    /// Forgets the time since the previous refresh (after a suspend).
+    pub fn reset_baseline(&mut self) {
+        self.last_record = None;
+    }
+}
+
+impl Drop for IdleTracker {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,110 @@
+//! # Idle Action Module
+//!
+//! This module implements the opt-in `idle_action` automation: once CPU, GPU and network
+//! have all stayed below their thresholds for `idle_minutes` (and, by default, nobody
+//! has touched keyboard or mouse), the machine is suspended or shut down. This suits
+//! workstations left running overnight for a job that has since finished.
+//!
+//! Nothing happens without warning: the main window first shows a countdown banner for
+//! `countdown_secs` with a Cancel button. Any activity during the countdown also stops
+//! it. After a cancel, the machine must be idle for a full `idle_minutes` again. The
+//! action itself runs through the privileged `--power-action` helper (via `pkexec`).
+
+use crate::settings::{IdleActionSettings, PowerAction};
+use std::time::{Duration, Instant};
+
+/// Activity of the machine during the last refresh.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IdleInputs {
+    /// Overall CPU usage (%).
+    pub cpu_percent: f32,
+    /// Utilization (%) of the busiest GPU.
+    pub gpu_percent: f32,
+    /// Combined receive and transmit rate (KB/s).
+    pub network_kbps: f32,
+    /// No keyboard or mouse input for `idle::IDLE_THRESHOLD`.
+    pub user_idle: bool,
+}
+
+/// Tracks the idle stretch and the countdown.
+#[derive(Debug, Default)]
+pub struct IdleActionWatcher {
+    settings: IdleActionSettings,
+    /// Start of the current stretch with every metric below its threshold.
+    idle_since: Option<Instant>,
+    /// When the action runs, while the countdown is shown.
+    deadline: Option<Instant>,
+}
+
+impl IdleActionWatcher {
+    pub fn new(settings: &IdleActionSettings) -> Self {
+        Self {
+            settings: settings.clone(),
+            idle_since: None,
+            deadline: None,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.settings.enabled
+    }
+
+    fn is_idle(&self, inputs: &IdleInputs) -> bool {
+        let settings = &self.settings;
+        inputs.cpu_percent < settings.cpu_percent
+            && inputs.gpu_percent < settings.gpu_percent
+            && inputs.network_kbps < settings.network_kbps
+            && (inputs.user_idle || !settings.require_user_idle)
+    }
+
+    /// Feeds one refresh; returns the action once its countdown has run out.
+    pub fn update(&mut self, inputs: &IdleInputs) -> Option<PowerAction> {
+        if !self.is_idle(inputs) {
+            self.cancel();
+            return None;
+        }
+        let now = Instant::now();
+        let idle_since = *self.idle_since.get_or_insert(now);
+        let idle_for = Duration::from_secs(self.settings.idle_minutes as u64 * 60);
+        match self.deadline {
+            None if now.duration_since(idle_since) >= idle_for => {
+                self.deadline =
+                    Some(now + Duration::from_secs(self.settings.countdown_secs as u64));
+                None
+            }
+            Some(deadline) if now >= deadline => {
+                self.cancel();
+                Some(self.settings.action)
+            }
+            _ => None,
+        }
+    }
+
+    /// Time left before the action runs, while the countdown is shown.
+    pub fn remaining(&self) -> Option<Duration> {
+        self.deadline
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+
+    /// Banner text while the countdown runs, e.g. "Idle for 30 min: suspending in 42 s".
+    pub fn countdown_text(&self) -> Option<String> {
+        let verb = match self.settings.action {
+            PowerAction::Suspend => "suspending",
+            PowerAction::Shutdown => "shutting down",
+        };
+        self.remaining().map(|left| {
+            format!(
+                "Idle for {} min: {} in {} s",
+                self.settings.idle_minutes,
+                verb,
+                left.as_secs()
+            )
+        })
+    }
+
+    /// Stops the countdown; a full idle stretch is needed before the next one.
+    pub fn cancel(&mut self) {
+        self.idle_since = None;
+        self.deadline = None;
+    }
+}
@@ -0,0 +1,185 @@
+//! # IOMMU Groups Module
+//!
+//! This module lists the IOMMU groups (`/sys/kernel/iommu_groups`) with the PCI devices
+//! in each and the driver they are bound to, for GPU passthrough setups. Devices in one
+//! group can only be passed to a VM together, so a GPU that shares its group with other
+//! devices needs all of them bound to `vfio-pci` (or an ACS override).
+//!
+//! Vendor and device names come from the `pci.ids` database when it is installed.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+const IOMMU_GROUPS_ROOT: &str = "/sys/kernel/iommu_groups";
+const PCI_IDS_PATHS: [&str; 3] = [
+    "/usr/share/hwdata/pci.ids",
+    "/usr/share/misc/pci.ids",
+    "/usr/share/pci.ids",
+];
+
+/// One PCI device of an IOMMU group.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct IommuDevice {
+    /// PCI address, e.g. "0000:01:00.0".
+    pub address: String,
+    /// e.g. "NVIDIA Corporation GA102 [GeForce RTX 3090]", or the IDs when unknown.
+    pub name: String,
+    /// e.g. "VGA controller"
+    pub class: String,
+    /// Bound driver, e.g. "nvidia" or "vfio-pci"; `None` when unbound.
+    pub driver: Option<String>,
+    /// Display controller (PCI class 0x03).
+    pub is_gpu: bool,
+}
+
+impl IommuDevice {
+    pub fn is_vfio(&self) -> bool {
+        self.driver.as_deref() == Some("vfio-pci")
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct IommuGroup {
+    pub id: u32,
+    pub devices: Vec<IommuDevice>,
+}
+
+impl IommuGroup {
+    /// Whether the group holds a GPU.
+    pub fn has_gpu(&self) -> bool {
+        self.devices.iter().any(|d| d.is_gpu)
+    }
+
+    /// Whether the group can be passed through as is: every device, except PCI bridges
+    /// (which stay with the host), is bound to `vfio-pci`.
+    pub fn ready_for_passthrough(&self) -> bool {
+        self.devices
+            .iter()
+            .filter(|d| d.class != "PCI bridge")
+            .all(IommuDevice::is_vfio)
+    }
+}
+
+fn read_trimmed(path: &Path) -> Option<String> {
+    std::fs::read_to_string(path)
+        .ok()
+        .map(|s| s.trim().to_string())
+}
+
+/// Vendor and device names from `pci.ids`, keyed by "vvvv" and "vvvv:dddd".
+fn load_pci_names() -> HashMap<String, String> {
+    let Some(text) = PCI_IDS_PATHS
+        .iter()
+        .find_map(|p| std::fs::read_to_string(p).ok())
+    else {
+        return HashMap::new();
+    };
+    let mut names = HashMap::new();
+    let mut vendor = String::new();
+    for line in text.lines() {
+        if line.starts_with('#') || line.is_empty() {
+            continue;
+        }
+        // The device classes section follows the vendors
+        if line.starts_with("C ") {
+            break;
+        }
+        if let Some(rest) = line.strip_prefix('\t') {
+            // Subsystem lines are indented twice
+            if rest.starts_with('\t') {
+                continue;
+            }
+            if let Some((id, name)) = rest.split_once("  ") {
+                names.insert(format!("{}:{}", vendor, id), name.to_string());
+            }
+        } else if let Some((id, name)) = line.split_once("  ") {
+            vendor = id.to_string();
+            names.insert(vendor.clone(), name.to_string());
+        }
+    }
+    names
+}
+
+/// Name of a PCI class code such as "0x030000".
+fn class_name(class: &str) -> String {
+    let code = class.trim_start_matches("0x");
+    match code.get(..4).unwrap_or(code) {
+        "0300" => "VGA controller",
+        "0302" => "3D controller",
+        "0403" => "Audio device",
+        "0106" => "SATA controller",
+        "0108" => "NVMe controller",
+        "0200" => "Ethernet controller",
+        "0280" => "Network controller",
+        "0600" => "Host bridge",
+        "0601" => "ISA bridge",
+        "0604" => "PCI bridge",
+        "0c03" => "USB controller",
+        "0c05" => "SMBus",
+        _ => match code.get(..2) {
+            Some("01") => "Storage controller",
+            Some("02") => "Network controller",
+            Some("03") => "Display controller",
+            Some("04") => "Multimedia controller",
+            Some("06") => "Bridge",
+            Some("0c") => "Serial bus controller",
+            _ => "Device",
+        },
+    }
+    .to_string()
+}
+
+fn read_device(dir: &Path, names: &HashMap<String, String>) -> IommuDevice {
+    let id = |file: &str| {
+        read_trimmed(&dir.join(file))
+            .map(|s| s.trim_start_matches("0x").to_string())
+            .unwrap_or_default()
+    };
+    let (vendor, device) = (id("vendor"), id("device"));
+    let class = read_trimmed(&dir.join("class")).unwrap_or_default();
+    let name = match (
+        names.get(&vendor),
+        names.get(&format!("{}:{}", vendor, device)),
+    ) {
+        (Some(v), Some(d)) => format!("{} {}", v, d),
+        (Some(v), None) => format!("{} [{}:{}]", v, vendor, device),
+        _ => format!("[{}:{}]", vendor, device),
+    };
+    IommuDevice {
+        address: dir
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        name,
+        is_gpu: class.starts_with("0x03"),
+        class: class_name(&class),
+        driver: std::fs::read_link(dir.join("driver"))
+            .ok()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string())),
+    }
+}
+
+/// Reads all IOMMU groups, sorted by number, with their devices sorted by address.
+/// Empty when the IOMMU is disabled (no `intel_iommu=on`/`amd_iommu` support).
+pub fn read_groups() -> Vec<IommuGroup> {
+    let Ok(entries) = std::fs::read_dir(IOMMU_GROUPS_ROOT) else {
+        return Vec::new();
+    };
+    let names = load_pci_names();
+    let mut groups: Vec<IommuGroup> = entries
+        .flatten()
+        .filter_map(|e| {
+            let id = e.file_name().to_string_lossy().parse().ok()?;
+            let mut devices: Vec<IommuDevice> = std::fs::read_dir(e.path().join("devices"))
+                .ok()?
+                .flatten()
+                .map(|d| read_device(&d.path(), &names))
+                .collect();
+            devices.sort_by(|a, b| a.address.cmp(&b.address));
+            Some(IommuGroup { id, devices })
+        })
+        .collect();
+    groups.sort_by_key(|g| g.id);
+    groups
+}
@@ -0,0 +1,199 @@
+//! # Kernel Log Module
+//!
+//! This module follows the kernel ring buffer through `/dev/kmsg` and keeps its recent
+//! warnings and errors (I/O errors, hung tasks, driver faults, ...) for the Kernel Log
+//! tab. Disk I/O errors are also added to the timeline as markers, so they line up with
+//! the usage graphs.
+//!
+//! `/dev/kmsg` is root-only on most distributions (`kernel.dmesg_restrict`), so the
+//! privileged worker reads it and reports the entries; each `read()` returns exactly one
+//! record, which avoids parsing `dmesg` output.
+
+use crate::markers::MarkerStore;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{ErrorKind, Read};
+use std::os::unix::fs::OpenOptionsExt;
+
+const KMSG_PATH: &str = "/dev/kmsg";
+/// `O_NONBLOCK` on Linux: reads return `WouldBlock` once the buffer is drained.
+const O_NONBLOCK: i32 = 0o4000;
+/// `EPIPE`: records were overwritten before they were read; the next read resumes.
+const EPIPE: i32 = 32;
+/// Highest (least severe) level kept: 4 = warning.
+const MAX_LEVEL: u8 = 4;
+/// Entries kept by the worker (the whole list is resent every interval).
+pub const MAX_ENTRIES: usize = 100;
+
+/// One kernel log record.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct KernelLogEntry {
+    /// Seconds since boot.
+    pub timestamp: f64,
+    /// Syslog level: 0 (emergency) to 7 (debug).
+    pub level: u8,
+    pub message: String,
+}
+
+impl KernelLogEntry {
+    pub fn level_name(&self) -> &'static str {
+        match self.level {
+            0 => "emerg",
+            1 => "alert",
+            2 => "crit",
+            3 => "err",
+            4 => "warning",
+            5 => "notice",
+            6 => "info",
+            _ => "debug",
+        }
+    }
+
+    /// Age relative to `now` (seconds since boot), e.g. "42s ago" or "3 h ago".
+    pub fn age(&self, now: f64) -> String {
+        let secs = (now - self.timestamp).max(0.0) as u64;
+        match secs {
+            0..=59 => format!("{}s ago", secs),
+            60..=3599 => format!("{} min ago", secs / 60),
+            3600..=86399 => format!("{} h ago", secs / 3600),
+            _ => format!("{} d ago", secs / 86400),
+        }
+    }
+
+    /// Device named in a block layer I/O error, e.g. "sda" or "nvme0n1".
+    pub fn io_error_device(&self) -> Option<String> {
+        let message = &self.message;
+        // "I/O error, dev sda, sector 1234 op 0x0:(READ) ..."
+        if let Some(rest) = message.split_once("I/O error, dev ").map(|(_, r)| r) {
+            return rest.split([',', ' ']).next().map(str::to_string);
+        }
+        // "Buffer I/O error on dev sda1, logical block 0, ..."
+        if let Some(rest) = message
+            .split_once("Buffer I/O error on dev ")
+            .map(|(_, r)| r)
+        {
+            return rest.split([',', ' ']).next().map(str::to_string);
+        }
+        None
+    }
+}
+
+/// Seconds since boot, on the same clock as kernel log timestamps.
+pub fn uptime_secs() -> f64 {
+    std::fs::read_to_string("/proc/uptime")
+        .ok()
+        .and_then(|s| s.split_whitespace().next()?.parse().ok())
+        .unwrap_or(0.0)
+}
+
+/// Parses a record: "<prefix>,<seq>,<usec>,<flags>[,...];<message>" followed by
+/// optional " KEY=value" continuation lines. Only kernel messages (facility 0) are kept.
+fn parse_record(record: &str) -> Option<KernelLogEntry> {
+    let (header, body) = record.split_once(';')?;
+    let mut fields = header.split(',');
+    let prefix: u32 = fields.next()?.parse().ok()?;
+    let usec: u64 = fields.nth(1)?.parse().ok()?;
+    if prefix >> 3 != 0 {
+        return None;
+    }
+    Some(KernelLogEntry {
+        timestamp: usec as f64 / 1_000_000.0,
+        level: (prefix & 7) as u8,
+        message: body.lines().next().unwrap_or_default().to_string(),
+    })
+}
+
+/// Worker side: reads new records from `/dev/kmsg`.
+pub struct KmsgReader {
+    file: Option<File>,
+    /// Recent warnings and errors, oldest first.
+    recent: Vec<KernelLogEntry>,
+}
+
+impl KmsgReader {
+    /// Opens `/dev/kmsg`. The first `read_new` returns the whole ring buffer.
+    pub fn open() -> Self {
+        let file = std::fs::OpenOptions::new()
+            .read(true)
+            .custom_flags(O_NONBLOCK)
+            .open(KMSG_PATH)
+            .map_err(|e| eprintln!("Failed to open {}: {}", KMSG_PATH, e))
+            .ok();
+        Self {
+            file,
+            recent: Vec::new(),
+        }
+    }
+
+    /// Returns all kernel records logged since the previous call, at every level.
+    pub fn read_new(&mut self) -> Vec<KernelLogEntry> {
+        let mut entries = Vec::new();
+        let Some(file) = self.file.as_mut() else {
+            return entries;
+        };
+        // Records are at most 8 KiB (one record per read)
+        let mut buf = vec![0u8; 8192];
+        loop {
+            match file.read(&mut buf) {
+                Ok(0) => break,
+                Ok(n) => {
+                    if let Some(entry) = parse_record(&String::from_utf8_lossy(&buf[..n])) {
+                        entries.push(entry);
+                    }
+                }
+                Err(e) if e.raw_os_error() == Some(EPIPE) => continue,
+                Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                Err(_) => break,
+            }
+        }
+
+        self.recent
+            .extend(entries.iter().filter(|e| e.level <= MAX_LEVEL).cloned());
+        let excess = self.recent.len().saturating_sub(MAX_ENTRIES);
+        self.recent.drain(..excess);
+        entries
+    }
+
+    /// Recent warnings and errors, oldest first.
+    pub fn recent(&self) -> &[KernelLogEntry] {
+        &self.recent
+    }
+}
+
+/// GUI side: adds timeline markers for disk I/O errors not seen yet.
+pub struct KernelLogWatcher {
+    /// Timestamp of the newest handled entry. Starts at the current uptime, so errors
+    /// from before Gjallarhorn started do not produce markers.
+    handled_until: f64,
+}
+
+impl Default for KernelLogWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl KernelLogWatcher {
+    pub fn new() -> Self {
+        Self {
+            handled_until: uptime_secs(),
+        }
+    }
+
+    pub fn update(&mut self, entries: &[KernelLogEntry], markers: &mut MarkerStore) {
+        let handled_until = self.handled_until;
+        // One marker per device per update, as failing disks log errors in bursts
+        let mut devices = Vec::new();
+        for entry in entries.iter().filter(|e| e.timestamp > handled_until) {
+            if let Some(device) = entry.io_error_device() {
+                if !devices.contains(&device) {
+                    devices.push(device);
+                }
+            }
+            self.handled_until = entry.timestamp;
+        }
+        for device in devices {
+            markers.add(&format!("I/O error on {}", device));
+        }
+    }
+}
@@ -0,0 +1,76 @@
+//! # Kernel Limits Module
+//!
+//! This module reads system-wide resource counters together with the kernel limits they
+//! run into:
+//! - Open file handles vs `fs.file-max` (`/proc/sys/fs/file-nr`).
+//! - Processes vs `kernel.pid_max`.
+//! - Threads vs `kernel.threads-max` (every thread also consumes a PID).
+//!
+//! Exhausting any of these makes `open()`/`fork()` fail system-wide, usually long before
+//! CPU or memory look alarming.
+
+/// Snapshot of system-wide usage counters and their limits.
+#[derive(Debug, Clone, Default)]
+pub struct KernelLimits {
+    pub open_files: u64,
+    pub file_max: u64,
+    pub processes: u64,
+    pub threads: u64,
+    pub pid_max: u64,
+    pub threads_max: u64,
+}
+
+fn read_u64(path: &str) -> u64 {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+impl KernelLimits {
+    /// Reads the current counters. `processes` is supplied by the caller, which already
+    /// keeps a refreshed process list.
+    pub fn read(processes: u64) -> Self {
+        // file-nr: "<allocated> <allocated but unused> <max>"
+        let file_nr: Vec<u64> = std::fs::read_to_string("/proc/sys/fs/file-nr")
+            .unwrap_or_default()
+            .split_whitespace()
+            .filter_map(|v| v.parse().ok())
+            .collect();
+        let (open_files, file_max) = match file_nr.as_slice() {
+            [allocated, unused, max] => (allocated.saturating_sub(*unused), *max),
+            _ => (0, 0),
+        };
+
+        // loadavg: "0.52 0.58 0.59 2/1234 5678" - the fourth field is running/total threads
+        let threads = std::fs::read_to_string("/proc/loadavg")
+            .ok()
+            .and_then(|s| {
+                s.split_whitespace()
+                    .nth(3)
+                    .and_then(|f| f.split_once('/'))
+                    .and_then(|(_, total)| total.parse().ok())
+            })
+            .unwrap_or(0);
+
+        Self {
+            open_files,
+            file_max,
+            processes,
+            threads,
+            pid_max: read_u64("/proc/sys/kernel/pid_max"),
+            threads_max: Self::effective_threads_max(),
+        }
+    }
+
+    /// `threads-max`, capped by `pid_max` since each thread needs a PID.
+    fn effective_threads_max() -> u64 {
+        let threads_max = read_u64("/proc/sys/kernel/threads-max");
+        let pid_max = read_u64("/proc/sys/kernel/pid_max");
+        match (threads_max, pid_max) {
+            (0, p) => p,
+            (t, 0) => t,
+            (t, p) => t.min(p),
+        }
+    }
+}
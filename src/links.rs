@@ -0,0 +1,123 @@
+//! # Link Events Module
+//!
+//! This module watches the carrier state and negotiated speed of physical network
+//! interfaces (`/sys/class/net/<iface>/{carrier,speed}`) and reports every change.
+//!
+//! `SystemMonitor` turns the events into timeline markers and raises an alert when a
+//! link goes up and down repeatedly (a "flapping" link, usually a bad cable or port).
+
+use crate::markers::now_secs;
+use std::collections::{HashMap, VecDeque};
+use std::path::Path;
+
+/// Window in which carrier changes are counted for flap detection.
+pub const FLAP_WINDOW_SECS: u64 = 300;
+/// Number of carrier changes within `FLAP_WINDOW_SECS` that counts as flapping.
+pub const FLAP_THRESHOLD: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+struct LinkState {
+    carrier: Option<bool>,
+    /// Negotiated speed in Mb/s.
+    speed: Option<u32>,
+}
+
+/// A change in an interface's link state.
+#[derive(Debug, Clone)]
+pub struct LinkEvent {
+    pub interface: String,
+    /// Human readable description, e.g. "eth0 link down" or "eth0 speed 1000 → 100 Mb/s".
+    pub description: String,
+}
+
+/// Tracks link state per physical interface between refreshes.
+#[derive(Default)]
+pub struct LinkTracker {
+    states: HashMap<String, LinkState>,
+    /// Timestamps of recent carrier changes per interface.
+    carrier_changes: HashMap<String, VecDeque<u64>>,
+}
+
+fn read_state(dir: &Path) -> LinkState {
+    // Both files return EINVAL while the interface is administratively down
+    let read = |name: &str| {
+        std::fs::read_to_string(dir.join(name))
+            .ok()
+            .and_then(|s| s.trim().parse::<i64>().ok())
+    };
+    LinkState {
+        carrier: read("carrier").map(|c| c == 1),
+        speed: read("speed").filter(|s| *s > 0).map(|s| s as u32),
+    }
+}
+
+impl LinkTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Polls all physical interfaces and returns the changes since the previous call.
+    /// The first poll only records the initial state.
+    pub fn refresh(&mut self) -> Vec<LinkEvent> {
+        let mut events = Vec::new();
+        let now = now_secs();
+
+        let Ok(entries) = std::fs::read_dir("/sys/class/net") else {
+            return events;
+        };
+        for entry in entries.flatten() {
+            // Virtual interfaces (veth, bridges, tunnels) have no backing device
+            if !entry.path().join("device").exists() {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            let state = read_state(&entry.path());
+
+            if let Some(prev) = self.states.insert(name.clone(), state) {
+                if prev.carrier.is_some()
+                    && state.carrier.is_some()
+                    && prev.carrier != state.carrier
+                {
+                    let up = state.carrier == Some(true);
+                    events.push(LinkEvent {
+                        interface: name.clone(),
+                        description: format!("{} link {}", name, if up { "up" } else { "down" }),
+                    });
+                    self.carrier_changes
+                        .entry(name.clone())
+                        .or_default()
+                        .push_back(now);
+                }
+
+                // Only report speed renegotiation while the link stays up
+                if let (Some(old), Some(new)) = (prev.speed, state.speed) {
+                    if old != new && state.carrier == Some(true) {
+                        events.push(LinkEvent {
+                            interface: name.clone(),
+                            description: format!("{} speed {} → {} Mb/s", name, old, new),
+                        });
+                    }
+                }
+            }
+        }
+
+        for changes in self.carrier_changes.values_mut() {
+            while changes
+                .front()
+                .is_some_and(|t| now.saturating_sub(*t) > FLAP_WINDOW_SECS)
+            {
+                changes.pop_front();
+            }
+        }
+
+        events
+    }
+
+    /// Number of carrier changes per interface within the flap window.
+    pub fn recent_changes(&self) -> Vec<(String, usize)> {
+        self.carrier_changes
+            .iter()
+            .map(|(name, changes)| (name.clone(), changes.len()))
+            .collect()
+    }
+}
@@ -0,0 +1,660 @@
+//! # Long-Term History Module
+//!
+//! This module keeps per-minute statistics of the Overview series (see `pins.rs`, without
+//! the per-core ones) in a SQLite database at `<data dir>/history.sqlite3`, so usage
+//! trends survive restarts and can be browsed over days or weeks on the History tab.
+//!
+//! Samples of the current minute are accumulated in memory and written in one transaction
+//! when the minute ends, as minimum, average and maximum. A minute written twice (the app
+//! restarted within it) is merged. Rows older than the retention period are deleted once
+//! an hour. `gjallarhorn query` reads the same database (see `cli.rs`).
+//!
+//! Two spans of the same length can be compared on one chart, e.g. today 09:00-10:00
+//! against yesterday 09:00-10:00; their start times are given like the `query` times
+//! (see `parse_time`).
+
+use crate::pins::{self, SeriesInfo};
+use crate::settings::LongTermHistorySettings;
+use crate::snapshot::MetricsSnapshot;
+use directories::ProjectDirs;
+use log::warn;
+use rusqlite::{params, Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Ranges offered by the History tab: label and length in seconds.
+pub const RANGES: &[(&str, u64)] = &[
+    ("Last 24 hours", 86_400),
+    ("Last 7 days", 7 * 86_400),
+    ("Last 30 days", 30 * 86_400),
+    ("Last 90 days", 90 * 86_400),
+];
+/// Span lengths offered for comparing two ranges: label and length in seconds.
+pub const COMPARE_LENGTHS: &[(&str, u64)] = &[
+    ("1 hour", 3600),
+    ("6 hours", 6 * 3600),
+    ("24 hours", 86_400),
+    ("7 days", 7 * 86_400),
+];
+/// Points a queried range is averaged down to.
+pub const QUERY_POINTS: u64 = 240;
+/// Minutes between two deletions of expired rows.
+const PRUNE_INTERVAL_MINUTES: u64 = 60;
+
+/// Seconds covered by one point of a queried range (at least a minute).
+pub fn bucket_secs(range_secs: u64) -> u64 {
+    (range_secs / QUERY_POINTS).max(60)
+}
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS series (
+        id TEXT PRIMARY KEY,
+        label TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS samples (
+        series TEXT NOT NULL,
+        minute INTEGER NOT NULL,
+        avg REAL NOT NULL,
+        min REAL NOT NULL,
+        max REAL NOT NULL,
+        count INTEGER NOT NULL,
+        PRIMARY KEY (series, minute)
+    ) WITHOUT ROWID;
+    CREATE INDEX IF NOT EXISTS samples_minute ON samples (minute);
+";
+
+/// One point of a queried series: the statistics of the minutes it covers.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct LongTermPoint {
+    /// Middle of the covered span, in seconds since the UNIX epoch.
+    pub time: f64,
+    pub avg: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+/// Samples of one series in the current minute.
+struct MinuteStats {
+    sum: f64,
+    count: u32,
+    min: f32,
+    max: f32,
+}
+
+/// Records the series of each snapshot to the database.
+pub struct LongTermStore {
+    connection: Connection,
+    retention_days: u32,
+    /// Minute (since the epoch) being accumulated.
+    minute: u64,
+    pending: BTreeMap<String, MinuteStats>,
+    /// Labels of the series seen in the current minute.
+    labels: BTreeMap<String, String>,
+    last_prune: u64,
+}
+
+impl LongTermStore {
+    /// Path of the database, which also holds the SMART history (see `smarttrend.rs`).
+    pub fn get_path() -> PathBuf {
+        if let Some(proj_dirs) = ProjectDirs::from("com", "gjallarhorn", "gjallarhorn") {
+            let data_dir = proj_dirs.data_dir();
+            if !data_dir.exists() {
+                let _ = fs::create_dir_all(data_dir);
+            }
+            data_dir.join("history.sqlite3")
+        } else {
+            PathBuf::from("history.sqlite3")
+        }
+    }
+
+    /// Opens the existing database without writing to it, for `gjallarhorn query`.
+    pub fn open_read_only() -> Result<Self, String> {
+        let path = Self::get_path();
+        if !path.exists() {
+            return Err(format!(
+                "No long-term history at {} (enable \"long_term_history\" in the config file)",
+                path.display()
+            ));
+        }
+        let connection = Connection::open_with_flags(&path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        Ok(Self {
+            connection,
+            retention_days: 0,
+            minute: 0,
+            pending: BTreeMap::new(),
+            labels: BTreeMap::new(),
+            last_prune: 0,
+        })
+    }
+
+    /// Opens (or creates) the database.
+    pub fn open(settings: &LongTermHistorySettings) -> Result<Self, String> {
+        let path = Self::get_path();
+        Connection::open(&path)
+            .and_then(|c| Self::with_connection(c, settings))
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))
+    }
+
+    /// Uses `connection`, creating the tables if needed.
+    fn with_connection(
+        connection: Connection,
+        settings: &LongTermHistorySettings,
+    ) -> rusqlite::Result<Self> {
+        connection.execute_batch(SCHEMA)?;
+        Ok(Self {
+            connection,
+            retention_days: settings.retention_days,
+            minute: 0,
+            pending: BTreeMap::new(),
+            labels: BTreeMap::new(),
+            last_prune: 0,
+        })
+    }
+
+    pub fn set_retention_days(&mut self, days: u32) {
+        self.retention_days = days;
+        // Apply a shorter retention at the next flush
+        self.last_prune = 0;
+    }
+
+    /// Adds the series of `snapshot` to the current minute, writing the previous minute
+    /// once a new one starts.
+    pub fn record(&mut self, snapshot: &MetricsSnapshot) {
+        let minute = snapshot.timestamp / 60;
+        if minute != self.minute {
+            self.flush();
+            self.minute = minute;
+        }
+        for (info, value) in pins::snapshot_series(snapshot) {
+            if info.id.starts_with("cpu.core.") || !value.is_finite() {
+                continue;
+            }
+            let stats = self.pending.entry(info.id.clone()).or_insert(MinuteStats {
+                sum: 0.0,
+                count: 0,
+                min: value,
+                max: value,
+            });
+            stats.sum += value as f64;
+            stats.count += 1;
+            stats.min = stats.min.min(value);
+            stats.max = stats.max.max(value);
+            self.labels.insert(info.id, info.label);
+        }
+    }
+
+    /// Writes the accumulated minute and deletes expired rows when due.
+    fn flush(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+        let pending = std::mem::take(&mut self.pending);
+        let labels = std::mem::take(&mut self.labels);
+        let result = self.write(&pending, &labels);
+        if let Err(e) = result {
+            warn!("Failed to write long-term history: {}", e);
+        }
+
+        if self.retention_days > 0 && self.minute >= self.last_prune + PRUNE_INTERVAL_MINUTES {
+            self.last_prune = self.minute;
+            let oldest = self
+                .minute
+                .saturating_sub(self.retention_days as u64 * 24 * 60);
+            if let Err(e) = self
+                .connection
+                .execute("DELETE FROM samples WHERE minute < ?1", params![oldest])
+            {
+                warn!("Failed to prune long-term history: {}", e);
+            }
+        }
+    }
+
+    fn write(
+        &mut self,
+        pending: &BTreeMap<String, MinuteStats>,
+        labels: &BTreeMap<String, String>,
+    ) -> rusqlite::Result<()> {
+        let transaction = self.connection.transaction()?;
+        {
+            let mut insert_sample = transaction.prepare_cached(
+                "INSERT INTO samples (series, minute, avg, min, max, count)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                 ON CONFLICT (series, minute) DO UPDATE SET
+                     avg = (avg * count + excluded.avg * excluded.count)
+                         / (count + excluded.count),
+                     min = MIN(min, excluded.min),
+                     max = MAX(max, excluded.max),
+                     count = count + excluded.count",
+            )?;
+            for (id, stats) in pending {
+                insert_sample.execute(params![
+                    id,
+                    self.minute,
+                    stats.sum / stats.count as f64,
+                    stats.min,
+                    stats.max,
+                    stats.count
+                ])?;
+            }
+            let mut insert_series = transaction.prepare_cached(
+                "INSERT INTO series (id, label) VALUES (?1, ?2)
+                 ON CONFLICT (id) DO UPDATE SET label = excluded.label",
+            )?;
+            for (id, label) in labels {
+                insert_series.execute(params![id, label])?;
+            }
+        }
+        transaction.commit()
+    }
+
+    /// Series with stored samples, sorted by label.
+    pub fn series(&self) -> Vec<SeriesInfo> {
+        let query = |connection: &Connection| -> rusqlite::Result<Vec<SeriesInfo>> {
+            let mut statement = connection.prepare_cached(
+                "SELECT id, label FROM series
+                 WHERE EXISTS (SELECT 1 FROM samples WHERE samples.series = series.id)
+                 ORDER BY label",
+            )?;
+            let rows = statement.query_map([], |row| {
+                Ok(SeriesInfo {
+                    id: row.get(0)?,
+                    label: row.get(1)?,
+                })
+            })?;
+            rows.collect()
+        };
+        query(&self.connection).unwrap_or_else(|e| {
+            warn!("Failed to list long-term series: {}", e);
+            Vec::new()
+        })
+    }
+
+    /// The samples of series `id` in the `range_secs` seconds before `now`, averaged down
+    /// to at most `QUERY_POINTS` points, oldest first.
+    pub fn query(&self, id: &str, range_secs: u64, now: u64) -> Result<Vec<LongTermPoint>, String> {
+        self.span(id, now.saturating_sub(range_secs), now)
+    }
+
+    /// The samples of series `id` from `start` up to (not including) `end`, in seconds
+    /// since the epoch, averaged down like `query`.
+    pub fn span(&self, id: &str, start: u64, end: u64) -> Result<Vec<LongTermPoint>, String> {
+        let bucket = bucket_secs(end.saturating_sub(start));
+        let mut statement = self
+            .connection
+            .prepare_cached(
+                "SELECT (minute * 60 - ?2) / ?3 AS bucket, AVG(avg), MIN(min), MAX(max)
+                 FROM samples WHERE series = ?1 AND minute >= ?4 AND minute < ?5
+                 GROUP BY bucket ORDER BY bucket",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = statement
+            .query_map(
+                params![id, start, bucket, start / 60, end.div_ceil(60)],
+                |row| {
+                    let index: i64 = row.get(0)?;
+                    Ok(LongTermPoint {
+                        time: start as f64 + (index as f64 + 0.5) * bucket as f64,
+                        avg: row.get::<_, f64>(1)? as f32,
+                        min: row.get::<_, f64>(2)? as f32,
+                        max: row.get::<_, f64>(3)? as f32,
+                    })
+                },
+            )
+            .map_err(|e| e.to_string())?;
+        rows.collect::<rusqlite::Result<_>>()
+            .map_err(|e| e.to_string())
+    }
+
+    /// The stored minutes of series `id` from `from` up to (not including) `to`, in
+    /// seconds since the epoch, oldest first.
+    pub fn minutes(&self, id: &str, from: u64, to: u64) -> Result<Vec<LongTermPoint>, String> {
+        let mut statement = self
+            .connection
+            .prepare_cached(
+                "SELECT minute, avg, min, max FROM samples
+                 WHERE series = ?1 AND minute >= ?2 AND minute < ?3 ORDER BY minute",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = statement
+            .query_map(params![id, from.div_ceil(60), to.div_ceil(60)], |row| {
+                let minute: i64 = row.get(0)?;
+                Ok(LongTermPoint {
+                    time: (minute * 60 + 30) as f64,
+                    avg: row.get::<_, f64>(1)? as f32,
+                    min: row.get::<_, f64>(2)? as f32,
+                    max: row.get::<_, f64>(3)? as f32,
+                })
+            })
+            .map_err(|e| e.to_string())?;
+        rows.collect::<rusqlite::Result<_>>()
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Parses a point in time: "now", "<n><unit> ago" with the unit s, m, h, d or w (e.g.
+/// "2h ago", "30m ago"), "today HH:MM", "yesterday HH:MM", a local "YYYY-MM-DD" or
+/// "YYYY-MM-DD HH:MM", or seconds since the epoch.
+pub fn parse_time(value: &str, now: u64) -> Result<u64, String> {
+    let value = value.trim();
+    let invalid = || {
+        format!(
+            "Invalid time '{}' (e.g. now, 2h ago, yesterday 09:00, 2026-10-17 09:00 or a UNIX timestamp)",
+            value
+        )
+    };
+    if value == "now" {
+        return Ok(now);
+    }
+    if let Ok(timestamp) = value.parse::<u64>() {
+        return Ok(timestamp);
+    }
+    if let Some(ago) = value.strip_suffix("ago") {
+        let ago = ago.trim().replace(' ', "");
+        let unit = match ago.chars().last() {
+            Some('s') => 1,
+            Some('m') => 60,
+            Some('h') => 3600,
+            Some('d') => 86_400,
+            Some('w') => 7 * 86_400,
+            _ => return Err(invalid()),
+        };
+        let count: u64 = ago[..ago.len() - 1].parse().map_err(|_| invalid())?;
+        return Ok(now.saturating_sub(count.saturating_mul(unit)));
+    }
+
+    let (day, time) = value.split_once(' ').unwrap_or((value, "00:00"));
+    let (hour, minute) = time
+        .trim()
+        .split_once(':')
+        .and_then(|(h, m)| Some((h.parse::<i32>().ok()?, m.parse::<i32>().ok()?)))
+        .filter(|(h, m)| (0..24).contains(h) && (0..60).contains(m))
+        .ok_or_else(invalid)?;
+    let mut tm = local_tm(now).ok_or_else(invalid)?;
+    match day {
+        // Relative days need a time, so "yesterday" alone isn't mistaken for a span
+        "today" | "yesterday" if !value.contains(' ') => return Err(invalid()),
+        "today" => {}
+        "yesterday" => tm.tm_mday -= 1,
+        date => {
+            let mut parts = date.split('-').map(|p| p.parse::<i32>().ok());
+            let (Some(Some(year)), Some(Some(month)), Some(Some(mday)), None) =
+                (parts.next(), parts.next(), parts.next(), parts.next())
+            else {
+                return Err(invalid());
+            };
+            if !(1..=12).contains(&month) || !(1..=31).contains(&mday) {
+                return Err(invalid());
+            }
+            tm.tm_year = year - 1900;
+            tm.tm_mon = month - 1;
+            tm.tm_mday = mday;
+        }
+    }
+    tm.tm_hour = hour;
+    tm.tm_min = minute;
+    tm.tm_sec = 0;
+    tm.tm_isdst = -1;
+    let time = unsafe { libc::mktime(&mut tm) };
+    u64::try_from(time).map_err(|_| invalid())
+}
+
+/// `time` (seconds since the epoch) as local "YYYY-MM-DD HH:MM", which `parse_time`
+/// reads back.
+pub fn format_local(time: u64) -> String {
+    match local_tm(time) {
+        Some(tm) => format!(
+            "{}-{:02}-{:02} {:02}:{:02}",
+            tm.tm_year + 1900,
+            tm.tm_mon + 1,
+            tm.tm_mday,
+            tm.tm_hour,
+            tm.tm_min
+        ),
+        None => crate::csvlog::format_timestamp(time),
+    }
+}
+
+/// The local calendar time of `time` (seconds since the epoch).
+fn local_tm(time: u64) -> Option<libc::tm> {
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    let time = time as libc::time_t;
+    if unsafe { libc::localtime_r(&time, &mut tm) }.is_null() {
+        None
+    } else {
+        Some(tm)
+    }
+}
+
+impl Drop for LongTermStore {
+    fn drop(&mut self) {
+        // Keep the partial minute; it is merged if the app restarts within it
+        self.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::CpuSnapshot;
+
+    /// Start of a day, in seconds since the epoch.
+    const DAY: u64 = 20_000 * 86_400;
+
+    fn store(retention_days: u32) -> LongTermStore {
+        let settings = LongTermHistorySettings {
+            retention_days,
+            ..Default::default()
+        };
+        LongTermStore::with_connection(Connection::open_in_memory().unwrap(), &settings).unwrap()
+    }
+
+    fn snapshot(timestamp: u64, cpu: f32) -> MetricsSnapshot {
+        MetricsSnapshot {
+            timestamp,
+            cpu: CpuSnapshot {
+                usage_percent: cpu,
+                cores: vec![cpu],
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Stored (avg, min, max, count) of the "cpu" series in `minute`.
+    fn stored(store: &LongTermStore, minute: u64) -> Option<(f64, f64, f64, u32)> {
+        store
+            .connection
+            .query_row(
+                "SELECT avg, min, max, count FROM samples WHERE series = 'cpu' AND minute = ?1",
+                params![minute],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .ok()
+    }
+
+    #[test]
+    fn minute_is_written_when_the_next_one_starts() {
+        let mut store = store(0);
+        store.record(&snapshot(DAY, 10.0));
+        store.record(&snapshot(DAY + 30, 30.0));
+        assert_eq!(stored(&store, DAY / 60), None);
+
+        store.record(&snapshot(DAY + 60, 50.0));
+        assert_eq!(stored(&store, DAY / 60), Some((20.0, 10.0, 30.0, 2)));
+        // Per-core series are not stored
+        let cores: u32 = store
+            .connection
+            .query_row(
+                "SELECT COUNT(*) FROM samples WHERE series LIKE 'cpu.core.%'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(cores, 0);
+        assert!(store
+            .series()
+            .iter()
+            .any(|s| s.id == "cpu" && s.label == "CPU"));
+    }
+
+    #[test]
+    fn minute_written_twice_is_merged() {
+        let mut store = store(0);
+        store.record(&snapshot(DAY, 10.0));
+        store.record(&snapshot(DAY + 10, 20.0));
+        store.flush();
+
+        // Restarted within the same minute
+        store.minute = 0;
+        store.record(&snapshot(DAY + 40, 60.0));
+        store.flush();
+        assert_eq!(stored(&store, DAY / 60), Some((30.0, 10.0, 60.0, 3)));
+    }
+
+    #[test]
+    fn rows_older_than_the_retention_are_pruned() {
+        let mut store = store(1);
+        store.record(&snapshot(DAY, 10.0));
+        store.record(&snapshot(DAY + 86_400, 20.0));
+        store.record(&snapshot(DAY + 2 * 86_400, 30.0));
+        store.record(&snapshot(DAY + 2 * 86_400 + 60, 40.0));
+
+        assert_eq!(stored(&store, DAY / 60), None);
+        assert!(stored(&store, (DAY + 86_400) / 60).is_some());
+        assert!(stored(&store, (DAY + 2 * 86_400) / 60).is_some());
+    }
+
+    #[test]
+    fn retention_of_zero_keeps_everything() {
+        let mut store = store(0);
+        store.record(&snapshot(DAY, 10.0));
+        store.record(&snapshot(DAY + 90 * 86_400, 20.0));
+        store.flush();
+        assert!(stored(&store, DAY / 60).is_some());
+    }
+
+    #[test]
+    fn query_averages_minutes_into_aligned_buckets() {
+        let mut store = store(0);
+        let range = 86_400;
+        let bucket = bucket_secs(range);
+        assert_eq!(bucket, 360);
+        let now = DAY + range;
+        let start = now - range;
+        // Two minutes in the first bucket, one in the third
+        for (offset, cpu) in [(0, 10.0), (60, 30.0), (2 * bucket, 50.0)] {
+            store.record(&snapshot(start + offset, cpu));
+        }
+        store.flush();
+        // Outside the range
+        store.minute = 0;
+        store.record(&snapshot(start - 60, 90.0));
+        store.flush();
+
+        let points = store.query("cpu", range, now).unwrap();
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].time, (start + bucket / 2) as f64);
+        assert_eq!(
+            (points[0].avg, points[0].min, points[0].max),
+            (20.0, 10.0, 30.0)
+        );
+        assert_eq!(points[1].time, (start + 2 * bucket + bucket / 2) as f64);
+        assert_eq!(points[1].avg, 50.0);
+        assert!(store.query("memory.swap", range, now).unwrap().is_empty());
+    }
+
+    #[test]
+    fn span_stops_at_its_end() {
+        let mut store = store(0);
+        for minute in 0..10 {
+            store.record(&snapshot(DAY + minute * 60, minute as f32));
+        }
+        store.flush();
+
+        // Minutes 2, 3 and 4; one-minute buckets at this length
+        let points = store.span("cpu", DAY + 120, DAY + 300).unwrap();
+        let values: Vec<f32> = points.iter().map(|p| p.avg).collect();
+        assert_eq!(values, [2.0, 3.0, 4.0]);
+        assert_eq!(points[0].time, (DAY + 150) as f64);
+    }
+
+    const NOW: u64 = 1_700_000_000;
+
+    #[test]
+    fn times_are_parsed_relative_to_now() {
+        assert_eq!(parse_time("now", NOW), Ok(NOW));
+        assert_eq!(parse_time("2h ago", NOW), Ok(NOW - 7200));
+        assert_eq!(parse_time("30m ago", NOW), Ok(NOW - 1800));
+        assert_eq!(parse_time("7 d ago", NOW), Ok(NOW - 7 * 86_400));
+        assert_eq!(parse_time("1w ago", NOW), Ok(NOW - 7 * 86_400));
+        assert_eq!(parse_time("1699990000", NOW), Ok(1_699_990_000));
+    }
+
+    #[test]
+    fn local_times_are_parsed_as_wall_clock() {
+        // 2023-11-14 in every time zone within a day of UTC
+        let today = parse_time("today 09:00", NOW).unwrap();
+        let tm = local_tm(today).unwrap();
+        assert_eq!((tm.tm_hour, tm.tm_min, tm.tm_sec), (9, 0, 0));
+        let date = format!(
+            "{}-{:02}-{:02}",
+            tm.tm_year + 1900,
+            tm.tm_mon + 1,
+            tm.tm_mday
+        );
+        assert_eq!(parse_time(&format!("{} 09:00", date), NOW), Ok(today));
+        assert_eq!(
+            parse_time(&format!("{} 09:30", date), NOW),
+            Ok(today + 1800)
+        );
+        assert_eq!(parse_time(&date, NOW), Ok(today - 9 * 3600));
+
+        assert_eq!(format_local(today + 1800), format!("{} 09:30", date));
+        assert_eq!(parse_time(&format_local(NOW), NOW), Ok(NOW - NOW % 60));
+
+        let yesterday = local_tm(parse_time("yesterday 09:00", NOW).unwrap()).unwrap();
+        assert_eq!((yesterday.tm_hour, yesterday.tm_min), (9, 0));
+        assert_ne!(yesterday.tm_mday, tm.tm_mday);
+    }
+
+    #[test]
+    fn invalid_times_are_rejected() {
+        for value in [
+            "",
+            "yesterday",
+            "today",
+            "2 ago",
+            "2x ago",
+            "h ago",
+            "-1h ago",
+            "today 24:00",
+            "today 9",
+            "2026-13-01",
+            "2026-10",
+            "tomorrow 09:00",
+        ] {
+            assert!(parse_time(value, NOW).is_err(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn minutes_are_listed_within_the_range() {
+        let mut store = store(0);
+        for minute in 0..5 {
+            store.record(&snapshot(DAY + minute * 60, minute as f32));
+        }
+        store.flush();
+
+        // From the middle of minute 1 up to minute 4: minutes 2 and 3
+        let points = store.minutes("cpu", DAY + 90, DAY + 240).unwrap();
+        let values: Vec<f32> = points.iter().map(|p| p.avg).collect();
+        assert_eq!(values, [2.0, 3.0]);
+        assert_eq!(points[0].time, (DAY + 150) as f64);
+        assert!(store
+            .minutes("memory.swap", DAY, DAY + 300)
+            .unwrap()
+            .is_empty());
+    }
+}
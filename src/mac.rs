@@ -0,0 +1,233 @@
+//! # Mandatory Access Control Module
+//!
+//! This module reports which MAC system is active (SELinux or AppArmor) and its mode,
+//! and counts recent access denials. A denial usually explains why an application fails
+//! with "permission denied" even though the file permissions look right.
+//!
+//! The mode comes from `/sys/fs/selinux` and `/sys/module/apparmor`, which anyone can
+//! read. The AppArmor profile list and the audit log (`/var/log/audit/audit.log`) are
+//! root-only, so the privileged worker scans them (`DenialScanner`) and reports a
+//! `DenialSummary`. Without auditd, the kernel logs denials to the ring buffer instead,
+//! which the worker reads anyway (`kmsg.rs`).
+
+use crate::kmsg::KernelLogEntry;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+
+const SELINUX_ROOT: &str = "/sys/fs/selinux";
+const APPARMOR_ENABLED: &str = "/sys/module/apparmor/parameters/enabled";
+const APPARMOR_PROFILES: &str = "/sys/kernel/security/apparmor/profiles";
+const AUDIT_LOG: &str = "/var/log/audit/audit.log";
+/// Denials older than this are not counted.
+pub const DENIAL_WINDOW_SECS: f64 = 24.0 * 3600.0;
+
+/// Active MAC system and mode, readable without privileges.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct MacStatus {
+    /// "SELinux", "AppArmor", or empty when neither is active.
+    pub system: String,
+    /// SELinux: "enforcing" or "permissive". AppArmor: "enabled".
+    pub mode: String,
+}
+
+impl MacStatus {
+    pub fn read() -> Self {
+        let selinux = Path::new(SELINUX_ROOT);
+        if let Ok(enforce) = std::fs::read_to_string(selinux.join("enforce")) {
+            return Self {
+                system: "SELinux".to_string(),
+                mode: if enforce.trim() == "1" {
+                    "enforcing"
+                } else {
+                    "permissive"
+                }
+                .to_string(),
+            };
+        }
+        if std::fs::read_to_string(APPARMOR_ENABLED).is_ok_and(|s| s.trim() == "Y") {
+            return Self {
+                system: "AppArmor".to_string(),
+                mode: "enabled".to_string(),
+            };
+        }
+        Self::default()
+    }
+
+    /// Whether a MAC system is loaded but does not block anything.
+    pub fn is_permissive(&self) -> bool {
+        self.mode == "permissive"
+    }
+
+    /// e.g. "SELinux (enforcing)" or "None".
+    pub fn summary(&self) -> String {
+        if self.system.is_empty() {
+            "None".to_string()
+        } else {
+            format!("{} ({})", self.system, self.mode)
+        }
+    }
+}
+
+/// Recent denials found by the worker.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct DenialSummary {
+    /// Where denials are read from: "audit log" or "kernel log".
+    pub source: String,
+    /// Denials within `DENIAL_WINDOW_SECS`.
+    pub recent: u32,
+    /// The latest denial record, shortened.
+    pub latest: Option<String>,
+    /// AppArmor profiles in enforce and complain mode.
+    pub apparmor_enforce: u32,
+    pub apparmor_complain: u32,
+}
+
+/// Timestamp of an audit record, from `audit(1700000000.123:456)`.
+fn audit_time(line: &str) -> Option<f64> {
+    let start = line.find("audit(")? + "audit(".len();
+    let rest = &line[start..];
+    rest[..rest.find(':')?].parse().ok()
+}
+
+/// SELinux AVC denials and AppArmor `DENIED` records, in the audit log or the kernel
+/// log (`audit: type=1400 audit(...): avc:  denied ...`).
+fn is_denial(line: &str) -> bool {
+    line.contains("avc:  denied") || line.contains("apparmor=\"DENIED\"")
+}
+
+/// Shortens a denial record to its interesting fields, e.g.
+/// `denied { read } comm="nginx" name="index.html"`.
+fn describe(line: &str) -> String {
+    let keep = [
+        "apparmor=",
+        "operation=",
+        "profile=",
+        "comm=",
+        "name=",
+        "tcontext=",
+        "requested_mask=",
+    ];
+    let mut parts: Vec<String> = Vec::new();
+    if let Some(start) = line.find("denied ") {
+        if let Some(end) = line[start..].find('}') {
+            let permissions = &line[start..=start + end];
+            parts.push(permissions.split_whitespace().collect::<Vec<_>>().join(" "));
+        }
+    }
+    parts.extend(
+        line.split_whitespace()
+            .filter(|field| keep.iter().any(|k| field.starts_with(k)))
+            .map(str::to_string),
+    );
+    parts.join(" ")
+}
+
+/// Counts AppArmor profiles by mode from the (root-only) profile list, whose lines
+/// look like `/usr/sbin/cupsd (enforce)`.
+fn apparmor_profile_counts() -> (u32, u32) {
+    let Ok(text) = std::fs::read_to_string(APPARMOR_PROFILES) else {
+        return (0, 0);
+    };
+    text.lines().fold((0, 0), |(enforce, complain), line| {
+        if line.ends_with("(enforce)") {
+            (enforce + 1, complain)
+        } else if line.ends_with("(complain)") {
+            (enforce, complain + 1)
+        } else {
+            (enforce, complain)
+        }
+    })
+}
+
+/// Follows the audit log (or the kernel log) and remembers the times of recent denials.
+/// Runs in the worker.
+pub struct DenialScanner {
+    offset: u64,
+    /// Unfinished last line of the previous read.
+    partial: String,
+    /// Times of denials within the window, oldest first.
+    times: VecDeque<f64>,
+    latest: Option<String>,
+}
+
+impl Default for DenialScanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DenialScanner {
+    pub fn new() -> Self {
+        Self {
+            offset: 0,
+            partial: String::new(),
+            times: VecDeque::new(),
+            latest: None,
+        }
+    }
+
+    /// Counts denials logged since the last call: from the audit log when auditd runs
+    /// (the whole log on the first call or after rotation), otherwise from
+    /// `kmsg_entries`, the kernel records read in this worker iteration.
+    pub fn scan(&mut self, kmsg_entries: &[KernelLogEntry]) -> DenialSummary {
+        let source = match self.read_audit_log() {
+            Some(lines) => {
+                self.record(lines.iter().map(String::as_str));
+                "audit log"
+            }
+            None => {
+                self.record(kmsg_entries.iter().map(|e| e.message.as_str()));
+                "kernel log"
+            }
+        };
+
+        let cutoff = crate::history::now() - DENIAL_WINDOW_SECS;
+        while self.times.front().is_some_and(|t| *t < cutoff) {
+            self.times.pop_front();
+        }
+        let (apparmor_enforce, apparmor_complain) = apparmor_profile_counts();
+        DenialSummary {
+            source: source.to_string(),
+            recent: self.times.len() as u32,
+            latest: self.latest.clone(),
+            apparmor_enforce,
+            apparmor_complain,
+        }
+    }
+
+    fn record<'a>(&mut self, lines: impl Iterator<Item = &'a str>) {
+        for line in lines.filter(|l| is_denial(l)) {
+            if let Some(time) = audit_time(line) {
+                self.times.push_back(time);
+                self.latest = Some(describe(line));
+            }
+        }
+    }
+
+    /// Complete lines appended to the audit log since the previous call, or `None`
+    /// when it cannot be read.
+    fn read_audit_log(&mut self) -> Option<Vec<String>> {
+        let mut file = File::open(AUDIT_LOG).ok()?;
+        let len = file.metadata().ok()?.len();
+        if len < self.offset {
+            // Rotated: the new file starts over
+            self.offset = 0;
+            self.partial.clear();
+        }
+        let mut new = Vec::new();
+        file.seek(SeekFrom::Start(self.offset)).ok()?;
+        file.take(len - self.offset).read_to_end(&mut new).ok()?;
+        self.offset = len;
+
+        let text = std::mem::take(&mut self.partial) + String::from_utf8_lossy(&new).as_ref();
+        let (complete, rest) = match text.rfind('\n') {
+            Some(end) => text.split_at(end + 1),
+            None => ("", text.as_str()),
+        };
+        self.partial = rest.to_string();
+        Some(complete.lines().map(str::to_string).collect())
+    }
+}
@@ -2,13 +2,201 @@
 //!
 //! Entry point for the executable. Delegates code to the library.
 
-fn main() -> Result<(), slint::PlatformError> {
+fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Check for worker flag
     let args: Vec<String> = std::env::args().collect();
-    if args.contains(&"--privileged-worker".to_string()) {
-        gjallarhorn::worker::run_worker();
+
+    // Command line subcommands: print and exit without starting the GUI
+    if args.get(1).map(String::as_str) == Some("metrics") {
+        match gjallarhorn::cli::metrics(&args[2..]) {
+            Ok(output) => {
+                // Ignore write errors (e.g. a closed pipe when piped into `head`)
+                use std::io::Write;
+                let _ = writeln!(std::io::stdout(), "{}", output);
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    if args.get(1).map(String::as_str) == Some("info") {
+        match gjallarhorn::cli::info(&args[2..]) {
+            Ok(output) => {
+                use std::io::Write;
+                let _ = writeln!(std::io::stdout(), "{}", output);
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    if args.get(1).map(String::as_str) == Some("query") {
+        match gjallarhorn::cli::query(&args[2..]) {
+            Ok(output) => {
+                use std::io::Write;
+                let _ = writeln!(std::io::stdout(), "{}", output);
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    if args.get(1).map(String::as_str) == Some("top") {
+        if let Err(e) = gjallarhorn::cli::top(&args[2..]) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if args.get(1).map(String::as_str) == Some("stream") {
+        if let Err(e) = gjallarhorn::cli::stream(&args[2..]) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Alert rule packs: export/import the thresholds of the settings file
+    if args.get(1).map(String::as_str) == Some("rules") {
+        match gjallarhorn::rulepack::run(&args[2..]) {
+            Ok(done) => println!("{}", done),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
         return Ok(());
     }
 
-    gjallarhorn::run()
+    // Polkit action for the worker: needs root, prints what was installed
+    if args.get(1).map(String::as_str) == Some("install-policy") {
+        match gjallarhorn::polkit::install_policy(&args[2..]) {
+            Ok(done) => println!("{}", done),
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+
+    // Headless mode: the monitor and its exporters without any window
+    if args.get(1).map(String::as_str) == Some("--headless") {
+        if let Err(e) = gjallarhorn::cli::headless(&args[2..]) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Desktop widget: runs instead of the main window
+    #[cfg(feature = "gui")]
+    if args.get(1).map(String::as_str) == Some("widget") {
+        return Ok(gjallarhorn::widget::run()?);
+    }
+
+    // Privileged helpers, started through pkexec. Polkit picks the action by argv[1]
+    // (see `polkit.rs`), so a helper only runs as the first argument and with exactly
+    // its own arguments: a second flag must not ride on another action's authorization.
+    let argv: Vec<&str> = args.iter().skip(1).map(String::as_str).collect();
+    let result = match argv.as_slice() {
+        ["--privileged-worker", flags @ ..] => {
+            if let Some(flag) = flags.iter().find(|f| !WORKER_FLAGS.contains(f)) {
+                Err(format!("Unknown worker flag: {}", flag))
+            } else {
+                let collectors = gjallarhorn::settings::CollectorSettings {
+                    smart: !flags.contains(&"--no-smart"),
+                    network: !flags.contains(&"--no-network"),
+                    gpu: !flags.contains(&"--no-gpu"),
+                };
+                gjallarhorn::worker::run_worker(flags.contains(&"--capture"), &collectors);
+                Ok(())
+            }
+        }
+        // One-shot privileged GPU power limit change: --set-gpu-power-limit <index> <watts>
+        ["--set-gpu-power-limit", rest @ ..] => match rest {
+            [index, watts] => match (index.parse::<u32>(), watts.parse::<f32>()) {
+                (Ok(index), Ok(watts)) => gjallarhorn::worker::set_gpu_power_limit(index, watts),
+                _ => Err("Usage: --set-gpu-power-limit <index> <watts>".to_string()),
+            },
+            _ => Err("Usage: --set-gpu-power-limit <index> <watts>".to_string()),
+        },
+        // One-shot privileged fan override: --set-fan-pwm <chip> <index> <value|auto>
+        ["--set-fan-pwm", rest @ ..] => {
+            let parsed = match rest {
+                [chip, index, value] => {
+                    let value = match *value {
+                        "auto" => Some(None),
+                        v => v.parse::<u8>().ok().map(Some),
+                    };
+                    index
+                        .parse::<u32>()
+                        .ok()
+                        .zip(value)
+                        .map(|(i, v)| (*chip, i, v))
+                }
+                _ => None,
+            };
+            match parsed {
+                Some((chip, index, value)) => gjallarhorn::worker::set_fan_pwm(chip, index, value),
+                None => Err("Usage: --set-fan-pwm <chip> <index> <0-255|auto>".to_string()),
+            }
+        }
+        // One-shot privileged scheduling change:
+        // --set-process-scheduling <pid> <nice> <policy> <cpus>
+        ["--set-process-scheduling", rest @ ..] => match rest {
+            [pid, nice, policy, cpus] => match (pid.parse::<u32>(), nice.parse::<i32>()) {
+                (Ok(pid), Ok(nice)) => {
+                    gjallarhorn::worker::set_process_scheduling(pid, nice, policy, cpus)
+                }
+                _ => Err(PROCESS_SCHEDULING_USAGE.to_string()),
+            },
+            _ => Err(PROCESS_SCHEDULING_USAGE.to_string()),
+        },
+        // One-shot privileged idle action: --power-action <suspend|shutdown>
+        ["--power-action", rest @ ..] => match rest {
+            [action] => gjallarhorn::worker::power_action(action),
+            _ => Err("Usage: --power-action <suspend|shutdown>".to_string()),
+        },
+        // One-shot privileged Wi-Fi scan: --wifi-scan (prints JSON results)
+        ["--wifi-scan", rest @ ..] => match rest {
+            [] => gjallarhorn::wifi::scan().map(|networks| {
+                println!("{}", serde_json::to_string(&networks).unwrap_or_default());
+            }),
+            _ => Err("Usage: --wifi-scan".to_string()),
+        },
+        _ => return run_gui(),
+    };
+    if let Err(e) = result {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Flags the privileged worker accepts after `--privileged-worker`.
+const WORKER_FLAGS: [&str; 4] = ["--capture", "--no-smart", "--no-network", "--no-gpu"];
+
+const PROCESS_SCHEDULING_USAGE: &str =
+    "Usage: --set-process-scheduling <pid> <nice> <other|batch|idle> <cpu list>";
+
+#[cfg(feature = "gui")]
+fn run_gui() -> Result<(), Box<dyn std::error::Error>> {
+    Ok(gjallarhorn::run()?)
+}
+
+/// Builds without the `gui` feature only have the command line modes.
+#[cfg(not(feature = "gui"))]
+fn run_gui() -> Result<(), Box<dyn std::error::Error>> {
+    Err("This build has no GUI (built without the `gui` feature); use --headless or a subcommand such as `metrics`".into())
 }
@@ -0,0 +1,109 @@
+//! # Session Markers Module
+//!
+//! This module manages named timeline annotations ("started compile", "game launched").
+//! Markers are stamped with wall-clock time and persisted to `markers.json` in the
+//! standard data directory, so they survive restarts. The markers within the chart window
+//! are included in every `MetricsSnapshot` (JSON snapshot, webhook) and new ones in the
+//! `markers` column of the CSV log.
+//!
+//! The UI renders every marker that falls inside the visible chart window as a vertical
+//! line on top of the usage graphs.
+
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Upper bound on stored markers; the oldest ones are dropped first.
+const MAX_MARKERS: usize = 1000;
+
+/// A single named point on the timeline.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Marker {
+    /// Seconds since the UNIX epoch.
+    pub timestamp: u64,
+    pub label: String,
+}
+
+/// Persistent, chronologically ordered list of markers.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct MarkerStore {
+    pub markers: Vec<Marker>,
+}
+
+impl MarkerStore {
+    fn get_path() -> PathBuf {
+        if let Some(proj_dirs) = ProjectDirs::from("com", "gjallarhorn", "gjallarhorn") {
+            let data_dir = proj_dirs.data_dir();
+            if !data_dir.exists() {
+                let _ = fs::create_dir_all(data_dir);
+            }
+            data_dir.join("markers.json")
+        } else {
+            PathBuf::from("markers.json")
+        }
+    }
+
+    pub fn load() -> Self {
+        let path = Self::get_path();
+        if let Ok(content) = fs::read_to_string(&path) {
+            if let Ok(store) = serde_json::from_str(&content) {
+                return store;
+            }
+        }
+        Self::default()
+    }
+
+    pub fn save(&self) {
+        let path = Self::get_path();
+        if let Ok(json) = serde_json::to_string_pretty(self) {
+            let _ = fs::write(path, json);
+        }
+    }
+
+    /// Adds a marker stamped with the current time and persists the store.
+    /// Empty labels are ignored.
+    pub fn add(&mut self, label: &str) {
+        let label = label.trim();
+        if label.is_empty() {
+            return;
+        }
+
+        self.markers.push(Marker {
+            timestamp: now_secs(),
+            label: label.to_string(),
+        });
+        if self.markers.len() > MAX_MARKERS {
+            let excess = self.markers.len() - MAX_MARKERS;
+            self.markers.drain(..excess);
+        }
+        self.save();
+    }
+
+    /// Returns the markers placed within the last `window_secs` seconds, paired with their
+    /// horizontal position on a chart (0.0 = left edge / oldest, 1.0 = right edge / now).
+    pub fn in_window(&self, window_secs: u64) -> Vec<(f32, &Marker)> {
+        let now = now_secs();
+        let window = window_secs.max(1);
+        self.markers
+            .iter()
+            .filter_map(|m| {
+                let age = now.saturating_sub(m.timestamp);
+                if age <= window {
+                    Some((1.0 - age as f32 / window as f32, m))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+/// Current wall-clock time in seconds since the UNIX epoch.
+pub fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
@@ -5,46 +5,173 @@
 //! - `sysinfo` for CPU, Memory, and Disk usage.
 //! - `nvml-wrapper` for NVIDIA GPU statistics.
 //! - `default-net` (via `sysinfo::Networks`) for Network traffic monitoring.
+//! - cgroup v2 (via `cgroups::CgroupTracker`) for per-slice CPU/Memory usage.
+//! - hwmon (via `hwmon::FanTracker`) for fan PWM duty cycles and control modes.
+//! - hwmon (via `sensors::SensorTracker`) for every temperature, fan and voltage input.
+//! - `/proc/stat` and `/proc/schedstat` (via `sched::SchedTracker`) for run-queue pressure.
+//! - `/proc/sys` (via `limits::KernelLimits`) for file descriptor and process/thread limits.
+//! - `/proc/sys/kernel/random` (via `entropy::EntropyStatus`) for RNG health.
+//! - `/sys/fs/selinux` and `/sys/module/apparmor` (via `mac::MacStatus`) for the MAC mode.
+//! - `/sys/class/net` (via `links::LinkTracker`) for carrier and link speed changes.
+//! - UDP DNS queries (via `dns::DnsTracker`, opt-in) for resolver latency.
+//! - SSH (via `remote::RemoteTracker`) for agentless remote host metrics.
+//! - `openssl` (via `certs::CertTracker`) for certificate expiry.
+//! - Paths and commands (via `backups::BackupTracker`) for backup freshness.
+//! - `virsh` (via `vms::VmTracker`) for libvirt/QEMU virtual machines.
+//! - `/proc/<pid>/environ` (via `games::GameTracker`) for Steam game sessions.
+//! - User-configured commands (via `custommetrics::CustomMetricTracker`) for custom metrics.
+//!
+//! Per-minute statistics of the snapshot series can be kept in SQLite
+//! (`longterm::LongTermStore`, opt-in) for the History tab.
+//!
+//! Optional tools and libraries are probed once at startup (`capabilities::Capabilities`)
+//! so missing ones can be reported instead of failing silently.
+//!
+//! Conditions that need attention are collected in an `alerts::AlertCenter` (optionally
+//! forwarded to the system log via `syslog::SyslogForwarder`), and
+//! `snapshot::MetricsSnapshot` captures the latest values for the exporters and the
+//! series pinned to the Overview (`pins::PinTracker`). Embedders can instead subscribe
+//! to per-tick deltas (`events::MetricEvent`).
 //!
 //! The `SystemMonitor` struct maintains historical data buffers (sliding windows)
 //! for each metric to facilitate real-time graph rendering.
 
-use log::error;
-use nvml_wrapper::Nvml;
-use std::collections::VecDeque;
-use sysinfo::{Disks, Networks, System};
+use crate::alerts::{Alert, AlertCenter, AlertSeverity, CRITICAL_FRACTION, WARNING_FRACTION};
+use crate::backups::{BackupStatus, BackupTracker};
+use crate::battery::{BatteryData, BatteryTracker};
+use crate::budget;
+use crate::capabilities::Capabilities;
+use crate::certs::{CertStatus, CertTracker, CERT_CRITICAL_DAYS};
+use crate::cgroups::{CgroupData, CgroupTracker, SandboxAppData, SandboxAttribution};
+use crate::csvlog::CsvLogger;
+use crate::custommetrics::{CustomMetricData, CustomMetricTracker};
+use crate::derived::DerivedMetrics;
+use crate::diskstats::{DiskIoData, DiskLatencyData, DiskLatencyTracker};
+use crate::dns::{DnsLatencyData, DnsTracker};
+use crate::drivetemp::{DriveTempData, DriveTempTracker};
+use crate::entropy::EntropyStatus;
+use crate::events::{EventBus, MetricEvent};
+use crate::frametime::{FrameTimeData, FrameTimeTracker};
+use crate::games::{GameSession, GameTracker};
+use crate::gpujobs::{self, GpuJobEvent, GpuJobWatcher};
+use crate::gpupower::{self, GpuPowerState};
+use crate::gpusession::GpuSessionRecorder;
+use crate::health::{HealthInputs, HealthReport, HealthRules};
+use crate::history::{self, History, HistoryLimits, HistoryOverlay};
+use crate::hwmon::{FanData, FanTracker};
+use crate::idle::IdleTracker;
+use crate::idleaction::{IdleActionWatcher, IdleInputs};
+use crate::kmsg::{KernelLogEntry, KernelLogWatcher};
+use crate::limits::KernelLimits;
+use crate::links::{LinkTracker, FLAP_THRESHOLD, FLAP_WINDOW_SECS};
+use crate::longterm::{LongTermPoint, LongTermStore};
+use crate::mac::{DenialSummary, MacStatus};
+use crate::markers::{now_secs, MarkerStore};
+use crate::mounts::{self, MountTracker};
+use crate::notify::Notifier;
+use crate::overlay::OverlayServer;
+use crate::pins::{PinTracker, PinnedSeriesData, SeriesInfo};
+use crate::processes::{
+    self, ProcessData, ProcessDetail, ProcessSort, ProcessStateData, ProcessTracker,
+};
+use crate::profiles::ProfileSwitcher;
+use crate::remote::{RemoteHostData, RemoteTracker};
+use crate::sched::{SchedData, SchedTracker};
+use crate::scopedrules::{self, ScopedInputs, ScopedRules};
+use crate::sensors::{SensorData, SensorTracker};
+use crate::settings::{
+    AppSettings, BackupSettings, CertSettings, ChartOverlaySettings, ChartScaleSettings,
+    CollectorSettings, CsvSettings, CustomMetric, DataBudgetSettings, DerivedMetric, GrpcSettings,
+    LongTermHistorySettings, NotificationSettings, OverlaySettings, PowerAction,
+    PrometheusSettings, ScriptSettings, WebhookSettings,
+};
+use crate::smarttrend::{SmartCounters, SmartHistory, SmartTrend, WEAR_WARN_DAYS};
+use crate::snapshot::MetricsSnapshot;
+use crate::suggestions::{Suggestion, SuggestionEngine};
+use crate::suspend::{self, SuspendDetector};
+use crate::syslog::SyslogForwarder;
+use crate::totals::CumulativeTotals;
+use crate::vms::{VmData, VmTracker};
+use crate::webhook::WebhookSink;
+use crate::xid::XidMonitor;
+use log::{error, info, warn};
+use nvml_wrapper::enum_wrappers::device::PerformanceState;
+use nvml_wrapper::enums::device::UsedGpuMemory;
+use nvml_wrapper::{Device, Nvml};
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Receiver;
+use std::time::Duration;
+use sysinfo::{Disks, Networks, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+/// Version of the serialized form of the public data structs (this module's `*Data` and
+/// `*Info` structs, the trackers' data structs and `snapshot::MetricsSnapshot`). It is
+/// incremented when a field is renamed or removed or changes its unit; new fields do
+/// not change it.
+pub const SCHEMA_VERSION: u32 = 1;
 
 /// Holds data for a single CPU core for external consumers
 #[allow(dead_code)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct CoreData {
     pub usage: f32,
-    pub history: Vec<f32>,
+    pub history: History,
 }
 
 /// Holds data for GPU
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct GpuData {
     pub name: String,
     pub util: f32,
     pub mem_used_mb: f32,
     pub mem_total_mb: f32,
-    pub util_history: Vec<f32>,
-    pub mem_history: Vec<f32>,
+    /// Core temperature in °C, if reported.
+    pub temperature: Option<f32>,
+    /// Powered down by runtime PM and not polled (see `gpupower.rs`).
+    pub suspended: bool,
+    pub util_history: History,
+    pub mem_history: History,
+}
+
+/// Holds data for a process running on an NVIDIA GPU
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct GpuProcessData {
+    pub pid: u32,
+    /// Process name; empty if the process is not visible (e.g. in another PID namespace).
+    pub name: String,
+    /// Index of the GPU, as in `get_gpu_data()`.
+    pub gpu: usize,
+    /// Has a compute (CUDA, OpenCL) and/or a graphics (OpenGL, Vulkan) context.
+    pub compute: bool,
+    pub graphics: bool,
+    /// Dedicated memory in MB; `None` if the driver does not report it.
+    pub vram_mb: Option<f32>,
+    /// Highest SM (3D/compute), encoder and decoder utilization in percent sampled
+    /// since the previous refresh; `None` if the GPU does not report them.
+    pub sm_util: Option<u32>,
+    pub enc_util: Option<u32>,
+    pub dec_util: Option<u32>,
 }
 
 /// Holds data for Network Interface
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct NetworkData {
     pub name: String,
     pub rx_bytes: u64,
     pub tx_bytes: u64,
     pub total_rx_bytes: u64,
     pub total_tx_bytes: u64,
-    pub history: Vec<f32>, // Stores RX in MB for graph
+    pub history: History, // Stores RX in MB for graph
+    /// TX in MB per refresh, like `history`.
+    #[serde(default)]
+    pub tx_history: History,
     pub ips_v4: Vec<String>,
     // pub ips_v6: Vec<String>, // Unused for now
     pub is_default: bool,
 }
 
 /// Holds data for Disk
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct DiskData {
     pub name: String,
     pub mount_point: String,
@@ -54,7 +181,7 @@ pub struct DiskData {
 }
 
 // Detailed hardware information structures for sub-tabs
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct CpuDetailedInfo {
     pub name: String,
     pub vendor: String,
@@ -92,24 +219,48 @@ pub struct StorageDetailedInfo {
     pub serial_number: String,
     pub firmware_version: String,
     pub health_status: String,
+    /// Drive temperature in °C from smartctl.
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    /// Failure-predicting counters from smartctl.
+    #[serde(default)]
+    pub smart: SmartCounters,
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct GpuDetailedInfo {
     pub name: String,
     pub vram_total: u64,
-    // ... (rest omitted, but replace block needs to be complete or targeted)
-    // Wait, I should target specific lines or reuse whole block.
-    // I'll use separate replacements for safety if possible? No, multi_replace.
-    // I'll target the derive lines.
     pub vram_used: u64,
     pub driver_version: String,
     pub temperature: Option<i32>,
+    /// Power values are in Watts.
     pub power_draw: Option<f32>,
     pub power_limit: Option<f32>,
+    pub power_limit_min: Option<f32>,
+    pub power_limit_max: Option<f32>,
+    pub power_limit_default: Option<f32>,
     pub fan_speed: Option<u32>,
     pub gpu_utilization: Option<u32>,
     pub memory_utilization: Option<u32>,
+    /// Clock values are in MHz.
+    pub clock_graphics: Option<u32>,
+    pub clock_graphics_max: Option<u32>,
+    pub clock_memory: Option<u32>,
+    pub clock_memory_max: Option<u32>,
+    /// Highest CUDA version the driver supports, e.g. "12.2".
+    pub cuda_version: Option<String>,
+    /// CUDA compute capability, e.g. "8.6".
+    pub compute_capability: Option<String>,
+    pub architecture: Option<String>,
+    pub memory_bus_width: Option<u32>,
+    /// Peak memory bandwidth in GB/s, derived from the bus width and max memory clock.
+    pub memory_bandwidth: Option<f32>,
+    /// Current and maximum PCIe link, e.g. "Gen 4 x16 (max Gen 4 x16)".
+    pub pcie_link: Option<String>,
+    /// Runtime power state, e.g. "Active" or "Suspended (D3cold)".
+    #[serde(default)]
+    pub power_state: Option<String>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -125,6 +276,29 @@ pub struct NetworkDetailedInfo {
     pub link_speed: String,
 }
 
+/// Hardware and OS summary that doesn't change while the application runs, shown on the
+/// Information tab and in shared snapshots.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct StaticSystemInfo {
+    pub hostname: String,
+    /// Name and version, e.g. "Fedora Linux 41".
+    pub os_name: String,
+    pub kernel: String,
+    pub cpu_brand: String,
+    pub cores: usize,
+    pub total_mem: String,
+    pub bios_version: String,
+    pub total_storage: String,
+    /// GPU names with VRAM, comma separated; empty without NVIDIA GPUs.
+    pub gpu_names: String,
+    pub cpu_freq: String,
+    pub cpu_arch: String,
+    pub motherboard: String,
+    pub boot_mode: String,
+    /// One line per physical disk, e.g. "Samsung SSD 980 PRO 1TB (931.5 GB)".
+    pub individual_disks: String,
+}
+
 /// The core system monitoring struct.
 ///
 /// It holds the state of the system resources and maintains historical data for rendering graphs.
@@ -133,27 +307,272 @@ pub struct SystemMonitor {
     pub disks: Disks,
     pub networks: Networks,
     pub nvml: Option<Nvml>,
+    /// Optional dependencies detected at startup.
+    pub capabilities: Capabilities,
+    /// Subsystems enabled in `settings.collectors`.
+    pub collectors: CollectorSettings,
+    /// User-defined display names, keyed by interface, disk, GPU or fan label.
+    pub aliases: BTreeMap<String, String>,
+    /// Fixed or automatic scale of the network and drive latency charts.
+    pub chart_scales: ChartScaleSettings,
+    /// Charts with average and percentile lines.
+    pub chart_overlays: ChartOverlaySettings,
 
     /// Sliding window of CPU usage history (per core).
-    pub cpu_history: Vec<VecDeque<f32>>,
+    pub cpu_history: Vec<History>,
     /// Sliding window of Memory usage history (percent).
-    pub mem_history: VecDeque<f32>,
+    pub mem_history: History,
+    /// Sliding window of swap usage history (percent of the swap space).
+    pub swap_history: History,
     /// Sliding window of GPU Utilization history (per GPU).
-    pub gpu_util_history: Vec<VecDeque<f32>>,
+    pub gpu_util_history: Vec<History>,
     /// Sliding window of GPU Memory usage history (per GPU).
-    pub gpu_mem_history: Vec<VecDeque<f32>>,
+    pub gpu_mem_history: Vec<History>,
+    /// Runtime PM state of the display controllers, read on every refresh.
+    gpu_power: Vec<GpuPowerState>,
+    /// Keep polling NVIDIA GPUs through NVML while they are runtime suspended.
+    poll_suspended_gpu: bool,
+    /// Last name reported by each NVML device, shown while it is suspended.
+    gpu_names: Vec<String>,
+    /// Processes using the NVML devices at the last refresh (names are looked up on read).
+    gpu_processes: Vec<GpuProcessData>,
+    /// Timestamp of the newest process utilization sample read from each NVML device,
+    /// so every refresh only reads the samples taken since the previous one.
+    gpu_process_seen: Vec<u64>,
     /// Sliding window of Network RX history (per Interface).
-    pub net_history: Vec<VecDeque<f32>>, // Keyed by sorted interface index
+    pub net_history: Vec<History>, // Keyed by sorted interface index
+    pub net_tx_history: Vec<History>, // Same keys as `net_history`
+
+    /// Per-slice/scope CPU and memory history from the cgroup v2 hierarchy.
+    pub cgroups: CgroupTracker,
+    /// Process list with each process's cgroup, unit and container.
+    pub processes: ProcessTracker,
+    /// Groups processes by Flatpak app ID / Snap name.
+    pub sandbox_attribution: SandboxAttribution,
+    /// Per-app usage totals for sandboxed apps, updated on each refresh.
+    pub sandbox_apps: Vec<SandboxAppData>,
+    /// Fan RPM and chip temperature history from hwmon.
+    pub fans: FanTracker,
+    /// Temperature, fan and voltage history of every hwmon input (Sensors tab).
+    pub sensors: SensorTracker,
+    /// Charge and power history of the laptop batteries.
+    pub batteries: BatteryTracker,
+    /// Running Steam games and their recorded play sessions.
+    pub games: GameTracker,
+    /// FPS and frame time of the running game (opt-in, from MangoHud logs).
+    pub frame_time: Option<FrameTimeTracker>,
+    /// Per-drive temperature history.
+    pub drive_temps: DriveTempTracker,
+    /// Per-drive request latency, queue depth and throughput history from
+    /// `/proc/diskstats`.
+    pub disk_latency: DiskLatencyTracker,
+    /// Runnable/blocked task counts and run-queue wait history.
+    pub sched: SchedTracker,
+    /// Open files and process/thread counts vs their kernel limits.
+    pub limits: KernelLimits,
+    /// Kernel entropy pool and RNG source status.
+    pub entropy: EntropyStatus,
+    /// Active SELinux/AppArmor mode.
+    pub mac: MacStatus,
+    /// Cached result of `entropy::detect_virtualization()`.
+    virtualized: bool,
+    /// Carrier/speed state of physical interfaces, for link event markers.
+    pub links: LinkTracker,
+    /// Read-only flag of mounted filesystems, for remount alerts.
+    mounts: MountTracker,
+    /// Wall-clock time of the previous refresh, to notice suspend/resume.
+    suspend: SuspendDetector,
+    /// NVIDIA driver errors reported by the worker, for alerts and markers.
+    xid: XidMonitor,
+    /// Kernel log entries reported by the worker, for I/O error markers.
+    kernel_log: KernelLogWatcher,
+    /// Resolver latency probe; `None` unless `settings.enable_dns_probe` is set.
+    pub dns: Option<DnsTracker>,
+    /// User idle time and active/idle session statistics; `None` for headless monitors.
+    pub idle: Option<IdleTracker>,
+    /// Power-saving hints derived from the collected data.
+    pub suggestions: SuggestionEngine,
+    /// Remote hosts polled over SSH.
+    pub remote: RemoteTracker,
+    /// libvirt VMs; `None` for headless monitors or when `virsh` is missing.
+    pub vms: Option<VmTracker>,
+    /// Watched TLS certificates.
+    pub certs: CertTracker,
+    /// Days before expiry at which certificates raise a warning.
+    cert_warn_days: u32,
+    /// Certificates with an active expiry alert.
+    cert_alerts: HashSet<String>,
+    /// Watched backup jobs.
+    pub backups: BackupTracker,
+    /// Hours after the last success at which a backup raises a warning.
+    backup_max_age_hours: u32,
+    /// Backup jobs with an active alert.
+    backup_alerts: HashSet<String>,
+    /// Metrics read from user-configured commands.
+    pub custom_metrics: CustomMetricTracker,
+    /// Parsed `derived_metrics`, computed into every snapshot.
+    pub derived: DerivedMetrics,
+    /// Persistent SMART counters, for trend alerts.
+    smart_history: SmartHistory,
+    /// Drives (by serial number) with an active SMART trend alert.
+    smart_alerts: HashSet<String>,
+    /// Temperature, SMART status, memory and disk space rules feeding the health score.
+    health_rules: HealthRules,
+    /// Usage rules for single processes, units, containers and VMs.
+    scoped_rules: ScopedRules,
+    /// Network and disk byte counters across restarts; only kept by the main window.
+    totals: Option<CumulativeTotals>,
+    /// Report totals since install instead of since boot.
+    totals_since_install: bool,
+    /// Monthly data cap, checked against the period's network totals.
+    data_budget: DataBudgetSettings,
+    /// Id of the active data budget alert.
+    budget_alert: Option<String>,
+    /// Busy/idle stretches of each GPU, for "job finished" alerts.
+    gpu_jobs: GpuJobWatcher,
+    /// Idle stretch and countdown of the opt-in suspend/shutdown automation.
+    pub idle_action: IdleActionWatcher,
+    /// Action whose countdown ran out, until `take_idle_action` hands it to the UI.
+    idle_action_due: Option<PowerAction>,
+    /// Currently active alerts.
+    pub alerts: AlertCenter,
+    /// Journald/syslog destination for alert transitions, if forwarding is enabled.
+    pub syslog: Option<SyslogForwarder>,
+    /// Periodic snapshot delivery; `None` unless `settings.webhook` is enabled.
+    pub webhook: Option<WebhookSink>,
+    /// Desktop/webhook announcements of alert transitions; `None` until
+    /// `set_notifications` is called (headless monitors stay quiet).
+    pub notifier: Option<Notifier>,
+    /// High-rate GPU capture for a labeled span; `None` when not recording.
+    pub gpu_session: Option<GpuSessionRecorder>,
+    /// Outcome of the last finished GPU capture (files written or error).
+    gpu_session_result: String,
+    /// Subscribers of `subscribe()`.
+    events: EventBus,
+    /// Daily CSV logging; `None` unless `settings.csv` is enabled.
+    pub csv_logger: Option<CsvLogger>,
+    /// Per-minute statistics database; `None` unless `settings.long_term_history` is
+    /// enabled, and for headless monitors.
+    pub long_term: Option<LongTermStore>,
+    /// `/metrics` endpoint; `None` unless `settings.prometheus` is enabled.
+    #[cfg(feature = "prometheus")]
+    pub prometheus: Option<crate::prometheus::PrometheusExporter>,
+    /// gRPC service; `None` unless `settings.grpc` is enabled.
+    #[cfg(feature = "grpc")]
+    pub grpc: Option<crate::grpc::GrpcServer>,
+    /// Streaming overlay page; `None` unless `settings.overlay` is enabled.
+    pub overlay: Option<OverlayServer>,
+    /// Where the `/metrics` endpoint, the overlay page and the gRPC service are served,
+    /// or why they failed to start; `None` while disabled.
+    pub prometheus_status: Option<Result<String, String>>,
+    pub overlay_status: Option<Result<String, String>>,
+    pub grpc_status: Option<Result<String, String>>,
+    /// Script run on every snapshot; `None` unless `settings.scripting` is enabled.
+    #[cfg(feature = "scripting")]
+    pub script: Option<crate::scripting::ScriptHook>,
+    /// Series pinned to the Overview tab.
+    pub pins: PinTracker,
 
     /// Stable sorted interface names to ensure consistent indexing across refreshes.
     pub interface_names: Vec<String>,
 
-    /// Maximum number of data points to keep in history buffers.
-    /// Calculated based on refresh rate to maintain a 60-second window.
-    pub max_history: usize,
+    /// Size and time span of every history buffer, from the refresh rate, the chart
+    /// window and the number of series (see `HistoryLimits::new`).
+    pub history_limits: HistoryLimits,
+    /// Time span shown by the charts, in seconds.
+    pub history_window_secs: u64,
+    pub refresh_rate_ms: u64,
+
+    /// Where `/sys` and `/proc` are read from for the detailed hardware info and by the
+    /// sysfs trackers (fans, sensors, batteries, drive temperatures and latency).
+    /// Change it with `set_sys_paths`.
+    pub sys_paths: SysPaths,
 
     // Privileged Data (Shared with UI)
     pub privileged_data: std::sync::Arc<std::sync::Mutex<Option<crate::worker::PrivilegedData>>>,
+
+    /// User-defined timeline annotations, persisted across sessions.
+    pub markers: MarkerStore,
+    /// Process names that trigger automatic launch/exit markers.
+    pub watched_processes: Vec<String>,
+    /// Watched process names that were running at the last refresh.
+    pub watched_running: HashSet<String>,
+    /// Usage view tab switching for configured applications.
+    pub profiles: ProfileSwitcher,
+}
+
+/// Processes with a compute or graphics context on `dev` (GPU `gpu`), with their VRAM
+/// and the utilization sampled since `last_seen`, which is advanced to the newest sample.
+fn collect_gpu_processes(dev: &Device, gpu: usize, last_seen: &mut u64) -> Vec<GpuProcessData> {
+    let mut processes: Vec<GpuProcessData> = Vec::new();
+    let contexts = [
+        (true, dev.running_compute_processes()),
+        (false, dev.running_graphics_processes()),
+    ];
+    for (compute, list) in contexts {
+        for info in list.unwrap_or_default() {
+            let vram_mb = match info.used_gpu_memory {
+                UsedGpuMemory::Used(bytes) => Some(bytes as f32 / 1024.0 / 1024.0),
+                UsedGpuMemory::Unavailable => None,
+            };
+            let index = match processes.iter().position(|p| p.pid == info.pid) {
+                Some(index) => index,
+                None => {
+                    processes.push(GpuProcessData {
+                        pid: info.pid,
+                        name: String::new(),
+                        gpu,
+                        compute: false,
+                        graphics: false,
+                        vram_mb: None,
+                        sm_util: None,
+                        enc_util: None,
+                        dec_util: None,
+                    });
+                    processes.len() - 1
+                }
+            };
+            let process = &mut processes[index];
+            if compute {
+                process.compute = true;
+            } else {
+                process.graphics = true;
+            }
+            // Both lists report the process' whole allocation; keep the larger one
+            process.vram_mb = match (process.vram_mb, vram_mb) {
+                (Some(a), Some(b)) => Some(a.max(b)),
+                (a, b) => a.or(b),
+            };
+        }
+    }
+
+    // Not supported before Maxwell; processes idle in the period have no sample
+    if let Ok(samples) = dev.process_utilization_stats(*last_seen) {
+        for process in &mut processes {
+            process.sm_util = Some(0);
+            process.enc_util = Some(0);
+            process.dec_util = Some(0);
+        }
+        for sample in samples {
+            *last_seen = (*last_seen).max(sample.timestamp);
+            if let Some(process) = processes.iter_mut().find(|p| p.pid == sample.pid) {
+                process.sm_util = process.sm_util.max(Some(sample.sm_util));
+                process.enc_util = process.enc_util.max(Some(sample.enc_util));
+                process.dec_util = process.dec_util.max(Some(sample.dec_util));
+            }
+        }
+    }
+    processes
+}
+
+/// Opens the long-term history database if enabled, logging why it could not be opened.
+fn open_long_term(settings: &LongTermHistorySettings) -> Option<LongTermStore> {
+    if !settings.enabled {
+        return None;
+    }
+    LongTermStore::open(settings)
+        .map_err(|e| warn!("{}", e))
+        .ok()
 }
 
 impl SystemMonitor {
@@ -161,63 +580,122 @@ impl SystemMonitor {
     ///
     /// Initializes `sysinfo` components, detects NVIDIA GPUs via `nvml`, and pre-allocation
     /// history buffers based on the provided `refresh_rate_ms`.
-    /// Also spawns the privileged worker process if possible.
-    pub fn new(refresh_rate_ms: u64) -> Self {
+    /// Also spawns the privileged worker process if possible, with packet capture
+    /// enabled when `settings.enable_packet_capture` is set.
+    pub fn new(settings: &AppSettings) -> Self {
+        Self::build(settings, true)
+    }
+
+    /// Creates a `SystemMonitor` without the privileged worker, for command line use
+    /// where a `pkexec` prompt would be unexpected.
+    pub fn new_headless(settings: &AppSettings) -> Self {
+        Self::build(settings, false)
+    }
+
+    fn build(settings: &AppSettings, spawn_worker: bool) -> Self {
+        let refresh_rate_ms = settings.refresh_rate_ms;
+        let sys_paths = SysPaths::default();
         let mut system = System::new_all();
         system.refresh_all();
         let disks = Disks::new_with_refreshed_list();
-        let networks = Networks::new_with_refreshed_list();
+        let collectors = settings.collectors.clone();
+        let networks = if collectors.network {
+            Networks::new_with_refreshed_list()
+        } else {
+            Networks::new()
+        };
+
+        let mut capabilities = Capabilities::detect();
+        let vms = (spawn_worker && capabilities.virsh).then(VmTracker::start);
+        let mut cgroups = CgroupTracker::new();
+        cgroups.set_targets(scopedrules::cgroup_targets(&settings.scoped_rules));
 
         // Privileged Data Holder
         let privileged_data = std::sync::Arc::new(std::sync::Mutex::new(None));
         let privileged_data_clone = privileged_data.clone();
 
         // Spawn Worker Thread
-        std::thread::spawn(move || {
-            let exe = std::env::current_exe().unwrap();
-            // Try to spawn worker via pkexec
-            // Note: pkexec might prompt for password.
-            if let Ok(mut child) = std::process::Command::new("pkexec")
-                .arg(exe)
-                .arg("--privileged-worker")
-                .stdout(std::process::Stdio::piped())
-                .stderr(std::process::Stdio::null()) // suppress errors or redirect?
-                .spawn()
-            {
-                if let Some(stdout) = child.stdout.take() {
-                    let reader = std::io::BufReader::new(stdout);
-                    use std::io::BufRead;
-                    for json in reader.lines().map_while(Result::ok) {
-                        if let Ok(data) =
-                            serde_json::from_str::<crate::worker::PrivilegedData>(&json)
-                        {
+        if spawn_worker && !capabilities.pkexec {
+            error!("pkexec not found; privileged data will be unavailable.");
+        } else if spawn_worker {
+            let mut worker_args = vec!["--privileged-worker"];
+            if settings.enable_packet_capture {
+                worker_args.push("--capture");
+            }
+            if !collectors.smart {
+                worker_args.push("--no-smart");
+            }
+            if !collectors.network {
+                worker_args.push("--no-network");
+            }
+            if !collectors.gpu {
+                worker_args.push("--no-gpu");
+            }
+            std::thread::spawn(move || {
+                let exe = match crate::worker::worker_executable() {
+                    Ok(exe) => exe,
+                    Err(e) => {
+                        error!("Not starting the privileged worker: {}", e);
+                        return;
+                    }
+                };
+                // Try to spawn worker via pkexec
+                // Note: pkexec might prompt for password.
+                if let Ok(mut child) = std::process::Command::new("pkexec")
+                    .arg(exe)
+                    .args(worker_args)
+                    .stdout(std::process::Stdio::piped())
+                    .stderr(std::process::Stdio::null()) // suppress errors or redirect?
+                    .spawn()
+                {
+                    if let Some(stdout) = child.stdout.take() {
+                        let reader = std::io::BufReader::new(stdout);
+                        let result = crate::worker::read_messages(reader, |data| {
                             if let Ok(mut guard) = privileged_data_clone.lock() {
                                 *guard = Some(data);
                             }
+                        });
+                        if let Err(e) = result {
+                            error!("Disconnecting the privileged worker: {}", e);
+                            let _ = child.kill();
                         }
                     }
+                    let _ = child.wait();
+                } else {
+                    error!("Failed to spawn privileged worker via pkexec.");
                 }
-                let _ = child.wait();
-            } else {
-                error!("Failed to spawn privileged worker via pkexec.");
-            }
-        });
+            });
+        }
 
-        // Initialize NVML
-        let nvml = match Nvml::init() {
-            Ok(n) => Some(n),
-            Err(e) => {
-                error!("NVML Init failed: {}", e);
-                None
+        // Initialize NVML (skipped when the GPU collector is disabled, e.g. AMD-only machines)
+        let nvml = if !collectors.gpu {
+            info!("GPU collector disabled; skipping NVML");
+            None
+        } else {
+            match Nvml::init() {
+                Ok(n) => Some(n),
+                Err(e) => {
+                    error!("NVML Init failed: {}", e);
+                    None
+                }
             }
         };
+        capabilities.nvml = nvml.is_some();
+        for missing in capabilities.report().iter().filter(|c| !c.available) {
+            info!(
+                "{} not available: {} disabled",
+                missing.name, missing.enables
+            );
+        }
 
         let mut interface_names: Vec<String> = networks.keys().cloned().collect();
         interface_names.sort();
 
         let cpu_count = system.cpus().len();
-        // 60 seconds * (1000 / ms) updates/second
-        let max_history = (60 * 1000 / refresh_rate_ms).max(1) as usize;
+        // Sized for the CPU histories alone; `rebalance_histories` counts them all after
+        // the first refresh
+        let history_limits =
+            HistoryLimits::new(settings.history_window_secs, refresh_rate_ms, cpu_count);
 
         // GPU Count logic
         let gpu_count = if let Some(n) = &nvml {
@@ -231,53 +709,671 @@ impl SystemMonitor {
             disks,
             networks,
             nvml,
-            cpu_history: vec![VecDeque::from(vec![0.0; max_history]); cpu_count],
-            mem_history: VecDeque::from(vec![0.0; max_history]),
-            gpu_util_history: vec![VecDeque::from(vec![0.0; max_history]); gpu_count],
-            gpu_mem_history: vec![VecDeque::from(vec![0.0; max_history]); gpu_count],
-            net_history: vec![VecDeque::from(vec![0.0; max_history]); interface_names.len()],
+            capabilities,
+            collectors,
+            aliases: settings.aliases.clone(),
+            chart_scales: settings.chart_scales.clone(),
+            chart_overlays: settings.chart_overlays.clone(),
+            cpu_history: vec![History::with_limits(history_limits); cpu_count],
+            mem_history: History::with_limits(history_limits),
+            swap_history: History::with_limits(history_limits),
+            gpu_util_history: vec![History::with_limits(history_limits); gpu_count],
+            gpu_mem_history: vec![History::with_limits(history_limits); gpu_count],
+            gpu_power: gpupower::read_power_states(),
+            poll_suspended_gpu: settings.poll_suspended_gpu,
+            gpu_names: Vec::new(),
+            gpu_processes: Vec::new(),
+            gpu_process_seen: Vec::new(),
+            net_history: vec![History::with_limits(history_limits); interface_names.len()],
+            net_tx_history: vec![History::with_limits(history_limits); interface_names.len()],
+            cgroups,
+            processes: ProcessTracker::new(&sys_paths, history_limits),
+            sandbox_attribution: SandboxAttribution::new(),
+            sandbox_apps: Vec::new(),
+            fans: FanTracker::new(&sys_paths),
+            sensors: SensorTracker::new(&sys_paths),
+            batteries: BatteryTracker::new(&sys_paths),
+            games: GameTracker::new(),
+            frame_time: settings
+                .frame_time
+                .enabled
+                .then(|| FrameTimeTracker::new(&settings.frame_time.log_dir, history_limits)),
+            drive_temps: DriveTempTracker::new(&sys_paths),
+            disk_latency: DiskLatencyTracker::new(&sys_paths),
+            sched: SchedTracker::new(history_limits),
+            limits: KernelLimits::default(),
+            entropy: EntropyStatus::default(),
+            mac: MacStatus::read(),
+            virtualized: crate::entropy::detect_virtualization(),
+            links: LinkTracker::new(),
+            mounts: MountTracker::new(),
+            suspend: SuspendDetector::new(),
+            xid: XidMonitor::new(),
+            kernel_log: KernelLogWatcher::new(),
+            dns: settings
+                .enable_dns_probe
+                .then(|| DnsTracker::start(settings.dns_probe_host.clone())),
+            idle: spawn_worker.then(IdleTracker::start),
+            suggestions: SuggestionEngine::new(),
+            remote: RemoteTracker::start(settings.remote_hosts.clone(), history_limits),
+            vms,
+            certs: CertTracker::start(settings.certificates.targets.clone()),
+            cert_warn_days: settings.certificates.warn_days,
+            cert_alerts: HashSet::new(),
+            backups: BackupTracker::start(settings.backups.jobs.clone()),
+            backup_max_age_hours: settings.backups.max_age_hours,
+            backup_alerts: HashSet::new(),
+            custom_metrics: CustomMetricTracker::start(&settings.custom_metrics, history_limits),
+            derived: DerivedMetrics::new(&settings.derived_metrics),
+            smart_history: if spawn_worker {
+                SmartHistory::open(&settings.long_term_history)
+            } else {
+                SmartHistory::default()
+            },
+            health_rules: HealthRules::new(settings.alert_rules.clone()),
+            scoped_rules: ScopedRules::new(&settings.scoped_rules),
+            totals: spawn_worker.then(CumulativeTotals::load),
+            totals_since_install: settings.totals_since_install,
+            data_budget: settings.data_budget.clone(),
+            budget_alert: None,
+            gpu_jobs: GpuJobWatcher::new(&settings.gpu_jobs),
+            idle_action: IdleActionWatcher::new(&settings.idle_action),
+            idle_action_due: None,
+            smart_alerts: HashSet::new(),
+            alerts: AlertCenter::new(),
+            syslog: settings
+                .forward_alerts_to_syslog
+                .then(SyslogForwarder::connect)
+                .flatten(),
+            webhook: (settings.webhook.enabled && !settings.webhook.url.is_empty()).then(|| {
+                WebhookSink::start(settings.webhook.url.clone(), settings.webhook.interval_secs)
+            }),
+            notifier: None,
+            gpu_session: None,
+            events: EventBus::new(),
+            gpu_session_result: String::new(),
+            csv_logger: settings.csv.enabled.then(|| CsvLogger::new(&settings.csv)),
+            long_term: if spawn_worker {
+                open_long_term(&settings.long_term_history)
+            } else {
+                None
+            },
+            #[cfg(feature = "prometheus")]
+            prometheus: None,
+            #[cfg(feature = "grpc")]
+            grpc: None,
+            overlay: None,
+            prometheus_status: None,
+            overlay_status: None,
+            grpc_status: None,
+            #[cfg(feature = "scripting")]
+            script: None,
+            pins: PinTracker::new(&settings.pinned_series, history_limits),
             interface_names,
-            max_history,
+            history_limits,
+            history_window_secs: settings.history_window_secs,
+            sys_paths,
+            refresh_rate_ms,
             privileged_data,
+            markers: MarkerStore::load(),
+            watched_processes: Vec::new(),
+            watched_running: HashSet::new(),
+            profiles: ProfileSwitcher::new(&settings.app_profiles),
+        }
+    }
+
+    /// Replaces the list of watched process names.
+    ///
+    /// Processes already running are recorded silently so that only subsequent
+    /// launches and exits produce markers.
+    pub fn set_watched_processes(&mut self, names: Vec<String>) {
+        self.watched_processes = names
+            .into_iter()
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty())
+            .collect();
+        self.refresh_processes();
+        self.watched_running = self.find_watched_running();
+    }
+
+    pub fn set_app_profiles(&mut self, profiles: &BTreeMap<String, String>) {
+        self.profiles.set_profiles(profiles);
+    }
+
+    /// Checks the application profiles against the running processes and returns the
+    /// Usage view tab to switch to, if any (see `ProfileSwitcher::update`).
+    pub fn profile_tab(&mut self, current_tab: i32) -> Option<i32> {
+        let running = self.find_running(&self.profiles.processes());
+        let tab = self.profiles.update(&running, current_tab);
+        if let Some(tab) = tab {
+            match self.profiles.active() {
+                Some(process) => info!("{} is running; switching to tab {}", process, tab),
+                None => info!("Profile application exited; back to tab {}", tab),
+            }
+        }
+        tab
+    }
+
+    /// Starts probing resolvers for `host`, or stops the probe when `host` is `None`.
+    pub fn set_dns_probe(&mut self, host: Option<String>) {
+        self.dns = host.map(DnsTracker::start);
+    }
+
+    /// Display name for `name`, or `name` itself when it has no alias. Disk names also
+    /// match without their `/dev/` prefix.
+    pub fn alias(&self, name: &str) -> String {
+        self.aliases
+            .get(name)
+            .or_else(|| {
+                name.strip_prefix("/dev/")
+                    .and_then(|short| self.aliases.get(short))
+            })
+            .cloned()
+            .unwrap_or_else(|| name.to_string())
+    }
+
+    /// "alias (name)" for aliased devices, so detail views still show the real name.
+    fn alias_with_name(&self, name: &str) -> String {
+        match self.alias(name) {
+            alias if alias == name => alias,
+            alias => format!("{} ({})", alias, name),
+        }
+    }
+
+    /// Whether suspended NVIDIA GPUs are still polled (which wakes them up).
+    pub fn set_poll_suspended_gpu(&mut self, enabled: bool) {
+        self.poll_suspended_gpu = enabled;
+    }
+
+    /// Whether NVML device `index` is runtime suspended and must not be queried.
+    fn gpu_asleep(&self, index: usize) -> bool {
+        !self.poll_suspended_gpu
+            && gpupower::nvidia_state(&self.gpu_power, index).is_some_and(|s| s.is_suspended())
+    }
+
+    fn gpu_name(&self, index: usize) -> String {
+        self.gpu_names
+            .get(index)
+            .cloned()
+            .unwrap_or_else(|| format!("NVIDIA GPU {}", index))
+    }
+
+    /// Replaces the user-defined display names.
+    pub fn set_aliases(&mut self, aliases: BTreeMap<String, String>) {
+        self.aliases = aliases;
+    }
+
+    pub fn set_chart_scales(&mut self, scales: ChartScaleSettings) {
+        self.chart_scales = scales;
+    }
+
+    pub fn set_chart_overlays(&mut self, overlays: ChartOverlaySettings) {
+        self.chart_overlays = overlays;
+    }
+
+    /// Average and percentile lines of `chart` (a `chart_overlays` key such as "cpu" or
+    /// "net.eth0") over `history`, as far as they are switched on for it.
+    pub fn get_chart_overlay(&self, chart: &str, history: &History) -> HistoryOverlay {
+        let overlays = &self.chart_overlays;
+        let average = overlays
+            .average
+            .iter()
+            .any(|c| c == chart)
+            .then_some(overlays.average_secs.max(1) as f64);
+        history.overlay(average, overlays.p95.iter().any(|c| c == chart))
+    }
+
+    /// Chooses between totals since boot and since install (see `totals.rs`).
+    pub fn set_totals_since_install(&mut self, since_install: bool) {
+        self.totals_since_install = since_install;
+    }
+
+    /// The since-install total of `key` when selected and kept, else `since_boot`.
+    fn select_total(&self, key: &str, since_boot: u64) -> u64 {
+        match &self.totals {
+            Some(totals) if self.totals_since_install => totals.get(key).unwrap_or(since_boot),
+            _ => since_boot,
         }
     }
 
+    /// Y-axis maximum of a network chart: the largest value in its window, or the fixed
+    /// maximum converted to MB per refresh interval (the unit of the network history).
+    pub fn network_chart_max(&self, history: &History) -> f32 {
+        if self.chart_scales.network_auto {
+            history.max_value().max(1.0)
+        } else {
+            (self.chart_scales.network_max_mbps.max(1) * self.refresh_rate_ms) as f32 / 1000.0
+        }
+    }
+
+    /// Current power-saving suggestions (see `suggestions.rs`).
+    pub fn get_suggestions(&self) -> Vec<Suggestion> {
+        self.suggestions.suggestions()
+    }
+
+    /// Session statistics line for the CPU tab, e.g. "Active 2 h 5 min (CPU 23%, GPU 4%) ·
+    /// Idle 40 min (CPU 31%, GPU 0%)".
+    pub fn session_stats_label(&self) -> String {
+        match &self.idle {
+            Some(idle) if idle.idle_time().is_some() => {
+                let state = if idle.is_idle() { "idle" } else { "active" };
+                format!("Session ({}): {}", state, idle.stats)
+            }
+            _ => "Session: idle time unavailable (needs GNOME, xprintidle or logind)".to_string(),
+        }
+    }
+
+    /// Replaces the polled remote hosts, restarting the SSH pollers.
+    pub fn set_remote_hosts(&mut self, hosts: Vec<String>) {
+        self.remote = RemoteTracker::start(hosts, self.history_limits);
+    }
+
+    /// Applies the certificate settings, restarting the checks if the targets changed.
+    pub fn set_certificates(&mut self, settings: &CertSettings) {
+        let current: Vec<String> = self
+            .certs
+            .get_data()
+            .into_iter()
+            .map(|c| c.target)
+            .collect();
+        if current != settings.targets {
+            self.certs = CertTracker::start(settings.targets.clone());
+        }
+        self.cert_warn_days = settings.warn_days;
+    }
+
+    /// Applies the backup settings, restarting the checks if the jobs changed.
+    pub fn set_backups(&mut self, settings: &BackupSettings) {
+        let current: Vec<String> = self
+            .backups
+            .get_data()
+            .into_iter()
+            .map(|b| b.target)
+            .collect();
+        if current != settings.jobs {
+            self.backups = BackupTracker::start(settings.jobs.clone());
+        }
+        self.backup_max_age_hours = settings.max_age_hours;
+    }
+
+    /// Applies the custom metric definitions, restarting the commands if they changed.
+    pub fn set_custom_metrics(&mut self, metrics: &[CustomMetric]) {
+        let configured: Vec<CustomMetric> = metrics
+            .iter()
+            .filter(|m| !m.command.trim().is_empty())
+            .cloned()
+            .collect();
+        if self.custom_metrics.metrics() != configured {
+            self.custom_metrics = CustomMetricTracker::start(&configured, self.history_limits);
+        }
+    }
+
+    /// Applies the derived metric definitions.
+    pub fn set_derived_metrics(&mut self, metrics: &[DerivedMetric]) {
+        self.derived = DerivedMetrics::new(metrics);
+    }
+
+    /// Applies the webhook settings, starting or stopping snapshot delivery.
+    pub fn set_webhook(&mut self, settings: &WebhookSettings) {
+        self.webhook = (settings.enabled && !settings.url.is_empty())
+            .then(|| WebhookSink::start(settings.url.clone(), settings.interval_secs));
+    }
+
+    /// Enables or disables forwarding alert transitions to the system log.
+    pub fn set_syslog_forwarding(&mut self, enabled: bool) {
+        self.syslog = enabled.then(SyslogForwarder::connect).flatten();
+    }
+
+    /// Applies the CSV logging settings, starting or stopping the logger.
+    pub fn set_csv_logging(&mut self, settings: &CsvSettings) {
+        self.csv_logger = settings.enabled.then(|| CsvLogger::new(settings));
+    }
+
+    /// Applies the long-term history settings, opening or closing the database. Their
+    /// retention also applies to the SMART history, which is stored either way.
+    pub fn set_long_term_history(&mut self, settings: &LongTermHistorySettings) {
+        self.smart_history
+            .set_retention_days(settings.retention_days);
+        match &mut self.long_term {
+            Some(store) if settings.enabled => store.set_retention_days(settings.retention_days),
+            Some(_) => self.long_term = None,
+            None => self.long_term = open_long_term(settings),
+        }
+    }
+
+    /// Applies the notification settings; `webhook_url` is the URL of the `webhook`
+    /// section, used by the webhook channel when the section sets none.
+    pub fn set_notifications(&mut self, settings: &NotificationSettings, webhook_url: &str) {
+        self.notifier = Some(Notifier::new(settings, webhook_url));
+    }
+
+    /// Applies the overlay settings, starting or stopping the overlay page.
+    pub fn set_overlay(&mut self, settings: &OverlaySettings) {
+        // Release the port before binding it again
+        self.overlay = None;
+        let started = settings.enabled.then(|| OverlayServer::start(settings));
+        self.overlay_status = started.as_ref().map(|result| match result {
+            Ok(_) => Ok(format!(
+                "Serving http://{}:{}/",
+                settings.bind_address, settings.port
+            )),
+            Err(e) => Err(e.clone()),
+        });
+        self.overlay = started.and_then(|result| result.map_err(|e| error!("{}", e)).ok());
+    }
+
+    /// Applies the Prometheus settings, starting or stopping the `/metrics` endpoint.
+    /// Without the `prometheus` cargo feature, an enabled endpoint is only reported.
+    pub fn set_prometheus(&mut self, settings: &PrometheusSettings) {
+        #[cfg(feature = "prometheus")]
+        {
+            // Release the port before binding it again
+            self.prometheus = None;
+            let started = settings
+                .enabled
+                .then(|| crate::prometheus::PrometheusExporter::start(settings));
+            self.prometheus_status = started.as_ref().map(|result| match result {
+                Ok(_) => Ok(format!(
+                    "Serving http://{}:{}/metrics",
+                    settings.bind_address, settings.port
+                )),
+                Err(e) => Err(e.clone()),
+            });
+            self.prometheus = started.and_then(|result| result.map_err(|e| error!("{}", e)).ok());
+        }
+        #[cfg(not(feature = "prometheus"))]
+        {
+            self.prometheus_status = settings
+                .enabled
+                .then(|| Err("This build lacks the `prometheus` feature".to_string()));
+            if settings.enabled {
+                error!(
+                    "Prometheus exporter enabled, but this build lacks the `prometheus` feature"
+                );
+            }
+        }
+    }
+
+    /// Applies the gRPC settings, starting or stopping the service.
+    /// Without the `grpc` cargo feature, an enabled service is only reported.
+    pub fn set_grpc(&mut self, settings: &GrpcSettings) {
+        #[cfg(feature = "grpc")]
+        {
+            // Release the port before binding it again
+            self.grpc = None;
+            let started = settings
+                .enabled
+                .then(|| crate::grpc::GrpcServer::start(settings));
+            self.grpc_status = started.as_ref().map(|result| match result {
+                Ok(_) => Ok(format!(
+                    "Listening on {}:{}",
+                    settings.bind_address, settings.port
+                )),
+                Err(e) => Err(e.clone()),
+            });
+            self.grpc = started.and_then(|result| result.map_err(|e| error!("{}", e)).ok());
+        }
+        #[cfg(not(feature = "grpc"))]
+        {
+            self.grpc_status = settings
+                .enabled
+                .then(|| Err("This build lacks the `grpc` feature".to_string()));
+            if settings.enabled {
+                error!("gRPC service enabled, but this build lacks the `grpc` feature");
+            }
+        }
+    }
+
+    /// Applies the scripting settings, (re)loading the script or stopping it.
+    /// Without the `scripting` cargo feature, an enabled script is only reported.
+    pub fn set_scripting(&mut self, settings: &ScriptSettings) {
+        #[cfg(feature = "scripting")]
+        {
+            if let Some(mut script) = self.script.take() {
+                script.clear_alerts(&mut self.alerts);
+            }
+            self.script = settings
+                .enabled
+                .then(|| crate::scripting::ScriptHook::load(settings, self.history_limits))
+                .and_then(|result| result.map_err(|e| error!("{}", e)).ok());
+        }
+        #[cfg(not(feature = "scripting"))]
+        if settings.enabled {
+            error!("Scripting enabled, but this build lacks the `scripting` feature");
+        }
+    }
+
+    /// Refreshes the process table (CPU, memory and executable path).
+    fn refresh_processes(&mut self) {
+        self.system.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing()
+                .with_cpu()
+                .with_memory()
+                .with_exe(UpdateKind::OnlyIfNotSet),
+        );
+    }
+
+    /// Returns the configured names of watched processes that are currently running.
+    /// Matches case-insensitively against the process name and executable file name.
+    fn find_watched_running(&self) -> HashSet<String> {
+        self.find_running(&self.watched_processes)
+    }
+
+    /// Which of `names` match the name or executable of a running process
+    /// (case-insensitive).
+    fn find_running(&self, names: &[String]) -> HashSet<String> {
+        if names.is_empty() {
+            return HashSet::new();
+        }
+
+        let mut running = HashSet::new();
+        for process in self.system.processes().values() {
+            let name = process.name().to_string_lossy().to_lowercase();
+            let exe_name = process
+                .exe()
+                .and_then(|p| p.file_name())
+                .map(|n| n.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            for watched in names {
+                let watched_lc = watched.to_lowercase();
+                if name == watched_lc || exe_name == watched_lc {
+                    running.insert(watched.clone());
+                }
+            }
+        }
+        running
+    }
+
     /// Updates the refresh rate and resizes history buffers accordingly.
     ///
-    /// This ensures that the graph history always represents exactly 60 seconds of data,
-    /// regardless of how often the data is polled.
+    /// This ensures that the graph history always covers the chart window, regardless of
+    /// how often the data is polled. Samples taken at the previous rate are kept (thinned
+    /// out when the buffers shrink), so the charts stay continuous.
     pub fn set_refresh_rate(&mut self, ms: u64) {
-        self.max_history = (60 * 1000 / ms).max(1) as usize;
+        self.refresh_rate_ms = ms;
+        self.resize_histories();
+    }
+
+    /// Changes the time span shown by the charts and resizes history buffers accordingly.
+    pub fn set_history_window(&mut self, secs: u64) {
+        self.history_window_secs = secs;
+        self.resize_histories();
+    }
+
+    /// Effective chart resolution, e.g. "1 sample / 500 ms". When the window holds more
+    /// refreshes than a history may keep (`history::MAX_SAMPLES`, or its share of
+    /// `history::SAMPLE_BUDGET` with many series), older samples are downsampled.
+    pub fn history_resolution(&self) -> String {
+        let resolution = format!("1 sample / {} ms", self.refresh_rate_ms);
+        let limits = self.history_limits;
+        if history::samples_per_window(limits.window_secs, self.refresh_rate_ms) > limits.capacity {
+            format!(
+                "{}, downsampled beyond {} samples",
+                resolution, limits.capacity
+            )
+        } else {
+            resolution
+        }
+    }
+
+    /// Number of history buffers of every series, for the sample budget.
+    fn history_count(&self) -> usize {
+        let mut count = self.cpu_history.len()
+            + 2
+            + self.gpu_util_history.len()
+            + self.gpu_mem_history.len()
+            + self.net_history.len()
+            + self.net_tx_history.len()
+            + self.cgroups.history_count()
+            + self.sensors.history_count()
+            + self.batteries.history_count()
+            + self.drive_temps.history_count()
+            + self.disk_latency.history_count()
+            + self.sched.history_count()
+            + self.custom_metrics.history_count()
+            + self.remote.history_count()
+            + self.pins.history_count()
+            + self.processes.history_count();
+        if let Some(frame_time) = &self.frame_time {
+            count += frame_time.history_count();
+        }
+        if let Some(dns) = &self.dns {
+            count += dns.history_count();
+        }
+        #[cfg(feature = "scripting")]
+        if let Some(script) = &self.script {
+            count += script.history_count();
+        }
+        if let Some(vms) = &self.vms {
+            count += vms.history_count();
+        }
+        count
+    }
+
+    /// Shrinks (or grows back) every history when series come or go and their share of
+    /// the sample budget changes.
+    fn rebalance_histories(&mut self) {
+        let limits = HistoryLimits::new(
+            self.history_window_secs,
+            self.refresh_rate_ms,
+            self.history_count(),
+        );
+        if limits != self.history_limits {
+            self.resize_histories();
+        }
+    }
+
+    fn resize_histories(&mut self) {
+        self.history_limits = HistoryLimits::new(
+            self.history_window_secs,
+            self.refresh_rate_ms,
+            self.history_count(),
+        );
 
         // Resize buffers
         // CPU
         for h in &mut self.cpu_history {
-            h.resize(self.max_history, 0.0);
+            h.set_limits(self.history_limits);
         }
         // RAM
-        self.mem_history.resize(self.max_history, 0.0);
+        self.mem_history.set_limits(self.history_limits);
+        self.swap_history.set_limits(self.history_limits);
 
         // GPU
         for h in &mut self.gpu_util_history {
-            h.resize(self.max_history, 0.0);
+            h.set_limits(self.history_limits);
         }
         for h in &mut self.gpu_mem_history {
-            h.resize(self.max_history, 0.0);
+            h.set_limits(self.history_limits);
         }
 
         // Net
         for h in &mut self.net_history {
-            h.resize(self.max_history, 0.0);
+            h.set_limits(self.history_limits);
         }
+        for h in &mut self.net_tx_history {
+            h.set_limits(self.history_limits);
+        }
+
+        // Cgroups
+        self.cgroups.resize_history(self.history_limits);
+
+        // Selected process
+        self.processes.resize_history(self.history_limits);
+
+        // Sensors (fan histories included)
+        self.sensors.resize_history(self.history_limits);
+
+        // Batteries
+        self.batteries.resize_history(self.history_limits);
+
+        // Frame times
+        if let Some(frame_time) = &mut self.frame_time {
+            frame_time.resize_history(self.history_limits);
+        }
+
+        // Drive temperatures
+        self.drive_temps.resize_history(self.history_limits);
+
+        // Disk latency
+        self.disk_latency.resize_history(self.history_limits);
+
+        // Scheduler
+        self.sched.resize_history(self.history_limits);
+
+        // DNS
+        if let Some(dns) = &mut self.dns {
+            dns.resize_history(self.history_limits);
+        }
+
+        // Custom metrics
+        self.custom_metrics.resize_history(self.history_limits);
+        #[cfg(feature = "scripting")]
+        if let Some(script) = &mut self.script {
+            script.resize_history(self.history_limits);
+        }
+
+        // Remote hosts
+        self.remote.resize_history(self.history_limits);
+
+        // Virtual machines
+        if let Some(vms) = &mut self.vms {
+            vms.resize_history(self.history_limits);
+        }
+
+        // Pinned series
+        self.pins.resize_history(self.history_limits);
     }
 
     /// Polls the system for current resource usage and updates history buffers.
     ///
     /// This should be called once per tick (timer event).
     pub fn refresh(&mut self) {
+        // --- Suspend/Resume ---
+        let interval = Duration::from_millis(self.refresh_rate_ms);
+        if let Some(gap) = self.suspend.check(interval) {
+            info!("Refresh gap of {:?}; resetting rate baselines", gap);
+            self.markers
+                .add(&format!("Resumed ({} gap)", suspend::format_gap(gap)));
+            // Counters sampled across the gap would show up as a spike
+            if self.collectors.network {
+                self.networks.refresh(true);
+            }
+            self.cgroups.reset_baseline();
+            self.sched.reset_baseline();
+            self.disk_latency.reset_baseline();
+            if let Some(idle) = &mut self.idle {
+                idle.reset_baseline();
+            }
+        }
+
         self.system.refresh_cpu_all();
         self.system.refresh_memory();
-        self.networks.refresh(true);
+        if self.collectors.network {
+            self.networks.refresh(true);
+        }
         self.disks.refresh(true);
 
         // --- Update CPU History ---
@@ -285,14 +1381,13 @@ impl SystemMonitor {
         if self.system.cpus().len() != self.cpu_history.len() {
             self.cpu_history.resize(
                 self.system.cpus().len(),
-                VecDeque::from(vec![0.0; self.max_history]),
+                History::with_limits(self.history_limits),
             );
         }
 
         for (i, cpu) in self.system.cpus().iter().enumerate() {
             if i < self.cpu_history.len() {
-                self.cpu_history[i].pop_front();
-                self.cpu_history[i].push_back(cpu.cpu_usage());
+                self.cpu_history[i].push(cpu.cpu_usage());
             }
         }
 
@@ -304,51 +1399,732 @@ impl SystemMonitor {
         } else {
             0.0
         };
-        self.mem_history.pop_front();
-        self.mem_history.push_back(pct);
+        self.mem_history.push(pct);
+
+        // --- Update Swap History ---
+        let used = self.system.used_swap() as f32;
+        let total = self.system.total_swap() as f32;
+        let pct = if total > 0.0 {
+            (used / total) * 100.0
+        } else {
+            0.0
+        };
+        self.swap_history.push(pct);
+
+        // --- Update GPU History ---
+        if self.collectors.gpu {
+            self.gpu_power = gpupower::read_power_states();
+        }
+        if let Some(nvml) = &self.nvml {
+            if let Ok(count) = nvml.device_count() {
+                let count = count as usize;
+                let previous = self.gpu_util_history.len();
+                if count != previous {
+                    // eGPU plugged in or removed; the UI rebuilds its GPU models to match
+                    self.markers.add(&if count > previous {
+                        format!("GPU added ({} total)", count)
+                    } else {
+                        format!("GPU removed ({} total)", count)
+                    });
+                    for i in count..previous {
+                        self.alerts.clear(&format!("gpu.{}.vram", i));
+                    }
+                    self.gpu_util_history
+                        .resize(count, History::with_limits(self.history_limits));
+                    self.gpu_mem_history
+                        .resize(count, History::with_limits(self.history_limits));
+                }
+                self.gpu_names.resize(count, String::new());
+                self.gpu_process_seen.resize(count, 0);
+                self.gpu_processes.clear();
+
+                for i in 0..count {
+                    // Querying a runtime suspended GPU would power it up again
+                    if self.gpu_asleep(i) {
+                        self.gpu_util_history[i].push(0.0);
+                        self.gpu_mem_history[i].push(0.0);
+                        self.suggestions.observe_gpu(i, "", false, 0.0);
+                        continue;
+                    }
+                    if let Ok(dev) = nvml.device_by_index(i as u32) {
+                        if let Ok(name) = dev.name() {
+                            self.gpu_names[i] = name;
+                        }
+
+                        // Util
+                        let util = dev.utilization_rates().map(|u| u.gpu as f32).unwrap_or(0.0);
+                        self.gpu_util_history[i].push(util);
+                        let p0 = dev
+                            .performance_state()
+                            .is_ok_and(|s| s == PerformanceState::Zero);
+                        let name = self.alias(&self.gpu_name(i));
+                        self.suggestions.observe_gpu(i, &name, p0, util);
+
+                        // Mem
+                        let mem_info = dev.memory_info();
+                        let mem_pct = match &mem_info {
+                            Ok(m) if m.total > 0 => (m.used as f32 / m.total as f32) * 100.0,
+                            _ => 0.0,
+                        };
+                        self.gpu_mem_history[i].push(mem_pct);
+
+                        // Processes
+                        let processes =
+                            collect_gpu_processes(&dev, i, &mut self.gpu_process_seen[i]);
+                        self.gpu_processes.extend(processes);
+
+                        // VRAM over-subscription: allocations start failing near the total
+                        let id = format!("gpu.{}.vram", i);
+                        let fraction = mem_pct / 100.0;
+                        if fraction >= WARNING_FRACTION {
+                            let name = self
+                                .alias(&dev.name().unwrap_or_else(|_| format!("NVIDIA GPU {}", i)));
+                            let (used, total) = mem_info
+                                .as_ref()
+                                .map(|m| (m.used as f64 / 1e9, m.total as f64 / 1e9))
+                                .unwrap_or_default();
+                            let severity = if fraction >= CRITICAL_FRACTION {
+                                AlertSeverity::Critical
+                            } else {
+                                AlertSeverity::Warning
+                            };
+                            self.alerts.raise(
+                                &id,
+                                severity,
+                                format!(
+                                    "{} VRAM at {:.0}% ({:.1} / {:.1} GB): new allocations may fail",
+                                    name, mem_pct, used, total
+                                ),
+                            );
+                        } else {
+                            self.alerts.clear(&id);
+                        }
+                    }
+                }
+            }
+        }
+
+        // --- Update Network History ---
+        // Check if interfaces changed? For now assume valid index mapping via sorted keys
+        for (i, name) in self.interface_names.iter().enumerate() {
+            if let Some(net) = self.networks.get(name) {
+                let rx_mb = net.received() as f32 / 1024.0 / 1024.0;
+                let tx_mb = net.transmitted() as f32 / 1024.0 / 1024.0;
+                if i < self.net_history.len() {
+                    self.net_history[i].push(rx_mb);
+                }
+                if i < self.net_tx_history.len() {
+                    self.net_tx_history[i].push(tx_mb);
+                }
+            }
+        }
+
+        // --- Update Cgroup (Slice) History ---
+        self.cgroups.refresh(
+            self.system.cpus().len(),
+            self.system.total_memory(),
+            self.history_limits,
+        );
+
+        // --- Update Scheduler History ---
+        self.sched.refresh();
+
+        // --- Update Sensor and Fan History ---
+        self.sensors.refresh(self.history_limits);
+        self.fans.refresh(&self.sensors);
+
+        // --- Update Battery History ---
+        self.batteries.refresh(self.history_limits);
+
+        // --- Update Frame Times ---
+        if let Some(frame_time) = &mut self.frame_time {
+            frame_time.refresh();
+        }
+
+        // --- Update Drive Temperature History ---
+        let worker_storage = self
+            .privileged_data
+            .lock()
+            .ok()
+            .and_then(|guard| guard.as_ref().map(|d| d.storage.clone()))
+            .unwrap_or_default();
+        self.drive_temps
+            .refresh(&worker_storage, self.history_limits);
+
+        // --- SMART Trends (growing pending sectors predict failure best) ---
+        let mut alerted = HashSet::new();
+        for drive in &worker_storage {
+            self.smart_history.record(&drive.serial_number, drive.smart);
+            let Some(trend) = self.smart_history.trend(&drive.serial_number) else {
+                continue;
+            };
+            let severity = if trend.pending_growth > 0 {
+                AlertSeverity::Critical
+            } else if trend.reallocated_growth > 0
+                || trend.media_error_growth > 0
+                || trend.wear_days_left.is_some_and(|d| d < WEAR_WARN_DAYS)
+            {
+                AlertSeverity::Warning
+            } else {
+                continue;
+            };
+            self.alerts.raise(
+                &format!("smart.{}", drive.serial_number),
+                severity,
+                format!(
+                    "Drive {} degrading: {}",
+                    self.alias_with_name(&drive.device_name),
+                    trend.describe()
+                ),
+            );
+            alerted.insert(drive.serial_number.clone());
+        }
+        if !worker_storage.is_empty() {
+            for serial in self.smart_alerts.difference(&alerted) {
+                self.alerts.clear(&format!("smart.{}", serial));
+            }
+            self.smart_alerts = alerted;
+        }
+
+        // --- Update Disk Latency History ---
+        self.disk_latency.refresh(self.history_limits);
+        for drive in self.disk_latency.get_data() {
+            self.suggestions
+                .observe_disk(&drive.device, drive.await_ms.is_some());
+            if let Some(totals) = &mut self.totals {
+                totals.update(
+                    &format!("disk.{}.written", drive.device),
+                    drive.written_bytes,
+                );
+            }
+        }
+
+        // --- Cumulative Totals ---
+        if let Some(totals) = &mut self.totals {
+            for (name, net) in &self.networks {
+                totals.update(&format!("net.{}.rx", name), net.total_received());
+                totals.update(&format!("net.{}.tx", name), net.total_transmitted());
+            }
+
+            // --- Data Budget ---
+            let now = now_secs() as i64;
+            let period = self
+                .data_budget
+                .enabled
+                .then(|| budget::current_period(self.data_budget.reset_day, now))
+                .flatten();
+            let finding = period.and_then(|period| {
+                totals.begin_period(&period.key);
+                let used: u64 = totals
+                    .period_totals()
+                    .filter_map(|(key, bytes)| {
+                        let (interface, direction) = key.strip_prefix("net.")?.rsplit_once('.')?;
+                        let counted = direction == "rx"
+                            || (direction == "tx" && self.data_budget.count_upload);
+                        (counted && budget::counts(&self.data_budget, interface)).then_some(bytes)
+                    })
+                    .sum();
+                budget::evaluate(&self.data_budget, used, &period, now)
+            });
+            let id = finding
+                .as_ref()
+                .map(|(step, _, _)| format!("budget.network.{}", step));
+            if let Some(previous) = self
+                .budget_alert
+                .as_ref()
+                .filter(|p| Some(*p) != id.as_ref())
+            {
+                self.alerts.clear(previous);
+            }
+            if let (Some(id), Some((_, severity, message))) = (&id, finding) {
+                self.alerts.raise(id, severity, message);
+            }
+            self.budget_alert = id;
+
+            totals.save_if_due();
+        }
+
+        // --- Processes ---
+        self.refresh_processes();
+        self.processes.refresh(&self.system);
+        self.sandbox_apps = self.sandbox_attribution.collect(&self.system);
+
+        // --- Kernel Limits ---
+        let process_count = self
+            .system
+            .processes()
+            .values()
+            .filter(|p| p.thread_kind().is_none())
+            .count() as u64;
+        self.limits = KernelLimits::read(process_count);
+        self.alerts.check_usage(
+            "limits.open-files",
+            "Open files",
+            self.limits.open_files,
+            self.limits.file_max,
+        );
+        self.alerts.check_usage(
+            "limits.processes",
+            "Processes",
+            self.limits.processes,
+            self.limits.pid_max,
+        );
+        self.alerts.check_usage(
+            "limits.threads",
+            "Threads",
+            self.limits.threads,
+            self.limits.threads_max,
+        );
+
+        // --- Entropy ---
+        self.entropy = EntropyStatus::read(&self.system, self.virtualized);
+        if self.entropy.is_starved() {
+            self.alerts.raise(
+                "entropy.low",
+                AlertSeverity::Warning,
+                format!(
+                    "Low kernel entropy ({} bits): crypto operations may block; {}",
+                    self.entropy.available,
+                    self.entropy.hint()
+                ),
+            );
+        } else {
+            self.alerts.clear("entropy.low");
+        }
+
+        // --- SELinux/AppArmor (mode changes, e.g. `setenforce 0`, become markers) ---
+        let mac = MacStatus::read();
+        if mac != self.mac {
+            self.markers.add(&format!("MAC: {}", mac.summary()));
+            self.mac = mac;
+        }
+
+        // --- Watched Processes (automatic markers) ---
+        if !self.watched_processes.is_empty() {
+            let running = self.find_watched_running();
+            for name in running.difference(&self.watched_running) {
+                self.markers.add(&format!("{} started", name));
+            }
+            for name in self.watched_running.difference(&running) {
+                self.markers.add(&format!("{} exited", name));
+            }
+            self.watched_running = running;
+        }
+
+        // --- DNS Latency ---
+        if let Some(dns) = &mut self.dns {
+            dns.refresh(self.history_limits);
+        }
+
+        // --- Custom Metrics ---
+        self.custom_metrics.refresh();
+
+        // --- Idle / Active Session Statistics ---
+        if let Some(idle) = &mut self.idle {
+            let cpu = self.system.global_cpu_usage();
+            let gpu = self
+                .gpu_util_history
+                .iter()
+                .filter_map(History::latest)
+                .fold(0.0, f32::max);
+            idle.record(cpu, gpu);
+        }
+        let user_idle = self.idle.as_ref().is_some_and(IdleTracker::is_idle);
+
+        // --- GPU Job Completion ---
+        if self.gpu_jobs.is_enabled() {
+            for i in 0..self.gpu_util_history.len() {
+                let util = self.gpu_util_history[i].latest().unwrap_or(0.0);
+                let id = format!("gpujob.{}", i);
+                match self.gpu_jobs.update(i, util) {
+                    Some(GpuJobEvent::Finished(busy)) => {
+                        let name = self.alias(&self.gpu_name(i));
+                        self.alerts.raise(
+                            &id,
+                            AlertSeverity::Warning,
+                            format!(
+                                "{} finished its job: idle after {} busy",
+                                name,
+                                gpujobs::format_duration(busy)
+                            ),
+                        );
+                    }
+                    Some(GpuJobEvent::Started | GpuJobEvent::Expired) => self.alerts.clear(&id),
+                    None => {}
+                }
+            }
+        }
+
+        // --- Idle Action ---
+        if self.idle_action.is_enabled() {
+            let interval_secs = self.refresh_rate_ms.max(1) as f32 / 1000.0;
+            let network_bytes: u64 = self
+                .networks
+                .iter()
+                .filter(|(name, _)| name.as_str() != "lo")
+                .map(|(_, net)| net.received() + net.transmitted())
+                .sum();
+            let inputs = IdleInputs {
+                cpu_percent: self.system.global_cpu_usage(),
+                gpu_percent: self
+                    .gpu_util_history
+                    .iter()
+                    .filter_map(History::latest)
+                    .fold(0.0, f32::max),
+                network_kbps: network_bytes as f32 / 1024.0 / interval_secs,
+                user_idle,
+            };
+            if let Some(action) = self.idle_action.update(&inputs) {
+                info!("Idle countdown finished, requesting {}", action.as_str());
+                self.markers.add(&format!("Idle {}", action.as_str()));
+                self.idle_action_due = Some(action);
+            }
+        }
+        self.suggestions.observe_cpu(&self.system, user_idle);
+
+        // --- Certificate Expiry ---
+        let certs = self.certs.get_data();
+        let mut alerted = HashSet::new();
+        for cert in &certs {
+            let Some(days) = cert.days_left() else {
+                continue;
+            };
+            let severity = if days < CERT_CRITICAL_DAYS {
+                AlertSeverity::Critical
+            } else if days < self.cert_warn_days as i64 {
+                AlertSeverity::Warning
+            } else {
+                continue;
+            };
+            self.alerts.raise(
+                &format!("cert.{}", cert.target),
+                severity,
+                format!("Certificate {}", cert.describe()),
+            );
+            alerted.insert(cert.target.clone());
+        }
+        for target in self.cert_alerts.difference(&alerted) {
+            self.alerts.clear(&format!("cert.{}", target));
+        }
+        self.cert_alerts = alerted;
+
+        // --- Backup Freshness (critical once twice as old as allowed) ---
+        let max_age = self.backup_max_age_hours as f64;
+        let mut alerted = HashSet::new();
+        for backup in self.backups.get_data() {
+            let (severity, message) = match backup.age_hours() {
+                Some(age) if age >= 2.0 * max_age => (AlertSeverity::Critical, backup.describe()),
+                Some(age) if age >= max_age => (AlertSeverity::Warning, backup.describe()),
+                None if !backup.error.is_empty() => (AlertSeverity::Warning, backup.describe()),
+                _ => continue,
+            };
+            self.alerts.raise(
+                &format!("backup.{}", backup.target),
+                severity,
+                format!("Backup {}", message),
+            );
+            alerted.insert(backup.target);
+        }
+        for target in self.backup_alerts.difference(&alerted) {
+            self.alerts.clear(&format!("backup.{}", target));
+        }
+        self.backup_alerts = alerted;
+
+        // --- Remote Hosts ---
+        self.remote.refresh();
+
+        // --- Virtual Machines ---
+        if let Some(vms) = &mut self.vms {
+            vms.refresh(self.history_limits);
+        }
+
+        // --- Scoped Alert Rules (per process, unit, container and VM) ---
+        let scoped_inputs = ScopedInputs {
+            processes: if self.scoped_rules.needs_processes() {
+                self.processes.collect(&self.system)
+            } else {
+                Vec::new()
+            },
+            cgroups: self.cgroups.get_data(),
+            vms: self.get_vm_data(),
+            cpu_count: self.system.cpus().len(),
+        };
+        self.scoped_rules.apply(&scoped_inputs, &mut self.alerts);
+
+        // --- Link Events (markers + flap alerts) ---
+        let link_events = if self.collectors.network {
+            self.links.refresh()
+        } else {
+            Vec::new()
+        };
+        for event in link_events {
+            self.markers.add(&event.description);
+        }
+        for (interface, changes) in self.links.recent_changes() {
+            let id = format!("link.{}.flapping", interface);
+            if changes >= FLAP_THRESHOLD {
+                let name = self.alias(&interface);
+                self.alerts.raise(
+                    &id,
+                    AlertSeverity::Warning,
+                    format!(
+                        "{} is flapping: {} carrier changes in the last {} minutes",
+                        name,
+                        changes,
+                        FLAP_WINDOW_SECS / 60
+                    ),
+                );
+            } else {
+                self.alerts.clear(&id);
+            }
+        }
+
+        // --- Read-only Remounts ---
+        for event in self.mounts.refresh() {
+            let state = if event.read_only {
+                "remounted read-only"
+            } else {
+                "writable again"
+            };
+            self.markers
+                .add(&format!("{} {}", event.mount_point, state));
+        }
+        let remounted = self.mounts.remounted_read_only();
+        for (mount_point, device) in &remounted {
+            let drive = mounts::backing_drive(device);
+            let smart = drive
+                .as_ref()
+                .and_then(|drive| {
+                    let guard = self.privileged_data.lock().ok()?;
+                    guard
+                        .as_ref()?
+                        .storage
+                        .iter()
+                        .find(|d| &d.device_name == drive)
+                        .map(|d| d.health_status.clone())
+                })
+                .unwrap_or_else(|| "unknown".to_string());
+            let name = self.alias(drive.as_deref().unwrap_or(device));
+            self.alerts.raise(
+                &format!("mount.{}.readonly", mount_point),
+                AlertSeverity::Critical,
+                format!(
+                    "{} was remounted read-only ({} on {}, SMART: {})",
+                    mount_point, device, name, smart
+                ),
+            );
+        }
+        let stale: Vec<String> = self
+            .alerts
+            .active()
+            .iter()
+            .filter(|a| a.id.starts_with("mount.") && a.id.ends_with(".readonly"))
+            .filter(|a| {
+                !remounted
+                    .iter()
+                    .any(|(m, _)| a.id == format!("mount.{}.readonly", m))
+            })
+            .map(|a| a.id.clone())
+            .collect();
+        for id in stale {
+            self.alerts.clear(&id);
+        }
+
+        // --- Health Rules ---
+        let mut chip_temps: Vec<(String, f32)> = Vec::new();
+        for fan in self.fans.get_data(&self.sensors) {
+            let chip = fan.label.split(':').next().unwrap_or(&fan.chip).to_string();
+            if let Some(temp) = fan.temperature {
+                if !chip_temps.iter().any(|(c, _)| *c == chip) {
+                    chip_temps.push((chip, temp));
+                }
+            }
+        }
+        let gpu_temps = match &self.nvml {
+            Some(nvml) => (0..self.gpu_names.len())
+                .filter(|i| !self.gpu_asleep(*i))
+                .filter_map(|i| {
+                    let temp = nvml
+                        .device_by_index(i as u32)
+                        .ok()?
+                        .temperature(nvml_wrapper::enum_wrappers::device::TemperatureSensor::Gpu)
+                        .ok()?;
+                    Some((i, self.alias(&self.gpu_name(i)), temp as f32))
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+        let inputs = HealthInputs {
+            gpu_temps,
+            chip_temps,
+            drive_temps: self
+                .drive_temps
+                .get_data()
+                .into_iter()
+                .filter_map(|d| {
+                    let name = self.alias(&d.device);
+                    Some((d.device, name, d.temperature?, d.max))
+                })
+                .collect(),
+            drive_health: worker_storage
+                .iter()
+                .map(|d| {
+                    (
+                        d.device_name.clone(),
+                        self.alias_with_name(&d.device_name),
+                        d.health_status.clone(),
+                    )
+                })
+                .collect(),
+            memory_used: self.system.used_memory(),
+            memory_total: self.system.total_memory(),
+            memory_pressure: crate::health::read_memory_pressure(),
+            disks: self
+                .disks
+                .iter()
+                .filter(|d| !d.is_read_only())
+                .map(|d| {
+                    (
+                        d.mount_point().to_string_lossy().to_string(),
+                        d.file_system().to_string_lossy().to_string(),
+                        d.total_space().saturating_sub(d.available_space()),
+                        d.total_space(),
+                    )
+                })
+                .collect(),
+        };
+        self.health_rules.apply(&inputs, &mut self.alerts);
+
+        // --- Kernel Log & GPU Driver Errors (from the worker) ---
+        let (kernel_log, gpu_events) = self
+            .privileged_data
+            .lock()
+            .ok()
+            .and_then(|guard| {
+                guard
+                    .as_ref()
+                    .map(|d| (d.kernel_log.clone(), d.gpu_events.clone()))
+            })
+            .unwrap_or_default();
+        self.kernel_log.update(&kernel_log, &mut self.markers);
+        self.xid
+            .update(&gpu_events, &mut self.alerts, &mut self.markers);
+
+        // --- Alert Forwarding ---
+        let alert_events = self.alerts.take_events();
+        for event in &alert_events {
+            if let Some(syslog) = &self.syslog {
+                syslog.forward(event);
+            }
+            if let Some(notifier) = &self.notifier {
+                notifier.notify(event);
+            }
+        }
+
+        // --- Snapshot (Overview pins and exporters) ---
+        let snapshot = self.snapshot();
+        self.pins.update(&snapshot);
+        self.games.update(&self.system, &snapshot);
+        if let Some(webhook) = self.webhook.as_mut().filter(|w| w.is_due()) {
+            webhook.send(&snapshot);
+        }
+        if let Some(logger) = &mut self.csv_logger {
+            logger.collect_markers(&snapshot);
+            if logger.is_due() {
+                logger.write(&snapshot);
+            }
+        }
+        if let Some(store) = &mut self.long_term {
+            store.record(&snapshot);
+        }
+        #[cfg(feature = "prometheus")]
+        if let Some(exporter) = &self.prometheus {
+            exporter.update(&snapshot);
+        }
+        if let Some(overlay) = &self.overlay {
+            overlay.update(&snapshot);
+        }
+        #[cfg(feature = "grpc")]
+        if let Some(server) = &self.grpc {
+            server.update(&snapshot);
+        }
+        #[cfg(feature = "scripting")]
+        if let Some(script) = &mut self.script {
+            script.run(&snapshot, &mut self.alerts);
+        }
+        self.events.publish(&snapshot, &alert_events);
+
+        // --- Sample Budget ---
+        self.rebalance_histories();
+    }
+
+    /// Returns a receiver of the changes found by each following `refresh()`, for
+    /// embedders that would rather react to events than poll the getters.
+    pub fn subscribe(&mut self) -> Receiver<MetricEvent> {
+        self.events.subscribe()
+    }
+
+    /// Returns the data of the last `refresh()` as a serializable snapshot, the same
+    /// payload the exporters send.
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot::capture(self)
+    }
+
+    /// Pins the series `id` to the Overview.
+    pub fn pin_series(&mut self, id: &str) {
+        self.pins.pin(id, self.history_limits);
+    }
+
+    pub fn unpin_series(&mut self, id: &str) {
+        self.pins.unpin(id);
+    }
+
+    pub fn get_pinned_data(&self) -> Vec<PinnedSeriesData> {
+        self.pins.get_data()
+    }
 
-        // --- Update GPU History ---
-        if let Some(nvml) = &self.nvml {
-            if let Ok(count) = nvml.device_count() {
-                let count = count as usize;
-                if count != self.gpu_util_history.len() {
-                    // Resize if strictly needed
-                    self.gpu_util_history
-                        .resize(count, VecDeque::from(vec![0.0; self.max_history]));
-                    self.gpu_mem_history
-                        .resize(count, VecDeque::from(vec![0.0; self.max_history]));
-                }
+    /// Series that can still be pinned.
+    pub fn get_pinnable_series(&self) -> Vec<SeriesInfo> {
+        self.pins.available()
+    }
 
-                for i in 0..count {
-                    if let Ok(dev) = nvml.device_by_index(i as u32) {
-                        // Util
-                        let util = dev.utilization_rates().map(|u| u.gpu as f32).unwrap_or(0.0);
-                        self.gpu_util_history[i].pop_front();
-                        self.gpu_util_history[i].push_back(util);
+    /// The idle action whose countdown ran out since the last call, to be executed.
+    pub fn take_idle_action(&mut self) -> Option<PowerAction> {
+        self.idle_action_due.take()
+    }
 
-                        // Mem
-                        let mem_info = dev.memory_info();
-                        let mem_pct = match mem_info {
-                            Ok(m) if m.total > 0 => (m.used as f32 / m.total as f32) * 100.0,
-                            _ => 0.0,
-                        };
-                        self.gpu_mem_history[i].pop_front();
-                        self.gpu_mem_history[i].push_back(mem_pct);
-                    }
-                }
+    /// Drops a named marker on the timeline at the current time.
+    pub fn add_marker(&mut self, label: &str) {
+        self.markers.add(label);
+    }
+
+    /// Starts a GPU session capture and marks its start on the timeline.
+    pub fn start_gpu_session(&mut self, label: &str) {
+        self.stop_gpu_session();
+        match GpuSessionRecorder::start(label) {
+            Ok(recorder) => {
+                self.markers
+                    .add(&format!("GPU capture: {}", recorder.label()));
+                self.gpu_session = Some(recorder);
             }
+            Err(e) => self.gpu_session_result = e,
         }
+    }
 
-        // --- Update Network History ---
-        // Check if interfaces changed? For now assume valid index mapping via sorted keys
-        for (i, name) in self.interface_names.iter().enumerate() {
-            if let Some(net) = self.networks.get(name) {
-                let rx_mb = net.received() as f32 / 1024.0 / 1024.0;
-                if i < self.net_history.len() {
-                    self.net_history[i].pop_front();
-                    self.net_history[i].push_back(rx_mb);
-                }
-            }
+    /// Stops the running GPU session capture, if any, and completes its files.
+    pub fn stop_gpu_session(&mut self) {
+        if let Some(recorder) = self.gpu_session.take() {
+            self.markers
+                .add(&format!("GPU capture ended: {}", recorder.label()));
+            self.gpu_session_result = recorder.finish().unwrap_or_else(|e| e);
+        }
+    }
+
+    /// Progress of the running capture, or the outcome of the last one.
+    pub fn gpu_session_status(&self) -> String {
+        match &self.gpu_session {
+            Some(recorder) => recorder.status(),
+            None => self.gpu_session_result.clone(),
         }
     }
 
@@ -357,8 +2133,8 @@ impl SystemMonitor {
     }
 
     // Helper to get raw history as reference for UI generation
-    pub fn get_cpu_history(&self, index: usize) -> &VecDeque<f32> {
-        static EMPTY: VecDeque<f32> = VecDeque::new();
+    pub fn get_cpu_history(&self, index: usize) -> &History {
+        static EMPTY: History = History::new(0);
         if index < self.cpu_history.len() {
             &self.cpu_history[index]
         } else {
@@ -366,28 +2142,81 @@ impl SystemMonitor {
         }
     }
 
+    /// Reads `/sys` and `/proc` from `paths` from now on, e.g. fixture trees in tests. The
+    /// sysfs trackers start over with empty histories.
+    pub fn set_sys_paths(&mut self, paths: SysPaths) {
+        self.fans = FanTracker::new(&paths);
+        self.sensors = SensorTracker::new(&paths);
+        self.batteries = BatteryTracker::new(&paths);
+        self.drive_temps = DriveTempTracker::new(&paths);
+        self.disk_latency = DiskLatencyTracker::new(&paths);
+        self.sys_paths = paths;
+    }
+
     pub fn get_memory_info(&self) -> (f32, f32) {
         let used = self.system.used_memory() as f32 / 1024.0 / 1024.0 / 1024.0;
         let total = self.system.total_memory() as f32 / 1024.0 / 1024.0 / 1024.0;
         (used, total)
     }
 
-    pub fn get_memory_history(&self) -> &VecDeque<f32> {
+    pub fn get_memory_history(&self) -> &History {
         &self.mem_history
     }
 
+    /// Used and total swap in GB; the total is 0 without swap.
+    pub fn get_swap_info(&self) -> (f32, f32) {
+        let used = self.system.used_swap() as f32 / 1024.0 / 1024.0 / 1024.0;
+        let total = self.system.total_swap() as f32 / 1024.0 / 1024.0 / 1024.0;
+        (used, total)
+    }
+
+    pub fn get_swap_history(&self) -> &History {
+        &self.swap_history
+    }
+
+    /// Returns the processes using the NVIDIA GPUs, largest VRAM users first; empty
+    /// without NVML. A process using two GPUs is listed once per GPU.
+    pub fn get_gpu_process_data(&self) -> Vec<GpuProcessData> {
+        let mut processes = self.gpu_processes.clone();
+        for process in &mut processes {
+            if let Some(p) = self.system.process(sysinfo::Pid::from_u32(process.pid)) {
+                process.name = p.name().to_string_lossy().into_owned();
+            }
+        }
+        processes.sort_by(|a, b| {
+            b.vram_mb
+                .unwrap_or(0.0)
+                .total_cmp(&a.vram_mb.unwrap_or(0.0))
+                .then(b.sm_util.cmp(&a.sm_util))
+        });
+        processes
+    }
+
     pub fn get_gpu_data(&self) -> Vec<GpuData> {
         let mut data = Vec::new();
         if let Some(nvml) = &self.nvml {
             if let Ok(count) = nvml.device_count() {
                 for i in 0..count {
+                    let history = |h: &Vec<History>| h.get(i as usize).cloned().unwrap_or_default();
+                    if self.gpu_asleep(i as usize) {
+                        data.push(GpuData {
+                            name: self.alias(&self.gpu_name(i as usize)),
+                            util: 0.0,
+                            mem_used_mb: 0.0,
+                            mem_total_mb: 0.0,
+                            temperature: None,
+                            suspended: true,
+                            util_history: history(&self.gpu_util_history),
+                            mem_history: history(&self.gpu_mem_history),
+                        });
+                        continue;
+                    }
                     if let Ok(dev) = nvml.device_by_index(i) {
                         let name = dev.name().unwrap_or(format!("GPU {}", i));
                         let util = self
                             .gpu_util_history
                             .get(i as usize)
-                            .and_then(|v| v.back())
-                            .cloned()
+                            .and_then(History::latest)
                             .unwrap_or(0.0);
 
                         let (mem_used, mem_total) = match dev.memory_info() {
@@ -398,21 +2227,22 @@ impl SystemMonitor {
                             _ => (0.0, 0.0),
                         };
 
+                        let temperature = dev
+                            .temperature(
+                                nvml_wrapper::enum_wrappers::device::TemperatureSensor::Gpu,
+                            )
+                            .ok()
+                            .map(|t| t as f32);
+
                         data.push(GpuData {
-                            name,
+                            name: self.alias(&name),
                             util,
                             mem_used_mb: mem_used,
                             mem_total_mb: mem_total,
-                            util_history: self
-                                .gpu_util_history
-                                .get(i as usize)
-                                .map(|v| Vec::from_iter(v.iter().copied()))
-                                .unwrap_or_default(),
-                            mem_history: self
-                                .gpu_mem_history
-                                .get(i as usize)
-                                .map(|v| Vec::from_iter(v.iter().copied()))
-                                .unwrap_or_default(),
+                            temperature,
+                            suspended: false,
+                            util_history: history(&self.gpu_util_history),
+                            mem_history: history(&self.gpu_mem_history),
                         });
                     }
                 }
@@ -437,16 +2267,15 @@ impl SystemMonitor {
                 }
 
                 res.push(NetworkData {
-                    name: name.clone(),
+                    name: self.alias(name),
                     rx_bytes: net.received(),
                     tx_bytes: net.transmitted(),
-                    total_rx_bytes: net.total_received(),
-                    total_tx_bytes: net.total_transmitted(),
-                    history: self
-                        .net_history
-                        .get(i)
-                        .map(|v| Vec::from_iter(v.iter().copied()))
-                        .unwrap_or_default(),
+                    total_rx_bytes: self
+                        .select_total(&format!("net.{}.rx", name), net.total_received()),
+                    total_tx_bytes: self
+                        .select_total(&format!("net.{}.tx", name), net.total_transmitted()),
+                    history: self.net_history.get(i).cloned().unwrap_or_default(),
+                    tx_history: self.net_tx_history.get(i).cloned().unwrap_or_default(),
                     ips_v4: ipv4s,
                     // ips_v6: ipv6s,
                     is_default: default_interface.as_ref() == Some(name),
@@ -456,11 +2285,237 @@ impl SystemMonitor {
         res
     }
 
+    pub fn get_cgroup_data(&self) -> Vec<CgroupData> {
+        self.cgroups.get_data()
+    }
+
+    pub fn get_sandbox_app_data(&self) -> &[SandboxAppData] {
+        &self.sandbox_apps
+    }
+
+    pub fn get_sched_data(&self) -> SchedData {
+        self.sched.get_data()
+    }
+
+    /// Returns per-resolver latency history; empty while the probe is disabled.
+    pub fn get_dns_latency_data(&self) -> Vec<DnsLatencyData> {
+        self.dns.as_ref().map(|d| d.get_data()).unwrap_or_default()
+    }
+
+    pub fn get_remote_host_data(&self) -> Vec<RemoteHostData> {
+        self.remote.get_data()
+    }
+
+    pub fn get_cert_data(&self) -> Vec<CertStatus> {
+        self.certs.get_data()
+    }
+
+    pub fn get_backup_data(&self) -> Vec<BackupStatus> {
+        self.backups.get_data()
+    }
+
+    /// Series with samples in the long-term history; empty while it is disabled.
+    pub fn get_long_term_series(&self) -> Vec<SeriesInfo> {
+        self.long_term
+            .as_ref()
+            .map(LongTermStore::series)
+            .unwrap_or_default()
+    }
+
+    /// Returns the long-term statistics of series `id` over the last `range_secs` seconds.
+    pub fn get_long_term_history(
+        &self,
+        id: &str,
+        range_secs: u64,
+    ) -> Result<Vec<LongTermPoint>, String> {
+        match &self.long_term {
+            Some(store) => store.query(id, range_secs, now_secs()),
+            None => Err("Long-term history is disabled".to_string()),
+        }
+    }
+
+    /// Returns the stored history of series `id` from `start` to `end` (seconds since
+    /// the epoch), averaged like `get_long_term_history`.
+    pub fn get_long_term_span(
+        &self,
+        id: &str,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<LongTermPoint>, String> {
+        match &self.long_term {
+            Some(store) => store.span(id, start, end),
+            None => Err("Long-term history is disabled".to_string()),
+        }
+    }
+
+    /// Returns the history of every custom metric, in configuration order.
+    pub fn get_custom_metric_data(&self) -> Vec<CustomMetricData> {
+        self.custom_metrics.get_data()
+    }
+
+    /// Returns the metrics reported by the script; empty without one.
+    pub fn get_script_metric_data(&self) -> Vec<CustomMetricData> {
+        #[cfg(feature = "scripting")]
+        if let Some(script) = &self.script {
+            return script.get_metric_data();
+        }
+        Vec::new()
+    }
+
+    /// Returns the badges set by the script's last successful run.
+    pub fn get_script_badges(&self) -> Vec<String> {
+        #[cfg(feature = "scripting")]
+        if let Some(script) = &self.script {
+            return script.badges();
+        }
+        Vec::new()
+    }
+
+    /// Returns the libvirt VMs; empty when `virsh` is unavailable.
+    pub fn get_vm_data(&self) -> Vec<VmData> {
+        self.vms.as_ref().map(|v| v.get_data()).unwrap_or_default()
+    }
+
+    /// libvirt connection in use or the reason none could be made.
+    pub fn vm_status(&self) -> String {
+        match &self.vms {
+            Some(vms) => vms.status(),
+            None if !self.capabilities.virsh => "virsh not found".to_string(),
+            None => String::new(),
+        }
+    }
+
+    pub fn get_limits(&self) -> &KernelLimits {
+        &self.limits
+    }
+
+    pub fn get_entropy(&self) -> &EntropyStatus {
+        &self.entropy
+    }
+
+    pub fn get_mac_status(&self) -> &MacStatus {
+        &self.mac
+    }
+
+    /// SELinux/AppArmor denials counted by the worker; `None` without it.
+    pub fn get_mac_denials(&self) -> Option<DenialSummary> {
+        let guard = self.privileged_data.lock().ok()?;
+        guard.as_ref().map(|data| data.mac_denials.clone())
+    }
+
+    pub fn get_alerts(&self) -> &[Alert] {
+        self.alerts.active()
+    }
+
+    pub fn get_drive_temp_data(&self) -> Vec<DriveTempData> {
+        let mut drives = self.drive_temps.get_data();
+        for drive in &mut drives {
+            drive.device = self.alias(&drive.device);
+        }
+        drives
+    }
+
+    /// Composite health score from the active alerts (see `health.rs`).
+    pub fn get_health(&self) -> HealthReport {
+        crate::health::assess(self.alerts.active())
+    }
+
+    pub fn get_disk_latency_data(&self) -> Vec<DiskLatencyData> {
+        let mut drives = self.disk_latency.get_data();
+        let scales = &self.chart_scales;
+        for drive in &mut drives {
+            drive.written_bytes = self.select_total(
+                &format!("disk.{}.written", drive.device),
+                drive.written_bytes,
+            );
+            drive.device = self.alias(&drive.device);
+            if !scales.disk_auto {
+                drive.await_scale_ms = scales.disk_await_max_ms.max(1) as f32;
+                drive.queue_scale = scales.disk_queue_max.max(1) as f32;
+            }
+        }
+        drives
+    }
+
+    /// Per-drive read/write MB/s and their history.
+    pub fn get_disk_io_data(&self) -> Vec<DiskIoData> {
+        let mut drives = self.disk_latency.get_io_data();
+        for drive in &mut drives {
+            drive.device = self.alias(&drive.device);
+        }
+        drives
+    }
+
+    /// The `limit` top processes by `sort` that match `filter` (see
+    /// `processes::matches_filter`), from the last refresh.
+    pub fn get_process_data(
+        &self,
+        sort: ProcessSort,
+        filter: &str,
+        limit: usize,
+    ) -> Vec<ProcessData> {
+        let mut list = self.processes.collect(&self.system);
+        list.retain(|p| processes::matches_filter(p, filter));
+        processes::sort(&mut list, sort);
+        list.truncate(limit);
+        self.processes.read_sched(&mut list);
+        list
+    }
+
+    /// Number of D-state tasks and zombies, with their history.
+    pub fn get_process_state_data(&self) -> ProcessStateData {
+        self.processes.state_counts()
+    }
+
+    /// Selects process `pid` for the detail pane, or closes it with `None`. Its history
+    /// starts now.
+    pub fn select_process(&mut self, pid: Option<u32>) {
+        self.processes
+            .select(pid, &self.system, self.history_limits);
+    }
+
+    /// The process selected with `select_process`, as of the last refresh.
+    pub fn get_process_detail(&self) -> Option<ProcessDetail> {
+        self.processes.selected().cloned()
+    }
+
+    pub fn get_fan_data(&self) -> Vec<FanData> {
+        let mut fans = self.fans.get_data(&self.sensors);
+        for fan in &mut fans {
+            fan.label = self.alias(&fan.label);
+        }
+        fans
+    }
+
+    /// Every hwmon temperature, fan and voltage input, sorted by chip, kind and channel.
+    pub fn get_sensor_data(&self) -> Vec<SensorData> {
+        let mut sensors = self.sensors.get_data();
+        for sensor in &mut sensors {
+            sensor.label = self.alias(&sensor.label);
+        }
+        sensors
+    }
+
+    pub fn get_battery_data(&self) -> Vec<BatteryData> {
+        self.batteries.get_data()
+    }
+
+    /// Finished game sessions, newest first.
+    pub fn get_game_sessions(&self) -> Vec<GameSession> {
+        self.games.sessions()
+    }
+
+    pub fn get_frame_time_data(&self) -> Option<FrameTimeData> {
+        self.frame_time
+            .as_ref()
+            .and_then(FrameTimeTracker::get_data)
+    }
+
     pub fn get_disk_data(&self) -> Vec<DiskData> {
         let mut res = Vec::new();
         for disk in &self.disks {
             res.push(DiskData {
-                name: disk.name().to_string_lossy().into_owned(),
+                name: self.alias(&disk.name().to_string_lossy()),
                 mount_point: disk.mount_point().to_string_lossy().into_owned(),
                 total_space_bytes: disk.total_space(),
                 available_space_bytes: disk.available_space(),
@@ -470,25 +2525,7 @@ impl SystemMonitor {
         res
     }
 
-    #[allow(clippy::type_complexity)]
-    pub fn get_static_info(
-        &self,
-    ) -> (
-        String, // hostname
-        String, // os_name
-        String, // kernel
-        String, // cpu_brand
-        usize,  // cores
-        String, // total_mem
-        String, // bios_version
-        String, // total_storage
-        String, // gpu_str
-        String, // cpu_freq
-        String, // cpu_arch
-        String, // motherboard
-        String, // boot_mode
-        String, // individual_disks
-    ) {
+    pub fn get_static_info(&self) -> StaticSystemInfo {
         let hostname = System::host_name().unwrap_or_else(|| "Unknown".to_string());
         let os_name = System::name().unwrap_or_else(|| "Unknown".to_string());
         let os_ver = System::os_version().unwrap_or_default();
@@ -508,10 +2545,11 @@ impl SystemMonitor {
         );
 
         // BIOS Version
-        let bios_version = std::fs::read_to_string("/sys/class/dmi/id/bios_version")
-            .unwrap_or_else(|_| "Unknown".to_string())
-            .trim()
-            .to_string();
+        let bios_version =
+            std::fs::read_to_string(self.sys_paths.sys.join("class/dmi/id/bios_version"))
+                .unwrap_or_else(|_| "Unknown".to_string())
+                .trim()
+                .to_string();
 
         // Total Storage
         let total_storage_bytes: u64 = self.disks.iter().map(|d| d.total_space()).sum();
@@ -556,14 +2594,16 @@ impl SystemMonitor {
         let cpu_arch = std::env::consts::ARCH.to_string();
 
         // Motherboard Info
-        let board_vendor = std::fs::read_to_string("/sys/class/dmi/id/board_vendor")
-            .unwrap_or_else(|_| "Unknown".to_string())
-            .trim()
-            .to_string();
-        let board_name = std::fs::read_to_string("/sys/class/dmi/id/board_name")
-            .unwrap_or_else(|_| "Unknown".to_string())
-            .trim()
-            .to_string();
+        let board_vendor =
+            std::fs::read_to_string(self.sys_paths.sys.join("class/dmi/id/board_vendor"))
+                .unwrap_or_else(|_| "Unknown".to_string())
+                .trim()
+                .to_string();
+        let board_name =
+            std::fs::read_to_string(self.sys_paths.sys.join("class/dmi/id/board_name"))
+                .unwrap_or_else(|_| "Unknown".to_string())
+                .trim()
+                .to_string();
         let motherboard = if board_vendor != "Unknown" && board_name != "Unknown" {
             format!("{} {}", board_vendor, board_name)
         } else {
@@ -571,14 +2611,14 @@ impl SystemMonitor {
         };
 
         // Boot Mode (UEFI or Legacy)
-        let boot_mode = if std::path::Path::new("/sys/firmware/efi").exists() {
+        let boot_mode = if self.sys_paths.sys.join("firmware/efi").exists() {
             "UEFI".to_string()
         } else {
             "Legacy BIOS".to_string()
         };
 
         // Physical Disks (not partitions)
-        let physical_disks = Self::get_physical_disks();
+        let physical_disks = Self::get_physical_disks(&self.sys_paths);
         let individual_disks = if physical_disks.is_empty() {
             "None detected".to_string()
         } else {
@@ -596,30 +2636,31 @@ impl SystemMonitor {
                 .join("\n")
         };
 
-        (
+        StaticSystemInfo {
             hostname,
-            format!("{} {}", os_name, os_ver),
+            os_name: format!("{} {}", os_name, os_ver),
             kernel,
             cpu_brand,
             cores,
             total_mem,
             bios_version,
             total_storage,
-            gpu_str,
+            gpu_names: gpu_str,
             cpu_freq,
             cpu_arch,
             motherboard,
             boot_mode,
             individual_disks,
-        )
+        }
     }
 
     /// Get physical disk information (models, not partitions)
-    fn get_physical_disks() -> Vec<(String, String, u64)> {
+    fn get_physical_disks(paths: &SysPaths) -> Vec<(String, String, u64)> {
         let mut disks = Vec::new();
 
         // Read /sys/class/block/ for block devices
-        if let Ok(entries) = std::fs::read_dir("/sys/class/block") {
+        let block = paths.sys.join("class/block");
+        if let Ok(entries) = std::fs::read_dir(&block) {
             for entry in entries.flatten() {
                 let device_name = entry.file_name().to_string_lossy().to_string();
 
@@ -650,7 +2691,7 @@ impl SystemMonitor {
                 }
 
                 // Read device model
-                let model_path = format!("/sys/class/block/{}/device/model", device_name);
+                let model_path = block.join(&device_name).join("device/model");
                 let mut model = std::fs::read_to_string(&model_path)
                     .unwrap_or_else(|_| "Unknown".to_string())
                     .trim()
@@ -658,7 +2699,7 @@ impl SystemMonitor {
 
                 // For NVMe, try alternative path
                 if model == "Unknown" && device_name.starts_with("nvme") {
-                    let nvme_model_path = format!("/sys/class/block/{}/device/model", device_name);
+                    let nvme_model_path = block.join(&device_name).join("device/model");
                     model = std::fs::read_to_string(&nvme_model_path)
                         .unwrap_or_else(|_| "Unknown".to_string())
                         .trim()
@@ -666,7 +2707,7 @@ impl SystemMonitor {
                 }
 
                 // Read device size (in 512-byte sectors)
-                let size_path = format!("/sys/class/block/{}/size", device_name);
+                let size_path = block.join(&device_name).join("size");
                 let size_sectors: u64 = std::fs::read_to_string(&size_path)
                     .ok()
                     .and_then(|s| s.trim().parse().ok())
@@ -691,7 +2732,9 @@ impl SystemMonitor {
     /// Get detailed CPU information
     pub fn get_cpu_detailed_info(&self) -> CpuDetailedInfo {
         // Read /proc/cpuinfo for detailed CPU data
-        let cpuinfo = std::fs::read_to_string("/proc/cpuinfo").unwrap_or_default();
+        let cpuinfo =
+            std::fs::read_to_string(self.sys_paths.proc.join("cpuinfo")).unwrap_or_default();
+        let cpu0 = self.sys_paths.sys.join("devices/system/cpu/cpu0");
 
         // Parse vendor_id
         let vendor = cpuinfo
@@ -764,19 +2807,17 @@ impl SystemMonitor {
             .unwrap_or(0.0);
 
         // Try to read max/min frequency from sysfs
-        let frequency_max =
-            std::fs::read_to_string("/sys/devices/system/cpu/cpu0/cpufreq/cpuinfo_max_freq")
-                .ok()
-                .and_then(|s| s.trim().parse::<f32>().ok())
-                .map(|f| f / 1_000_000.0) // Convert kHz to GHz
-                .unwrap_or(0.0);
-
-        let frequency_min =
-            std::fs::read_to_string("/sys/devices/system/cpu/cpu0/cpufreq/cpuinfo_min_freq")
-                .ok()
-                .and_then(|s| s.trim().parse::<f32>().ok())
-                .map(|f| f / 1_000_000.0)
-                .unwrap_or(0.0);
+        let frequency_max = std::fs::read_to_string(cpu0.join("cpufreq/cpuinfo_max_freq"))
+            .ok()
+            .and_then(|s| s.trim().parse::<f32>().ok())
+            .map(|f| f / 1_000_000.0) // Convert kHz to GHz
+            .unwrap_or(0.0);
+
+        let frequency_min = std::fs::read_to_string(cpu0.join("cpufreq/cpuinfo_min_freq"))
+            .ok()
+            .and_then(|s| s.trim().parse::<f32>().ok())
+            .map(|f| f / 1_000_000.0)
+            .unwrap_or(0.0);
 
         // Parse cache information from lscpu or sysfs
         let cache_l3 = if cache_size_kb > 0 {
@@ -786,17 +2827,17 @@ impl SystemMonitor {
         };
 
         // Try to get L1/L2 cache from sysfs
-        let cache_l1d = std::fs::read_to_string("/sys/devices/system/cpu/cpu0/cache/index0/size")
+        let cache_l1d = std::fs::read_to_string(cpu0.join("cache/index0/size"))
             .unwrap_or_else(|_| "N/A".to_string())
             .trim()
             .to_string();
 
-        let cache_l1i = std::fs::read_to_string("/sys/devices/system/cpu/cpu0/cache/index1/size")
+        let cache_l1i = std::fs::read_to_string(cpu0.join("cache/index1/size"))
             .unwrap_or_else(|_| "N/A".to_string())
             .trim()
             .to_string();
 
-        let cache_l2 = std::fs::read_to_string("/sys/devices/system/cpu/cpu0/cache/index2/size")
+        let cache_l2 = std::fs::read_to_string(cpu0.join("cache/index2/size"))
             .unwrap_or_else(|_| "N/A".to_string())
             .trim()
             .to_string();
@@ -829,55 +2870,18 @@ impl SystemMonitor {
         let used_capacity = format!("{:.1} GB", used_mem as f64 / 1024.0 / 1024.0 / 1024.0);
 
         // Detailed info from dmidecode
-        let mut memory_type = "Unknown".to_string();
-        let mut speed = "Unknown".to_string();
-        let mut module_count = 0;
-        // let channels; // Removed needless late init
-
-        // Try dmidecode
-        if let Ok(output) = std::process::Command::new("dmidecode")
+        let (memory_type, speed, module_count) = match std::process::Command::new("dmidecode")
             .arg("-t")
             .arg("memory")
             .output()
         {
-            if output.status.success() {
-                let stdout = String::from_utf8_lossy(&output.stdout);
-                let devices: Vec<&str> = stdout.split("Memory Device").collect();
-                // Skip the first split part as it's header/preamble
-                for device in devices.iter().skip(1) {
-                    // Check if device is present (Size is not "No Module Installed")
-                    if device.contains("Size: No Module Installed") {
-                        continue;
-                    }
-
-                    // Extract Type
-                    if memory_type == "Unknown" {
-                        if let Some(line) = device.lines().find(|l| l.trim().starts_with("Type:")) {
-                            memory_type = line.split(':').nth(1).unwrap_or("").trim().to_string();
-                        }
-                    }
-
-                    // Extract Speed
-                    if speed == "Unknown" {
-                        if let Some(line) = device.lines().find(|l| l.trim().starts_with("Speed:"))
-                        {
-                            let s = line.split(':').nth(1).unwrap_or("").trim();
-                            if s != "Unknown" {
-                                speed = s.to_string();
-                            }
-                        }
-                    }
-                    module_count += 1;
-                }
-            } else {
-                memory_type = "Root required".to_string();
-                speed = "Root required".to_string();
+            Ok(output) if output.status.success() => {
+                parse_dmidecode_memory(&String::from_utf8_lossy(&output.stdout))
             }
-        } else {
+            Ok(_) => ("Root required".to_string(), "Root required".to_string(), 0),
             // dmidecode not found or failed to run
-            memory_type = "Unknown".to_string();
-            speed = "Unknown".to_string();
-        }
+            Err(_) => ("Unknown".to_string(), "Unknown".to_string(), 0),
+        };
 
         let channels = module_count;
 
@@ -893,6 +2897,19 @@ impl SystemMonitor {
 
     /// Get detailed storage information for all physical disks
     pub fn get_storage_detailed_info(&self) -> Vec<StorageDetailedInfo> {
+        let mut devices = self.get_storage_detailed_info_unaliased();
+        for device in &mut devices {
+            device.device_name = self.alias_with_name(&device.device_name);
+        }
+        devices
+    }
+
+    /// Trend of a drive's SMART counters, by serial number.
+    pub fn get_smart_trend(&self, serial: &str) -> Option<SmartTrend> {
+        self.smart_history.trend(serial)
+    }
+
+    fn get_storage_detailed_info_unaliased(&self) -> Vec<StorageDetailedInfo> {
         // Try to get privileged data first
         if let Ok(guard) = self.privileged_data.lock() {
             if let Some(data) = &*guard {
@@ -910,7 +2927,7 @@ impl SystemMonitor {
         // But wait, the "Legacy" logic inside `Monitor` had `self`? No, it just iterated `/sys`.
         // So I can replace the entire body with:
 
-        crate::monitor::get_storage_detailed_info_headless()
+        crate::monitor::get_storage_detailed_info_headless(&self.sys_paths, self.collectors.smart)
     }
 
     /// Get detailed GPU information
@@ -920,6 +2937,19 @@ impl SystemMonitor {
         if let Some(nvml) = &self.nvml {
             if let Ok(count) = nvml.device_count() {
                 for i in 0..count {
+                    let power_state = gpupower::nvidia_state(&self.gpu_power, i as usize)
+                        .map(GpuPowerState::describe);
+                    if self.gpu_asleep(i as usize) {
+                        gpus.push(GpuDetailedInfo {
+                            name: self.alias_with_name(&self.gpu_name(i as usize)),
+                            driver_version: nvml
+                                .sys_driver_version()
+                                .unwrap_or_else(|_| "Unknown".to_string()),
+                            power_state,
+                            ..Default::default()
+                        });
+                        continue;
+                    }
                     if let Ok(dev) = nvml.device_by_index(i) {
                         let name = dev.name().unwrap_or_else(|_| format!("NVIDIA GPU {}", i));
 
@@ -948,6 +2978,23 @@ impl SystemMonitor {
                         let power_limit =
                             dev.power_management_limit().ok().map(|p| p as f32 / 1000.0);
 
+                        let constraints = dev.power_management_limit_constraints().ok();
+                        let power_limit_min =
+                            constraints.as_ref().map(|c| c.min_limit as f32 / 1000.0);
+                        let power_limit_max =
+                            constraints.as_ref().map(|c| c.max_limit as f32 / 1000.0);
+                        let power_limit_default = dev
+                            .power_management_limit_default()
+                            .ok()
+                            .map(|p| p as f32 / 1000.0);
+
+                        // Clocks
+                        use nvml_wrapper::enum_wrappers::device::Clock;
+                        let clock_graphics = dev.clock_info(Clock::Graphics).ok();
+                        let clock_graphics_max = dev.max_clock_info(Clock::Graphics).ok();
+                        let clock_memory = dev.clock_info(Clock::Memory).ok();
+                        let clock_memory_max = dev.max_clock_info(Clock::Memory).ok();
+
                         // Fan speed
                         let fan_speed = dev.fan_speed(0).ok();
 
@@ -956,17 +3003,63 @@ impl SystemMonitor {
 
                         let memory_utilization = dev.utilization_rates().ok().map(|u| u.memory);
 
+                        // Compute & memory subsystem
+                        let cuda_version = nvml.sys_cuda_driver_version().ok().map(|v| {
+                            format!(
+                                "{}.{}",
+                                nvml_wrapper::cuda_driver_version_major(v),
+                                nvml_wrapper::cuda_driver_version_minor(v)
+                            )
+                        });
+                        let compute_capability = dev
+                            .cuda_compute_capability()
+                            .ok()
+                            .map(|c| format!("{}.{}", c.major, c.minor));
+                        let architecture = dev.architecture().ok().map(|a| a.to_string());
+                        let memory_bus_width = dev.memory_bus_width().ok();
+                        // Double data rate: two transfers per memory clock
+                        let memory_bandwidth = memory_bus_width
+                            .zip(clock_memory_max)
+                            .map(|(bits, mhz)| bits as f32 / 8.0 * mhz as f32 * 2.0 / 1000.0);
+                        let pcie_link = dev
+                            .current_pcie_link_gen()
+                            .ok()
+                            .zip(dev.current_pcie_link_width().ok())
+                            .map(|(generation, width)| {
+                                match (dev.max_pcie_link_gen(), dev.max_pcie_link_width()) {
+                                    (Ok(max_gen), Ok(max_width)) => format!(
+                                        "Gen {} x{} (max Gen {} x{})",
+                                        generation, width, max_gen, max_width
+                                    ),
+                                    _ => format!("Gen {} x{}", generation, width),
+                                }
+                            });
+
                         gpus.push(GpuDetailedInfo {
-                            name,
+                            name: self.alias_with_name(&name),
                             vram_total,
                             vram_used,
                             driver_version,
                             temperature,
                             power_draw,
                             power_limit,
+                            power_limit_min,
+                            power_limit_max,
+                            power_limit_default,
                             fan_speed,
                             gpu_utilization,
                             memory_utilization,
+                            clock_graphics,
+                            clock_graphics_max,
+                            clock_memory,
+                            clock_memory_max,
+                            cuda_version,
+                            compute_capability,
+                            architecture,
+                            memory_bus_width,
+                            memory_bandwidth,
+                            pcie_link,
+                            power_state,
                         });
                     }
                 }
@@ -978,6 +3071,14 @@ impl SystemMonitor {
 
     /// Get detailed network information
     pub fn get_network_detailed_info(&self) -> Vec<NetworkDetailedInfo> {
+        let mut interfaces = self.get_network_detailed_info_unaliased();
+        for interface in &mut interfaces {
+            interface.name = self.alias_with_name(&interface.name);
+        }
+        interfaces
+    }
+
+    fn get_network_detailed_info_unaliased(&self) -> Vec<NetworkDetailedInfo> {
         // Try to get privileged data first
         if let Ok(guard) = self.privileged_data.lock() {
             if let Some(data) = &*guard {
@@ -988,15 +3089,160 @@ impl SystemMonitor {
         }
 
         // Fallback
-        crate::monitor::get_network_detailed_info_headless(&self.networks)
+        crate::monitor::get_network_detailed_info_headless(&self.sys_paths, &self.networks)
+    }
+
+    /// Per-protocol traffic from the worker's packet capture, as `(protocol, bytes/s)`,
+    /// largest first. Empty unless packet capture is enabled.
+    pub fn get_protocol_breakdown(&self) -> Vec<(String, f64)> {
+        if let Ok(guard) = self.privileged_data.lock() {
+            if let Some(data) = &*guard {
+                return data
+                    .protocols
+                    .iter()
+                    .map(|p| {
+                        (
+                            p.protocol.clone(),
+                            p.bytes as f64 / crate::worker::WORKER_INTERVAL_SECS as f64,
+                        )
+                    })
+                    .collect();
+            }
+        }
+        Vec::new()
+    }
+
+    /// Recent kernel warnings and errors from the worker, newest first. Empty without
+    /// the privileged worker.
+    pub fn get_kernel_log(&self) -> Vec<KernelLogEntry> {
+        if let Ok(guard) = self.privileged_data.lock() {
+            if let Some(data) = &*guard {
+                return data.kernel_log.iter().rev().cloned().collect();
+            }
+        }
+        Vec::new()
+    }
+
+    /// Lists non-host network namespaces, preferring the worker's complete view.
+    pub fn get_network_namespaces(&self) -> Vec<crate::netns::NetNamespace> {
+        if let Ok(guard) = self.privileged_data.lock() {
+            if let Some(data) = &*guard {
+                return data.namespaces.clone();
+            }
+        }
+
+        // Fallback (only our own processes are visible)
+        crate::netns::list_namespaces()
     }
 }
 // --- Standalone Data Gathering Functions (Reused by Worker) ---
 
-pub fn get_storage_detailed_info_headless() -> Vec<StorageDetailedInfo> {
+/// Roots of the `/sys` and `/proc` trees the hardware details are read from. Tests
+/// point them at fixture trees; everywhere else they are the real ones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SysPaths {
+    pub sys: PathBuf,
+    pub proc: PathBuf,
+}
+
+impl Default for SysPaths {
+    fn default() -> Self {
+        Self {
+            sys: PathBuf::from("/sys"),
+            proc: PathBuf::from("/proc"),
+        }
+    }
+}
+
+impl SysPaths {
+    /// `<root>/sys` and `<root>/proc`.
+    pub fn under(root: impl AsRef<Path>) -> Self {
+        Self {
+            sys: root.as_ref().join("sys"),
+            proc: root.as_ref().join("proc"),
+        }
+    }
+}
+
+/// Reads memory type, speed and the number of populated slots from `dmidecode -t memory`
+/// output. Empty slots are skipped; fields the firmware leaves as "Unknown" or "Other"
+/// are taken from the next module that reports them.
+pub fn parse_dmidecode_memory(output: &str) -> (String, String, u32) {
+    let mut memory_type = "Unknown".to_string();
+    let mut speed = "Unknown".to_string();
+    let mut module_count = 0;
+
+    // Sections are separated by blank lines and start with a "Handle" line, followed by
+    // the section name ("Memory Device", "Memory Device Mapped Address", ...)
+    for section in output.split("\n\n") {
+        let mut lines = section.lines().map(str::trim).skip_while(|l| l.is_empty());
+        if !lines.next().is_some_and(|l| l.starts_with("Handle"))
+            || lines.next() != Some("Memory Device")
+        {
+            continue;
+        }
+        let field = |name: &str| {
+            section.lines().find_map(|l| {
+                let (key, value) = l.trim().split_once(':')?;
+                (key == name).then(|| value.trim().to_string())
+            })
+        };
+        if field("Size").is_none_or(|s| s == "No Module Installed" || s.is_empty()) {
+            continue;
+        }
+        module_count += 1;
+
+        if memory_type == "Unknown" {
+            if let Some(t) = field("Type").filter(|t| !matches!(t.as_str(), "Unknown" | "Other")) {
+                memory_type = t;
+            }
+        }
+        if speed == "Unknown" {
+            if let Some(s) = field("Speed").filter(|s| s != "Unknown" && !s.is_empty()) {
+                speed = s;
+            }
+        }
+    }
+    (memory_type, speed, module_count)
+}
+
+/// Link speed of a network interface, e.g. "1000 Mbps". Virtual and disconnected
+/// interfaces report -1 or fail to read, both shown as "Unknown".
+pub fn read_link_speed(paths: &SysPaths, interface: &str) -> String {
+    std::fs::read_to_string(paths.sys.join("class/net").join(interface).join("speed"))
+        .ok()
+        .and_then(|s| s.trim().parse::<i64>().ok())
+        .filter(|mbps| *mbps > 0)
+        .map(|mbps| format!("{} Mbps", mbps))
+        .unwrap_or_else(|| "Unknown".to_string())
+}
+
+/// Reads the NVMe critical warning bitmask with `nvme-cli`, for when smartctl is missing.
+fn nvme_critical_warning(device_name: &str) -> Option<u64> {
+    let output = crate::sandbox::command("nvme")
+        .args([
+            "smart-log",
+            "--output-format=json",
+            &format!("/dev/{}", device_name),
+        ])
+        .output()
+        .ok()
+        .filter(|o| o.status.success())?;
+    serde_json::from_slice::<serde_json::Value>(&output.stdout)
+        .ok()?
+        .get("critical_warning")?
+        .as_u64()
+}
+
+/// Lists block devices; `smart` enables the health query (smartctl, then nvme-cli).
+pub fn get_storage_detailed_info_headless(
+    paths: &SysPaths,
+    smart: bool,
+) -> Vec<StorageDetailedInfo> {
     let mut storage_devices = Vec::new();
     // Read /sys/class/block for devices
-    let entries = match std::fs::read_dir("/sys/class/block") {
+    let block = paths.sys.join("class/block");
+    let entries = match std::fs::read_dir(&block) {
         Ok(e) => e,
         Err(_) => return Vec::new(),
     };
@@ -1013,14 +3259,13 @@ pub fn get_storage_detailed_info_headless() -> Vec<StorageDetailedInfo> {
         }
         // Exclude partitions: check if it ends with digit (for sd*) or p+digit (nvme)
         // Heuristic: check if /sys/class/block/{name}/partition exists
-        let partition_path = format!("/sys/class/block/{}/partition", device_name);
-        if std::path::Path::new(&partition_path).exists() {
+        let device_dir = block.join(&device_name);
+        if device_dir.join("partition").exists() {
             continue;
         }
 
         // Capacity
-        let size_path = format!("/sys/class/block/{}/size", device_name);
-        let capacity_sectors = std::fs::read_to_string(&size_path)
+        let capacity_sectors = std::fs::read_to_string(device_dir.join("size"))
             .unwrap_or("0".to_string())
             .trim()
             .parse::<u64>()
@@ -1028,17 +3273,14 @@ pub fn get_storage_detailed_info_headless() -> Vec<StorageDetailedInfo> {
         let capacity_bytes = capacity_sectors * 512; // Standard sector size assumption
 
         // Model
-        let model_path = format!("/sys/class/block/{}/device/model", device_name);
-        let mut model = std::fs::read_to_string(&model_path)
+        let mut model = std::fs::read_to_string(device_dir.join("device/model"))
             .unwrap_or("Unknown".to_string())
             .trim()
             .to_string();
 
         if model == "Unknown" && device_name.starts_with("nvme") {
             // NVMe model path
-            if let Ok(m) =
-                std::fs::read_to_string(format!("/sys/class/block/{}/device/model", device_name))
-            {
+            if let Ok(m) = std::fs::read_to_string(device_dir.join("device/model")) {
                 model = m.trim().to_string();
             }
         }
@@ -1055,40 +3297,40 @@ pub fn get_storage_detailed_info_headless() -> Vec<StorageDetailedInfo> {
         };
 
         // SSD Check
-        let rotational_path = format!("/sys/class/block/{}/queue/rotational", device_name);
-        let is_ssd = std::fs::read_to_string(&rotational_path)
+        let is_ssd = std::fs::read_to_string(device_dir.join("queue/rotational"))
             .ok()
             .and_then(|s| s.trim().parse::<u8>().ok())
             .map(|v| v == 0)
             .unwrap_or(true);
 
         // Serial & Firmware (Fallback)
-        let mut serial_number =
-            std::fs::read_to_string(format!("/sys/class/block/{}/device/serial", device_name))
-                .unwrap_or("Unknown".to_string())
-                .trim()
-                .to_string();
-        let mut firmware_version =
-            std::fs::read_to_string(format!("/sys/class/block/{}/device/rev", device_name))
-                .unwrap_or("Unknown".to_string())
-                .trim()
-                .to_string();
+        let mut serial_number = std::fs::read_to_string(device_dir.join("device/serial"))
+            .unwrap_or("Unknown".to_string())
+            .trim()
+            .to_string();
+        let mut firmware_version = std::fs::read_to_string(device_dir.join("device/rev"))
+            .unwrap_or("Unknown".to_string())
+            .trim()
+            .to_string();
 
         if device_name.starts_with("nvme") && firmware_version == "Unknown" {
-            if let Ok(fw) = std::fs::read_to_string(format!(
-                "/sys/class/block/{}/device/firmware_rev",
-                device_name
-            )) {
+            if let Ok(fw) = std::fs::read_to_string(device_dir.join("device/firmware_rev")) {
                 firmware_version = fw.trim().to_string();
             }
         }
 
         // Health via smartctl (Privileged part)
         let mut health_status = "Unknown".to_string();
+        let mut temperature = None;
+        let mut smart_counters = SmartCounters::default();
 
         // Only try smartctl if we are likely root (headless fn implies usage by worker) or it's installed
         // The worker will be root, so this should succeed.
-        if let Ok(output) = std::process::Command::new("smartctl")
+        if !smart {
+            health_status = "Disabled".to_string();
+        } else if !crate::sandbox::is_device_name(&device_name) {
+            health_status = "Invalid device name".to_string();
+        } else if let Ok(output) = crate::sandbox::command("smartctl")
             .args(["--json", "-a", &format!("/dev/{}", device_name)])
             .output()
         {
@@ -1101,6 +3343,8 @@ pub fn get_storage_detailed_info_headless() -> Vec<StorageDetailedInfo> {
                     if let Some(f) = v["firmware_version"].as_str() {
                         firmware_version = f.to_string();
                     }
+                    temperature = v["temperature"]["current"].as_f64().map(|t| t as f32);
+                    smart_counters = SmartCounters::from_smartctl(&v);
                     if let Some(passed) = v["smart_status"]["passed"].as_bool() {
                         health_status = if passed {
                             "Passed".to_string()
@@ -1129,6 +3373,15 @@ pub fn get_storage_detailed_info_headless() -> Vec<StorageDetailedInfo> {
             }
         } else {
             health_status = "Smartctl not found".to_string();
+            if device_name.starts_with("nvme") {
+                if let Some(warning) = nvme_critical_warning(&device_name) {
+                    health_status = if warning == 0 {
+                        "Passed".to_string()
+                    } else {
+                        "Warning".to_string()
+                    };
+                }
+            }
         }
 
         storage_devices.push(StorageDetailedInfo {
@@ -1140,13 +3393,18 @@ pub fn get_storage_detailed_info_headless() -> Vec<StorageDetailedInfo> {
             serial_number,
             firmware_version,
             health_status,
+            temperature,
+            smart: smart_counters,
         });
     }
 
     storage_devices
 }
 
-pub fn get_network_detailed_info_headless(networks: &Networks) -> Vec<NetworkDetailedInfo> {
+pub fn get_network_detailed_info_headless(
+    paths: &SysPaths,
+    networks: &Networks,
+) -> Vec<NetworkDetailedInfo> {
     let mut networks_info = Vec::new();
     for (interface_name, data) in networks {
         // ... (Logic from get_network_detailed_info)
@@ -1161,10 +3419,7 @@ pub fn get_network_detailed_info_headless(networks: &Networks) -> Vec<NetworkDet
             }
         }
 
-        let speed_path = format!("/sys/class/net/{}/speed", interface_name);
-        let link_speed = std::fs::read_to_string(&speed_path)
-            .map(|s| format!("{} Mbps", s.trim()))
-            .unwrap_or("Unknown".to_string());
+        let link_speed = read_link_speed(paths, interface_name);
 
         networks_info.push(NetworkDetailedInfo {
             name: interface_name.clone(),
@@ -0,0 +1,171 @@
+//! # Mount Events Module
+//!
+//! This module watches the mount options of block-device filesystems
+//! (`/proc/self/mounts`) and reports when one that was mounted read-write becomes
+//! read-only. ext4 and others remount themselves read-only (`errors=remount-ro`) after
+//! an I/O error they cannot recover from, which is often the first visible sign of a
+//! failing drive.
+//!
+//! `SystemMonitor` raises a critical alert per affected mount point, together with the
+//! SMART status of the drive behind it, and clears it once the filesystem is writable
+//! again.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+const MOUNTS_PATH: &str = "/proc/self/mounts";
+
+/// A filesystem that went from read-write to read-only, or back.
+#[derive(Debug, Clone)]
+pub struct MountEvent {
+    pub mount_point: String,
+    /// Source device, e.g. "/dev/nvme0n1p2".
+    pub device: String,
+    pub read_only: bool,
+}
+
+#[derive(Debug, Clone)]
+struct MountState {
+    device: String,
+    read_only: bool,
+}
+
+/// Tracks the read-only flag of block-device mounts between refreshes.
+#[derive(Default)]
+pub struct MountTracker {
+    states: HashMap<String, MountState>,
+    /// Mount points that became read-only while being watched, with their device.
+    remounted: HashMap<String, String>,
+}
+
+/// Decodes the octal escapes `/proc/self/mounts` uses for spaces, tabs and backslashes.
+fn unescape(field: &str) -> String {
+    let mut out = String::new();
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            let code: String = chars.by_ref().take(3).collect();
+            match u8::from_str_radix(&code, 8) {
+                Ok(byte) => out.push(byte as char),
+                Err(_) => {
+                    out.push(c);
+                    out.push_str(&code);
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Parses `(mount_point, state)` pairs for filesystems backed by a `/dev` node.
+fn parse_mounts(content: &str) -> Vec<(String, MountState)> {
+    content
+        .lines()
+        .filter_map(|line| {
+            // device mount_point fstype options dump pass
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 4 || !fields[0].starts_with("/dev/") {
+                return None;
+            }
+            Some((
+                unescape(fields[1]),
+                MountState {
+                    device: unescape(fields[0]),
+                    read_only: fields[3].split(',').any(|o| o == "ro"),
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Whole drive behind a mount source: the parent of a partition, or the first underlying
+/// device of a device-mapper/md volume (LUKS, LVM, RAID).
+pub fn backing_drive(device: &str) -> Option<String> {
+    let mut name = std::fs::canonicalize(device)
+        .ok()?
+        .file_name()?
+        .to_string_lossy()
+        .to_string();
+    // Follow stacked volumes down to the first physical device
+    for _ in 0..4 {
+        let slaves = Path::new("/sys/class/block").join(&name).join("slaves");
+        let first = std::fs::read_dir(&slaves).ok().and_then(|entries| {
+            let mut names: Vec<String> = entries
+                .flatten()
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .collect();
+            names.sort();
+            names.into_iter().next()
+        });
+        match first {
+            Some(slave) => name = slave,
+            None => break,
+        }
+    }
+    let sys = Path::new("/sys/class/block").join(&name);
+    if sys.join("partition").exists() {
+        // /sys/class/block/sda1 links to .../block/sda/sda1
+        return std::fs::canonicalize(&sys)
+            .ok()?
+            .parent()?
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string());
+    }
+    Some(name)
+}
+
+impl MountTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Polls the mount table and returns read-only transitions since the previous call.
+    /// Filesystems that are mounted read-only from the start are not reported.
+    pub fn refresh(&mut self) -> Vec<MountEvent> {
+        let mut events = Vec::new();
+        let Ok(content) = std::fs::read_to_string(MOUNTS_PATH) else {
+            return events;
+        };
+
+        let mut seen = HashMap::new();
+        for (mount_point, state) in parse_mounts(&content) {
+            // With stacked mounts the last entry is the visible one
+            seen.insert(mount_point, state);
+        }
+        for (mount_point, state) in &seen {
+            if let Some(prev) = self.states.get(mount_point) {
+                if prev.device == state.device && prev.read_only != state.read_only {
+                    events.push(MountEvent {
+                        mount_point: mount_point.clone(),
+                        device: state.device.clone(),
+                        read_only: state.read_only,
+                    });
+                    if state.read_only {
+                        self.remounted
+                            .insert(mount_point.clone(), state.device.clone());
+                    } else {
+                        self.remounted.remove(mount_point);
+                    }
+                }
+            }
+        }
+        // Unmounted filesystems are no longer a concern
+        self.remounted
+            .retain(|mount_point, _| seen.contains_key(mount_point));
+        self.states = seen;
+        events
+    }
+
+    /// `(mount_point, device)` of filesystems that were remounted read-only and still are.
+    pub fn remounted_read_only(&self) -> Vec<(String, String)> {
+        let mut mounts: Vec<(String, String)> = self
+            .remounted
+            .iter()
+            .map(|(m, d)| (m.clone(), d.clone()))
+            .collect();
+        mounts.sort();
+        mounts
+    }
+}
@@ -0,0 +1,136 @@
+//! # Network Namespace Module
+//!
+//! This module discovers network namespaces other than the host's (containers, VPN
+//! sandboxes, `ip netns` namespaces) so their traffic doesn't go unnoticed.
+//!
+//! Namespaces are identified by the inode of `/proc/<pid>/ns/net`. Interface counters are
+//! read from `/proc/<pid>/net/dev` of a representative process, which reflects the
+//! namespace of that process without having to `setns()` into it.
+//!
+//! Unprivileged callers can only inspect their own processes, so the complete picture
+//! comes from the privileged worker (`PrivilegedData::namespaces`).
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::os::unix::fs::MetadataExt;
+
+/// Directory where `ip netns add` bind-mounts named namespaces.
+const NAMED_NETNS_DIR: &str = "/run/netns";
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NamespaceInterface {
+    pub name: String,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NetNamespace {
+    /// Namespace inode number (as shown by `lsns -t net`).
+    pub inode: u64,
+    /// Name under `/run/netns`, if the namespace was created with `ip netns add`.
+    pub name: Option<String>,
+    /// Number of processes living in the namespace.
+    pub process_count: usize,
+    /// Name of the first process found in the namespace (e.g. "containerd-shim", "openvpn").
+    pub owner: String,
+    pub interfaces: Vec<NamespaceInterface>,
+}
+
+/// Extracts the inode from a `net:[4026531840]` link target.
+fn parse_ns_link(link: &str) -> Option<u64> {
+    link.strip_prefix("net:[")?.strip_suffix(']')?.parse().ok()
+}
+
+fn ns_inode(pid: &str) -> Option<u64> {
+    std::fs::read_link(format!("/proc/{}/ns/net", pid))
+        .ok()
+        .and_then(|p| parse_ns_link(&p.to_string_lossy()))
+}
+
+/// Parses the per-interface byte counters from the contents of `/proc/<pid>/net/dev`.
+/// The loopback interface is skipped.
+fn parse_net_dev(content: &str) -> Vec<NamespaceInterface> {
+    content
+        .lines()
+        .skip(2) // Two header lines
+        .filter_map(|line| {
+            let (name, stats) = line.split_once(':')?;
+            let name = name.trim();
+            if name == "lo" {
+                return None;
+            }
+            let fields: Vec<u64> = stats
+                .split_whitespace()
+                .filter_map(|v| v.parse().ok())
+                .collect();
+            // Receive: bytes packets errs drop fifo frame compressed multicast, then transmit
+            Some(NamespaceInterface {
+                name: name.to_string(),
+                rx_bytes: *fields.first()?,
+                tx_bytes: *fields.get(8)?,
+            })
+        })
+        .collect()
+}
+
+/// Lists all network namespaces except the host's one, sorted by inode.
+///
+/// Without root, only namespaces of the caller's own processes and named namespaces
+/// are visible.
+pub fn list_namespaces() -> Vec<NetNamespace> {
+    let host = ns_inode("1").or_else(|| ns_inode("self"));
+
+    // inode -> (representative pid, process count)
+    let mut found: BTreeMap<u64, (String, usize)> = BTreeMap::new();
+    if let Ok(entries) = std::fs::read_dir("/proc") {
+        for entry in entries.flatten() {
+            let pid = entry.file_name().to_string_lossy().to_string();
+            if !pid.chars().all(|c| c.is_ascii_digit()) {
+                continue;
+            }
+            if let Some(inode) = ns_inode(&pid).filter(|i| Some(*i) != host) {
+                found.entry(inode).or_insert((pid, 0)).1 += 1;
+            }
+        }
+    }
+
+    // Named namespaces may have no processes at all
+    let mut names: BTreeMap<u64, String> = BTreeMap::new();
+    if let Ok(entries) = std::fs::read_dir(NAMED_NETNS_DIR) {
+        for entry in entries.flatten() {
+            if let Ok(meta) = std::fs::metadata(entry.path()) {
+                names.insert(meta.ino(), entry.file_name().to_string_lossy().to_string());
+            }
+        }
+    }
+
+    let mut inodes: Vec<u64> = found.keys().chain(names.keys()).copied().collect();
+    inodes.sort();
+    inodes.dedup();
+
+    inodes
+        .into_iter()
+        .map(|inode| {
+            let (owner, process_count, interfaces) = match found.get(&inode) {
+                Some((pid, count)) => (
+                    std::fs::read_to_string(format!("/proc/{}/comm", pid))
+                        .map(|s| s.trim().to_string())
+                        .unwrap_or_default(),
+                    *count,
+                    std::fs::read_to_string(format!("/proc/{}/net/dev", pid))
+                        .map(|c| parse_net_dev(&c))
+                        .unwrap_or_default(),
+                ),
+                None => (String::new(), 0, Vec::new()),
+            };
+            NetNamespace {
+                inode,
+                name: names.get(&inode).cloned(),
+                process_count,
+                owner,
+                interfaces,
+            }
+        })
+        .collect()
+}
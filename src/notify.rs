@@ -0,0 +1,236 @@
+//! # Alert Notifications Module
+//!
+//! This module announces alert transitions on the channel chosen for each rule in the
+//! `notifications` settings section: a desktop notification through `notify-send`, a
+//! POST to a webhook, only the alert banner of the main window, or nothing at all.
+//! Rules match alert ids by prefix ("smart." covers the SMART alerts of every drive), so
+//! one noisy rule can be silenced without losing the others.
+//!
+//! During quiet hours desktop and webhook notifications are held back (the alert still
+//! shows in the banner), except critical ones unless `critical_ignores_quiet_hours` is
+//! off. Held-back notifications are not sent later; the banner lists what is still active.
+
+use crate::alerts::{AlertEvent, AlertSeverity, AlertTransition};
+use crate::settings::{NotificationSettings, NotifyChannel, QuietHours};
+use crate::syslog::transition_name;
+use log::{info, warn};
+use serde::Serialize;
+use std::process::{Command, Stdio};
+
+/// Weekday names accepted in `QuietHours::days`, indexed like `tm_wday`.
+const DAY_NAMES: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+/// Milliseconds a desktop notification stays on screen (critical ones stay until closed).
+const EXPIRE_MS: u32 = 10_000;
+
+/// Parses "HH:MM" into minutes since midnight.
+pub fn parse_clock(text: &str) -> Option<u32> {
+    let (hours, minutes) = text.trim().split_once(':')?;
+    let (hours, minutes): (u32, u32) = (hours.parse().ok()?, minutes.parse().ok()?);
+    (hours < 24 && minutes < 60).then_some(hours * 60 + minutes)
+}
+
+/// A parsed `QuietHours` entry.
+#[derive(Debug, Clone, Copy)]
+struct QuietPeriod {
+    start: u32,
+    end: u32,
+    /// One bit per weekday the period starts on (bit 0 = Sunday).
+    days: u8,
+}
+
+impl QuietPeriod {
+    fn parse(hours: &QuietHours) -> Result<Self, String> {
+        let clock = |text: &str| {
+            parse_clock(text).ok_or_else(|| format!("Invalid time '{}' (expected HH:MM)", text))
+        };
+        let mut days = 0u8;
+        for day in &hours.days {
+            let day = day.trim().to_lowercase();
+            let index = DAY_NAMES
+                .iter()
+                .position(|name| day.starts_with(name))
+                .ok_or_else(|| format!("Invalid day '{}' (expected mon ... sun)", day))?;
+            days |= 1 << index;
+        }
+        Ok(Self {
+            start: clock(&hours.start)?,
+            end: clock(&hours.end)?,
+            days: if days == 0 { 0x7f } else { days },
+        })
+    }
+
+    /// Whether `minute` (since midnight) of `weekday` falls into the period.
+    fn contains(&self, weekday: u32, minute: u32) -> bool {
+        let starts_on = |day: u32| self.days & (1 << day) != 0;
+        if self.start <= self.end {
+            starts_on(weekday) && (self.start..self.end).contains(&minute)
+        } else {
+            // Runs past midnight: the early hours belong to the previous day's period
+            (starts_on(weekday) && minute >= self.start)
+                || (starts_on((weekday + 6) % 7) && minute < self.end)
+        }
+    }
+}
+
+/// Local weekday (0 = Sunday) and minute of the day.
+fn local_time() -> Option<(u32, u32)> {
+    let now = unsafe { libc::time(std::ptr::null_mut()) };
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    if unsafe { libc::localtime_r(&now, &mut tm) }.is_null() {
+        return None;
+    }
+    Some((tm.tm_wday as u32, (tm.tm_hour * 60 + tm.tm_min) as u32))
+}
+
+/// JSON body POSTed by the webhook channel.
+#[derive(Serialize)]
+struct AlertPayload<'a> {
+    id: &'a str,
+    /// "warning" or "critical".
+    severity: String,
+    /// "raised", "severity-changed" or "cleared".
+    state: &'static str,
+    message: &'a str,
+    since: u64,
+}
+
+/// Shows `event` with `notify-send`, without waiting for it.
+fn send_desktop(event: &AlertEvent) {
+    let alert = &event.alert;
+    let (urgency, summary) = match (event.transition, alert.severity) {
+        (AlertTransition::Cleared, _) => ("low", "Alert cleared"),
+        (_, AlertSeverity::Critical) => ("critical", "Critical alert"),
+        (_, AlertSeverity::Warning) => ("normal", "Warning"),
+    };
+    let mut command = Command::new("notify-send");
+    command
+        .args([
+            "--app-name=Gjallarhorn",
+            &format!("--urgency={}", urgency),
+            &format!("--expire-time={}", EXPIRE_MS),
+            summary,
+            alert.message.as_str(),
+        ])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    // Reaped on a short-lived thread so the refresh never waits for the notification daemon
+    std::thread::spawn(move || {
+        if let Err(e) = command.status() {
+            warn!("Failed to run notify-send: {}", e);
+        }
+    });
+}
+
+/// POSTs `event` to `url` from a background thread.
+fn send_webhook(url: &str, event: &AlertEvent) {
+    let alert = &event.alert;
+    let payload = AlertPayload {
+        id: &alert.id,
+        severity: format!("{:?}", alert.severity).to_lowercase(),
+        state: transition_name(event.transition),
+        message: &alert.message,
+        since: alert.since,
+    };
+    let payload = match serde_json::to_string(&payload) {
+        Ok(p) => p,
+        Err(e) => {
+            warn!("Failed to serialize alert: {}", e);
+            return;
+        }
+    };
+    let url = url.to_string();
+    std::thread::spawn(move || {
+        if let Err(e) = crate::webhook::post(&url, &payload) {
+            warn!("Alert notification to {} failed: {}", url, e);
+        }
+    });
+}
+
+/// Routes alert events to their channels.
+pub struct Notifier {
+    default_channel: NotifyChannel,
+    /// (alert id prefix, channel), longest prefix first.
+    rules: Vec<(String, NotifyChannel)>,
+    quiet_hours: Vec<QuietPeriod>,
+    critical_ignores_quiet_hours: bool,
+    webhook_url: String,
+}
+
+impl Notifier {
+    /// Builds the routing from `settings`; `fallback_webhook_url` is used when the
+    /// section has no URL of its own. Invalid quiet hours are logged and skipped.
+    pub fn new(settings: &NotificationSettings, fallback_webhook_url: &str) -> Self {
+        let mut rules: Vec<(String, NotifyChannel)> = settings
+            .rules
+            .iter()
+            .map(|(prefix, channel)| (prefix.clone(), *channel))
+            .collect();
+        rules.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+
+        let quiet_hours = settings
+            .quiet_hours
+            .iter()
+            .filter_map(|hours| {
+                QuietPeriod::parse(hours)
+                    .map_err(|e| warn!("Ignoring quiet hours: {}", e))
+                    .ok()
+            })
+            .collect();
+
+        let webhook_url = if settings.webhook_url.trim().is_empty() {
+            fallback_webhook_url
+        } else {
+            &settings.webhook_url
+        };
+
+        Self {
+            default_channel: settings.default_channel,
+            rules,
+            quiet_hours,
+            critical_ignores_quiet_hours: settings.critical_ignores_quiet_hours,
+            webhook_url: webhook_url.trim().to_string(),
+        }
+    }
+
+    /// Channel of the alert `id`.
+    pub fn channel(&self, id: &str) -> NotifyChannel {
+        self.rules
+            .iter()
+            .find(|(prefix, _)| id.starts_with(prefix.as_str()))
+            .map_or(self.default_channel, |(_, channel)| *channel)
+    }
+
+    /// Whether the current local time falls into quiet hours.
+    pub fn is_quiet(&self) -> bool {
+        local_time().is_some_and(|(weekday, minute)| {
+            self.quiet_hours
+                .iter()
+                .any(|period| period.contains(weekday, minute))
+        })
+    }
+
+    /// Announces `event` on its channel, unless quiet hours hold it back.
+    pub fn notify(&self, event: &AlertEvent) {
+        let channel = self.channel(&event.alert.id);
+        if matches!(channel, NotifyChannel::Badge | NotifyChannel::None) {
+            return;
+        }
+        let critical = event.alert.severity == AlertSeverity::Critical;
+        if self.is_quiet() && !(critical && self.critical_ignores_quiet_hours) {
+            info!("Quiet hours, not announcing: {}", event.alert.message);
+            return;
+        }
+
+        match channel {
+            NotifyChannel::Desktop => send_desktop(event),
+            NotifyChannel::Webhook if self.webhook_url.is_empty() => {
+                warn!(
+                    "Alert {} uses the webhook channel, but no webhook URL is set",
+                    event.alert.id
+                );
+            }
+            NotifyChannel::Webhook => send_webhook(&self.webhook_url, event),
+            NotifyChannel::Badge | NotifyChannel::None => {}
+        }
+    }
+}
@@ -0,0 +1,141 @@
+//! # Streaming Overlay Module
+//!
+//! This module serves a small web page with live CPU, GPU, memory and temperature text,
+//! meant to be added to OBS (or any streaming tool) as a browser source. It is started
+//! when the `overlay` settings section enables it.
+//!
+//! Routes, on `http://<bind_address>:<port>`:
+//! - `/` — the overlay page: white text with a dark outline on a transparent background.
+//!   It polls `/overlay.txt` once a second; OBS's custom CSS can restyle `#overlay`.
+//! - `/overlay.txt` — the overlay text as plain lines.
+//! - `/snapshot.json` — the full `MetricsSnapshot`, for hand-written overlays.
+//!
+//! Like the Prometheus exporter, the server (see `http.rs`) is one background thread that
+//! answers with the output formatted at the last refresh.
+
+use crate::http::{HttpServer, Response};
+use crate::settings::OverlaySettings;
+use crate::snapshot::MetricsSnapshot;
+use log::info;
+use std::sync::{Arc, Mutex};
+
+const PAGE: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Gjallarhorn Overlay</title>
+<style>
+  html, body { margin: 0; background: transparent; }
+  #overlay {
+    font: bold 28px sans-serif;
+    color: #fff;
+    white-space: pre;
+    -webkit-text-stroke: 1px #000;
+    text-shadow: 0 0 4px #000;
+    padding: 8px;
+  }
+</style>
+</head>
+<body>
+<div id="overlay"></div>
+<script>
+  const overlay = document.getElementById("overlay");
+  async function update() {
+    try {
+      const response = await fetch("/overlay.txt", { cache: "no-store" });
+      overlay.textContent = await response.text();
+    } catch (e) {
+      overlay.textContent = "";
+    }
+  }
+  update();
+  setInterval(update, 1000);
+</script>
+</body>
+</html>
+"#;
+
+/// Formats the overlay lines, e.g. "CPU 34% · 65 °C", "GPU 87% · 72 °C",
+/// "RAM 12.3 / 32.0 GB". The CPU temperature is the hottest hwmon chip.
+pub fn format_overlay(snapshot: &MetricsSnapshot) -> String {
+    let with_temp = |label: String, temp: Option<f32>| match temp {
+        Some(t) => format!("{} · {:.0} °C", label, t),
+        None => label,
+    };
+    let chip_temp = snapshot
+        .fans
+        .iter()
+        .filter_map(|f| f.temperature)
+        .reduce(f32::max);
+
+    let mut lines = vec![with_temp(
+        format!("CPU {:.0}%", snapshot.cpu.usage_percent),
+        chip_temp,
+    )];
+    for (i, gpu) in snapshot.gpus.iter().enumerate() {
+        let name = if snapshot.gpus.len() > 1 {
+            format!("GPU{}", i)
+        } else {
+            "GPU".to_string()
+        };
+        lines.push(with_temp(
+            format!("{} {:.0}%", name, gpu.util_percent),
+            gpu.temperature,
+        ));
+    }
+    lines.push(format!(
+        "RAM {:.1} / {:.1} GB",
+        snapshot.memory.used_gb, snapshot.memory.total_gb
+    ));
+    lines.join("\n")
+}
+
+/// Latest output of each route, formatted at the last refresh.
+#[derive(Default)]
+struct Latest {
+    text: String,
+    json: String,
+}
+
+/// The response for `path` from `latest`; `None` for unknown paths.
+fn route(path: &str, latest: &Mutex<Latest>) -> Option<Response> {
+    let latest = latest.lock().ok()?;
+    let (content_type, body) = match path {
+        "/" | "/index.html" => ("text/html; charset=utf-8", PAGE.to_string()),
+        "/overlay.txt" => ("text/plain; charset=utf-8", latest.text.clone()),
+        "/snapshot.json" => ("application/json", latest.json.clone()),
+        _ => return None,
+    };
+    Some(Response { content_type, body })
+}
+
+/// Serves the overlay until dropped.
+pub struct OverlayServer {
+    latest: Arc<Mutex<Latest>>,
+    _server: HttpServer,
+}
+
+impl OverlayServer {
+    /// Binds the listener and starts the server thread.
+    pub fn start(settings: &OverlaySettings) -> Result<Self, String> {
+        let address = format!("{}:{}", settings.bind_address, settings.port);
+        let latest = Arc::new(Mutex::new(Latest::default()));
+        let server_latest = latest.clone();
+        let server =
+            HttpServer::start("Overlay", &address, move |path| route(path, &server_latest))?;
+        info!("Serving the streaming overlay on http://{}/", address);
+        Ok(Self {
+            latest,
+            _server: server,
+        })
+    }
+
+    /// Replaces the served text and JSON with those of `snapshot`.
+    pub fn update(&self, snapshot: &MetricsSnapshot) {
+        let text = format_overlay(snapshot);
+        let json = serde_json::to_string(snapshot).unwrap_or_default();
+        if let Ok(mut latest) = self.latest.lock() {
+            *latest = Latest { text, json };
+        }
+    }
+}
@@ -0,0 +1,316 @@
+//! # Pinned Series Module
+//!
+//! This module tracks the individual series a user pinned to the Overview tab (a single
+//! core, one GPU's temperature, one interface, ...). Values are read from the shared
+//! `MetricsSnapshot`, so the Overview shows the same numbers the exporters report.
+//!
+//! Series are identified by stable ids such as `cpu.core.3`, `gpu.0.temperature` or
+//! `net.eth0.rx` (`derived.<name>` for derived metrics), which are stored in
+//! `AppSettings::pinned_series`.
+
+use crate::history::{History, HistoryLimits};
+use crate::snapshot::MetricsSnapshot;
+use serde::{Deserialize, Serialize};
+
+/// A series that can be pinned.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SeriesInfo {
+    pub id: String,
+    pub label: String,
+}
+
+/// Latest value of one series.
+struct SeriesValue {
+    label: String,
+    value: f32,
+    /// Formatted value with unit.
+    display: String,
+    /// Fixed chart maximum (e.g. 100 for percentages); `None` scales to the history.
+    scale: Option<f32>,
+}
+
+/// Data for one pinned series, ready for display.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PinnedSeriesData {
+    pub id: String,
+    pub label: String,
+    /// Formatted latest value, or "N/A" if the series is currently missing.
+    pub value_str: String,
+    pub history: History,
+    pub scale: f32,
+}
+
+struct PinnedEntry {
+    id: String,
+    label: String,
+    value_str: String,
+    history: History,
+    scale: Option<f32>,
+}
+
+fn format_rate(bytes_per_sec: f64) -> String {
+    if bytes_per_sec > 1024.0 * 1024.0 {
+        format!("{:.1} MB/s", bytes_per_sec / 1024.0 / 1024.0)
+    } else {
+        format!("{:.0} KB/s", bytes_per_sec / 1024.0)
+    }
+}
+
+fn percent(label: String, value: f32) -> SeriesValue {
+    SeriesValue {
+        label,
+        value,
+        display: format!("{:.1}%", value),
+        scale: Some(100.0),
+    }
+}
+
+fn temperature(label: String, value: f32) -> SeriesValue {
+    SeriesValue {
+        label,
+        value,
+        display: format!("{:.1} °C", value),
+        scale: Some(100.0),
+    }
+}
+
+/// Every series available in `snapshot`, keyed by id.
+fn series(snapshot: &MetricsSnapshot) -> Vec<(String, SeriesValue)> {
+    let mut all = vec![
+        (
+            "cpu".to_string(),
+            percent("CPU".to_string(), snapshot.cpu.usage_percent),
+        ),
+        (
+            "memory".to_string(),
+            SeriesValue {
+                label: "Memory".to_string(),
+                value: snapshot.memory.used_gb / snapshot.memory.total_gb.max(f32::EPSILON) * 100.0,
+                display: format!(
+                    "{:.1} / {:.1} GB",
+                    snapshot.memory.used_gb, snapshot.memory.total_gb
+                ),
+                scale: Some(100.0),
+            },
+        ),
+    ];
+    if snapshot.memory.swap_total_gb > 0.0 {
+        all.push((
+            "swap".to_string(),
+            SeriesValue {
+                label: "Swap".to_string(),
+                value: snapshot.memory.swap_used_gb / snapshot.memory.swap_total_gb * 100.0,
+                display: format!(
+                    "{:.1} / {:.1} GB",
+                    snapshot.memory.swap_used_gb, snapshot.memory.swap_total_gb
+                ),
+                scale: Some(100.0),
+            },
+        ));
+    }
+    for (i, usage) in snapshot.cpu.cores.iter().enumerate() {
+        all.push((
+            format!("cpu.core.{}", i),
+            percent(format!("Core {}", i), *usage),
+        ));
+    }
+    for (i, gpu) in snapshot.gpus.iter().enumerate() {
+        all.push((
+            format!("gpu.{}.util", i),
+            percent(format!("{} Usage", gpu.name), gpu.util_percent),
+        ));
+        all.push((
+            format!("gpu.{}.memory", i),
+            SeriesValue {
+                label: format!("{} Memory", gpu.name),
+                value: gpu.mem_used_mb / gpu.mem_total_mb.max(f32::EPSILON) * 100.0,
+                display: format!("{:.0} / {:.0} MB", gpu.mem_used_mb, gpu.mem_total_mb),
+                scale: Some(100.0),
+            },
+        ));
+        if let Some(t) = gpu.temperature {
+            all.push((
+                format!("gpu.{}.temperature", i),
+                temperature(format!("{} Temperature", gpu.name), t),
+            ));
+        }
+    }
+    for net in &snapshot.networks {
+        for (direction, name, rate) in [
+            ("rx", "Download", net.rx_bytes_per_sec),
+            ("tx", "Upload", net.tx_bytes_per_sec),
+        ] {
+            all.push((
+                format!("net.{}.{}", net.interface, direction),
+                SeriesValue {
+                    label: format!("{} {}", net.interface, name),
+                    value: rate as f32,
+                    display: format_rate(rate),
+                    scale: None,
+                },
+            ));
+        }
+    }
+    for disk in &snapshot.disks {
+        let used = disk.total_bytes.saturating_sub(disk.available_bytes);
+        let value = if disk.total_bytes > 0 {
+            used as f32 / disk.total_bytes as f32 * 100.0
+        } else {
+            0.0
+        };
+        all.push((
+            format!("disk.{}.used", disk.mount_point),
+            percent(format!("{} Used", disk.mount_point), value),
+        ));
+    }
+    for fan in &snapshot.fans {
+        all.push((
+            format!("fan.{}.rpm", fan.label),
+            SeriesValue {
+                label: fan.label.clone(),
+                value: fan.rpm as f32,
+                display: format!("{} RPM", fan.rpm),
+                scale: None,
+            },
+        ));
+        if let Some(t) = fan.temperature {
+            all.push((
+                format!("fan.{}.temperature", fan.label),
+                temperature(format!("{} Temperature", fan.label), t),
+            ));
+        }
+    }
+    for metric in &snapshot.derived {
+        all.push((
+            format!("derived.{}", metric.name),
+            SeriesValue {
+                label: metric.name.clone(),
+                value: metric.value,
+                display: crate::custommetrics::format_value(metric.value, &metric.unit),
+                scale: None,
+            },
+        ));
+    }
+    all
+}
+
+/// Every series in `snapshot` with its current value, for consumers that record series
+/// by id (see `longterm.rs`).
+pub fn snapshot_series(snapshot: &MetricsSnapshot) -> Vec<(SeriesInfo, f32)> {
+    series(snapshot)
+        .into_iter()
+        .map(|(id, v)| (SeriesInfo { id, label: v.label }, v.value))
+        .collect()
+}
+
+/// Keeps the history of each pinned series.
+pub struct PinTracker {
+    entries: Vec<PinnedEntry>,
+    /// Series found in the last snapshot, offered for pinning.
+    available: Vec<SeriesInfo>,
+}
+
+impl PinTracker {
+    pub fn new(ids: &[String], limits: HistoryLimits) -> Self {
+        let mut tracker = Self {
+            entries: Vec::new(),
+            available: Vec::new(),
+        };
+        for id in ids {
+            tracker.pin(id, limits);
+        }
+        tracker
+    }
+
+    /// Ids of the pinned series, in display order.
+    pub fn pinned_ids(&self) -> Vec<String> {
+        self.entries.iter().map(|e| e.id.clone()).collect()
+    }
+
+    /// Adds `id` to the pinned series; does nothing if it is already pinned.
+    pub fn pin(&mut self, id: &str, limits: HistoryLimits) {
+        if self.entries.iter().any(|e| e.id == id) {
+            return;
+        }
+        let label = self
+            .available
+            .iter()
+            .find(|s| s.id == id)
+            .map(|s| s.label.clone())
+            .unwrap_or_else(|| id.to_string());
+        self.entries.push(PinnedEntry {
+            id: id.to_string(),
+            label,
+            value_str: "N/A".to_string(),
+            history: History::with_limits(limits),
+            scale: None,
+        });
+    }
+
+    pub fn unpin(&mut self, id: &str) {
+        self.entries.retain(|e| e.id != id);
+    }
+
+    /// Series that can be pinned (not pinned yet), as of the last update.
+    pub fn available(&self) -> Vec<SeriesInfo> {
+        self.available
+            .iter()
+            .filter(|s| !self.entries.iter().any(|e| e.id == s.id))
+            .cloned()
+            .collect()
+    }
+
+    /// Appends the values from `snapshot`. Missing series (e.g. an unplugged
+    /// interface) are recorded as 0.
+    pub fn update(&mut self, snapshot: &MetricsSnapshot) {
+        let all = series(snapshot);
+        self.available = all
+            .iter()
+            .map(|(id, v)| SeriesInfo {
+                id: id.clone(),
+                label: v.label.clone(),
+            })
+            .collect();
+
+        for entry in &mut self.entries {
+            let value = match all.iter().find(|(id, _)| *id == entry.id) {
+                Some((_, v)) => {
+                    entry.label = v.label.clone();
+                    entry.value_str = v.display.clone();
+                    entry.scale = v.scale;
+                    v.value
+                }
+                None => {
+                    entry.value_str = "N/A".to_string();
+                    0.0
+                }
+            };
+            entry.history.push(value);
+        }
+    }
+
+    /// Applies new `limits` to every history buffer.
+    pub fn resize_history(&mut self, limits: HistoryLimits) {
+        for entry in &mut self.entries {
+            entry.history.set_limits(limits);
+        }
+    }
+
+    /// Number of history buffers, for the monitor's sample budget.
+    pub fn history_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn get_data(&self) -> Vec<PinnedSeriesData> {
+        self.entries
+            .iter()
+            .map(|e| PinnedSeriesData {
+                id: e.id.clone(),
+                label: e.label.clone(),
+                value_str: e.value_str.clone(),
+                history: e.history.clone(),
+                scale: e.scale.unwrap_or_else(|| e.history.max_value().max(1.0)),
+            })
+            .collect()
+    }
+}
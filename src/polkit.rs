@@ -0,0 +1,250 @@
+//! # Polkit Policy Module
+//!
+//! This module implements `gjallarhorn install-policy`, which installs a polkit action
+//! for the privileged worker. Without one, `pkexec` shows its generic "run a program as
+//! another user" dialog and asks for the password on every launch; with it, the dialog
+//! explains what the worker is for and the authorization is kept for a few minutes
+//! (`auth_admin_keep`). The action is tied to the path of the binary that installs it,
+//! so `make install` runs the subcommand with the installed binary.
+//!
+//! Each privileged helper has its own action, selected by `pkexec` through the helper's
+//! flag (the `exec.argv1` annotation): the read-only worker keeps its authorization for a
+//! few minutes, while the helpers that change the machine (fan speed, GPU power limit,
+//! process scheduling, suspend or power off) ask every time. `main` therefore only runs
+//! a helper whose flag is the first argument, followed by exactly its own arguments, so
+//! no helper can run under another one's action.
+//!
+//! `--passwordless` also installs a polkit rule that lets members of the usual admin
+//! groups (`wheel`, `sudo`, `admin`) in an active local session start the read-only
+//! worker without a prompt. The other helpers keep prompting.
+//! `--uninstall` removes both files.
+//!
+//! The actions run the binary as root, so they are only installed for a binary that only
+//! root can replace: it and every directory above it must belong to root (e.g. after
+//! `sudo make install`), not to the user who built it (e.g. `~/.cargo/bin`).
+
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+
+/// Action id of the read-only worker, matched by the rule file.
+pub const ACTION_ID: &str = "com.gjallarhorn.worker";
+const POLICY_PATH: &str = "/usr/share/polkit-1/actions/com.gjallarhorn.worker.policy";
+const RULES_PATH: &str = "/etc/polkit-1/rules.d/50-gjallarhorn.rules";
+
+/// A privileged helper: action id, helper flag, description, message and whether the
+/// authorization is kept for a few minutes.
+struct HelperAction {
+    id: &'static str,
+    flag: &'static str,
+    description: &'static str,
+    message: &'static str,
+    keep: bool,
+}
+
+const ACTIONS: &[HelperAction] = &[
+    HelperAction {
+        id: ACTION_ID,
+        flag: "--privileged-worker",
+        description: "Read hardware health data",
+        message: "Gjallarhorn needs administrator rights to read disk health (SMART), the kernel log and network namespaces.",
+        keep: true,
+    },
+    HelperAction {
+        id: "com.gjallarhorn.wifi-scan",
+        flag: "--wifi-scan",
+        description: "Scan for Wi-Fi networks",
+        message: "Gjallarhorn needs administrator rights to scan for Wi-Fi networks.",
+        keep: true,
+    },
+    HelperAction {
+        id: "com.gjallarhorn.set-fan-pwm",
+        flag: "--set-fan-pwm",
+        description: "Change fan speed",
+        message: "Gjallarhorn needs administrator rights to change the speed of a fan.",
+        keep: false,
+    },
+    HelperAction {
+        id: "com.gjallarhorn.set-gpu-power-limit",
+        flag: "--set-gpu-power-limit",
+        description: "Change GPU power limit",
+        message: "Gjallarhorn needs administrator rights to change the power limit of a GPU.",
+        keep: false,
+    },
+    HelperAction {
+        id: "com.gjallarhorn.set-process-scheduling",
+        flag: "--set-process-scheduling",
+        description: "Change process priority and CPU affinity",
+        message: "Gjallarhorn needs administrator rights to change the priority, scheduling class or CPU affinity of a process.",
+        keep: false,
+    },
+    HelperAction {
+        id: "com.gjallarhorn.power-action",
+        flag: "--power-action",
+        description: "Suspend or power off the computer",
+        message: "Gjallarhorn needs administrator rights to suspend or power off the computer after it was idle.",
+        keep: false,
+    },
+];
+
+/// Escapes text for use in an XML attribute or element.
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// The action definitions for running the helpers of `exe` through `pkexec`.
+pub fn policy_xml(exe: &Path) -> String {
+    let exe = xml_escape(&exe.to_string_lossy());
+    let actions: String = ACTIONS
+        .iter()
+        .map(|action| {
+            format!(
+                r#"  <action id="{id}">
+    <description>{description}</description>
+    <message>{message}</message>
+    <icon_name>utilities-system-monitor</icon_name>
+    <defaults>
+      <allow_any>auth_admin</allow_any>
+      <allow_inactive>auth_admin</allow_inactive>
+      <allow_active>{active}</allow_active>
+    </defaults>
+    <annotate key="org.freedesktop.policykit.exec.path">{exe}</annotate>
+    <annotate key="org.freedesktop.policykit.exec.argv1">{flag}</annotate>
+  </action>
+"#,
+                id = action.id,
+                description = xml_escape(action.description),
+                message = xml_escape(action.message),
+                active = if action.keep {
+                    "auth_admin_keep"
+                } else {
+                    "auth_admin"
+                },
+                exe = exe,
+                flag = action.flag,
+            )
+        })
+        .collect();
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE policyconfig PUBLIC
+ "-//freedesktop//DTD PolicyKit Policy Configuration 1.0//EN"
+ "http://www.freedesktop.org/standards/PolicyKit/1/policyconfig.dtd">
+<policyconfig>
+  <vendor>Gjallarhorn</vendor>
+  <vendor_url>https://github.com/arunkumar-mourougappane/gjallarhorn-rs</vendor_url>
+{actions}</policyconfig>
+"#
+    )
+}
+
+/// The rule that skips the prompt of the read-only worker for admin group members.
+pub fn passwordless_rule() -> String {
+    format!(
+        r#"// Installed by `gjallarhorn install-policy --passwordless`
+polkit.addRule(function(action, subject) {{
+    if (action.id == "{}" && subject.local && subject.active &&
+        (subject.isInGroup("wheel") || subject.isInGroup("sudo") || subject.isInGroup("admin"))) {{
+        return polkit.Result.YES;
+    }}
+}});
+"#,
+        ACTION_ID
+    )
+}
+
+fn write(path: &str, content: &str) -> Result<(), String> {
+    if let Some(dir) = Path::new(path).parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    }
+    std::fs::write(path, content).map_err(|e| format!("{}: {}", path, e))
+}
+
+/// Removes `path`, treating a missing file as removed.
+fn remove(path: &str) -> Result<bool, String> {
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(format!("{}: {}", path, e)),
+    }
+}
+
+/// Checks that only root can replace `exe`: it and every directory above it must be owned
+/// by root and not writable by group or others. Sticky directories (e.g. `/tmp`) are
+/// allowed, since only the owner of an entry can replace it there.
+fn check_root_only(exe: &Path) -> Result<(), String> {
+    for (i, path) in exe.ancestors().enumerate() {
+        let metadata = std::fs::metadata(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let sticky = i > 0 && metadata.mode() & 0o1000 != 0;
+        let problem = if metadata.uid() != 0 {
+            format!("is owned by uid {}", metadata.uid())
+        } else if metadata.mode() & 0o022 != 0 && !sticky {
+            "is writable by other users".to_string()
+        } else {
+            continue;
+        };
+        return Err(format!(
+            "{} {}, so the policy would let a non-root user run code as root. Install gjallarhorn to a root-owned prefix first (e.g. `sudo make install` for /usr/local/bin) and run install-policy from there.",
+            path.display(),
+            problem
+        ));
+    }
+    Ok(())
+}
+
+/// Runs `gjallarhorn install-policy [--passwordless] [--uninstall]` and returns what
+/// was done.
+pub fn install_policy(args: &[String]) -> Result<String, String> {
+    // Only root can write the polkit directories
+    if unsafe { libc::geteuid() } != 0 {
+        return Err("Run as root: sudo gjallarhorn install-policy".to_string());
+    }
+
+    if args.iter().any(|a| a == "--uninstall") {
+        let mut removed = Vec::new();
+        for path in [POLICY_PATH, RULES_PATH] {
+            if remove(path)? {
+                removed.push(path);
+            }
+        }
+        return Ok(if removed.is_empty() {
+            "No policy installed".to_string()
+        } else {
+            format!("Removed {}", removed.join(" and "))
+        });
+    }
+
+    let exe = crate::worker::worker_executable()?;
+    check_root_only(&exe)?;
+    write(POLICY_PATH, &policy_xml(&exe))?;
+    let mut done = format!("Installed {} for {}", POLICY_PATH, exe.display());
+    if args.iter().any(|a| a == "--passwordless") {
+        write(RULES_PATH, &passwordless_rule())?;
+        done.push_str(&format!("\nInstalled {}", RULES_PATH));
+    } else if remove(RULES_PATH)? {
+        done.push_str(&format!("\nRemoved {}", RULES_PATH));
+    }
+    Ok(done)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+
+    #[test]
+    fn binaries_in_user_writable_directories_are_refused() {
+        let dir = std::env::temp_dir().join(format!("gjallarhorn-polkit-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let exe = dir.join("gjallarhorn");
+        std::fs::write(&exe, "").unwrap();
+        std::fs::set_permissions(&dir, std::fs::Permissions::from_mode(0o777)).unwrap();
+
+        let result = check_root_only(&exe);
+        std::fs::remove_dir_all(&dir).unwrap();
+        let error = result.unwrap_err();
+        assert!(error.contains("gjallarhorn-polkit-"), "{}", error);
+    }
+}
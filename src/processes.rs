@@ -0,0 +1,647 @@
+//! # Process List Module
+//!
+//! This module turns the process table that `SystemMonitor` already refreshes every tick
+//! (for the watched processes, application profiles and limits) into a sortable list of
+//! the top consumers, so a spike on the CPU charts can be traced to a process.
+//!
+//! CPU usage is per core, like `top`: a process keeping two cores busy shows 200%.
+//! Threads (Linux tasks sysinfo lists alongside their process) are left out; their usage
+//! is included in the process's.
+//!
+//! Each process is attributed to the systemd unit or container it runs in, from its
+//! cgroup (`/proc/<pid>/cgroup`), so the list can be narrowed to e.g. `nginx.service`
+//! or one Docker container. A process keeps its cgroup for its lifetime in practice, so
+//! `ProcessTracker` reads it once per PID.
+//!
+//! One process can be selected for the detail pane: its command line, thread and open
+//! file counts are read from `/proc/<pid>` on every refresh, and its CPU and memory
+//! usage are kept in a history from the moment it was selected.
+//!
+//! Processes in uninterruptible sleep (D, usually waiting on storage or NFS) and zombies
+//! (Z, exited but not reaped by their parent) are flagged, and their number is counted
+//! on every refresh: D-state tasks that don't go away are a classic sign of a stalled
+//! disk, to be read next to the disk I/O charts. Threads count towards the D-state
+//! total, like the "b" column of `vmstat`; they are not listed.
+//!
+//! The listed rows also show each process's nice value, scheduling policy and CPU
+//! affinity (`/proc/<pid>/stat` and `status`). Changing them for processes of other
+//! users needs root, so it is done by the privileged helper (see
+//! `worker::set_process_scheduling`).
+
+use crate::cgroups;
+use crate::history::{History, HistoryLimits};
+use crate::monitor::SysPaths;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use sysinfo::{Pid, Process, ProcessStatus, System};
+
+/// Rows shown on the Processes tab.
+pub const MAX_ROWS: usize = 100;
+
+/// One process of the list.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct ProcessData {
+    pub pid: u32,
+    pub name: String,
+    /// CPU usage since the previous refresh, in percent of one core.
+    pub cpu_percent: f32,
+    /// Resident set size in bytes.
+    pub rss_bytes: u64,
+    /// Scheduler state, e.g. "Runnable", "Sleeping", "Disk Sleep" or "Zombie".
+    pub state: String,
+    /// In uninterruptible sleep (D) or a zombie (Z).
+    pub stuck: bool,
+    /// Only read for the rows `SystemMonitor::get_process_data` returns.
+    pub sched: Option<SchedInfo>,
+    /// cgroup v2 path, e.g. "/system.slice/nginx.service"; empty if unknown.
+    pub cgroup: String,
+    /// Container or systemd unit of `cgroup` (see `cgroup_owner`).
+    pub owner: String,
+}
+
+/// Linux scheduling policy (`SCHED_*`).
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SchedPolicy {
+    Other,
+    Fifo,
+    RoundRobin,
+    Batch,
+    Idle,
+    Deadline,
+}
+
+impl SchedPolicy {
+    /// The policies `set_process_scheduling` accepts, in the order of the detail pane's
+    /// class selector. Real-time and deadline scheduling can starve the machine and need
+    /// more parameters; they are left to `chrt`.
+    pub const EDITABLE: [SchedPolicy; 3] =
+        [SchedPolicy::Other, SchedPolicy::Batch, SchedPolicy::Idle];
+
+    /// The policy of a `SCHED_*` value, as in field 41 of `/proc/<pid>/stat`.
+    pub fn from_raw(policy: u32) -> Option<Self> {
+        match policy {
+            0 => Some(SchedPolicy::Other),
+            1 => Some(SchedPolicy::Fifo),
+            2 => Some(SchedPolicy::RoundRobin),
+            3 => Some(SchedPolicy::Batch),
+            5 => Some(SchedPolicy::Idle),
+            6 => Some(SchedPolicy::Deadline),
+            _ => None,
+        }
+    }
+
+    /// The `SCHED_*` value.
+    pub fn raw(self) -> i32 {
+        match self {
+            SchedPolicy::Other => 0,
+            SchedPolicy::Fifo => 1,
+            SchedPolicy::RoundRobin => 2,
+            SchedPolicy::Batch => 3,
+            SchedPolicy::Idle => 5,
+            SchedPolicy::Deadline => 6,
+        }
+    }
+
+    /// Name as `chrt` prints it, e.g. "other" or "batch".
+    pub fn name(self) -> &'static str {
+        match self {
+            SchedPolicy::Other => "other",
+            SchedPolicy::Fifo => "fifo",
+            SchedPolicy::RoundRobin => "rr",
+            SchedPolicy::Batch => "batch",
+            SchedPolicy::Idle => "idle",
+            SchedPolicy::Deadline => "deadline",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        [
+            SchedPolicy::Other,
+            SchedPolicy::Fifo,
+            SchedPolicy::RoundRobin,
+            SchedPolicy::Batch,
+            SchedPolicy::Idle,
+            SchedPolicy::Deadline,
+        ]
+        .into_iter()
+        .find(|p| p.name() == name)
+    }
+}
+
+/// Scheduling settings of a process (of its main thread).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SchedInfo {
+    /// -20 (highest priority) to 19.
+    pub nice: i32,
+    /// `None` for a policy this build doesn't know.
+    pub policy: Option<SchedPolicy>,
+    /// CPUs the process may run on, e.g. "0-3,8".
+    pub affinity: String,
+}
+
+/// Nice value and raw policy from the contents of `/proc/<pid>/stat`. The command name
+/// in parentheses may contain spaces, so the fields are counted after its last ')'.
+pub fn parse_stat_sched(stat: &str) -> Option<(i32, u32)> {
+    let fields: Vec<&str> = stat
+        .get(stat.rfind(')')? + 1..)?
+        .split_whitespace()
+        .collect();
+    // The first field after the name is field 3 (state); nice is 19, policy 41
+    let nice = fields.get(16)?.parse().ok()?;
+    let policy = fields.get(38)?.parse().ok()?;
+    Some((nice, policy))
+}
+
+/// The CPUs of a list like "0-3,8" (the format of `Cpus_allowed_list` and `taskset -c`).
+pub fn parse_cpu_list(list: &str) -> Result<Vec<usize>, String> {
+    let mut cpus = Vec::new();
+    for part in list.split(',').map(str::trim) {
+        let invalid = || format!("Invalid CPU list: {}", list);
+        let (first, last) = match part.split_once('-') {
+            Some((first, last)) => (first.parse(), last.parse()),
+            None => (part.parse(), part.parse()),
+        };
+        let (first, last): (usize, usize) =
+            (first.map_err(|_| invalid())?, last.map_err(|_| invalid())?);
+        if first > last {
+            return Err(invalid());
+        }
+        cpus.extend(first..=last);
+    }
+    cpus.sort_unstable();
+    cpus.dedup();
+    Ok(cpus)
+}
+
+/// Reads the scheduling settings of process `pid` below the `/proc` root `proc_root`.
+pub fn read_sched(proc_root: &Path, pid: u32) -> Option<SchedInfo> {
+    let dir = proc_root.join(pid.to_string());
+    let (nice, policy) = parse_stat_sched(&std::fs::read_to_string(dir.join("stat")).ok()?)?;
+    let status = std::fs::read_to_string(dir.join("status")).ok()?;
+    Some(SchedInfo {
+        nice,
+        policy: SchedPolicy::from_raw(policy),
+        affinity: status_field(&status, "Cpus_allowed_list")?.to_string(),
+    })
+}
+
+/// Details of a process that are only read for the selected one.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ProcessInfo {
+    /// Arguments separated by spaces; empty for kernel threads.
+    pub cmdline: String,
+    pub threads: Option<u32>,
+    /// Open file descriptors; `None` if `/proc/<pid>/fd` isn't readable (processes of
+    /// other users).
+    pub open_files: Option<usize>,
+}
+
+/// The selected process and its usage since it was selected.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProcessDetail {
+    /// The process as of the last refresh it was seen in.
+    pub process: ProcessData,
+    pub info: ProcessInfo,
+    /// `false` once the process exited; the histories stop there.
+    pub running: bool,
+    /// CPU usage in percent of one core.
+    pub cpu_history: History,
+    /// Resident set size in MB.
+    pub mem_history: History,
+}
+
+/// Number of D-state tasks and zombie processes, now and over the chart window.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProcessStateData {
+    /// Tasks (threads included) in uninterruptible sleep.
+    pub blocked: usize,
+    pub zombies: usize,
+    pub blocked_history: History,
+    pub zombie_history: History,
+}
+
+/// Sort order of the list, as the column index of the Processes tab.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProcessSort {
+    #[default]
+    Cpu,
+    Memory,
+    Pid,
+    Name,
+}
+
+impl ProcessSort {
+    /// Sort order from the column index (0 = CPU, 1 = memory, 2 = PID, 3 = name).
+    pub fn from_index(index: i32) -> Self {
+        match index {
+            1 => ProcessSort::Memory,
+            2 => ProcessSort::Pid,
+            3 => ProcessSort::Name,
+            _ => ProcessSort::Cpu,
+        }
+    }
+}
+
+/// The unified hierarchy path from the contents of `/proc/<pid>/cgroup` (its `0::` line).
+pub fn parse_cgroup_path(cgroup: &str) -> Option<&str> {
+    cgroup.lines().find_map(|l| l.strip_prefix("0::"))
+}
+
+/// Whether the cgroup directory `name` is a Docker or Podman container or an nspawn
+/// machine. Podman's `libpod-conmon-*` scopes hold the monitor, not the container.
+fn is_container_dir(name: &str) -> bool {
+    let scope = |prefix: &str| {
+        name.strip_prefix(prefix)
+            .is_some_and(|rest| rest.ends_with(".scope"))
+    };
+    (scope("docker-") || scope("libpod-") || scope("machine-")) && !scope("libpod-conmon-")
+}
+
+/// The container or systemd unit owning the cgroup at `path`, for display and filtering:
+/// the innermost container ("docker-3f2a9c1b7d4e", "machine-web.scope"), since a
+/// container runs its own units, else the innermost service or scope ("nginx.service",
+/// "session-2.scope"). Empty for the root cgroup (kernel threads).
+pub fn cgroup_owner(path: &str) -> String {
+    let dirs: Vec<&str> = path.split('/').filter(|d| !d.is_empty()).collect();
+    if let Some(container) = dirs.iter().rev().find(|d| is_container_dir(d)) {
+        return cgroups::short_label(container);
+    }
+    // Docker's cgroupfs driver: "/docker/<id>"
+    if let Some(pair) = dirs.windows(2).rev().find(|pair| pair[0] == "docker") {
+        return cgroups::short_label(&format!("docker-{}.scope", pair[1]))
+            .trim_end_matches(".scope")
+            .to_string();
+    }
+    dirs.iter()
+        .rev()
+        .find(|d| d.ends_with(".service") || d.ends_with(".scope"))
+        .map(|d| d.to_string())
+        .unwrap_or_default()
+}
+
+/// The value of `field` in the contents of `/proc/<pid>/status`, e.g. "12" for "Threads".
+pub fn status_field<'a>(status: &'a str, field: &str) -> Option<&'a str> {
+    status.lines().find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        (name == field).then(|| value.trim())
+    })
+}
+
+/// `/proc/<pid>/cmdline` (NUL-terminated arguments) as one line.
+pub fn parse_cmdline(cmdline: &[u8]) -> String {
+    cmdline
+        .split(|&b| b == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(String::from_utf8_lossy)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Reads the details of process `pid` below the `/proc` root `proc_root`.
+pub fn read_info(proc_root: &Path, pid: u32) -> ProcessInfo {
+    let dir = proc_root.join(pid.to_string());
+    ProcessInfo {
+        cmdline: std::fs::read(dir.join("cmdline"))
+            .map(|c| parse_cmdline(&c))
+            .unwrap_or_default(),
+        threads: std::fs::read_to_string(dir.join("status"))
+            .ok()
+            .and_then(|s| status_field(&s, "Threads")?.parse().ok()),
+        open_files: std::fs::read_dir(dir.join("fd"))
+            .ok()
+            .map(|entries| entries.count()),
+    }
+}
+
+/// Whether `process` matches the Processes tab filter: a case-insensitive substring of
+/// its name, owner or cgroup path. An empty filter matches everything.
+pub fn matches_filter(process: &ProcessData, filter: &str) -> bool {
+    let filter = filter.trim().to_lowercase();
+    filter.is_empty()
+        || [&process.name, &process.owner, &process.cgroup]
+            .iter()
+            .any(|field| field.to_lowercase().contains(&filter))
+}
+
+/// Lists the processes of the `System` that `SystemMonitor` refreshes, with their cgroup,
+/// and follows the selected process.
+pub struct ProcessTracker {
+    proc_root: PathBuf,
+    /// cgroup path per PID, read when the PID first shows up.
+    cgroups: HashMap<u32, String>,
+    selected: Option<ProcessDetail>,
+    blocked: usize,
+    zombies: usize,
+    blocked_history: History,
+    zombie_history: History,
+}
+
+impl ProcessTracker {
+    /// Reads `/proc/<pid>` below the `/proc` root of `paths`.
+    pub fn new(paths: &SysPaths, limits: HistoryLimits) -> Self {
+        Self {
+            proc_root: paths.proc.clone(),
+            cgroups: HashMap::new(),
+            selected: None,
+            blocked: 0,
+            zombies: 0,
+            blocked_history: History::with_limits(limits),
+            zombie_history: History::with_limits(limits),
+        }
+    }
+
+    /// Looks up the cgroup of processes new since the last refresh of `system`, forgets
+    /// the ones that exited, counts D-state and zombie tasks and samples the selected
+    /// process.
+    pub fn refresh(&mut self, system: &System) {
+        let processes = system.processes();
+        self.cgroups
+            .retain(|pid, _| processes.contains_key(&sysinfo::Pid::from_u32(*pid)));
+        self.blocked = 0;
+        self.zombies = 0;
+        for (pid, process) in processes {
+            let thread = process.thread_kind().is_some();
+            match process.status() {
+                ProcessStatus::UninterruptibleDiskSleep => self.blocked += 1,
+                ProcessStatus::Zombie if !thread => self.zombies += 1,
+                _ => {}
+            }
+            if thread {
+                continue;
+            }
+            let proc_root = &self.proc_root;
+            self.cgroups.entry(pid.as_u32()).or_insert_with(|| {
+                std::fs::read_to_string(proc_root.join(pid.to_string()).join("cgroup"))
+                    .ok()
+                    .and_then(|c| parse_cgroup_path(&c).map(str::to_string))
+                    .unwrap_or_default()
+            });
+        }
+        self.blocked_history.push(self.blocked as f32);
+        self.zombie_history.push(self.zombies as f32);
+        self.sample_selected(system);
+    }
+
+    /// Reads the scheduling settings of `processes`, e.g. the rows of the Processes tab.
+    pub fn read_sched(&self, processes: &mut [ProcessData]) {
+        for process in processes {
+            process.sched = read_sched(&self.proc_root, process.pid);
+        }
+    }
+
+    /// D-state and zombie counts as of the last refresh, with their history.
+    pub fn state_counts(&self) -> ProcessStateData {
+        ProcessStateData {
+            blocked: self.blocked,
+            zombies: self.zombies,
+            blocked_history: self.blocked_history.clone(),
+            zombie_history: self.zombie_history.clone(),
+        }
+    }
+
+    /// Selects process `pid` for the detail pane, starting its history with `limits`;
+    /// `None` closes the pane.
+    pub fn select(&mut self, pid: Option<u32>, system: &System, limits: HistoryLimits) {
+        self.selected = pid.and_then(|pid| {
+            let process = system.process(Pid::from_u32(pid))?;
+            Some(ProcessDetail {
+                process: self.process_data(Pid::from_u32(pid), process),
+                info: ProcessInfo::default(),
+                running: true,
+                cpu_history: History::with_limits(limits),
+                mem_history: History::with_limits(limits),
+            })
+        });
+        self.sample_selected(system);
+    }
+
+    /// The selected process, if any.
+    pub fn selected(&self) -> Option<&ProcessDetail> {
+        self.selected.as_ref()
+    }
+
+    fn sample_selected(&mut self, system: &System) {
+        let Some(pid) = self.selected.as_ref().map(|d| d.process.pid) else {
+            return;
+        };
+        let process = system
+            .process(Pid::from_u32(pid))
+            .map(|p| self.process_data(Pid::from_u32(pid), p));
+        let info = read_info(&self.proc_root, pid);
+        let sched = read_sched(&self.proc_root, pid);
+        let Some(detail) = &mut self.selected else {
+            return;
+        };
+        // A PID reused by a new process (a different name) is not the one selected
+        match process.filter(|p| detail.running && p.name == detail.process.name) {
+            Some(mut process) => {
+                process.sched = sched;
+                detail.cpu_history.push(process.cpu_percent);
+                detail
+                    .mem_history
+                    .push(process.rss_bytes as f32 / (1024.0 * 1024.0));
+                detail.process = process;
+                detail.info = info;
+            }
+            None => detail.running = false,
+        }
+    }
+
+    /// Applies new `limits` to the state count and selected process histories.
+    pub fn resize_history(&mut self, limits: HistoryLimits) {
+        self.blocked_history.set_limits(limits);
+        self.zombie_history.set_limits(limits);
+        if let Some(detail) = &mut self.selected {
+            detail.cpu_history.set_limits(limits);
+            detail.mem_history.set_limits(limits);
+        }
+    }
+
+    /// Number of history buffers, for the monitor's sample budget.
+    pub fn history_count(&self) -> usize {
+        if self.selected.is_some() {
+            4
+        } else {
+            2
+        }
+    }
+
+    fn process_data(&self, pid: Pid, process: &Process) -> ProcessData {
+        let cgroup = self.cgroups.get(&pid.as_u32()).cloned().unwrap_or_default();
+        ProcessData {
+            pid: pid.as_u32(),
+            name: process.name().to_string_lossy().into_owned(),
+            cpu_percent: process.cpu_usage(),
+            rss_bytes: process.memory(),
+            state: match process.status() {
+                // sysinfo's "UninterruptibleDiskSleep" doesn't fit the column
+                ProcessStatus::UninterruptibleDiskSleep => "Disk Sleep".to_string(),
+                status => status.to_string(),
+            },
+            stuck: matches!(
+                process.status(),
+                ProcessStatus::UninterruptibleDiskSleep | ProcessStatus::Zombie
+            ),
+            sched: None,
+            owner: cgroup_owner(&cgroup),
+            cgroup,
+        }
+    }
+
+    /// Snapshot of the processes in `system`, as of its last process refresh.
+    pub fn collect(&self, system: &System) -> Vec<ProcessData> {
+        system
+            .processes()
+            .iter()
+            .filter(|(_, p)| p.thread_kind().is_none())
+            .map(|(pid, p)| self.process_data(*pid, p))
+            .collect()
+    }
+}
+
+/// Sorts `processes` by `sort`: usage descending, PID and name ascending.
+pub fn sort(processes: &mut [ProcessData], sort: ProcessSort) {
+    match sort {
+        ProcessSort::Cpu => processes.sort_by(|a, b| {
+            b.cpu_percent
+                .total_cmp(&a.cpu_percent)
+                .then(b.rss_bytes.cmp(&a.rss_bytes))
+        }),
+        ProcessSort::Memory => processes.sort_by_key(|p| Reverse(p.rss_bytes)),
+        ProcessSort::Pid => processes.sort_by_key(|p| p.pid),
+        ProcessSort::Name => processes.sort_by(|a, b| {
+            a.name
+                .to_lowercase()
+                .cmp(&b.name.to_lowercase())
+                .then(a.pid.cmp(&b.pid))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCKER_ID: &str = "3f2a9c1b7d4e5f60718293a4b5c6d7e8f90a1b2c3d4e5f60718293a4b5c6d7e8";
+
+    #[test]
+    fn cgroup_path_is_the_unified_hierarchy_line() {
+        let cgroup = "12:pids:/system.slice/nginx.service\n0::/system.slice/nginx.service\n";
+        assert_eq!(
+            parse_cgroup_path(cgroup),
+            Some("/system.slice/nginx.service")
+        );
+        assert_eq!(parse_cgroup_path("1:name=systemd:/init.scope\n"), None);
+    }
+
+    #[test]
+    fn owner_is_the_innermost_unit() {
+        assert_eq!(cgroup_owner("/system.slice/nginx.service"), "nginx.service");
+        assert_eq!(
+            cgroup_owner("/user.slice/user-1000.slice/session-2.scope"),
+            "session-2.scope"
+        );
+        assert_eq!(
+            cgroup_owner(
+                "/user.slice/user-1000.slice/user@1000.service/app.slice/app-firefox-4711.scope"
+            ),
+            "app-firefox-4711.scope"
+        );
+        assert_eq!(cgroup_owner("/"), "");
+        assert_eq!(cgroup_owner(""), "");
+    }
+
+    #[test]
+    fn owner_prefers_the_container() {
+        assert_eq!(
+            cgroup_owner(&format!("/system.slice/docker-{}.scope", DOCKER_ID)),
+            "docker-3f2a9c1b7d4e"
+        );
+        assert_eq!(
+            cgroup_owner(&format!("/docker/{}", DOCKER_ID)),
+            "docker-3f2a9c1b7d4e"
+        );
+        assert_eq!(
+            cgroup_owner("/machine.slice/machine-web.scope/payload/system.slice/nginx.service"),
+            "machine-web.scope"
+        );
+        assert_eq!(
+            cgroup_owner(&format!(
+                "/user.slice/user-1000.slice/user@1000.service/user.slice/libpod-conmon-{}.scope",
+                DOCKER_ID
+            )),
+            format!("libpod-conmon-{}.scope", DOCKER_ID)
+        );
+    }
+
+    #[test]
+    fn status_fields_are_found_by_name() {
+        let status = "Name:\tnginx\nState:\tS (sleeping)\nThreads:\t4\nCpus_allowed_list:\t0-3\n";
+        assert_eq!(status_field(status, "Threads"), Some("4"));
+        assert_eq!(status_field(status, "State"), Some("S (sleeping)"));
+        assert_eq!(status_field(status, "Thread"), None);
+    }
+
+    #[test]
+    fn cmdline_arguments_are_joined() {
+        assert_eq!(
+            parse_cmdline(b"nginx: worker process\0\0"),
+            "nginx: worker process"
+        );
+        assert_eq!(
+            parse_cmdline(b"/usr/bin/python3\0-m\0http.server\0"),
+            "/usr/bin/python3 -m http.server"
+        );
+        assert_eq!(parse_cmdline(b""), "");
+    }
+
+    #[test]
+    fn stat_fields_are_counted_after_the_name() {
+        let stat = "812 (nginx: worker) S 811 811 811 0 -1 4194624 1153 0 0 0 12 7 0 0 20 5 4 0 \
+                    1837 91238400 1650 18446744073709551615 1 1 0 0 0 0 0 4096 0 0 0 0 17 2 0 3 \
+                    0 0 0 0 0 0 0 0 0 0 0";
+        assert_eq!(parse_stat_sched(stat), Some((5, 3)));
+        assert_eq!(parse_stat_sched("812 (nginx) S 811"), None);
+    }
+
+    #[test]
+    fn cpu_lists_are_expanded() {
+        assert_eq!(parse_cpu_list("0-3,8"), Ok(vec![0, 1, 2, 3, 8]));
+        assert_eq!(parse_cpu_list("2"), Ok(vec![2]));
+        assert_eq!(parse_cpu_list(" 4 , 1-2 ,2"), Ok(vec![1, 2, 4]));
+        for invalid in ["", "3-1", "a", "0-", "1,,2"] {
+            assert!(parse_cpu_list(invalid).is_err(), "{:?}", invalid);
+        }
+    }
+
+    #[test]
+    fn policies_round_trip() {
+        for raw in [0, 1, 2, 3, 5, 6] {
+            let policy = SchedPolicy::from_raw(raw).unwrap();
+            assert_eq!(policy.raw() as u32, raw);
+            assert_eq!(SchedPolicy::from_name(policy.name()), Some(policy));
+        }
+        assert_eq!(SchedPolicy::from_raw(4), None);
+    }
+
+    #[test]
+    fn filter_matches_name_owner_and_path() {
+        let process = ProcessData {
+            pid: 812,
+            name: "nginx".to_string(),
+            cpu_percent: 0.0,
+            rss_bytes: 0,
+            state: "Sleeping".to_string(),
+            stuck: false,
+            sched: None,
+            cgroup: "/system.slice/nginx.service".to_string(),
+            owner: "nginx.service".to_string(),
+        };
+        for filter in ["", "NGINX", "nginx.service", "system.slice", " nginx "] {
+            assert!(matches_filter(&process, filter), "{:?}", filter);
+        }
+        assert!(!matches_filter(&process, "sshd.service"));
+    }
+}
@@ -0,0 +1,98 @@
+//! # Application Profiles Module
+//!
+//! This module switches the Usage view to the tab that matters while a configured
+//! application runs, e.g. the GPU tab during a game or the CPU tab during a compile, and
+//! back to the previous tab once it exits. Profiles are `process=tab` pairs in the
+//! `app_profiles` setting.
+
+use std::collections::{BTreeMap, HashSet};
+
+/// Usage view tabs by index, as accepted in profiles (case-insensitive).
+pub const TAB_NAMES: [&str; 14] = [
+    "CPU",
+    "RAM",
+    "GPU",
+    "Network",
+    "Storage",
+    "Slices",
+    "Fans",
+    "Remote",
+    "Overview",
+    "VMs",
+    "Processes",
+    "Battery",
+    "History",
+    "Sensors",
+];
+
+/// Index of the Usage view tab called `name`.
+pub fn tab_index(name: &str) -> Option<i32> {
+    TAB_NAMES
+        .iter()
+        .position(|t| t.eq_ignore_ascii_case(name.trim()))
+        .map(|i| i as i32)
+}
+
+/// Tracks which profile is active and the tab to return to.
+#[derive(Debug, Default)]
+pub struct ProfileSwitcher {
+    /// Process name → tab index. Entries with an unknown tab name are dropped.
+    profiles: BTreeMap<String, i32>,
+    active: Option<String>,
+    /// Tab shown before the first profile kicked in.
+    saved_tab: Option<i32>,
+}
+
+impl ProfileSwitcher {
+    pub fn new(profiles: &BTreeMap<String, String>) -> Self {
+        let mut switcher = Self::default();
+        switcher.set_profiles(profiles);
+        switcher
+    }
+
+    /// Replaces the profiles. An active profile stays active until its process exits.
+    pub fn set_profiles(&mut self, profiles: &BTreeMap<String, String>) {
+        self.profiles = profiles
+            .iter()
+            .filter_map(|(process, tab)| Some((process.clone(), tab_index(tab)?)))
+            .collect();
+    }
+
+    /// Process names that have a profile.
+    pub fn processes(&self) -> Vec<String> {
+        self.profiles.keys().cloned().collect()
+    }
+
+    /// Name of the process whose profile is active.
+    pub fn active(&self) -> Option<&str> {
+        self.active.as_deref()
+    }
+
+    /// Given the profile processes that are running, returns the tab to switch to, if any.
+    /// `current_tab` is remembered when a profile becomes active and restored once no
+    /// profile process runs anymore.
+    pub fn update(&mut self, running: &HashSet<String>, current_tab: i32) -> Option<i32> {
+        if self.active.as_ref().is_some_and(|p| running.contains(p)) {
+            return None;
+        }
+        // Profiles are checked in name order, so the pick is stable
+        let next = self
+            .profiles
+            .iter()
+            .find(|(process, _)| running.contains(*process))
+            .map(|(process, tab)| (process.clone(), *tab));
+        match (self.active.take(), next) {
+            (None, Some((process, tab))) => {
+                self.saved_tab = Some(current_tab);
+                self.active = Some(process);
+                Some(tab)
+            }
+            (Some(_), Some((process, tab))) => {
+                self.active = Some(process);
+                Some(tab)
+            }
+            (Some(_), None) => self.saved_tab.take(),
+            (None, None) => None,
+        }
+    }
+}
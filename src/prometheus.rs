@@ -0,0 +1,284 @@
+//! # Prometheus Exporter Module
+//!
+//! This module serves the latest `MetricsSnapshot` in the Prometheus text exposition
+//! format on `http://<bind_address>:<port>/metrics`, so the numbers Gjallarhorn shows can
+//! be scraped into Prometheus and graphed in Grafana. It is compiled only with the
+//! `prometheus` cargo feature and started when the `prometheus` settings section enables
+//! it.
+//!
+//! The server (see `http.rs`) is a single background thread; each scrape gets the text
+//! formatted at the last refresh, so it never waits for the monitor. It
+//! binds to localhost by default; set `bind_address` to `0.0.0.0` to scrape from another
+//! machine.
+
+use crate::http::{HttpServer, Response};
+use crate::settings::PrometheusSettings;
+use crate::snapshot::MetricsSnapshot;
+use log::info;
+use std::fmt::Write as _;
+use std::sync::{Arc, Mutex};
+
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Escapes a label value (backslash, double quote and newline).
+fn label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Appends a metric family: its HELP and TYPE lines and one sample per
+/// `(labels, value)`. Families without samples are left out.
+fn family(out: &mut String, name: &str, kind: &str, help: &str, samples: &[(String, f64)]) {
+    if samples.is_empty() {
+        return;
+    }
+    let _ = writeln!(out, "# HELP gjallarhorn_{} {}", name, help);
+    let _ = writeln!(out, "# TYPE gjallarhorn_{} {}", name, kind);
+    for (labels, value) in samples {
+        if labels.is_empty() {
+            let _ = writeln!(out, "gjallarhorn_{} {}", name, value);
+        } else {
+            let _ = writeln!(out, "gjallarhorn_{}{{{}}} {}", name, labels, value);
+        }
+    }
+}
+
+/// Formats `snapshot` in the Prometheus text exposition format.
+pub fn format_metrics(snapshot: &MetricsSnapshot) -> String {
+    let mut out = String::new();
+    let single = |value: f64| vec![(String::new(), value)];
+    let gb = 1024.0 * 1024.0 * 1024.0;
+    let mb = 1024.0 * 1024.0;
+
+    family(
+        &mut out,
+        "uptime_seconds",
+        "gauge",
+        "Time since boot.",
+        &single(snapshot.uptime_secs as f64),
+    );
+    family(
+        &mut out,
+        "cpu_usage_percent",
+        "gauge",
+        "Average CPU usage across all cores.",
+        &single(snapshot.cpu.usage_percent as f64),
+    );
+    let cores: Vec<(String, f64)> = snapshot
+        .cpu
+        .cores
+        .iter()
+        .enumerate()
+        .map(|(i, u)| (format!("core=\"{}\"", i), *u as f64))
+        .collect();
+    family(
+        &mut out,
+        "cpu_core_usage_percent",
+        "gauge",
+        "CPU usage per core.",
+        &cores,
+    );
+    family(
+        &mut out,
+        "memory_used_bytes",
+        "gauge",
+        "Used memory.",
+        &single((snapshot.memory.used_gb as f64 * gb).round()),
+    );
+    family(
+        &mut out,
+        "memory_total_bytes",
+        "gauge",
+        "Total memory.",
+        &single((snapshot.memory.total_gb as f64 * gb).round()),
+    );
+    family(
+        &mut out,
+        "swap_used_bytes",
+        "gauge",
+        "Used swap.",
+        &single((snapshot.memory.swap_used_gb as f64 * gb).round()),
+    );
+    family(
+        &mut out,
+        "swap_total_bytes",
+        "gauge",
+        "Total swap.",
+        &single((snapshot.memory.swap_total_gb as f64 * gb).round()),
+    );
+
+    let gpu_labels = |i: usize, name: &str| format!("gpu=\"{}\",name=\"{}\"", i, label(name));
+    let gpu_samples = |value: &dyn Fn(&crate::snapshot::GpuSnapshot) -> Option<f64>| {
+        snapshot
+            .gpus
+            .iter()
+            .enumerate()
+            .filter_map(|(i, g)| Some((gpu_labels(i, &g.name), value(g)?)))
+            .collect::<Vec<_>>()
+    };
+    family(
+        &mut out,
+        "gpu_utilization_percent",
+        "gauge",
+        "GPU core utilization.",
+        &gpu_samples(&|g| Some(g.util_percent as f64)),
+    );
+    family(
+        &mut out,
+        "gpu_memory_used_bytes",
+        "gauge",
+        "Used GPU memory.",
+        &gpu_samples(&|g| Some((g.mem_used_mb as f64 * mb).round())),
+    );
+    family(
+        &mut out,
+        "gpu_memory_total_bytes",
+        "gauge",
+        "Total GPU memory.",
+        &gpu_samples(&|g| Some((g.mem_total_mb as f64 * mb).round())),
+    );
+    family(
+        &mut out,
+        "gpu_temperature_celsius",
+        "gauge",
+        "GPU core temperature.",
+        &gpu_samples(&|g| g.temperature.map(f64::from)),
+    );
+
+    let net_samples = |value: &dyn Fn(&crate::snapshot::NetworkSnapshot) -> f64| {
+        snapshot
+            .networks
+            .iter()
+            .map(|n| (format!("interface=\"{}\"", label(&n.interface)), value(n)))
+            .collect::<Vec<_>>()
+    };
+    family(
+        &mut out,
+        "network_receive_bytes_per_second",
+        "gauge",
+        "Receive rate over the last refresh interval.",
+        &net_samples(&|n| n.rx_bytes_per_sec),
+    );
+    family(
+        &mut out,
+        "network_transmit_bytes_per_second",
+        "gauge",
+        "Transmit rate over the last refresh interval.",
+        &net_samples(&|n| n.tx_bytes_per_sec),
+    );
+    family(
+        &mut out,
+        "network_receive_bytes_total",
+        "counter",
+        "Bytes received.",
+        &net_samples(&|n| n.total_rx_bytes as f64),
+    );
+    family(
+        &mut out,
+        "network_transmit_bytes_total",
+        "counter",
+        "Bytes transmitted.",
+        &net_samples(&|n| n.total_tx_bytes as f64),
+    );
+
+    let disk_samples = |value: &dyn Fn(&crate::snapshot::DiskSnapshot) -> u64| {
+        snapshot
+            .disks
+            .iter()
+            .map(|d| {
+                (
+                    format!(
+                        "device=\"{}\",mountpoint=\"{}\"",
+                        label(&d.name),
+                        label(&d.mount_point)
+                    ),
+                    value(d) as f64,
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+    family(
+        &mut out,
+        "filesystem_size_bytes",
+        "gauge",
+        "File system size.",
+        &disk_samples(&|d| d.total_bytes),
+    );
+    family(
+        &mut out,
+        "filesystem_available_bytes",
+        "gauge",
+        "Free file system space.",
+        &disk_samples(&|d| d.available_bytes),
+    );
+
+    let fans: Vec<(String, f64)> = snapshot
+        .fans
+        .iter()
+        .map(|f| (format!("fan=\"{}\"", label(&f.label)), f.rpm as f64))
+        .collect();
+    family(&mut out, "fan_rpm", "gauge", "Fan speed.", &fans);
+
+    let derived: Vec<(String, f64)> = snapshot
+        .derived
+        .iter()
+        .map(|d| {
+            (
+                format!("name=\"{}\",unit=\"{}\"", label(&d.name), label(&d.unit)),
+                d.value as f64,
+            )
+        })
+        .collect();
+    family(
+        &mut out,
+        "derived_value",
+        "gauge",
+        "Configured derived metrics.",
+        &derived,
+    );
+
+    family(
+        &mut out,
+        "active_alerts",
+        "gauge",
+        "Number of active alerts.",
+        &single(snapshot.alerts.len() as f64),
+    );
+    out
+}
+
+/// Serves the latest metrics until dropped.
+pub struct PrometheusExporter {
+    latest: Arc<Mutex<String>>,
+    _server: HttpServer,
+}
+
+impl PrometheusExporter {
+    /// Binds the listener and starts the server thread.
+    pub fn start(settings: &PrometheusSettings) -> Result<Self, String> {
+        let address = format!("{}:{}", settings.bind_address, settings.port);
+        let latest = Arc::new(Mutex::new(String::new()));
+        let server_latest = latest.clone();
+        let server = HttpServer::start("Prometheus", &address, move |path| {
+            (path == "/metrics").then(|| Response {
+                content_type: CONTENT_TYPE,
+                body: server_latest.lock().map(|b| b.clone()).unwrap_or_default(),
+            })
+        })?;
+        info!("Serving Prometheus metrics on http://{}/metrics", address);
+        Ok(Self {
+            latest,
+            _server: server,
+        })
+    }
+
+    /// Replaces the served metrics with those of `snapshot`.
+    pub fn update(&self, snapshot: &MetricsSnapshot) {
+        let text = format_metrics(snapshot);
+        if let Ok(mut latest) = self.latest.lock() {
+            *latest = text;
+        }
+    }
+}
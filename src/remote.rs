@@ -0,0 +1,275 @@
+//! # Remote Hosts Module
+//!
+//! This module collects basic metrics from remote Linux hosts over SSH, without installing
+//! anything on them. Each poll runs one small shell script that prints `/proc` and sysfs
+//! files (and `smartctl` health, when available) and parses the output locally.
+//!
+//! Authentication is left to the user's SSH setup (keys, agent, `~/.ssh/config`); `ssh`
+//! runs in batch mode so a host that would prompt for a password is reported as offline.
+
+use crate::history::{History, HistoryLimits};
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Seconds between two polls of the same host.
+pub const REMOTE_POLL_INTERVAL_SECS: u64 = 5;
+
+/// Collection script run on the remote host. Sections are introduced by `@name` lines.
+const COLLECT_SCRIPT: &str = "\
+echo @stat; head -n1 /proc/stat; \
+echo @meminfo; grep -E '^(MemTotal|MemAvailable):' /proc/meminfo; \
+echo @loadavg; cat /proc/loadavg; \
+echo @uptime; cat /proc/uptime; \
+echo @hostname; cat /proc/sys/kernel/hostname; \
+echo @thermal; cat /sys/class/thermal/thermal_zone*/temp 2>/dev/null; \
+echo @smart; command -v smartctl >/dev/null 2>&1 && \
+for d in $(smartctl --scan 2>/dev/null | cut -d' ' -f1); do \
+echo \"$d $(smartctl -H $d 2>/dev/null | grep -Ei 'overall-health|health status' | cut -d: -f2)\"; \
+done; true";
+
+/// One parsed poll of a remote host.
+#[derive(Debug, Clone, Default)]
+struct RemoteSample {
+    hostname: String,
+    /// Aggregate jiffies from the first line of `/proc/stat`: (total, idle).
+    cpu_jiffies: (u64, u64),
+    mem_total_kb: u64,
+    mem_available_kb: u64,
+    load: [f32; 3],
+    uptime_secs: u64,
+    /// Hottest thermal zone in °C.
+    temperature: Option<f32>,
+    /// SMART health per device, e.g. ("/dev/sda", "PASSED").
+    disks: Vec<(String, String)>,
+}
+
+/// Latest poll result of one host, shared with its polling thread.
+#[derive(Debug, Clone, Default)]
+struct HostState {
+    sample: Option<RemoteSample>,
+    cpu_usage: f32,
+    /// Error of the last poll; empty while the host answers.
+    error: String,
+}
+
+/// Holds remote host data for external consumers
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RemoteHostData {
+    /// SSH destination as configured (e.g. "user@server" or a `~/.ssh/config` alias).
+    pub target: String,
+    pub hostname: String,
+    pub online: bool,
+    pub error: String,
+    pub cpu_usage: f32,
+    pub cpu_history: History,
+    pub mem_used_gb: f32,
+    pub mem_total_gb: f32,
+    pub load: [f32; 3],
+    pub uptime_secs: u64,
+    pub temperature: Option<f32>,
+    pub disks: Vec<(String, String)>,
+}
+
+/// Polls a list of SSH hosts in background threads and keeps their CPU history.
+/// The polling threads stop when the tracker is dropped.
+pub struct RemoteTracker {
+    targets: Vec<String>,
+    states: Vec<Arc<Mutex<HostState>>>,
+    histories: Vec<History>,
+    stop: Arc<AtomicBool>,
+}
+
+fn parse_output(output: &str) -> RemoteSample {
+    let mut sample = RemoteSample::default();
+    let mut section = "";
+    for line in output.lines() {
+        if let Some(name) = line.strip_prefix('@') {
+            section = name.trim();
+            continue;
+        }
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match section {
+            "stat" if fields.first() == Some(&"cpu") => {
+                let values: Vec<u64> = fields[1..].iter().filter_map(|v| v.parse().ok()).collect();
+                // idle + iowait count as idle time; guest time is already part of user
+                let idle =
+                    values.get(3).copied().unwrap_or(0) + values.get(4).copied().unwrap_or(0);
+                sample.cpu_jiffies = (values.iter().take(8).sum(), idle);
+            }
+            "meminfo" => {
+                let kb = fields.get(1).and_then(|v| v.parse().ok()).unwrap_or(0);
+                match fields.first() {
+                    Some(&"MemTotal:") => sample.mem_total_kb = kb,
+                    Some(&"MemAvailable:") => sample.mem_available_kb = kb,
+                    _ => {}
+                }
+            }
+            "loadavg" => {
+                for (i, v) in fields.iter().take(3).enumerate() {
+                    sample.load[i] = v.parse().unwrap_or(0.0);
+                }
+            }
+            "uptime" => {
+                sample.uptime_secs = fields
+                    .first()
+                    .and_then(|v| v.parse::<f64>().ok())
+                    .unwrap_or(0.0) as u64;
+            }
+            "hostname" => sample.hostname = line.trim().to_string(),
+            "thermal" => {
+                if let Ok(millis) = line.trim().parse::<f32>() {
+                    let celsius = millis / 1000.0;
+                    sample.temperature =
+                        Some(sample.temperature.map_or(celsius, |t: f32| t.max(celsius)));
+                }
+            }
+            "smart" => {
+                if let Some((device, health)) = line.split_once(' ') {
+                    let health = health.trim();
+                    sample.disks.push((
+                        device.to_string(),
+                        if health.is_empty() { "Unknown" } else { health }.to_string(),
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+    sample
+}
+
+/// Runs the collection script on `target` over SSH.
+fn collect(target: &str) -> Result<RemoteSample, String> {
+    let output = Command::new("ssh")
+        .args([
+            "-o",
+            "BatchMode=yes",
+            "-o",
+            "ConnectTimeout=5",
+            "--",
+            target,
+            COLLECT_SCRIPT,
+        ])
+        .output()
+        .map_err(|e| format!("Failed to run ssh: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(stderr
+            .lines()
+            .last()
+            .unwrap_or("ssh failed")
+            .trim()
+            .to_string());
+    }
+    Ok(parse_output(&String::from_utf8_lossy(&output.stdout)))
+}
+
+impl RemoteTracker {
+    /// Starts one polling thread per SSH destination.
+    pub fn start(targets: Vec<String>, limits: HistoryLimits) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let states: Vec<Arc<Mutex<HostState>>> = targets
+            .iter()
+            .map(|_| Arc::new(Mutex::new(HostState::default())))
+            .collect();
+
+        for (target, state) in targets.iter().zip(&states) {
+            let target = target.clone();
+            let state = state.clone();
+            let stop = stop.clone();
+            std::thread::spawn(move || {
+                let mut prev_jiffies: Option<(u64, u64)> = None;
+                while !stop.load(Ordering::Relaxed) {
+                    let result = collect(&target);
+                    if let Ok(mut guard) = state.lock() {
+                        match result {
+                            Ok(sample) => {
+                                if let Some((total, idle)) = prev_jiffies {
+                                    let d_total = sample.cpu_jiffies.0.saturating_sub(total);
+                                    let d_idle = sample.cpu_jiffies.1.saturating_sub(idle);
+                                    if d_total > 0 {
+                                        guard.cpu_usage =
+                                            (1.0 - d_idle as f32 / d_total as f32) * 100.0;
+                                    }
+                                }
+                                prev_jiffies = Some(sample.cpu_jiffies);
+                                guard.sample = Some(sample);
+                                guard.error.clear();
+                            }
+                            Err(e) => {
+                                prev_jiffies = None;
+                                guard.cpu_usage = 0.0;
+                                guard.error = e;
+                            }
+                        }
+                    }
+                    std::thread::sleep(Duration::from_secs(REMOTE_POLL_INTERVAL_SECS));
+                }
+            });
+        }
+
+        Self {
+            histories: vec![History::with_limits(limits); targets.len()],
+            targets,
+            states,
+            stop,
+        }
+    }
+
+    /// Appends each host's latest CPU usage to its history (sample-and-hold between polls).
+    pub fn refresh(&mut self) {
+        for (state, history) in self.states.iter().zip(&mut self.histories) {
+            let usage = state.lock().map(|s| s.cpu_usage).unwrap_or(0.0);
+            history.push(usage);
+        }
+    }
+
+    pub fn resize_history(&mut self, limits: HistoryLimits) {
+        for history in &mut self.histories {
+            history.set_limits(limits);
+        }
+    }
+
+    /// Number of history buffers, for the monitor's sample budget.
+    pub fn history_count(&self) -> usize {
+        self.histories.len()
+    }
+
+    pub fn get_data(&self) -> Vec<RemoteHostData> {
+        self.targets
+            .iter()
+            .zip(&self.states)
+            .zip(&self.histories)
+            .map(|((target, state), history)| {
+                let state = state.lock().map(|s| s.clone()).unwrap_or_default();
+                let online = state.error.is_empty() && state.sample.is_some();
+                let sample = state.sample.unwrap_or_default();
+                RemoteHostData {
+                    target: target.clone(),
+                    hostname: sample.hostname,
+                    online,
+                    error: state.error,
+                    cpu_usage: state.cpu_usage,
+                    cpu_history: history.clone(),
+                    mem_used_gb: sample.mem_total_kb.saturating_sub(sample.mem_available_kb) as f32
+                        / 1024.0
+                        / 1024.0,
+                    mem_total_gb: sample.mem_total_kb as f32 / 1024.0 / 1024.0,
+                    load: sample.load,
+                    uptime_secs: sample.uptime_secs,
+                    temperature: sample.temperature,
+                    disks: sample.disks,
+                }
+            })
+            .collect()
+    }
+}
+
+impl Drop for RemoteTracker {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
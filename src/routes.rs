@@ -0,0 +1,97 @@
+//! # Routing Module
+//!
+//! This module provides read-only snapshots of the kernel routing tables and the
+//! neighbor (ARP/NDP) cache for troubleshooting.
+//!
+//! Data is queried over netlink through iproute2's JSON output (`ip -j route`,
+//! `ip -j neigh`), in the same way storage details come from `smartctl`. Both lists are
+//! only fetched on demand, not on every refresh tick.
+
+use serde_json::Value;
+use std::process::Command;
+
+#[derive(Debug, Clone)]
+pub struct RouteEntry {
+    /// Destination prefix or "default".
+    pub destination: String,
+    pub gateway: String,
+    pub device: String,
+    /// Origin of the route ("kernel", "dhcp", "static", "ra", ...).
+    pub protocol: String,
+    pub metric: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NeighborEntry {
+    pub address: String,
+    /// Link-layer (MAC) address; empty for incomplete entries.
+    pub lladdr: String,
+    pub device: String,
+    /// NUD state(s), e.g. "REACHABLE", "STALE".
+    pub state: String,
+}
+
+/// Runs `ip -j <args>` and returns the parsed JSON array.
+fn ip_json(args: &[&str]) -> Result<Vec<Value>, String> {
+    let output = Command::new("ip")
+        .arg("-j")
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run ip: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    match serde_json::from_slice::<Value>(&output.stdout) {
+        Ok(Value::Array(items)) => Ok(items),
+        Ok(_) => Ok(Vec::new()),
+        Err(e) => Err(format!("Unexpected ip output: {}", e)),
+    }
+}
+
+fn str_field(item: &Value, key: &str) -> String {
+    item.get(key)
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Returns the IPv4 and IPv6 main routing tables.
+pub fn get_routes() -> Result<Vec<RouteEntry>, String> {
+    let mut routes = Vec::new();
+    for family in ["-4", "-6"] {
+        for item in ip_json(&[family, "route", "show"])? {
+            routes.push(RouteEntry {
+                destination: str_field(&item, "dst"),
+                gateway: str_field(&item, "gateway"),
+                device: str_field(&item, "dev"),
+                protocol: str_field(&item, "protocol"),
+                metric: item.get("metric").and_then(Value::as_u64),
+            });
+        }
+    }
+    Ok(routes)
+}
+
+/// Returns the neighbor cache (ARP for IPv4, NDP for IPv6).
+pub fn get_neighbors() -> Result<Vec<NeighborEntry>, String> {
+    Ok(ip_json(&["neigh", "show"])?
+        .iter()
+        .map(|item| NeighborEntry {
+            address: str_field(item, "dst"),
+            lladdr: str_field(item, "lladdr"),
+            device: str_field(item, "dev"),
+            state: item
+                .get("state")
+                .and_then(Value::as_array)
+                .map(|states| {
+                    states
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .collect::<Vec<_>>()
+                        .join(",")
+                })
+                .unwrap_or_default(),
+        })
+        .collect())
+}
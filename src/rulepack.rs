@@ -0,0 +1,206 @@
+//! # Alert Rule Packs Module
+//!
+//! This module implements `gjallarhorn rules`, which exports the alert rule thresholds
+//! (the `alert_rules` settings section) to a standalone file and imports such files, so
+//! a team can keep one standard rule pack and apply it on every machine. Packs are JSON,
+//! or TOML when the file name ends in `.toml`:
+//!
+//! ```toml
+//! version = 1
+//! description = "Build servers"
+//!
+//! [rules]
+//! gpu_temp_warn = 80.0
+//! disk_warn_percent = 85.0
+//! ```
+//!
+//! A pack may list only some thresholds; the others are left as they are. Unknown names
+//! and out-of-range values are rejected before anything is written. A threshold that was
+//! changed locally (differs from the default) and would be changed again by the pack is a
+//! conflict: the import stops and lists them unless `--force` is given.
+
+use crate::settings::{AlertRuleSettings, AppSettings};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Version of the pack format written by `export`.
+pub const PACK_VERSION: u32 = 1;
+
+/// A rule pack file.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RulePack {
+    pub version: u32,
+    #[serde(default)]
+    pub description: String,
+    /// Threshold name (an `AlertRuleSettings` field) → value.
+    pub rules: BTreeMap<String, f32>,
+}
+
+/// A threshold the import would change.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleChange {
+    pub name: String,
+    pub current: f32,
+    pub imported: f32,
+    /// The current value was changed locally, so the import overrides a local choice.
+    pub conflict: bool,
+}
+
+/// Thresholds of `rules` by name.
+fn to_map(rules: &AlertRuleSettings) -> BTreeMap<String, f32> {
+    serde_json::to_value(rules)
+        .ok()
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+fn is_toml(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "toml")
+}
+
+/// Serializes `pack` as TOML or pretty JSON, depending on `path`.
+pub fn format_pack(pack: &RulePack, path: &Path) -> Result<String, String> {
+    if is_toml(path) {
+        toml::to_string(pack).map_err(|e| e.to_string())
+    } else {
+        serde_json::to_string_pretty(pack).map_err(|e| e.to_string())
+    }
+}
+
+/// Parses a pack and checks its version and threshold names.
+pub fn parse_pack(content: &str, path: &Path) -> Result<RulePack, String> {
+    let pack: RulePack = if is_toml(path) {
+        toml::from_str(content).map_err(|e| format!("Invalid rule pack: {}", e))?
+    } else {
+        serde_json::from_str(content).map_err(|e| format!("Invalid rule pack: {}", e))?
+    };
+    if pack.version == 0 || pack.version > PACK_VERSION {
+        return Err(format!(
+            "Unsupported rule pack version {} (expected {})",
+            pack.version, PACK_VERSION
+        ));
+    }
+    let known = to_map(&AlertRuleSettings::default());
+    if let Some(unknown) = pack.rules.keys().find(|name| !known.contains_key(*name)) {
+        return Err(format!(
+            "Unknown rule '{}' (expected one of {})",
+            unknown,
+            known.keys().cloned().collect::<Vec<_>>().join(", ")
+        ));
+    }
+    Ok(pack)
+}
+
+/// Applies `pack` on top of `current`. Returns the resulting thresholds, validated, and
+/// the thresholds that change.
+pub fn merge(
+    current: &AlertRuleSettings,
+    pack: &RulePack,
+) -> Result<(AlertRuleSettings, Vec<RuleChange>), String> {
+    let defaults = to_map(&AlertRuleSettings::default());
+    let mut values = to_map(current);
+    let mut changes = Vec::new();
+    for (name, &imported) in &pack.rules {
+        let Some(value) = values.get_mut(name) else {
+            continue;
+        };
+        if *value != imported {
+            changes.push(RuleChange {
+                name: name.clone(),
+                current: *value,
+                imported,
+                conflict: defaults.get(name) != Some(value),
+            });
+            *value = imported;
+        }
+    }
+    let merged: AlertRuleSettings = serde_json::to_value(values)
+        .and_then(serde_json::from_value)
+        .map_err(|e| e.to_string())?;
+    merged.validate()?;
+    Ok((merged, changes))
+}
+
+fn describe(changes: &[RuleChange]) -> String {
+    changes
+        .iter()
+        .map(|c| {
+            format!(
+                "  {}: {} -> {}{}",
+                c.name,
+                c.current,
+                c.imported,
+                if c.conflict { " (changed locally)" } else { "" }
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Writes the current thresholds to `path`.
+fn export(path: &Path, description: &str) -> Result<String, String> {
+    let pack = RulePack {
+        version: PACK_VERSION,
+        description: description.to_string(),
+        rules: to_map(&AppSettings::load().alert_rules),
+    };
+    std::fs::write(path, format_pack(&pack, path)?)
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(format!(
+        "Exported {} rules to {}",
+        pack.rules.len(),
+        path.display()
+    ))
+}
+
+/// Applies the pack at `path` to the settings file.
+fn import(path: &Path, dry_run: bool, force: bool) -> Result<String, String> {
+    let content =
+        std::fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let pack = parse_pack(&content, path)?;
+    let mut settings = AppSettings::load();
+    let (merged, changes) = merge(&settings.alert_rules, &pack)?;
+
+    if changes.is_empty() {
+        return Ok("All rules already match the pack".to_string());
+    }
+    if dry_run {
+        return Ok(format!("Would change:\n{}", describe(&changes)));
+    }
+    if !force && changes.iter().any(|c| c.conflict) {
+        return Err(format!(
+            "The pack overrides locally changed rules (use --force to apply anyway):\n{}",
+            describe(&changes)
+        ));
+    }
+    settings.alert_rules = merged;
+    settings.save();
+    Ok(format!(
+        "Changed (applies on the next start of Gjallarhorn):\n{}",
+        describe(&changes)
+    ))
+}
+
+/// Runs `gjallarhorn rules export <file> [--description <text>]` or
+/// `gjallarhorn rules import <file> [--dry-run] [--force]`.
+pub fn run(args: &[String]) -> Result<String, String> {
+    const USAGE: &str = "Usage: gjallarhorn rules export <file> [--description <text>]\n       gjallarhorn rules import <file> [--dry-run] [--force]";
+    let path = args.get(1).filter(|a| !a.starts_with("--")).ok_or(USAGE)?;
+    match args.first().map(String::as_str) {
+        Some("export") => {
+            let description = args
+                .iter()
+                .position(|a| a == "--description")
+                .and_then(|i| args.get(i + 1))
+                .map_or("", String::as_str);
+            export(Path::new(path), description)
+        }
+        Some("import") => import(
+            Path::new(path),
+            args.iter().any(|a| a == "--dry-run"),
+            args.iter().any(|a| a == "--force"),
+        ),
+        _ => Err(USAGE.to_string()),
+    }
+}
@@ -0,0 +1,260 @@
+//! # Worker Sandbox Module
+//!
+//! This module confines the privileged worker once it has opened what it needs at
+//! startup (`/dev/kmsg`, the packet capture). Root keeps only the capabilities the
+//! collectors still use — `CAP_SYS_RAWIO` and `CAP_SYS_ADMIN` for the ATA and NVMe
+//! pass-through of smartctl and nvme-cli, `CAP_SYS_PTRACE` for other users'
+//! `/proc/<pid>/ns/net`, `CAP_DAC_READ_SEARCH` for reading — and they are removed from
+//! the bounding set too, so the tools it runs cannot regain the rest. A Landlock ruleset
+//! then limits the filesystem to reading the system directories, with `/dev` the only
+//! writable tree. Kernels without Landlock (before 5.13) only get the capability drop.
+//!
+//! External tools are started through `command`, which resolves them in the system
+//! directories with a clean environment, and device names are checked with
+//! `is_device_name` before they become arguments.
+
+use std::ffi::CString;
+use std::os::unix::ffi::OsStrExt;
+use std::path::Path;
+use std::process::Command;
+
+/// Directories tools are looked up in. The user's `PATH` is not trusted.
+const TRUSTED_PATH: &str = "/usr/local/sbin:/usr/local/bin:/usr/sbin:/usr/bin:/sbin:/bin";
+
+const CAP_DAC_READ_SEARCH: u32 = 2;
+const CAP_SETPCAP: u32 = 8;
+const CAP_SYS_RAWIO: u32 = 17;
+const CAP_SYS_PTRACE: u32 = 19;
+const CAP_SYS_ADMIN: u32 = 21;
+const LINUX_CAPABILITY_VERSION_3: u32 = 0x2008_0522;
+
+// Landlock ABI 1 filesystem access rights
+const ACCESS_FS_EXECUTE: u64 = 1 << 0;
+const ACCESS_FS_WRITE_FILE: u64 = 1 << 1;
+const ACCESS_FS_READ_FILE: u64 = 1 << 2;
+const ACCESS_FS_READ_DIR: u64 = 1 << 3;
+/// Every right of ABI 1 (execute, read, write, remove and create files of each kind).
+const ACCESS_FS_ALL: u64 = (1 << 13) - 1;
+const CREATE_RULESET_VERSION: u32 = 1 << 0;
+const RULE_PATH_BENEATH: u32 = 1;
+
+/// Trees the worker and its tools may read (and execute from).
+const READ_ONLY_PATHS: [&str; 11] = [
+    "/usr", "/bin", "/sbin", "/lib", "/lib64", "/etc", "/proc", "/sys", "/run", "/var/lib",
+    "/var/log",
+];
+/// Trees that are also writable: device nodes are opened read-write for pass-through.
+const WRITABLE_PATHS: [&str; 1] = ["/dev"];
+
+#[repr(C)]
+struct CapHeader {
+    version: u32,
+    pid: i32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+struct CapData {
+    effective: u32,
+    permitted: u32,
+    inheritable: u32,
+}
+
+#[repr(C)]
+struct RulesetAttr {
+    handled_access_fs: u64,
+}
+
+#[repr(C, packed)]
+struct PathBeneathAttr {
+    allowed_access: u64,
+    parent_fd: i32,
+}
+
+/// Whether `name` is a plain kernel device name such as "sda" or "nvme0n1", safe to
+/// append to `/dev/` and pass to a tool.
+pub fn is_device_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(['-', '.'])
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':'))
+}
+
+/// A `Command` for `program` found in the system directories, with an environment
+/// reduced to `PATH` and `LC_ALL=C` so the output is parseable and no `LD_*` variables
+/// or aliases reach the tool.
+pub fn command(program: &str) -> Command {
+    let resolved = TRUSTED_PATH
+        .split(':')
+        .map(|dir| Path::new(dir).join(program))
+        .find(|path| path.is_file())
+        .unwrap_or_else(|| program.into());
+    let mut command = Command::new(resolved);
+    command
+        .env_clear()
+        .env("PATH", TRUSTED_PATH)
+        .env("LC_ALL", "C");
+    command
+}
+
+/// Capabilities the enabled collectors need after startup.
+fn needed_capabilities(smart: bool, network: bool) -> Vec<u32> {
+    let mut caps = vec![CAP_DAC_READ_SEARCH];
+    if smart {
+        caps.extend([CAP_SYS_RAWIO, CAP_SYS_ADMIN]);
+    }
+    if network {
+        caps.push(CAP_SYS_PTRACE);
+    }
+    caps
+}
+
+/// Highest capability number the kernel knows.
+fn last_capability() -> u32 {
+    std::fs::read_to_string("/proc/sys/kernel/cap_last_cap")
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(40)
+}
+
+/// Removes every capability but `keep` from the bounding set and the current sets.
+fn drop_capabilities(keep: &[u32]) -> Result<(), String> {
+    // CAP_SETPCAP is needed for each PR_CAPBSET_DROP, so it goes last
+    let last = last_capability();
+    for cap in (0..=last).filter(|c| !keep.contains(c) && *c != CAP_SETPCAP) {
+        // Fails with EINVAL for capabilities newer than the headers; nothing to drop then
+        unsafe { libc::prctl(libc::PR_CAPBSET_DROP, cap as libc::c_ulong, 0, 0, 0) };
+    }
+    if !keep.contains(&CAP_SETPCAP) {
+        unsafe { libc::prctl(libc::PR_CAPBSET_DROP, CAP_SETPCAP as libc::c_ulong, 0, 0, 0) };
+    }
+
+    let mut data = [CapData::default(); 2];
+    for cap in keep {
+        let (word, bit) = ((*cap / 32) as usize, 1u32 << (cap % 32));
+        data[word].effective |= bit;
+        data[word].permitted |= bit;
+    }
+    let header = CapHeader {
+        version: LINUX_CAPABILITY_VERSION_3,
+        pid: 0,
+    };
+    if unsafe { libc::syscall(libc::SYS_capset, &header, data.as_ptr()) } != 0 {
+        return Err(format!(
+            "capset failed: {}",
+            std::io::Error::last_os_error()
+        ));
+    }
+    Ok(())
+}
+
+/// Adds a rule allowing `access` beneath `path`. Missing paths are skipped.
+fn add_path_rule(ruleset: i32, path: &str, access: u64) -> Result<(), String> {
+    let c_path = CString::new(Path::new(path).as_os_str().as_bytes()).map_err(|e| e.to_string())?;
+    let fd = unsafe { libc::open(c_path.as_ptr(), libc::O_PATH | libc::O_CLOEXEC) };
+    if fd < 0 {
+        return Ok(());
+    }
+    let rule = PathBeneathAttr {
+        allowed_access: access,
+        parent_fd: fd,
+    };
+    let result = unsafe {
+        libc::syscall(
+            libc::SYS_landlock_add_rule,
+            ruleset,
+            RULE_PATH_BENEATH,
+            &rule as *const PathBeneathAttr,
+            0,
+        )
+    };
+    unsafe { libc::close(fd) };
+    if result != 0 {
+        return Err(format!(
+            "Landlock rule for {} failed: {}",
+            path,
+            std::io::Error::last_os_error()
+        ));
+    }
+    Ok(())
+}
+
+/// Limits the filesystem to `READ_ONLY_PATHS` and `WRITABLE_PATHS`. Returns `Ok(false)`
+/// when the kernel has no Landlock support.
+fn restrict_filesystem() -> Result<bool, String> {
+    let abi = unsafe {
+        libc::syscall(
+            libc::SYS_landlock_create_ruleset,
+            std::ptr::null::<RulesetAttr>(),
+            0,
+            CREATE_RULESET_VERSION,
+        )
+    };
+    if abi < 1 {
+        return Ok(false);
+    }
+
+    let attr = RulesetAttr {
+        handled_access_fs: ACCESS_FS_ALL,
+    };
+    let ruleset = unsafe {
+        libc::syscall(
+            libc::SYS_landlock_create_ruleset,
+            &attr as *const RulesetAttr,
+            std::mem::size_of::<RulesetAttr>(),
+            0,
+        )
+    } as i32;
+    if ruleset < 0 {
+        return Err(format!(
+            "Landlock ruleset failed: {}",
+            std::io::Error::last_os_error()
+        ));
+    }
+
+    let read = ACCESS_FS_EXECUTE | ACCESS_FS_READ_FILE | ACCESS_FS_READ_DIR;
+    let result = READ_ONLY_PATHS
+        .iter()
+        .map(|path| add_path_rule(ruleset, path, read))
+        .chain(
+            WRITABLE_PATHS
+                .iter()
+                .map(|path| add_path_rule(ruleset, path, read | ACCESS_FS_WRITE_FILE)),
+        )
+        .collect::<Result<(), String>>()
+        .and_then(|()| {
+            if unsafe { libc::syscall(libc::SYS_landlock_restrict_self, ruleset, 0) } != 0 {
+                return Err(format!(
+                    "Landlock restrict failed: {}",
+                    std::io::Error::last_os_error()
+                ));
+            }
+            Ok(())
+        });
+    unsafe { libc::close(ruleset) };
+    result.map(|()| true)
+}
+
+/// Drops the worker to the capabilities the enabled collectors need and restricts its
+/// filesystem view. Call after everything privileged to open is open. Failures are
+/// reported on stderr and leave the worker running with what could be applied.
+pub fn confine_worker(smart: bool, network: bool) {
+    if let Err(e) = drop_capabilities(&needed_capabilities(smart, network)) {
+        eprintln!("Failed to drop capabilities: {}", e);
+    }
+    // Required for an unprivileged Landlock ruleset, and keeps execve from adding
+    // privileges (setuid binaries, file capabilities) back
+    if unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) } != 0 {
+        eprintln!(
+            "Failed to set no_new_privs: {}",
+            std::io::Error::last_os_error()
+        );
+        return;
+    }
+    match restrict_filesystem() {
+        Ok(true) => {}
+        Ok(false) => eprintln!("Landlock unavailable; filesystem access is not restricted"),
+        Err(e) => eprintln!("{}", e),
+    }
+}
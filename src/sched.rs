@@ -0,0 +1,148 @@
+//! # Scheduler Module
+//!
+//! This module tracks how "interactive" the system feels rather than how busy it is:
+//! - `procs_running` / `procs_blocked` from `/proc/stat` (tasks on the run queue and
+//!   tasks waiting for I/O).
+//! - Average run-queue wait per timeslice from `/proc/schedstat`, when the kernel was
+//!   built with `CONFIG_SCHEDSTATS`.
+//!
+//! A machine can sit at 100% CPU and still be responsive; a growing run queue and rising
+//! wait time is what users perceive as lag.
+
+use crate::history::{History, HistoryLimits};
+use serde::{Deserialize, Serialize};
+
+/// Lower bound of the wait-time chart scale (in microseconds).
+const MIN_WAIT_SCALE_US: f32 = 1000.0;
+
+/// Holds scheduler data for external consumers
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SchedData {
+    pub procs_running: u32,
+    pub procs_blocked: u32,
+    /// Average time a task waited on a run queue per timeslice, in microseconds.
+    /// `None` if `/proc/schedstat` is unavailable.
+    pub avg_wait_us: Option<f32>,
+    pub running_history: History,
+    pub wait_history: History,
+    /// Highest wait seen so far (at least 1 ms), used to scale the wait chart.
+    pub wait_scale_us: f32,
+}
+
+/// Samples scheduler statistics on every refresh and keeps their history.
+pub struct SchedTracker {
+    procs_running: u32,
+    procs_blocked: u32,
+    avg_wait_us: Option<f32>,
+    /// Last `(run_delay_ns, pcount)` totals across all CPUs.
+    last_schedstat: Option<(u64, u64)>,
+    wait_scale_us: f32,
+    running_history: History,
+    wait_history: History,
+}
+
+/// Parses `procs_running` and `procs_blocked` from the contents of `/proc/stat`.
+fn parse_proc_stat(content: &str) -> (u32, u32) {
+    let field = |name: &str| {
+        content
+            .lines()
+            .find_map(|l| l.strip_prefix(name))
+            .and_then(|v| v.trim().parse::<u32>().ok())
+            .unwrap_or(0)
+    };
+    (field("procs_running"), field("procs_blocked"))
+}
+
+/// Sums `run_delay` (ns) and `pcount` over all `cpuN` lines of `/proc/schedstat`.
+fn parse_schedstat(content: &str) -> Option<(u64, u64)> {
+    let mut totals: Option<(u64, u64)> = None;
+    for line in content.lines().filter(|l| l.starts_with("cpu")) {
+        // cpuN yld_count sched_switch sched_count sched_goidle ttwu_count ttwu_local
+        //      rq_cpu_time run_delay pcount
+        let fields: Vec<u64> = line
+            .split_whitespace()
+            .skip(1)
+            .filter_map(|v| v.parse().ok())
+            .collect();
+        if fields.len() >= 9 {
+            let (delay, count) = totals.unwrap_or((0, 0));
+            totals = Some((delay + fields[7], count + fields[8]));
+        }
+    }
+    totals
+}
+
+impl SchedTracker {
+    pub fn new(limits: HistoryLimits) -> Self {
+        Self {
+            procs_running: 0,
+            procs_blocked: 0,
+            avg_wait_us: None,
+            last_schedstat: None,
+            wait_scale_us: MIN_WAIT_SCALE_US,
+            running_history: History::with_limits(limits),
+            wait_history: History::with_limits(limits),
+        }
+    }
+
+    /// Reads `/proc/stat` and `/proc/schedstat` and appends one sample to each history.
+    pub fn refresh(&mut self) {
+        let (running, blocked) = std::fs::read_to_string("/proc/stat")
+            .map(|c| parse_proc_stat(&c))
+            .unwrap_or((0, 0));
+        // The reading process itself is always counted as running
+        self.procs_running = running.saturating_sub(1);
+        self.procs_blocked = blocked;
+
+        let schedstat = std::fs::read_to_string("/proc/schedstat")
+            .ok()
+            .and_then(|c| parse_schedstat(&c));
+        self.avg_wait_us = match (schedstat, self.last_schedstat) {
+            (Some((delay, count)), Some((prev_delay, prev_count))) => {
+                let slices = count.saturating_sub(prev_count);
+                Some(if slices > 0 {
+                    delay.saturating_sub(prev_delay) as f32 / slices as f32 / 1000.0
+                } else {
+                    0.0
+                })
+            }
+            _ => None,
+        };
+        self.last_schedstat = schedstat;
+
+        if let Some(wait) = self.avg_wait_us {
+            self.wait_scale_us = self.wait_scale_us.max(wait);
+        }
+
+        self.running_history.push(self.procs_running as f32);
+        self.wait_history.push(self.avg_wait_us.unwrap_or(0.0));
+    }
+
+    /// Forgets the previous `/proc/schedstat` totals, so the next refresh only sets a new
+    /// baseline (e.g. after a suspend).
+    pub fn reset_baseline(&mut self) {
+        self.last_schedstat = None;
+    }
+
+    /// Applies new `limits` to both history buffers.
+    pub fn resize_history(&mut self, limits: HistoryLimits) {
+        self.running_history.set_limits(limits);
+        self.wait_history.set_limits(limits);
+    }
+
+    /// Number of history buffers, for the monitor's sample budget.
+    pub fn history_count(&self) -> usize {
+        2
+    }
+
+    pub fn get_data(&self) -> SchedData {
+        SchedData {
+            procs_running: self.procs_running,
+            procs_blocked: self.procs_blocked,
+            avg_wait_us: self.avg_wait_us,
+            running_history: self.running_history.clone(),
+            wait_history: self.wait_history.clone(),
+            wait_scale_us: self.wait_scale_us,
+        }
+    }
+}
@@ -0,0 +1,184 @@
+//! # Scoped Alert Rules Module
+//!
+//! This module evaluates the `scoped_rules` settings section: alert rules on the CPU or
+//! memory usage of one workload instead of the whole machine, such as "postgres memory
+//! above 8 GB" or "nginx.service CPU above 50%". A rule addresses its workload by label:
+//!
+//! - `process`: every process with that name, summed (e.g. all `postgres` backends).
+//! - `unit`: a systemd unit's cgroup, "postgresql.service" or just "postgresql".
+//! - `container`: a Docker or Podman container by ID prefix, or an nspawn machine by name.
+//! - `vm`: a libvirt domain by name (polled for the VMs tab).
+//!
+//! CPU is in percent of one core like `top` (200% = two busy cores), except for VMs, where
+//! it is in percent of the VM's vCPUs. Memory is the resident set of processes, the
+//! `memory.current` of units and containers and the balloon size of VMs. Units and
+//! containers named by a rule are tracked by `CgroupTracker` even without limits.
+//! A rule whose workload is not running raises nothing.
+
+use crate::alerts::{AlertCenter, AlertSeverity};
+use crate::cgroups::{CgroupData, CgroupTarget};
+use crate::processes::ProcessData;
+use crate::settings::{RuleScope, ScopedMetric, ScopedRule};
+use crate::vms::VmData;
+use log::warn;
+use std::collections::HashSet;
+
+const GB: f32 = 1024.0 * 1024.0 * 1024.0;
+
+/// The cgroups `rules` address, for `CgroupTracker::set_targets`.
+pub fn cgroup_targets(rules: &[ScopedRule]) -> Vec<CgroupTarget> {
+    rules
+        .iter()
+        .filter_map(|rule| match rule.scope {
+            RuleScope::Unit => Some(CgroupTarget::Unit(rule.target.clone())),
+            RuleScope::Container => Some(CgroupTarget::Container(rule.target.clone())),
+            RuleScope::Process | RuleScope::Vm => None,
+        })
+        .collect()
+}
+
+/// Usage of the workloads, gathered by `SystemMonitor` on each refresh.
+#[derive(Debug, Clone, Default)]
+pub struct ScopedInputs {
+    /// Empty unless a rule targets a process.
+    pub processes: Vec<ProcessData>,
+    pub cgroups: Vec<CgroupData>,
+    pub vms: Vec<VmData>,
+    pub cpu_count: usize,
+}
+
+/// Current reading of `rule`'s metric, or `None` if its workload is not running.
+fn measure(rule: &ScopedRule, inputs: &ScopedInputs) -> Option<f32> {
+    let (cpu, memory_bytes) = match rule.scope {
+        RuleScope::Process => {
+            let matching: Vec<&ProcessData> = inputs
+                .processes
+                .iter()
+                .filter(|p| p.name == rule.target)
+                .collect();
+            if matching.is_empty() {
+                return None;
+            }
+            (
+                matching.iter().map(|p| p.cpu_percent).sum(),
+                matching.iter().map(|p| p.rss_bytes).sum::<u64>(),
+            )
+        }
+        RuleScope::Unit | RuleScope::Container => {
+            let target = if rule.scope == RuleScope::Unit {
+                CgroupTarget::Unit(rule.target.clone())
+            } else {
+                CgroupTarget::Container(rule.target.clone())
+            };
+            let matching: Vec<&CgroupData> = inputs
+                .cgroups
+                .iter()
+                .filter(|c| target.matches(c.name.rsplit('/').next().unwrap_or(&c.name)))
+                .collect();
+            if matching.is_empty() {
+                return None;
+            }
+            // `cpu_percent` of a cgroup is relative to all cores
+            (
+                matching.iter().map(|c| c.cpu_percent).sum::<f32>() * inputs.cpu_count as f32,
+                matching.iter().map(|c| c.memory_bytes).sum::<u64>(),
+            )
+        }
+        RuleScope::Vm => {
+            let vm = inputs
+                .vms
+                .iter()
+                .find(|v| v.running && v.name == rule.target)?;
+            (vm.cpu_usage, vm.memory_mb * 1024 * 1024)
+        }
+    };
+    Some(match rule.metric {
+        ScopedMetric::CpuPercent => cpu,
+        ScopedMetric::MemoryGb => memory_bytes as f32 / GB,
+    })
+}
+
+fn scope_name(scope: RuleScope) -> &'static str {
+    match scope {
+        RuleScope::Process => "process",
+        RuleScope::Unit => "unit",
+        RuleScope::Container => "container",
+        RuleScope::Vm => "vm",
+    }
+}
+
+/// The alert for `rule` at `value`, as (id, severity, message), if a threshold is crossed.
+fn evaluate(rule: &ScopedRule, value: f32) -> Option<(String, AlertSeverity, String)> {
+    let (severity, threshold) = match rule.critical {
+        Some(critical) if value >= critical => (AlertSeverity::Critical, critical),
+        _ if value >= rule.warn => (AlertSeverity::Warning, rule.warn),
+        _ => return None,
+    };
+    let (metric, reading) = match rule.metric {
+        ScopedMetric::CpuPercent => (
+            "cpu",
+            format!("CPU at {:.0}% (threshold {:.0}%)", value, threshold),
+        ),
+        ScopedMetric::MemoryGb => (
+            "memory",
+            format!("memory at {:.1} GB (threshold {:.1} GB)", value, threshold),
+        ),
+    };
+    Some((
+        format!(
+            "scoped.{}.{}.{}",
+            scope_name(rule.scope),
+            rule.target,
+            metric
+        ),
+        severity,
+        format!("{} {}", rule.target, reading),
+    ))
+}
+
+/// Raises and clears the alerts of the scoped rules.
+#[derive(Debug, Default)]
+pub struct ScopedRules {
+    rules: Vec<ScopedRule>,
+    /// Alerts raised on the previous evaluation.
+    raised: HashSet<String>,
+}
+
+impl ScopedRules {
+    /// Keeps the valid `rules`; invalid ones are logged and skipped.
+    pub fn new(rules: &[ScopedRule]) -> Self {
+        Self {
+            rules: rules
+                .iter()
+                .filter(|rule| {
+                    rule.validate()
+                        .map_err(|e| warn!("Ignoring scoped alert rule: {}", e))
+                        .is_ok()
+                })
+                .cloned()
+                .collect(),
+            raised: HashSet::new(),
+        }
+    }
+
+    /// Whether a rule targets a process name, so the process list is needed.
+    pub fn needs_processes(&self) -> bool {
+        self.rules.iter().any(|r| r.scope == RuleScope::Process)
+    }
+
+    /// Evaluates the rules and updates `alerts`, clearing the alerts of recovered rules.
+    pub fn apply(&mut self, inputs: &ScopedInputs, alerts: &mut AlertCenter) {
+        let mut raised = HashSet::new();
+        for rule in &self.rules {
+            let finding = measure(rule, inputs).and_then(|value| evaluate(rule, value));
+            if let Some((id, severity, message)) = finding {
+                alerts.raise(&id, severity, message);
+                raised.insert(id);
+            }
+        }
+        for id in self.raised.difference(&raised) {
+            alerts.clear(id);
+        }
+        self.raised = raised;
+    }
+}
@@ -0,0 +1,150 @@
+//! # Screenshot Module
+//!
+//! This module saves what the dashboard shows, so a spike can be shared without external
+//! tools: a screenshot as PNG, or a recording of the window for a number of seconds as an
+//! animated PNG (APNG, played by browsers and most image viewers).
+//!
+//! Frames come from Slint's `Window::take_snapshot()`, which renders the window
+//! contents, so no desktop portal or compositor support is needed. Recording frames are
+//! taken on the UI thread by a timer and encoded by a background thread as they arrive.
+//! Files are written to `<Pictures>/Gjallarhorn/` (the data directory when there is no
+//! pictures directory), named after the local time.
+
+use directories::{ProjectDirs, UserDirs};
+use slint::{Rgba8Pixel, SharedPixelBuffer};
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Sender};
+
+/// Frames per second of recordings.
+pub const RECORD_FPS: u32 = 10;
+/// Longest recording, in seconds.
+pub const MAX_RECORD_SECS: u32 = 120;
+
+type Frame = SharedPixelBuffer<Rgba8Pixel>;
+
+/// Directory screenshots and recordings are saved to (created if missing).
+pub fn output_dir() -> Result<PathBuf, String> {
+    let dir = UserDirs::new()
+        .and_then(|u| u.picture_dir().map(|p| p.join("Gjallarhorn")))
+        .or_else(|| {
+            ProjectDirs::from("com", "gjallarhorn", "gjallarhorn")
+                .map(|p| p.data_dir().join("screenshots"))
+        })
+        .unwrap_or_else(|| PathBuf::from("screenshots"));
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    Ok(dir)
+}
+
+/// File name for a capture taken now, e.g. "gjallarhorn-2026-03-14T200512Z.png".
+fn file_name(extension: &str) -> String {
+    let stamp = crate::csvlog::format_timestamp(crate::markers::now_secs()).replace(':', "");
+    format!("gjallarhorn-{}.{}", stamp, extension)
+}
+
+fn encoder(
+    path: &Path,
+    width: u32,
+    height: u32,
+) -> Result<png::Encoder<'static, BufWriter<File>>, String> {
+    let file =
+        File::create(path).map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    Ok(encoder)
+}
+
+/// Saves `frame` as a PNG file and returns its path.
+pub fn save_png(frame: &Frame) -> Result<PathBuf, String> {
+    let path = output_dir()?.join(file_name("png"));
+    let mut writer = encoder(&path, frame.width(), frame.height())?
+        .write_header()
+        .map_err(|e| e.to_string())?;
+    writer
+        .write_image_data(frame.as_bytes())
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    writer.finish().map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+/// `frame`'s pixels cropped or padded (transparent) to `width` x `height`, for frames
+/// taken after the window was resized.
+fn fit(frame: &Frame, width: u32, height: u32) -> Vec<u8> {
+    if frame.width() == width && frame.height() == height {
+        return frame.as_bytes().to_vec();
+    }
+    let mut pixels = vec![0u8; (width * height * 4) as usize];
+    let row = (width.min(frame.width()) * 4) as usize;
+    let source = frame.as_bytes();
+    for y in 0..height.min(frame.height()) as usize {
+        let from = y * frame.width() as usize * 4;
+        let to = y * width as usize * 4;
+        pixels[to..to + row].copy_from_slice(&source[from..from + row]);
+    }
+    pixels
+}
+
+/// An APNG recording in progress. Frames are passed to the encoder thread with `push`;
+/// the file is complete once `frames` frames were pushed.
+pub struct Recording {
+    sender: Sender<Frame>,
+    remaining: u32,
+    pub path: PathBuf,
+}
+
+impl Recording {
+    /// Starts a recording of `secs` seconds (at most `MAX_RECORD_SECS`) at the size of
+    /// `first`, which becomes its first frame. `done` is called from the encoder thread
+    /// with the outcome.
+    pub fn start(
+        first: Frame,
+        secs: u32,
+        done: impl FnOnce(Result<PathBuf, String>) + Send + 'static,
+    ) -> Result<Self, String> {
+        let frames = secs.clamp(1, MAX_RECORD_SECS) * RECORD_FPS;
+        let (width, height) = (first.width(), first.height());
+        let path = output_dir()?.join(file_name("apng"));
+        let mut encoder = encoder(&path, width, height)?;
+        encoder.set_compression(png::Compression::Fast);
+        encoder
+            .set_animated(frames, 0)
+            .and_then(|()| encoder.set_frame_delay(1, RECORD_FPS as u16))
+            .map_err(|e| e.to_string())?;
+        let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+
+        let (sender, receiver) = mpsc::channel::<Frame>();
+        let thread_path = path.clone();
+        std::thread::spawn(move || {
+            let mut result = Ok(());
+            for frame in receiver.iter().take(frames as usize) {
+                result = writer.write_image_data(&fit(&frame, width, height));
+                if result.is_err() {
+                    break;
+                }
+            }
+            let result = result
+                .and_then(|()| writer.finish())
+                .map(|()| thread_path.clone())
+                .map_err(|e| format!("Failed to write {}: {}", thread_path.display(), e));
+            done(result);
+        });
+
+        let mut recording = Self {
+            sender,
+            remaining: frames,
+            path,
+        };
+        recording.push(first);
+        Ok(recording)
+    }
+
+    /// Queues the next frame. Returns `false` once the recording has all its frames.
+    pub fn push(&mut self, frame: Frame) -> bool {
+        if self.remaining > 0 && self.sender.send(frame).is_ok() {
+            self.remaining -= 1;
+        }
+        self.remaining > 0
+    }
+}
@@ -0,0 +1,263 @@
+//! # Scripting Module
+//!
+//! This module runs a user's Rhai script (https://rhai.rs) on every snapshot, so power
+//! users can extend the monitor without recompiling. It is built with the `scripting`
+//! cargo feature and started when the `scripting` settings section enables it.
+//!
+//! The script sees the current `MetricsSnapshot` as the `snapshot` object map (the same
+//! fields as `/snapshot.json` of the overlay) and a `state` map that is kept between
+//! runs. It reports through these functions:
+//! - `metric(name, value)` / `metric(name, value, unit)` — a derived metric, charted on
+//!   the Overview tab next to the custom metrics.
+//! - `badge(text)` — a short line shown at the top of the Overview tab until the next run.
+//! - `alert(id, severity, message)` — raises the alert `script.<id>` with severity
+//!   `"warning"` or `"critical"`; it is cleared at the first run that does not raise it.
+//! - `print(text)` — writes to the log.
+//!
+//! ```rhai
+//! let vram = snapshot.gpus.reduce(|sum, g| sum + g.mem_used_mb, 0.0);
+//! metric("Total VRAM", vram / 1024.0, "GB");
+//! if snapshot.cpu.usage_percent > 90.0 { alert("cpu", "warning", "CPU is saturated"); }
+//! ```
+//!
+//! The script is compiled once; a run that fails or exceeds its operation budget raises
+//! the `script.error` alert instead of stopping the monitor.
+
+use crate::alerts::{AlertCenter, AlertSeverity};
+use crate::custommetrics::CustomMetricData;
+use crate::history::{History, HistoryLimits};
+use crate::settings::ScriptSettings;
+use crate::snapshot::MetricsSnapshot;
+use log::info;
+use rhai::{Dynamic, Engine, Scope, AST};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+/// Operations one run may take when the settings set no limit.
+const DEFAULT_MAX_OPERATIONS: u64 = 100_000;
+/// Id of the alert raised when the script fails.
+const ERROR_ALERT: &str = "script.error";
+
+/// What one run of the script reported.
+#[derive(Default)]
+struct ScriptOutput {
+    /// Name, value and unit of each `metric()` call.
+    metrics: Vec<(String, f32, String)>,
+    badges: Vec<String>,
+    /// Id, severity and message of each `alert()` call.
+    alerts: Vec<(String, AlertSeverity, String)>,
+}
+
+struct ScriptMetric {
+    name: String,
+    unit: String,
+    /// Value of the last run; `None` if it did not report the metric.
+    value: Option<f32>,
+    history: History,
+}
+
+/// A compiled script and what its runs reported.
+pub struct ScriptHook {
+    engine: Engine,
+    ast: AST,
+    /// Holds `state` between runs.
+    scope: Scope<'static>,
+    output: Arc<Mutex<ScriptOutput>>,
+    metrics: Vec<ScriptMetric>,
+    badges: Vec<String>,
+    /// Ids of the alerts raised by the last run.
+    raised: HashSet<String>,
+    limits: HistoryLimits,
+}
+
+fn parse_severity(severity: &str) -> AlertSeverity {
+    if severity.eq_ignore_ascii_case("critical") {
+        AlertSeverity::Critical
+    } else {
+        AlertSeverity::Warning
+    }
+}
+
+/// `value` as a number; integers are accepted too, anything else is ignored.
+fn to_number(value: &Dynamic) -> Option<f32> {
+    value
+        .as_float()
+        .ok()
+        .or_else(|| value.as_int().ok().map(|v| v as f64))
+        .map(|v| v as f32)
+}
+
+impl ScriptHook {
+    /// Reads and compiles the script named by `settings`.
+    pub fn load(settings: &ScriptSettings, limits: HistoryLimits) -> Result<Self, String> {
+        let path = settings.script_path();
+        let source = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+
+        let output = Arc::new(Mutex::new(ScriptOutput::default()));
+        let mut engine = Engine::new();
+        engine.set_max_operations(match settings.max_operations {
+            0 => DEFAULT_MAX_OPERATIONS,
+            n => n,
+        });
+        engine.on_print(|text| info!("Script: {}", text));
+
+        let metric_output = output.clone();
+        engine.register_fn("metric", move |name: &str, value: Dynamic| {
+            if let (Ok(mut output), Some(value)) = (metric_output.lock(), to_number(&value)) {
+                output
+                    .metrics
+                    .push((name.to_string(), value, String::new()));
+            }
+        });
+        let metric_output = output.clone();
+        engine.register_fn("metric", move |name: &str, value: Dynamic, unit: &str| {
+            if let (Ok(mut output), Some(value)) = (metric_output.lock(), to_number(&value)) {
+                output
+                    .metrics
+                    .push((name.to_string(), value, unit.to_string()));
+            }
+        });
+        let badge_output = output.clone();
+        engine.register_fn("badge", move |text: &str| {
+            if let Ok(mut output) = badge_output.lock() {
+                output.badges.push(text.to_string());
+            }
+        });
+        let alert_output = output.clone();
+        engine.register_fn("alert", move |id: &str, severity: &str, message: &str| {
+            if let Ok(mut output) = alert_output.lock() {
+                output.alerts.push((
+                    id.to_string(),
+                    parse_severity(severity),
+                    message.to_string(),
+                ));
+            }
+        });
+
+        let ast = engine
+            .compile(&source)
+            .map_err(|e| format!("Failed to compile {}: {}", path.display(), e))?;
+        info!("Loaded script {}", path.display());
+
+        let mut scope = Scope::new();
+        scope.push("state", rhai::Map::new());
+        scope.push("snapshot", Dynamic::UNIT);
+        Ok(Self {
+            engine,
+            ast,
+            scope,
+            output,
+            metrics: Vec::new(),
+            badges: Vec::new(),
+            raised: HashSet::new(),
+            limits,
+        })
+    }
+
+    /// Runs the script on `snapshot` and applies what it reported. Alerts are raised or
+    /// cleared in `alerts`.
+    pub fn run(&mut self, snapshot: &MetricsSnapshot, alerts: &mut AlertCenter) {
+        if let Ok(mut output) = self.output.lock() {
+            *output = ScriptOutput::default();
+        }
+        let result = rhai::serde::to_dynamic(snapshot).and_then(|value| {
+            self.scope.set_value("snapshot", value);
+            self.engine.run_ast_with_scope(&mut self.scope, &self.ast)
+        });
+        // Keep the badges and metrics of the last successful run
+        if let Err(e) = result {
+            alerts.raise(
+                ERROR_ALERT,
+                AlertSeverity::Warning,
+                format!("Script failed: {}", e),
+            );
+            return;
+        }
+        alerts.clear(ERROR_ALERT);
+        let output = match self.output.lock() {
+            Ok(mut guard) => std::mem::take(&mut *guard),
+            Err(_) => return,
+        };
+
+        for metric in &mut self.metrics {
+            metric.value = None;
+        }
+        let time = crate::history::now();
+        for (name, value, unit) in output.metrics {
+            let index = match self.metrics.iter().position(|m| m.name == name) {
+                Some(index) => index,
+                None => {
+                    self.metrics.push(ScriptMetric {
+                        name,
+                        unit: String::new(),
+                        value: None,
+                        history: History::with_limits(self.limits),
+                    });
+                    self.metrics.len() - 1
+                }
+            };
+            let metric = &mut self.metrics[index];
+            if !unit.is_empty() {
+                metric.unit = unit;
+            }
+            if value.is_finite() && metric.value.is_none() {
+                metric.value = Some(value);
+                metric.history.push_at(time, value);
+            }
+        }
+        self.badges = output.badges;
+
+        let mut raised = HashSet::new();
+        for (id, severity, message) in output.alerts {
+            let id = format!("script.{}", id);
+            alerts.raise(&id, severity, message);
+            raised.insert(id);
+        }
+        for id in self.raised.difference(&raised) {
+            alerts.clear(id);
+        }
+        self.raised = raised;
+    }
+
+    /// Applies new `limits` to every history buffer.
+    pub fn resize_history(&mut self, limits: HistoryLimits) {
+        self.limits = limits;
+        for metric in &mut self.metrics {
+            metric.history.set_limits(limits);
+        }
+    }
+
+    /// Number of history buffers, for the monitor's sample budget.
+    pub fn history_count(&self) -> usize {
+        self.metrics.len()
+    }
+
+    /// Clears the alerts raised by the script, before it is replaced or disabled.
+    pub fn clear_alerts(&mut self, alerts: &mut AlertCenter) {
+        for id in self.raised.drain() {
+            alerts.clear(&id);
+        }
+        alerts.clear(ERROR_ALERT);
+    }
+
+    /// The derived metrics, in the order they were first reported.
+    pub fn get_metric_data(&self) -> Vec<CustomMetricData> {
+        self.metrics
+            .iter()
+            .map(|metric| CustomMetricData {
+                name: metric.name.clone(),
+                unit: metric.unit.clone(),
+                value: metric.value,
+                error: String::new(),
+                history: metric.history.clone(),
+                scale: metric.history.max_value().max(1.0),
+            })
+            .collect()
+    }
+
+    /// The badges of the last successful run.
+    pub fn badges(&self) -> Vec<String> {
+        self.badges.clone()
+    }
+}
@@ -0,0 +1,152 @@
+//! # Sensors Module
+//!
+//! This module reads every temperature, fan and voltage input of every hwmon chip under
+//! `/sys/class/hwmon`, like `sensors` from lm_sensors, for the Sensors tab.
+//!
+//! Inputs are `tempN_input` (millidegrees Celsius), `fanN_input` (RPM) and `inN_input`
+//! (millivolts), labeled with the chip's `name` and the matching `*_label` file. The
+//! `*_max` and `*_crit` files, where the driver has them, are kept as limits. The inputs
+//! are listed by `hwmon::HwmonScanner`, which only walks the chips again when one is
+//! hot-plugged or removed; fan entries are shared with `hwmon::FanTracker`.
+
+use crate::history::{History, HistoryLimits};
+pub use crate::hwmon::SensorKind;
+use crate::hwmon::{read_input, HwmonScanner, HWMON_DIR};
+use crate::monitor::SysPaths;
+use serde::{Deserialize, Serialize};
+
+/// Lower bound of the chart scale, so small values don't fill the whole graph.
+fn min_scale(kind: SensorKind) -> f32 {
+    match kind {
+        SensorKind::Temperature => 100.0,
+        SensorKind::Fan => 1000.0,
+        SensorKind::Voltage => 1.0,
+    }
+}
+
+/// Holds data for a single hwmon input for external consumers
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SensorData {
+    /// Chip directory name (e.g., "hwmon3").
+    pub chip: String,
+    /// Driver name from the chip's `name` file (e.g., "nct6798").
+    pub driver: String,
+    pub kind: SensorKind,
+    /// Channel number (`N` in `tempN_input`).
+    pub index: u32,
+    /// Channel label, e.g. "Package id 0", "CPU Fan" or "in3".
+    pub label: String,
+    /// Latest reading in `kind.unit()`; `None` if the input could not be read.
+    pub value: Option<f32>,
+    /// `*_max`, the high limit, if the driver reports one.
+    pub max: Option<f32>,
+    /// `*_crit`, the critical limit, if the driver reports one.
+    pub critical: Option<f32>,
+    pub history: History,
+    /// Highest value seen so far (at least a per-kind minimum), used to scale the chart.
+    pub scale: f32,
+}
+
+impl SensorData {
+    /// e.g. "45.5 °C (high 80.0 °C, crit 100.0 °C)" or "N/A".
+    pub fn describe(&self) -> String {
+        let Some(value) = self.value else {
+            return "N/A".to_string();
+        };
+        let limits: Vec<String> = [("high", self.max), ("crit", self.critical)]
+            .into_iter()
+            .filter_map(|(name, limit)| Some(format!("{} {}", name, self.kind.format(limit?))))
+            .collect();
+        if limits.is_empty() {
+            self.kind.format(value)
+        } else {
+            format!("{} ({})", self.kind.format(value), limits.join(", "))
+        }
+    }
+}
+
+/// Samples every hwmon input on each refresh and keeps its history.
+pub struct SensorTracker {
+    scanner: HwmonScanner,
+    entries: Vec<SensorData>,
+}
+
+impl SensorTracker {
+    /// Reads the chips under the `/sys` root of `paths`.
+    pub fn new(paths: &SysPaths) -> Self {
+        Self {
+            scanner: HwmonScanner::new(paths.sys.join(HWMON_DIR)),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Polls every input and appends one sample to each history buffer.
+    pub fn refresh(&mut self, limits: HistoryLimits) {
+        let inputs = self.scanner.scan();
+        self.entries.retain(|e| {
+            inputs
+                .iter()
+                .any(|i| i.chip == e.chip && i.kind == e.kind && i.index == e.index)
+        });
+        for input in inputs {
+            if !self
+                .entries
+                .iter()
+                .any(|e| e.chip == input.chip && e.kind == input.kind && e.index == input.index)
+            {
+                self.entries.push(SensorData {
+                    chip: input.chip.clone(),
+                    driver: input.driver.clone(),
+                    kind: input.kind,
+                    index: input.index,
+                    label: input.label.clone(),
+                    value: None,
+                    max: None,
+                    critical: None,
+                    history: History::with_limits(limits),
+                    scale: min_scale(input.kind),
+                });
+            }
+        }
+        self.entries
+            .sort_by(|a, b| (&a.chip, a.kind, a.index).cmp(&(&b.chip, b.kind, b.index)));
+
+        for entry in &mut self.entries {
+            let dir = self.scanner.root().join(&entry.chip);
+            entry.value = read_input(&dir, entry.kind, entry.index, "input");
+            entry.max = read_input(&dir, entry.kind, entry.index, "max");
+            entry.critical = read_input(&dir, entry.kind, entry.index, "crit");
+            if let Some(value) = entry.value {
+                entry.scale = entry.scale.max(value);
+                entry.history.push(value);
+            }
+        }
+    }
+
+    /// Applies new `limits` to every history buffer.
+    pub fn resize_history(&mut self, limits: HistoryLimits) {
+        for entry in &mut self.entries {
+            entry.history.set_limits(limits);
+        }
+    }
+
+    /// Number of history buffers, for the monitor's sample budget.
+    pub fn history_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Every input as of the last refresh.
+    pub fn sensors(&self) -> &[SensorData] {
+        &self.entries
+    }
+
+    pub fn get_data(&self) -> Vec<SensorData> {
+        self.entries.clone()
+    }
+}
+
+impl Default for SensorTracker {
+    fn default() -> Self {
+        Self::new(&SysPaths::default())
+    }
+}
@@ -5,19 +5,30 @@
 //! - Visual Theme (Dark Mode)
 //! - CPU Color Mode (Uniform vs Per-Core)
 //! - Custom Chart Colors (CPU, RAM, GPU, Network)
+//! - Watched Process Names (automatic timeline markers)
 //!
 //! It handles serialization and deserialization (via `serde`) to a JSON file stored in the
 //! standard system configuration directory using the `directories` crate.
 
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::PathBuf;
 
+/// Accepted refresh rates, matching the Preferences slider.
+pub const MIN_REFRESH_RATE_MS: u64 = 100;
+pub const MAX_REFRESH_RATE_MS: u64 = 2000;
+/// Accepted chart windows, matching the Preferences slider.
+pub const MIN_HISTORY_WINDOW_SECS: u64 = 30;
+pub const MAX_HISTORY_WINDOW_SECS: u64 = 1800;
+
 /// Persistent application settings.
 /// Stores user preferences such as theme (dark mode), chart colors, and per-core CPU colors.
 /// Serialized to `config.json` in the system's standard configuration directory.
+/// Fields missing from an older `config.json` fall back to their `Default` values.
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
 pub struct AppSettings {
     pub dark_mode: bool,
     pub use_uniform_cpu: bool,
@@ -26,7 +37,882 @@ pub struct AppSettings {
     pub gpu_color: String,
     pub net_color: String,
     pub cpu_core_colors: Vec<String>,
+    /// CPU tab layout (0 = chart per core, 1 = stacked, 2 = heatmap).
+    pub cpu_view: i32,
     pub refresh_rate_ms: u64,
+    /// Time span shown by the charts, in seconds.
+    pub history_window_secs: u64,
+    /// Process names whose launch/exit automatically drops a timeline marker.
+    pub watched_processes: Vec<String>,
+    /// Usage view tab to show while a process runs (process name → tab name, e.g.
+    /// "steam" → "GPU"), see `profiles.rs`.
+    pub app_profiles: BTreeMap<String, String>,
+    /// Opt-in: allow changing GPU power limits (applied through `pkexec`).
+    pub allow_gpu_power_control: bool,
+    /// Keep polling a runtime suspended laptop dGPU, which wakes it up every refresh.
+    pub poll_suspended_gpu: bool,
+    /// Opt-in: allow manual fan speed overrides (applied through `pkexec`).
+    pub allow_fan_control: bool,
+    /// Show traffic of non-host network namespaces (containers, VPN sandboxes).
+    pub show_network_namespaces: bool,
+    /// Network and disk totals count since install instead of since boot (see `totals.rs`).
+    pub totals_since_install: bool,
+    /// Monthly data cap alerts from the persisted network totals.
+    pub data_budget: DataBudgetSettings,
+    /// Opt-in: capture packets in the privileged worker for the protocol breakdown.
+    pub enable_packet_capture: bool,
+    /// Opt-in: periodically time DNS lookups against the configured resolvers.
+    pub enable_dns_probe: bool,
+    /// Host name looked up by the DNS latency probe.
+    pub dns_probe_host: String,
+    /// SSH destinations polled for the Remote tab (e.g. "user@server" or a config alias).
+    pub remote_hosts: Vec<String>,
+    /// Periodic snapshot delivery to a webhook.
+    pub webhook: WebhookSettings,
+    /// Daily CSV logging of selected metrics.
+    pub csv: CsvSettings,
+    /// Prometheus `/metrics` endpoint (needs the `prometheus` cargo feature).
+    pub prometheus: PrometheusSettings,
+    /// Web page with live metrics text, for streaming tools (OBS browser source).
+    pub overlay: OverlaySettings,
+    /// gRPC service streaming the metrics (needs the `grpc` cargo feature).
+    pub grpc: GrpcSettings,
+    /// Forward alert transitions to journald/syslog.
+    pub forward_alerts_to_syslog: bool,
+    /// Thresholds of the health alert rules, shareable as a rule pack.
+    pub alert_rules: AlertRuleSettings,
+    /// Alert rules on the usage of single processes, units, containers and VMs.
+    pub scoped_rules: Vec<ScopedRule>,
+    /// "Job finished" alerts when a GPU goes idle after a long busy stretch.
+    pub gpu_jobs: GpuJobSettings,
+    /// Suspend or shut down after a sustained idle stretch (opt-in).
+    pub idle_action: IdleActionSettings,
+    /// FPS and frame time of the running game, from MangoHud logs.
+    pub frame_time: FrameTimeSettings,
+    /// Where alert transitions are announced, per rule, and when to stay quiet.
+    pub notifications: NotificationSettings,
+    /// TLS certificates watched for approaching expiry.
+    pub certificates: CertSettings,
+    /// Backup jobs checked for their last success.
+    pub backups: BackupSettings,
+    /// Subsystems to collect; disabled ones are never probed.
+    pub collectors: CollectorSettings,
+    /// Fixed or automatic Y-axis scale of the network and drive latency charts.
+    pub chart_scales: ChartScaleSettings,
+    /// Average and percentile lines over charts.
+    pub chart_overlays: ChartOverlaySettings,
+    /// Display names for interfaces, disks, GPUs and fans (e.g. "enp5s0" → "LAN"),
+    /// used in the UI and by the exporters.
+    pub aliases: BTreeMap<String, String>,
+    /// Ids of the series pinned to the Overview tab (see `pins.rs`), in display order.
+    pub pinned_series: Vec<String>,
+    /// Usage tabs popped out into their own windows, reopened on startup.
+    pub detached_panels: Vec<DetachedPanel>,
+    /// Layout of the desktop widget (`gjallarhorn widget`).
+    pub desktop_widget: DesktopWidgetSettings,
+    /// Metrics read from command output, charted on the Overview tab.
+    pub custom_metrics: Vec<CustomMetric>,
+    /// Series computed from other series, shown and exported like native ones.
+    pub derived_metrics: Vec<DerivedMetric>,
+    /// Per-minute statistics kept in a database for the History tab.
+    pub long_term_history: LongTermHistorySettings,
+    /// Rhai script run on every snapshot (needs the `scripting` cargo feature).
+    pub scripting: ScriptSettings,
+}
+
+/// `desktop_widget` section: the transparent graph layer started with `gjallarhorn widget`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct DesktopWidgetSettings {
+    /// Series ids to graph (see `pins.rs`); empty uses the Overview pins.
+    pub series: Vec<String>,
+    /// Position of the top-left corner, in physical pixels.
+    pub x: i32,
+    pub y: i32,
+    /// Width in logical pixels; the height follows from the number of graphs.
+    pub width: u32,
+    /// Opacity of the graph backgrounds, 0.0 - 1.0.
+    pub background_opacity: f32,
+    /// Let clicks pass through to the desktop below.
+    pub click_through: bool,
+}
+
+impl Default for DesktopWidgetSettings {
+    fn default() -> Self {
+        Self {
+            series: Vec::new(),
+            x: 40,
+            y: 40,
+            width: 280,
+            background_opacity: 0.3,
+            click_through: true,
+        }
+    }
+}
+
+/// Position and size of a detached panel window, in physical pixels.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct DetachedPanel {
+    /// Usage view tab shown in the window (0 = CPU ... 8 = Overview, 9 = VMs,
+    /// 10 = Processes).
+    pub tab: i32,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// `collectors` section: lets locked-down systems skip whole subsystems.
+/// Changes take effect after a restart.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct CollectorSettings {
+    /// NVIDIA GPUs via NVML.
+    pub gpu: bool,
+    /// Disk health via smartctl/nvme-cli in the privileged worker.
+    pub smart: bool,
+    /// Interface traffic, link events and network details.
+    pub network: bool,
+}
+
+impl Default for CollectorSettings {
+    fn default() -> Self {
+        Self {
+            gpu: true,
+            smart: true,
+            network: true,
+        }
+    }
+}
+
+/// `certificates` section: certificate files and `host:port` endpoints checked for expiry.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct CertSettings {
+    /// PEM file paths or `host[:port]` endpoints (port 443 by default).
+    pub targets: Vec<String>,
+    /// Days before expiry at which a warning alert is raised.
+    pub warn_days: u32,
+}
+
+impl Default for CertSettings {
+    fn default() -> Self {
+        Self {
+            targets: Vec::new(),
+            warn_days: 14,
+        }
+    }
+}
+
+/// `alert_rules` section: thresholds of the health alert rules (see `health.rs`).
+/// Temperatures are in °C, usage in percent of the total.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct AlertRuleSettings {
+    pub gpu_temp_warn: f32,
+    pub gpu_temp_critical: f32,
+    /// hwmon chips, e.g. the CPU package.
+    pub chip_temp_warn: f32,
+    pub chip_temp_critical: f32,
+    /// Used for drives that report no maximum temperature.
+    pub drive_temp_warn: f32,
+    /// Degrees above the drive's warning temperature at which its alert is critical.
+    pub drive_temp_critical_margin: f32,
+    pub memory_warn_percent: f32,
+    pub memory_critical_percent: f32,
+    /// Share of time some tasks stalled on memory (PSI `some avg10`) for a warning.
+    pub memory_pressure_warn: f32,
+    /// Share of time all tasks stalled on memory (PSI `full avg10`) for a critical alert.
+    pub memory_pressure_critical: f32,
+    pub disk_warn_percent: f32,
+    pub disk_critical_percent: f32,
+}
+
+impl Default for AlertRuleSettings {
+    fn default() -> Self {
+        use crate::alerts::{CRITICAL_FRACTION, WARNING_FRACTION};
+        use crate::health::{
+            CHIP_TEMP_CRITICAL, CHIP_TEMP_WARN, DRIVE_TEMP_CRITICAL_MARGIN, DRIVE_TEMP_WARN,
+            GPU_TEMP_CRITICAL, GPU_TEMP_WARN, MEMORY_PRESSURE_CRITICAL, MEMORY_PRESSURE_WARN,
+        };
+        Self {
+            gpu_temp_warn: GPU_TEMP_WARN,
+            gpu_temp_critical: GPU_TEMP_CRITICAL,
+            chip_temp_warn: CHIP_TEMP_WARN,
+            chip_temp_critical: CHIP_TEMP_CRITICAL,
+            drive_temp_warn: DRIVE_TEMP_WARN,
+            drive_temp_critical_margin: DRIVE_TEMP_CRITICAL_MARGIN,
+            memory_warn_percent: WARNING_FRACTION * 100.0,
+            memory_critical_percent: CRITICAL_FRACTION * 100.0,
+            memory_pressure_warn: MEMORY_PRESSURE_WARN,
+            memory_pressure_critical: MEMORY_PRESSURE_CRITICAL,
+            disk_warn_percent: WARNING_FRACTION * 100.0,
+            disk_critical_percent: CRITICAL_FRACTION * 100.0,
+        }
+    }
+}
+
+impl AlertRuleSettings {
+    /// Checks that every threshold is in range and warnings come before critical alerts.
+    pub fn validate(&self) -> Result<(), String> {
+        let ranges = [
+            ("gpu_temp_warn", self.gpu_temp_warn, 0.0, 150.0),
+            ("gpu_temp_critical", self.gpu_temp_critical, 0.0, 150.0),
+            ("chip_temp_warn", self.chip_temp_warn, 0.0, 150.0),
+            ("chip_temp_critical", self.chip_temp_critical, 0.0, 150.0),
+            ("drive_temp_warn", self.drive_temp_warn, 0.0, 150.0),
+            (
+                "drive_temp_critical_margin",
+                self.drive_temp_critical_margin,
+                0.0,
+                100.0,
+            ),
+            ("memory_warn_percent", self.memory_warn_percent, 0.0, 100.0),
+            (
+                "memory_critical_percent",
+                self.memory_critical_percent,
+                0.0,
+                100.0,
+            ),
+            (
+                "memory_pressure_warn",
+                self.memory_pressure_warn,
+                0.0,
+                100.0,
+            ),
+            (
+                "memory_pressure_critical",
+                self.memory_pressure_critical,
+                0.0,
+                100.0,
+            ),
+            ("disk_warn_percent", self.disk_warn_percent, 0.0, 100.0),
+            (
+                "disk_critical_percent",
+                self.disk_critical_percent,
+                0.0,
+                100.0,
+            ),
+        ];
+        for (name, value, min, max) in ranges {
+            if !(min..=max).contains(&value) {
+                return Err(format!(
+                    "{} is {} (expected {} to {})",
+                    name, value, min, max
+                ));
+            }
+        }
+        let pairs = [
+            ("gpu_temp", self.gpu_temp_warn, self.gpu_temp_critical),
+            ("chip_temp", self.chip_temp_warn, self.chip_temp_critical),
+            (
+                "memory",
+                self.memory_warn_percent,
+                self.memory_critical_percent,
+            ),
+            ("disk", self.disk_warn_percent, self.disk_critical_percent),
+        ];
+        for (name, warn, critical) in pairs {
+            if warn > critical {
+                return Err(format!(
+                    "{} warning threshold ({}) is above the critical one ({})",
+                    name, warn, critical
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Kind of workload a scoped alert rule targets.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleScope {
+    /// Every process with the name, summed.
+    #[default]
+    Process,
+    /// A systemd unit's cgroup.
+    Unit,
+    /// A Docker or Podman container (ID prefix) or nspawn machine (name).
+    Container,
+    /// A libvirt domain.
+    Vm,
+}
+
+/// Reading a scoped alert rule compares against its thresholds.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ScopedMetric {
+    /// Percent of one core (of the VM's vCPUs for VMs).
+    #[default]
+    CpuPercent,
+    MemoryGb,
+}
+
+/// One `scoped_rules` entry, e.g. "postgres memory above 8 GB" (see `scopedrules.rs`).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default)]
+pub struct ScopedRule {
+    pub scope: RuleScope,
+    /// Process name, unit ("postgresql.service"), container ID or machine name, or VM name.
+    pub target: String,
+    pub metric: ScopedMetric,
+    pub warn: f32,
+    /// `None` raises only warnings.
+    pub critical: Option<f32>,
+}
+
+impl ScopedRule {
+    /// Checks that the rule names a target and its thresholds are positive and ordered.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.target.trim().is_empty() {
+            return Err("scoped rule without a target".to_string());
+        }
+        if self.warn <= 0.0 || !self.warn.is_finite() {
+            return Err(format!("{}: warn must be positive", self.target));
+        }
+        if self
+            .critical
+            .is_some_and(|c| c < self.warn || !c.is_finite())
+        {
+            return Err(format!("{}: critical must not be below warn", self.target));
+        }
+        Ok(())
+    }
+}
+
+/// Where an alert is announced.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyChannel {
+    /// Desktop notification (`notify-send`) and the alert banner.
+    Desktop,
+    /// Only the alert banner of the main window.
+    #[default]
+    Badge,
+    /// POST to the webhook URL and the alert banner.
+    Webhook,
+    /// Not announced at all; the alert still counts towards the health score.
+    None,
+}
+
+/// A daily period in which desktop and webhook notifications are held back, e.g.
+/// 22:00-07:00. Times are local; a period ending before it starts runs past midnight.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct QuietHours {
+    /// "HH:MM".
+    pub start: String,
+    /// "HH:MM".
+    pub end: String,
+    /// Days the period starts on ("mon" ... "sun"); empty means every day.
+    pub days: Vec<String>,
+}
+
+impl Default for QuietHours {
+    fn default() -> Self {
+        Self {
+            start: "22:00".to_string(),
+            end: "07:00".to_string(),
+            days: Vec::new(),
+        }
+    }
+}
+
+/// `notifications` section: the channel of each alert rule and the quiet hours (see
+/// `notify.rs`).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct NotificationSettings {
+    /// Channel of alerts no entry of `rules` matches.
+    pub default_channel: NotifyChannel,
+    /// Alert id prefix (e.g. "smart." or "thermal.drive.") → channel; the longest
+    /// matching prefix wins.
+    pub rules: BTreeMap<String, NotifyChannel>,
+    pub quiet_hours: Vec<QuietHours>,
+    /// Critical alerts are still announced during quiet hours.
+    pub critical_ignores_quiet_hours: bool,
+    /// URL for the `webhook` channel; empty uses the `webhook` section's URL.
+    pub webhook_url: String,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            default_channel: NotifyChannel::Badge,
+            // A finished job is only useful news if it reaches the user
+            rules: BTreeMap::from([("gpujob.".to_string(), NotifyChannel::Desktop)]),
+            quiet_hours: Vec::new(),
+            critical_ignores_quiet_hours: true,
+            webhook_url: String::new(),
+        }
+    }
+}
+
+/// `gpu_jobs` section: notices a GPU going idle after a long busy stretch, e.g. a
+/// finished training run (see `gpujobs.rs`).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct GpuJobSettings {
+    pub enabled: bool,
+    /// Utilization (%) at or above which the GPU counts as busy.
+    pub busy_percent: f32,
+    /// Minutes the GPU must stay busy before its idling counts as a finished job.
+    pub busy_minutes: u32,
+    /// Utilization (%) below which the GPU counts as idle.
+    pub idle_percent: f32,
+    /// Minutes the GPU must stay idle before the job is reported finished.
+    pub idle_minutes: u32,
+}
+
+impl Default for GpuJobSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            busy_percent: 80.0,
+            busy_minutes: 10,
+            idle_percent: 10.0,
+            idle_minutes: 3,
+        }
+    }
+}
+
+/// `frame_time` section: follows the CSV logs of MangoHud for the FPS and frame time of
+/// the running game (see `frametime.rs`).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct FrameTimeSettings {
+    pub enabled: bool,
+    /// MangoHud's `output_folder` ("~/" is the home directory).
+    pub log_dir: String,
+}
+
+impl Default for FrameTimeSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            log_dir: "~/mangohud".to_string(),
+        }
+    }
+}
+
+/// What `idle_action` does once the machine has been idle long enough.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PowerAction {
+    #[default]
+    Suspend,
+    Shutdown,
+}
+
+impl PowerAction {
+    /// Argument of the `--power-action` helper.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PowerAction::Suspend => "suspend",
+            PowerAction::Shutdown => "shutdown",
+        }
+    }
+}
+
+/// `idle_action` section: suspends or shuts the machine down after CPU, GPU and network
+/// have stayed below their thresholds for `idle_minutes` (see `idleaction.rs`).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct IdleActionSettings {
+    pub enabled: bool,
+    pub action: PowerAction,
+    /// Minutes every metric must stay below its threshold.
+    pub idle_minutes: u32,
+    /// Overall CPU usage (%) below which the CPU counts as idle.
+    pub cpu_percent: f32,
+    /// Utilization (%) of the busiest GPU below which the GPUs count as idle.
+    pub gpu_percent: f32,
+    /// Combined receive and transmit rate (KB/s) of all interfaces but `lo`.
+    pub network_kbps: f32,
+    /// Also require no keyboard or mouse input, so a user reading a page isn't cut off.
+    pub require_user_idle: bool,
+    /// Seconds the countdown banner is shown before the action runs.
+    pub countdown_secs: u32,
+}
+
+impl Default for IdleActionSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            action: PowerAction::Suspend,
+            idle_minutes: 30,
+            cpu_percent: 10.0,
+            gpu_percent: 10.0,
+            network_kbps: 50.0,
+            require_user_idle: true,
+            countdown_secs: 60,
+        }
+    }
+}
+
+/// `data_budget` section: warns as the network traffic of the billing period approaches
+/// a monthly cap (see `budget.rs`).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct DataBudgetSettings {
+    pub enabled: bool,
+    /// Cap per billing period in GB (10^9 bytes, as providers count).
+    pub monthly_cap_gb: f64,
+    /// Day of the month (1-28) the billing period starts on.
+    pub reset_day: u32,
+    /// Fractions of the cap at which a warning is raised; reaching the cap is critical.
+    pub warn_fractions: Vec<f64>,
+    /// Interfaces counted; empty counts all but loopback.
+    pub interfaces: Vec<String>,
+    /// Count uploads as well as downloads.
+    pub count_upload: bool,
+}
+
+impl Default for DataBudgetSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            monthly_cap_gb: 1000.0,
+            reset_day: 1,
+            warn_fractions: vec![0.5, 0.8, 0.9],
+            interfaces: Vec::new(),
+            count_upload: true,
+        }
+    }
+}
+
+/// `backups` section: backup jobs whose last success is checked (see `backups.rs`).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct BackupSettings {
+    /// Paths, or commands prefixed with `!` that print the last success time.
+    pub jobs: Vec<String>,
+    /// Hours after the last success at which a backup counts as stale.
+    pub max_age_hours: u32,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        // A daily job with some slack
+        Self {
+            jobs: Vec::new(),
+            max_age_hours: 26,
+        }
+    }
+}
+
+/// One `custom_metrics` entry: a command whose output is charted (see `custommetrics.rs`).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct CustomMetric {
+    pub name: String,
+    /// Run with `sh -c`; the first number it prints is the value.
+    pub command: String,
+    /// Seconds between two runs.
+    pub interval_secs: u64,
+    /// Shown after the value, e.g. "°C" or "W".
+    pub unit: String,
+    /// Fixed chart maximum; `None` scales to the largest value in view.
+    pub max: Option<f32>,
+}
+
+impl Default for CustomMetric {
+    fn default() -> Self {
+        Self {
+            name: "Custom".to_string(),
+            command: String::new(),
+            interval_secs: 5,
+            unit: String::new(),
+            max: None,
+        }
+    }
+}
+
+/// One `derived_metrics` entry: a series computed from others (see `derived.rs`).
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct DerivedMetric {
+    pub name: String,
+    /// Arithmetic over Overview series ids, e.g. `net.eth0.rx + net.wlan0.rx`.
+    pub expression: String,
+    /// Shown after the value.
+    pub unit: String,
+}
+
+/// `chart_scales` section: per panel, scale the charts to their own largest value or to a
+/// fixed maximum, which keeps charts of different devices comparable side by side.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct ChartScaleSettings {
+    pub network_auto: bool,
+    /// Fixed maximum of the network charts, in MB/s.
+    pub network_max_mbps: u64,
+    pub disk_auto: bool,
+    /// Fixed maximum of the drive await charts, in milliseconds.
+    pub disk_await_max_ms: u64,
+    /// Fixed maximum of the drive queue depth charts.
+    pub disk_queue_max: u64,
+}
+
+impl Default for ChartScaleSettings {
+    fn default() -> Self {
+        Self {
+            network_auto: true,
+            network_max_mbps: 100,
+            disk_auto: true,
+            disk_await_max_ms: 50,
+            disk_queue_max: 8,
+        }
+    }
+}
+
+/// `chart_overlays` section: rolling average and 95th percentile lines drawn over the
+/// CPU, memory and network charts, switched on per chart (see `History::overlay`).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct ChartOverlaySettings {
+    /// Seconds the rolling average covers.
+    pub average_secs: u64,
+    /// Charts with the average line: "cpu", "memory" or "net.<interface>".
+    pub average: Vec<String>,
+    /// Charts with the 95th percentile line.
+    pub p95: Vec<String>,
+}
+
+impl Default for ChartOverlaySettings {
+    fn default() -> Self {
+        Self {
+            average_secs: 10,
+            average: Vec::new(),
+            p95: Vec::new(),
+        }
+    }
+}
+
+impl ChartOverlaySettings {
+    /// Switches overlay `kind` ("average" or "p95") of `chart` on or off.
+    pub fn toggle(&mut self, chart: &str, kind: &str) {
+        let charts = match kind {
+            "average" => &mut self.average,
+            "p95" => &mut self.p95,
+            _ => return,
+        };
+        if let Some(pos) = charts.iter().position(|c| c == chart) {
+            charts.remove(pos);
+        } else {
+            charts.push(chart.to_string());
+        }
+    }
+}
+
+/// `webhook` section: POSTs the `MetricsSnapshot` JSON to a URL at an interval.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct WebhookSettings {
+    pub enabled: bool,
+    pub url: String,
+    /// Seconds between two deliveries.
+    pub interval_secs: u64,
+}
+
+impl Default for WebhookSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: String::new(),
+            interval_secs: 60,
+        }
+    }
+}
+
+impl WebhookSettings {
+    /// Checks that `url` is an absolute http(s) URL.
+    pub fn validate_url(url: &str) -> Result<(), String> {
+        let url = url.trim();
+        let rest = url
+            .strip_prefix("https://")
+            .or_else(|| url.strip_prefix("http://"))
+            .ok_or("URL must start with http:// or https://")?;
+        if rest.is_empty() || rest.starts_with('/') || url.contains(char::is_whitespace) {
+            return Err("URL is missing a host name".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// `prometheus` section: serves the metrics for Prometheus to scrape (see `prometheus.rs`).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct PrometheusSettings {
+    pub enabled: bool,
+    /// Address to listen on; `0.0.0.0` allows scrapes from other machines.
+    pub bind_address: String,
+    pub port: u16,
+}
+
+impl Default for PrometheusSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: "127.0.0.1".to_string(),
+            port: 9813,
+        }
+    }
+}
+
+/// `grpc` section: serves the snapshot stream and history queries (see `grpc.rs`).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct GrpcSettings {
+    pub enabled: bool,
+    /// Address to listen on; `0.0.0.0` allows clients on other machines.
+    pub bind_address: String,
+    pub port: u16,
+}
+
+impl Default for GrpcSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: "127.0.0.1".to_string(),
+            port: 9815,
+        }
+    }
+}
+
+/// `overlay` section: serves the streaming overlay page (see `overlay.rs`).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct OverlaySettings {
+    pub enabled: bool,
+    /// Address to listen on; `0.0.0.0` allows a streaming PC to load the page.
+    pub bind_address: String,
+    pub port: u16,
+}
+
+impl Default for OverlaySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: "127.0.0.1".to_string(),
+            port: 9814,
+        }
+    }
+}
+
+/// Checks the `bind_address` of the `prometheus`, `overlay` and `grpc` sections: an IPv4
+/// address or a host name. The port is appended after a colon, which rules out IPv6.
+pub fn validate_bind_address(address: &str) -> Result<(), String> {
+    let address = address.trim();
+    if address.is_empty() {
+        return Err("Enter an address to listen on, e.g. 127.0.0.1".to_string());
+    }
+    if address.parse::<std::net::Ipv4Addr>().is_ok() {
+        return Ok(());
+    }
+    if address.contains(':') {
+        return Err("IPv6 addresses are not supported".to_string());
+    }
+    let host_name = address.split('.').all(|label| {
+        !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    });
+    if !host_name {
+        return Err(format!("{} is not an IPv4 address or host name", address));
+    }
+    Ok(())
+}
+
+/// `long_term_history` section: per-minute statistics stored in SQLite (see `longterm.rs`).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct LongTermHistorySettings {
+    pub enabled: bool,
+    /// Days of samples to keep; 0 keeps them forever.
+    pub retention_days: u32,
+}
+
+impl Default for LongTermHistorySettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            retention_days: 90,
+        }
+    }
+}
+
+/// `scripting` section: a Rhai script run on every snapshot (see `scripting.rs`).
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct ScriptSettings {
+    pub enabled: bool,
+    /// Script file; empty means `script.rhai` next to `config.json`.
+    pub path: String,
+    /// Operations one run may take before it is aborted; 0 uses the default.
+    pub max_operations: u64,
+}
+
+impl ScriptSettings {
+    /// The script file to run.
+    pub fn script_path(&self) -> PathBuf {
+        if self.path.trim().is_empty() {
+            AppSettings::get_path().with_file_name("script.rhai")
+        } else {
+            PathBuf::from(self.path.trim())
+        }
+    }
+}
+
+/// `csv` section: appends selected metrics to daily-rotated CSV files.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default)]
+pub struct CsvSettings {
+    pub enabled: bool,
+    /// Metric groups to write (see `csvlog::CSV_METRICS`).
+    pub metrics: Vec<String>,
+    /// Seconds between two rows.
+    pub interval_secs: u64,
+    /// Days of files to keep; 0 keeps them forever.
+    pub retention_days: u64,
+}
+
+impl Default for CsvSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            metrics: crate::csvlog::CSV_METRICS
+                .iter()
+                .map(|m| m.to_string())
+                .collect(),
+            interval_secs: 10,
+            retention_days: 30,
+        }
+    }
+}
+
+impl CsvSettings {
+    /// Parses a comma separated list of metric groups, rejecting unknown names.
+    pub fn parse_metrics(list: &str) -> Result<Vec<String>, String> {
+        let metrics: Vec<String> = list
+            .split(',')
+            .map(|m| m.trim().to_lowercase())
+            .filter(|m| !m.is_empty())
+            .collect();
+        if metrics.is_empty() {
+            return Err("Select at least one metric group".to_string());
+        }
+        if let Some(unknown) = metrics
+            .iter()
+            .find(|m| !crate::csvlog::CSV_METRICS.contains(&m.as_str()))
+        {
+            return Err(format!(
+                "Unknown metric group '{}' (expected {})",
+                unknown,
+                crate::csvlog::CSV_METRICS.join(", ")
+            ));
+        }
+        Ok(metrics)
+    }
 }
 
 impl Default for AppSettings {
@@ -39,7 +925,46 @@ impl Default for AppSettings {
             gpu_color: "#9b59b6".to_string(), // Purple
             net_color: "#e67e22".to_string(), // Orange
             cpu_core_colors: Vec::new(),
+            cpu_view: 0,
             refresh_rate_ms: 500,
+            history_window_secs: 60,
+            watched_processes: Vec::new(),
+            app_profiles: BTreeMap::new(),
+            allow_gpu_power_control: false,
+            poll_suspended_gpu: false,
+            allow_fan_control: false,
+            show_network_namespaces: false,
+            totals_since_install: false,
+            data_budget: DataBudgetSettings::default(),
+            enable_packet_capture: false,
+            enable_dns_probe: false,
+            dns_probe_host: "example.com".to_string(),
+            remote_hosts: Vec::new(),
+            webhook: WebhookSettings::default(),
+            csv: CsvSettings::default(),
+            prometheus: PrometheusSettings::default(),
+            overlay: OverlaySettings::default(),
+            grpc: GrpcSettings::default(),
+            forward_alerts_to_syslog: false,
+            alert_rules: AlertRuleSettings::default(),
+            scoped_rules: Vec::new(),
+            gpu_jobs: GpuJobSettings::default(),
+            idle_action: IdleActionSettings::default(),
+            frame_time: FrameTimeSettings::default(),
+            notifications: NotificationSettings::default(),
+            certificates: CertSettings::default(),
+            backups: BackupSettings::default(),
+            collectors: CollectorSettings::default(),
+            chart_scales: ChartScaleSettings::default(),
+            chart_overlays: ChartOverlaySettings::default(),
+            aliases: BTreeMap::new(),
+            pinned_series: Vec::new(),
+            detached_panels: Vec::new(),
+            desktop_widget: DesktopWidgetSettings::default(),
+            custom_metrics: Vec::new(),
+            derived_metrics: Vec::new(),
+            long_term_history: LongTermHistorySettings::default(),
+            scripting: ScriptSettings::default(),
         }
     }
 }
@@ -57,16 +982,74 @@ impl AppSettings {
         }
     }
 
+    /// Parses comma separated `name=alias` pairs, ignoring malformed entries.
+    pub fn parse_aliases(list: &str) -> BTreeMap<String, String> {
+        list.split(',')
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(name, alias)| (name.trim().to_string(), alias.trim().to_string()))
+            .filter(|(name, alias)| !name.is_empty() && !alias.is_empty())
+            .collect()
+    }
+
+    /// Formats `aliases` as editable `name=alias` pairs.
+    pub fn format_aliases(aliases: &BTreeMap<String, String>) -> String {
+        aliases
+            .iter()
+            .map(|(name, alias)| format!("{}={}", name, alias))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Parses semicolon separated `name=expression` entries, ignoring malformed ones.
+    /// Units are kept from the `previous` entry of the same name.
+    pub fn parse_derived_metrics(list: &str, previous: &[DerivedMetric]) -> Vec<DerivedMetric> {
+        list.split(';')
+            .filter_map(|entry| entry.split_once('='))
+            .map(|(name, expression)| (name.trim(), expression.trim()))
+            .filter(|(name, expression)| !name.is_empty() && !expression.is_empty())
+            .map(|(name, expression)| DerivedMetric {
+                name: name.to_string(),
+                expression: expression.to_string(),
+                unit: previous
+                    .iter()
+                    .find(|m| m.name == name)
+                    .map(|m| m.unit.clone())
+                    .unwrap_or_default(),
+            })
+            .collect()
+    }
+
+    /// Formats `metrics` as editable `name=expression` entries.
+    pub fn format_derived_metrics(metrics: &[DerivedMetric]) -> String {
+        metrics
+            .iter()
+            .map(|m| format!("{}={}", m.name, m.expression))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
     pub fn load() -> Self {
         let path = Self::get_path();
         if let Ok(content) = fs::read_to_string(&path) {
-            if let Ok(settings) = serde_json::from_str(&content) {
+            if let Ok(mut settings) = serde_json::from_str::<Self>(&content) {
+                settings.sanitize();
                 return settings;
             }
         }
         Self::default()
     }
 
+    /// Clamps values a hand-edited settings file could set to something pathological
+    /// (e.g. a 1 ms refresh rate with a 30-minute chart window).
+    fn sanitize(&mut self) {
+        self.refresh_rate_ms = self
+            .refresh_rate_ms
+            .clamp(MIN_REFRESH_RATE_MS, MAX_REFRESH_RATE_MS);
+        self.history_window_secs = self
+            .history_window_secs
+            .clamp(MIN_HISTORY_WINDOW_SECS, MAX_HISTORY_WINDOW_SECS);
+    }
+
     pub fn save(&self) {
         let path = Self::get_path();
         if let Ok(json) = serde_json::to_string_pretty(self) {
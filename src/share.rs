@@ -0,0 +1,190 @@
+//! # Snapshot Sharing Module
+//!
+//! This module implements the "Share snapshot" actions of the File menu: a compact text
+//! summary of the machine (hardware like neofetch, plus the current utilization) for
+//! pasting into a chat or bug report, and a JSON file with the same data for tools.
+//! Both are built from `StaticSystemInfo` and `MetricsSnapshot`, so they match what the
+//! Information and Usage tabs show.
+//!
+//! The clipboard is set through `wl-copy` on Wayland and `xclip` or `xsel` on X11, the
+//! same way the shell would, so the text stays available after the menu closes.
+
+use crate::monitor::StaticSystemInfo;
+use crate::snapshot::MetricsSnapshot;
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// The shareable JSON document.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SharedSnapshot {
+    pub system: StaticSystemInfo,
+    pub metrics: MetricsSnapshot,
+}
+
+/// Formats seconds as "2d 3h 4m", like the Information tab.
+pub fn format_uptime(secs: u64) -> String {
+    format!(
+        "{}d {}h {}m",
+        secs / 86400,
+        (secs % 86400) / 3600,
+        (secs % 3600) / 60
+    )
+}
+
+/// Formats a byte rate as "1.2 MB/s" or "340 KB/s".
+pub fn format_rate(bytes_per_sec: f64) -> String {
+    if bytes_per_sec > 1024.0 * 1024.0 {
+        format!("{:.1} MB/s", bytes_per_sec / 1024.0 / 1024.0)
+    } else {
+        format!("{:.0} KB/s", bytes_per_sec / 1024.0)
+    }
+}
+
+/// Hardware and OS lines ("OS: Fedora Linux 41", ...), headed by the host name.
+pub fn format_system(info: &StaticSystemInfo, uptime_secs: u64) -> Vec<String> {
+    let mut lines = vec![
+        info.hostname.clone(),
+        "-".repeat(info.hostname.chars().count().max(1)),
+        format!("OS: {}", info.os_name),
+        format!("Kernel: {}", info.kernel),
+        format!("Uptime: {}", format_uptime(uptime_secs)),
+        format!(
+            "CPU: {} ({} threads) @ {}",
+            info.cpu_brand, info.cores, info.cpu_freq
+        ),
+    ];
+    if !info.gpu_names.is_empty() {
+        lines.push(format!("GPU: {}", info.gpu_names));
+    }
+    lines.push(format!("Memory: {}", info.total_mem));
+    if info.motherboard != "Unknown" {
+        lines.push(format!("Board: {} ({})", info.motherboard, info.boot_mode));
+    }
+    for disk in info.individual_disks.lines() {
+        lines.push(format!("Disk: {}", disk));
+    }
+    lines
+}
+
+/// Current utilization lines: CPU, memory, GPUs, network rates, file systems and alerts.
+pub fn format_utilization(metrics: &MetricsSnapshot) -> Vec<String> {
+    let mut lines = vec![format!("CPU usage: {:.0}%", metrics.cpu.usage_percent)];
+    if metrics.memory.total_gb > 0.0 {
+        lines.push(format!(
+            "Memory usage: {:.1} / {:.1} GB ({:.0}%)",
+            metrics.memory.used_gb,
+            metrics.memory.total_gb,
+            metrics.memory.used_gb / metrics.memory.total_gb * 100.0
+        ));
+    }
+    for gpu in &metrics.gpus {
+        let temp = gpu
+            .temperature
+            .map(|t| format!(", {:.0} °C", t))
+            .unwrap_or_default();
+        lines.push(format!(
+            "GPU usage: {} {:.0}%, {:.1} / {:.1} GB{}",
+            gpu.name,
+            gpu.util_percent,
+            gpu.mem_used_mb / 1024.0,
+            gpu.mem_total_mb / 1024.0,
+            temp
+        ));
+    }
+    for net in metrics
+        .networks
+        .iter()
+        .filter(|n| n.total_rx_bytes + n.total_tx_bytes > 0)
+    {
+        lines.push(format!(
+            "Network: {} ↓ {} ↑ {}",
+            net.interface,
+            format_rate(net.rx_bytes_per_sec),
+            format_rate(net.tx_bytes_per_sec)
+        ));
+    }
+    for disk in metrics.disks.iter().filter(|d| d.total_bytes > 0) {
+        let used = disk.total_bytes.saturating_sub(disk.available_bytes);
+        lines.push(format!(
+            "Space: {} {:.1} / {:.1} GB ({:.0}%)",
+            disk.mount_point,
+            used as f64 / 1e9,
+            disk.total_bytes as f64 / 1e9,
+            used as f64 / disk.total_bytes as f64 * 100.0
+        ));
+    }
+    if !metrics.alerts.is_empty() {
+        lines.push(format!("Alerts: {}", metrics.alerts.len()));
+        for alert in &metrics.alerts {
+            lines.push(format!("  {}: {}", alert.severity, alert.message));
+        }
+    }
+    lines
+}
+
+/// The text copied by "Copy snapshot".
+pub fn format_summary(snapshot: &SharedSnapshot) -> String {
+    let mut lines = format_system(&snapshot.system, snapshot.metrics.uptime_secs);
+    lines.push(String::new());
+    lines.extend(format_utilization(&snapshot.metrics));
+    lines.push(format!(
+        "Sampled {}",
+        crate::csvlog::format_timestamp(snapshot.metrics.timestamp)
+    ));
+    lines.join("\n")
+}
+
+/// Copies `text` to the clipboard and returns the tool that did it.
+pub fn copy_to_clipboard(text: &str) -> Result<&'static str, String> {
+    let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+    let tools: [(&'static str, &[&str], bool); 3] = [
+        ("wl-copy", &[], wayland),
+        ("xclip", &["-selection", "clipboard"], true),
+        ("xsel", &["--clipboard", "--input"], true),
+    ];
+    for (tool, args, usable) in tools {
+        if !usable {
+            continue;
+        }
+        let Ok(mut child) = Command::new(tool)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue;
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            let _ = stdin.write_all(text.as_bytes());
+        }
+        if child.wait().is_ok_and(|s| s.success()) {
+            return Ok(tool);
+        }
+    }
+    Err("No clipboard tool found (install wl-clipboard, xclip or xsel)".to_string())
+}
+
+fn snapshot_dir() -> PathBuf {
+    ProjectDirs::from("com", "gjallarhorn", "gjallarhorn")
+        .map(|dirs| dirs.data_dir().join("snapshots"))
+        .unwrap_or_else(|| PathBuf::from("snapshots"))
+}
+
+/// Writes `snapshot` as `snapshots/snapshot-<time>.json` in the data directory and
+/// returns the path.
+pub fn write_json(snapshot: &SharedSnapshot) -> Result<PathBuf, String> {
+    let dir = snapshot_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    let name = format!(
+        "snapshot-{}.json",
+        crate::csvlog::format_timestamp(snapshot.metrics.timestamp).replace(':', "-")
+    );
+    let path = dir.join(name);
+    let json = serde_json::to_string_pretty(snapshot).map_err(|e| e.to_string())?;
+    std::fs::write(&path, json).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(path)
+}
@@ -0,0 +1,120 @@
+//! # Shell Command Module
+//!
+//! This module runs the user's shell commands (custom metrics, `!command` backup jobs)
+//! with a deadline. Each command runs with `sh -c` in its own process group, so a timeout
+//! also kills everything it started. Its stdout and stderr are read by two threads while
+//! it runs, so a chatty command can't block on a full pipe; only the first
+//! `MAX_OUTPUT_BYTES` of each are kept.
+
+use std::io::Read;
+use std::os::unix::process::CommandExt;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+
+/// Output kept per stream; the rest is read and dropped.
+pub const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+/// Time between two checks whether the command exited.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Reads `pipe` to the end in a thread, keeping its first `MAX_OUTPUT_BYTES`.
+fn drain(mut pipe: impl Read + Send + 'static) -> Receiver<Vec<u8>> {
+    let (sender, receiver) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut kept = Vec::new();
+        let _ = pipe
+            .by_ref()
+            .take(MAX_OUTPUT_BYTES as u64)
+            .read_to_end(&mut kept);
+        let _ = std::io::copy(&mut pipe, &mut std::io::sink());
+        let _ = sender.send(kept);
+    });
+    receiver
+}
+
+/// Kills the process group `pgid`.
+fn kill_group(pgid: u32) {
+    unsafe {
+        libc::kill(-(pgid as libc::pid_t), libc::SIGKILL);
+    }
+}
+
+/// Runs `command` with `sh -c` and returns what it printed to stdout. Fails with the first
+/// line of its stderr if it exits unsuccessfully, and kills it (with everything it
+/// started) after `timeout` or once `stop` is set.
+pub fn run(command: &str, timeout: Duration, stop: &AtomicBool) -> Result<String, String> {
+    let mut child = Command::new("sh")
+        .args(["-c", command])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .process_group(0)
+        .spawn()
+        .map_err(|e| e.to_string())?;
+    let stdout = child.stdout.take().map(drain);
+    let stderr = child.stderr.take().map(drain);
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait().map_err(|e| e.to_string())? {
+            break status;
+        }
+        if Instant::now() >= deadline || stop.load(Ordering::Relaxed) {
+            kill_group(child.id());
+            let _ = child.wait();
+            return Err(format!("Timed out after {}s", timeout.as_secs()));
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    };
+    // Programs left running in the background would keep the pipes open
+    kill_group(child.id());
+
+    let collect = |receiver: Option<Receiver<Vec<u8>>>| {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        receiver
+            .and_then(|r| r.recv_timeout(remaining.max(POLL_INTERVAL)).ok())
+            .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+            .unwrap_or_default()
+    };
+    let stdout = collect(stdout);
+    if !status.success() {
+        let stderr = collect(stderr);
+        return Err(stderr
+            .lines()
+            .next()
+            .unwrap_or("Command failed")
+            .to_string());
+    }
+    Ok(stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    #[test]
+    fn output_larger_than_a_pipe_does_not_block() {
+        let stop = AtomicBool::new(false);
+        let output = run("head -c 1000000 /dev/zero | tr '\\0' x", TIMEOUT, &stop).unwrap();
+        assert_eq!(output.len(), MAX_OUTPUT_BYTES);
+    }
+
+    #[test]
+    fn failures_report_the_first_stderr_line() {
+        let stop = AtomicBool::new(false);
+        let error = run("echo first >&2; echo second >&2; exit 3", TIMEOUT, &stop);
+        assert_eq!(error, Err("first".to_string()));
+    }
+
+    #[test]
+    fn slow_commands_are_killed_at_the_deadline() {
+        let stop = AtomicBool::new(false);
+        let started = Instant::now();
+        let result = run("sleep 30", Duration::from_millis(200), &stop);
+        assert!(result.unwrap_err().starts_with("Timed out"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,418 @@
+//! # S.M.A.R.T Trend Module
+//!
+//! This module keeps a history of the SMART counters that predict drive failure —
+//! reallocated and pending sectors, media errors and wear — so a drive that slowly
+//! degrades is noticed while the overall SMART status still says "PASSED". The counters
+//! come from the worker's `smartctl --json` run; samples are stored per serial number in
+//! the `smart_samples` table of the long-term history database (see `longterm.rs`), at
+//! most once an hour unless a counter changes. They are kept even while the
+//! `long_term_history` section is disabled, for its `retention_days`.
+
+use crate::longterm::LongTermStore;
+use crate::settings::LongTermHistorySettings;
+use log::warn;
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Minimum seconds between two samples of unchanged counters.
+const SAMPLE_INTERVAL_SECS: u64 = 3600;
+/// Span over which counter growth and the wear rate are measured.
+const TREND_WINDOW_SECS: u64 = 30 * 86400;
+/// Shortest span the wear rate is extrapolated from.
+const MIN_WEAR_SPAN_SECS: u64 = 7 * 86400;
+/// Projected days of life left below which worn drives raise a warning.
+pub const WEAR_WARN_DAYS: f64 = 180.0;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS smart_samples (
+        serial TEXT NOT NULL,
+        timestamp INTEGER NOT NULL,
+        reallocated_sectors INTEGER,
+        pending_sectors INTEGER,
+        media_errors INTEGER,
+        wear_percent INTEGER,
+        PRIMARY KEY (serial, timestamp)
+    ) WITHOUT ROWID;
+";
+
+/// Failure-predicting counters of one drive. `None` when the drive does not report one.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq)]
+pub struct SmartCounters {
+    /// ATA attribute 5 (Reallocated_Sector_Ct).
+    pub reallocated_sectors: Option<u64>,
+    /// ATA attribute 197 (Current_Pending_Sector).
+    pub pending_sectors: Option<u64>,
+    /// NVMe media and data integrity errors.
+    pub media_errors: Option<u64>,
+    /// Percent of the rated endurance used (NVMe `percentage_used`, or the SSD wear
+    /// attributes 177/231/233, which count down from 100).
+    pub wear_percent: Option<u8>,
+}
+
+impl SmartCounters {
+    /// Reads the counters from `smartctl --json -a` output.
+    pub fn from_smartctl(v: &serde_json::Value) -> Self {
+        let attribute = |id: u64| {
+            v["ata_smart_attributes"]["table"]
+                .as_array()?
+                .iter()
+                .find(|a| a["id"].as_u64() == Some(id))
+        };
+        let raw = |id: u64| attribute(id).and_then(|a| a["raw"]["value"].as_u64());
+        let nvme = &v["nvme_smart_health_information_log"];
+        let wear_percent = nvme["percentage_used"]
+            .as_u64()
+            .or_else(|| v["endurance_used"]["current_percent"].as_u64())
+            .or_else(|| {
+                [177, 231, 233]
+                    .into_iter()
+                    .find_map(|id| attribute(id).and_then(|a| a["value"].as_u64()))
+                    .map(|remaining| 100u64.saturating_sub(remaining))
+            })
+            .map(|p| p.min(255) as u8);
+        Self {
+            reallocated_sectors: raw(5),
+            pending_sectors: raw(197),
+            media_errors: nvme["media_errors"].as_u64(),
+            wear_percent,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+pub struct SmartSample {
+    /// Seconds since the UNIX epoch.
+    pub timestamp: u64,
+    #[serde(flatten)]
+    pub counters: SmartCounters,
+}
+
+/// How a drive's counters developed over `TREND_WINDOW_SECS`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SmartTrend {
+    pub latest: SmartCounters,
+    /// Span the growth below was measured over, in days.
+    pub span_days: f64,
+    pub reallocated_growth: u64,
+    pub pending_growth: u64,
+    pub media_error_growth: u64,
+    /// Days until the wear reaches 100 % at the current rate; `None` while the wear
+    /// has not moved or the history is too short.
+    pub wear_days_left: Option<f64>,
+}
+
+impl SmartTrend {
+    /// e.g. "Reallocated 12 (+4 in 30 d) · Pending 0 · Wear 23 % (~4.1 years left)".
+    pub fn describe(&self) -> String {
+        let days = self.span_days.round().max(1.0) as u64;
+        let counter = |label: &str, value: Option<u64>, growth: u64| {
+            value.map(|v| match growth {
+                0 => format!("{} {}", label, v),
+                g => format!("{} {} (+{} in {} d)", label, v, g, days),
+            })
+        };
+        let mut parts: Vec<String> = [
+            counter(
+                "Reallocated",
+                self.latest.reallocated_sectors,
+                self.reallocated_growth,
+            ),
+            counter("Pending", self.latest.pending_sectors, self.pending_growth),
+            counter(
+                "Media errors",
+                self.latest.media_errors,
+                self.media_error_growth,
+            ),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        if let Some(wear) = self.latest.wear_percent {
+            parts.push(match self.wear_days_left {
+                Some(left) if left >= 365.0 => {
+                    format!("Wear {} % (~{:.1} years left)", wear, left / 365.0)
+                }
+                Some(left) => format!("Wear {} % (~{:.0} days left)", wear, left),
+                None => format!("Wear {} %", wear),
+            });
+        }
+        parts.join(" · ")
+    }
+}
+
+/// SMART counter history, keyed by drive serial number.
+#[derive(Default)]
+pub struct SmartHistory {
+    drives: BTreeMap<String, Vec<SmartSample>>,
+    /// `None` when the database can't be opened (or for headless monitors); the samples
+    /// then only live in memory.
+    connection: Option<Connection>,
+    /// Days of samples to keep; 0 keeps them forever.
+    retention_days: u32,
+}
+
+/// `smart_history.json` of earlier versions.
+#[derive(Deserialize, Default)]
+struct JsonHistory {
+    drives: BTreeMap<String, Vec<SmartSample>>,
+}
+
+impl SmartHistory {
+    /// Opens the history database and loads the stored samples, moving those of the
+    /// `smart_history.json` file of earlier versions into it.
+    pub fn open(settings: &LongTermHistorySettings) -> Self {
+        let path = LongTermStore::get_path();
+        let json_path = path.with_file_name("smart_history.json");
+        Connection::open(&path)
+            .and_then(|c| Self::with_connection(c, settings, &json_path))
+            .unwrap_or_else(|e| {
+                warn!("Failed to open {}: {}", path.display(), e);
+                Self {
+                    retention_days: settings.retention_days,
+                    ..Default::default()
+                }
+            })
+    }
+
+    /// Uses `connection`, creating the table if needed and importing `json_path` when it
+    /// exists.
+    fn with_connection(
+        mut connection: Connection,
+        settings: &LongTermHistorySettings,
+        json_path: &Path,
+    ) -> rusqlite::Result<Self> {
+        connection.execute_batch(SCHEMA)?;
+        if let Ok(content) = fs::read_to_string(json_path) {
+            let history: JsonHistory = serde_json::from_str(&content).unwrap_or_default();
+            let transaction = connection.transaction()?;
+            for (serial, samples) in &history.drives {
+                for sample in samples {
+                    insert(&transaction, serial, sample)?;
+                }
+            }
+            transaction.commit()?;
+            let _ = fs::remove_file(json_path);
+        }
+
+        let mut drives: BTreeMap<String, Vec<SmartSample>> = BTreeMap::new();
+        {
+            let mut statement = connection.prepare(
+                "SELECT serial, timestamp, reallocated_sectors, pending_sectors, media_errors,
+                     wear_percent
+                 FROM smart_samples ORDER BY serial, timestamp",
+            )?;
+            let rows = statement.query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    SmartSample {
+                        timestamp: row.get(1)?,
+                        counters: SmartCounters {
+                            reallocated_sectors: row.get(2)?,
+                            pending_sectors: row.get(3)?,
+                            media_errors: row.get(4)?,
+                            wear_percent: row.get(5)?,
+                        },
+                    },
+                ))
+            })?;
+            for row in rows {
+                let (serial, sample) = row?;
+                drives.entry(serial).or_default().push(sample);
+            }
+        }
+        let mut history = Self {
+            drives,
+            connection: Some(connection),
+            retention_days: settings.retention_days,
+        };
+        history.prune(crate::markers::now_secs());
+        Ok(history)
+    }
+
+    pub fn set_retention_days(&mut self, days: u32) {
+        self.retention_days = days;
+        self.prune(crate::markers::now_secs());
+    }
+
+    /// Records a drive's counters when they changed or the last sample is an hour old.
+    /// Drives without a serial number are skipped, since device names can change between
+    /// boots.
+    pub fn record(&mut self, serial: &str, counters: SmartCounters) {
+        if serial.is_empty() || serial == "Unknown" || counters.is_empty() {
+            return;
+        }
+        let now = crate::markers::now_secs();
+        let samples = self.drives.entry(serial.to_string()).or_default();
+        if let Some(last) = samples.last() {
+            if last.counters == counters && now < last.timestamp + SAMPLE_INTERVAL_SECS {
+                return;
+            }
+        }
+        let sample = SmartSample {
+            timestamp: now,
+            counters,
+        };
+        samples.push(sample);
+        if let Some(connection) = &self.connection {
+            if let Err(e) = insert(connection, serial, &sample) {
+                warn!("Failed to write SMART history: {}", e);
+            }
+        }
+        self.prune(now);
+    }
+
+    /// Drops the samples older than the retention period.
+    fn prune(&mut self, now: u64) {
+        if self.retention_days == 0 {
+            return;
+        }
+        let oldest = now.saturating_sub(self.retention_days as u64 * 86400);
+        for samples in self.drives.values_mut() {
+            samples.retain(|s| s.timestamp >= oldest);
+        }
+        self.drives.retain(|_, samples| !samples.is_empty());
+        if let Some(connection) = &self.connection {
+            if let Err(e) = connection.execute(
+                "DELETE FROM smart_samples WHERE timestamp < ?1",
+                params![oldest],
+            ) {
+                warn!("Failed to prune SMART history: {}", e);
+            }
+        }
+    }
+
+    /// Growth of the counters between the oldest sample in the trend window and the
+    /// latest one.
+    pub fn trend(&self, serial: &str) -> Option<SmartTrend> {
+        let samples = self.drives.get(serial)?;
+        let latest = samples.last()?;
+        let cutoff = latest.timestamp.saturating_sub(TREND_WINDOW_SECS);
+        let first = samples.iter().find(|s| s.timestamp >= cutoff)?;
+        let growth = |pick: fn(&SmartCounters) -> Option<u64>| match (
+            pick(&first.counters),
+            pick(&latest.counters),
+        ) {
+            (Some(old), Some(new)) => new.saturating_sub(old),
+            _ => 0,
+        };
+
+        let span_secs = latest.timestamp - first.timestamp;
+        let wear_days_left = match (first.counters.wear_percent, latest.counters.wear_percent) {
+            (Some(old), Some(new)) if new > old && span_secs >= MIN_WEAR_SPAN_SECS => {
+                let per_day = (new - old) as f64 / (span_secs as f64 / 86400.0);
+                Some(100u8.saturating_sub(new) as f64 / per_day)
+            }
+            _ => None,
+        };
+        Some(SmartTrend {
+            latest: latest.counters,
+            span_days: span_secs as f64 / 86400.0,
+            reallocated_growth: growth(|c| c.reallocated_sectors),
+            pending_growth: growth(|c| c.pending_sectors),
+            media_error_growth: growth(|c| c.media_errors),
+            wear_days_left,
+        })
+    }
+}
+
+/// Writes one sample (kept if one with the same timestamp exists).
+fn insert(connection: &Connection, serial: &str, sample: &SmartSample) -> rusqlite::Result<()> {
+    let counters = &sample.counters;
+    connection.execute(
+        "INSERT OR IGNORE INTO smart_samples (serial, timestamp, reallocated_sectors,
+             pending_sectors, media_errors, wear_percent)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            serial,
+            sample.timestamp,
+            counters.reallocated_sectors,
+            counters.pending_sectors,
+            counters.media_errors,
+            counters.wear_percent
+        ],
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 86_400;
+
+    fn sample(timestamp: u64, pending_sectors: u64) -> SmartSample {
+        SmartSample {
+            timestamp,
+            counters: SmartCounters {
+                pending_sectors: Some(pending_sectors),
+                ..Default::default()
+            },
+        }
+    }
+
+    fn settings(retention_days: u32) -> LongTermHistorySettings {
+        LongTermHistorySettings {
+            retention_days,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn json_history_is_moved_into_the_database() {
+        let now = crate::markers::now_secs();
+        let dir = std::env::temp_dir().join(format!("gjallarhorn-smart-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let json_path = dir.join("smart_history.json");
+        fs::write(
+            &json_path,
+            r#"{"drives":{"S1":[{"timestamp":1000,"pending_sectors":0}]}}"#,
+        )
+        .unwrap();
+
+        let connection = Connection::open_in_memory().unwrap();
+        let mut history =
+            SmartHistory::with_connection(connection, &settings(0), &json_path).unwrap();
+        assert!(!json_path.exists());
+        history.record("S1", sample(now, 3).counters);
+
+        let trend = history.trend("S1").unwrap();
+        assert_eq!(trend.latest.pending_sectors, Some(3));
+        let stored: u32 = history
+            .connection
+            .as_ref()
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM smart_samples", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(stored, 2);
+        let _ = fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn samples_older_than_the_retention_are_pruned() {
+        let now = crate::markers::now_secs();
+        let connection = Connection::open_in_memory().unwrap();
+        connection.execute_batch(SCHEMA).unwrap();
+        insert(&connection, "S1", &sample(now - 40 * DAY, 0)).unwrap();
+        insert(&connection, "S1", &sample(now - 10 * DAY, 1)).unwrap();
+
+        let missing = Path::new("/nonexistent/smart_history.json");
+        let mut history = SmartHistory::with_connection(connection, &settings(0), missing).unwrap();
+        assert_eq!(history.drives["S1"].len(), 2);
+        history.set_retention_days(30);
+        assert_eq!(history.drives["S1"].len(), 1);
+        let stored: u32 = history
+            .connection
+            .as_ref()
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM smart_samples", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(stored, 1);
+    }
+}
@@ -0,0 +1,199 @@
+//! # Metrics Snapshot Module
+//!
+//! This module defines `MetricsSnapshot`, a serializable point-in-time view of everything
+//! `SystemMonitor` collects. It is the common payload of the exporters and integrations
+//! (webhook, CSV, command line output), so they all report the same numbers.
+
+use crate::markers::Marker;
+use crate::monitor::SystemMonitor;
+use serde::{Deserialize, Serialize};
+use sysinfo::System;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct CpuSnapshot {
+    /// Average usage across all cores in percent.
+    pub usage_percent: f32,
+    /// Usage per core in percent.
+    pub cores: Vec<f32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct MemorySnapshot {
+    pub used_gb: f32,
+    pub total_gb: f32,
+    /// 0 without swap.
+    #[serde(default)]
+    pub swap_used_gb: f32,
+    #[serde(default)]
+    pub swap_total_gb: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct GpuSnapshot {
+    pub name: String,
+    pub util_percent: f32,
+    pub mem_used_mb: f32,
+    pub mem_total_mb: f32,
+    pub temperature: Option<f32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct NetworkSnapshot {
+    pub interface: String,
+    pub rx_bytes_per_sec: f64,
+    pub tx_bytes_per_sec: f64,
+    pub total_rx_bytes: u64,
+    pub total_tx_bytes: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct DiskSnapshot {
+    pub name: String,
+    pub mount_point: String,
+    pub total_bytes: u64,
+    pub available_bytes: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct FanSnapshot {
+    pub label: String,
+    pub rpm: u32,
+    pub temperature: Option<f32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AlertSnapshot {
+    pub id: String,
+    /// "warning" or "critical".
+    pub severity: String,
+    pub message: String,
+    pub since: u64,
+}
+
+/// Value of one `derived_metrics` entry (see `derived.rs`).
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DerivedSnapshot {
+    pub name: String,
+    pub unit: String,
+    pub value: f32,
+}
+
+/// Point-in-time view of all collected metrics.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct MetricsSnapshot {
+    /// `monitor::SCHEMA_VERSION` of the producer; 0 for snapshots from older versions.
+    #[serde(default)]
+    pub schema_version: u32,
+    /// Seconds since the UNIX epoch at which the values were sampled.
+    pub timestamp: u64,
+    pub hostname: String,
+    pub uptime_secs: u64,
+    pub cpu: CpuSnapshot,
+    pub memory: MemorySnapshot,
+    pub gpus: Vec<GpuSnapshot>,
+    pub networks: Vec<NetworkSnapshot>,
+    pub disks: Vec<DiskSnapshot>,
+    pub fans: Vec<FanSnapshot>,
+    pub alerts: Vec<AlertSnapshot>,
+    /// Configured derived metrics whose series were all available.
+    #[serde(default)]
+    pub derived: Vec<DerivedSnapshot>,
+    /// Timeline markers within the chart window, oldest first.
+    #[serde(default)]
+    pub markers: Vec<Marker>,
+}
+
+impl MetricsSnapshot {
+    /// Captures the latest values from `monitor` (as of its last `refresh()`).
+    pub fn capture(monitor: &SystemMonitor) -> Self {
+        let cores: Vec<f32> = (0..monitor.get_cpu_count())
+            .map(|i| monitor.get_cpu_history(i).latest().unwrap_or(0.0))
+            .collect();
+        let (used_gb, total_gb) = monitor.get_memory_info();
+        let (swap_used_gb, swap_total_gb) = monitor.get_swap_info();
+        // Network counters are deltas over one refresh interval
+        let interval_secs = monitor.refresh_rate_ms.max(1) as f64 / 1000.0;
+
+        let mut snapshot = Self {
+            schema_version: crate::monitor::SCHEMA_VERSION,
+            // Time of the refresh the values come from, not of the capture
+            timestamp: monitor
+                .get_memory_history()
+                .latest_sample()
+                .map_or_else(crate::markers::now_secs, |s| s.time as u64),
+            hostname: System::host_name().unwrap_or_default(),
+            uptime_secs: monitor.get_uptime(),
+            cpu: CpuSnapshot {
+                usage_percent: cores.iter().sum::<f32>() / cores.len().max(1) as f32,
+                cores,
+            },
+            memory: MemorySnapshot {
+                used_gb,
+                total_gb,
+                swap_used_gb,
+                swap_total_gb,
+            },
+            gpus: monitor
+                .get_gpu_data()
+                .into_iter()
+                .map(|g| GpuSnapshot {
+                    name: g.name,
+                    util_percent: g.util,
+                    mem_used_mb: g.mem_used_mb,
+                    mem_total_mb: g.mem_total_mb,
+                    temperature: g.temperature,
+                })
+                .collect(),
+            networks: monitor
+                .get_network_data()
+                .into_iter()
+                .map(|n| NetworkSnapshot {
+                    interface: n.name,
+                    rx_bytes_per_sec: n.rx_bytes as f64 / interval_secs,
+                    tx_bytes_per_sec: n.tx_bytes as f64 / interval_secs,
+                    total_rx_bytes: n.total_rx_bytes,
+                    total_tx_bytes: n.total_tx_bytes,
+                })
+                .collect(),
+            disks: monitor
+                .get_disk_data()
+                .into_iter()
+                .map(|d| DiskSnapshot {
+                    name: d.name,
+                    mount_point: d.mount_point,
+                    total_bytes: d.total_space_bytes,
+                    available_bytes: d.available_space_bytes,
+                })
+                .collect(),
+            fans: monitor
+                .get_fan_data()
+                .into_iter()
+                .map(|f| FanSnapshot {
+                    label: f.label,
+                    rpm: f.rpm,
+                    temperature: f.temperature,
+                })
+                .collect(),
+            alerts: monitor
+                .get_alerts()
+                .iter()
+                .map(|a| AlertSnapshot {
+                    id: a.id.clone(),
+                    severity: format!("{:?}", a.severity).to_lowercase(),
+                    message: a.message.clone(),
+                    since: a.since,
+                })
+                .collect(),
+            derived: Vec::new(),
+            markers: monitor
+                .markers
+                .in_window(monitor.history_window_secs)
+                .into_iter()
+                .map(|(_, m)| m.clone())
+                .collect(),
+        };
+        // Derived metrics are computed from the series above
+        snapshot.derived = monitor.derived.evaluate(&snapshot);
+        snapshot
+    }
+}
@@ -0,0 +1,239 @@
+//! # Power-Saving Suggestions Module
+//!
+//! This module watches the collected data for common causes of wasted power and turns
+//! them into actionable hints for the Suggestions tab:
+//! - The compositor or display server using noticeable CPU while the user is idle.
+//! - A spinning disk that never gets a quiet stretch long enough to spin down.
+//! - An NVIDIA GPU that stays in its highest performance state (P0) while idle.
+//! - The `performance` CPU frequency governor on a mostly idle machine.
+//!
+//! Observations are fed by `SystemMonitor` on every refresh and need a while to build
+//! up, so a suggestion only appears after its condition has held for several minutes.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use sysinfo::System;
+
+/// Process names of compositors and display servers.
+const COMPOSITORS: [&str; 10] = [
+    "gnome-shell",
+    "kwin_wayland",
+    "kwin_x11",
+    "Xorg",
+    "Xwayland",
+    "sway",
+    "Hyprland",
+    "picom",
+    "weston",
+    "mutter",
+];
+/// Average CPU (percent of one core) while idle above which the compositor is flagged.
+const COMPOSITOR_IDLE_CPU: f32 = 5.0;
+/// Idle samples needed before judging the compositor.
+const MIN_IDLE_SAMPLES: u32 = 60;
+/// Quiet stretch a spinning disk needs to spin down with common standby timeouts.
+const SPIN_DOWN_QUIET: Duration = Duration::from_secs(10 * 60);
+/// Observation time before a disk that never went quiet is flagged.
+const SPIN_DOWN_OBSERVATION: Duration = Duration::from_secs(30 * 60);
+/// GPU utilization below which staying in P0 counts as stuck.
+const GPU_IDLE_UTIL: f32 = 5.0;
+const GPU_STUCK_AFTER: Duration = Duration::from_secs(5 * 60);
+/// Average CPU usage below which the `performance` governor is flagged.
+const GOVERNOR_IDLE_CPU: f32 = 10.0;
+const GOVERNOR_OBSERVATION: Duration = Duration::from_secs(10 * 60);
+
+/// One hint for the Suggestions tab.
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    pub title: String,
+    pub detail: String,
+}
+
+struct DiskQuiet {
+    observed_since: Instant,
+    last_io: Instant,
+    longest_quiet: Duration,
+}
+
+/// Accumulates observations and derives suggestions from them.
+pub struct SuggestionEngine {
+    started: Instant,
+    /// Compositor name → summed CPU percent over idle samples.
+    compositor_idle_cpu: HashMap<String, f32>,
+    idle_samples: u32,
+    disks: HashMap<String, DiskQuiet>,
+    /// Per GPU: since when it has been in P0 at low utilization, and its name.
+    gpu_p0_since: HashMap<usize, (Instant, String)>,
+    cpu_sum: f64,
+    cpu_samples: u64,
+}
+
+/// Whether the block device `name` is a rotational (spinning) disk.
+fn is_rotational(name: &str) -> bool {
+    let path = Path::new("/sys/block").join(name).join("queue/rotational");
+    std::fs::read_to_string(path).is_ok_and(|s| s.trim() == "1")
+}
+
+/// Scaling governor of the first CPU, e.g. "powersave" or "performance".
+fn cpu_governor() -> Option<String> {
+    std::fs::read_to_string("/sys/devices/system/cpu/cpu0/cpufreq/scaling_governor")
+        .ok()
+        .map(|s| s.trim().to_string())
+}
+
+impl Default for SuggestionEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SuggestionEngine {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            compositor_idle_cpu: HashMap::new(),
+            idle_samples: 0,
+            disks: HashMap::new(),
+            gpu_p0_since: HashMap::new(),
+            cpu_sum: 0.0,
+            cpu_samples: 0,
+        }
+    }
+
+    /// Records overall CPU usage, and compositor CPU usage while the user is idle.
+    pub fn observe_cpu(&mut self, system: &System, user_idle: bool) {
+        self.cpu_sum += system.global_cpu_usage() as f64;
+        self.cpu_samples += 1;
+        if !user_idle {
+            return;
+        }
+        self.idle_samples += 1;
+        for process in system.processes().values() {
+            let name = process.name().to_string_lossy();
+            if COMPOSITORS.contains(&name.as_ref()) {
+                *self
+                    .compositor_idle_cpu
+                    .entry(name.to_string())
+                    .or_default() += process.cpu_usage();
+            }
+        }
+    }
+
+    /// Records whether drive `device` had any I/O since the previous refresh.
+    /// Only rotational drives are tracked.
+    pub fn observe_disk(&mut self, device: &str, had_io: bool) {
+        let now = Instant::now();
+        if !self.disks.contains_key(device) {
+            if !is_rotational(device) {
+                return;
+            }
+            self.disks.insert(
+                device.to_string(),
+                DiskQuiet {
+                    observed_since: now,
+                    last_io: now,
+                    longest_quiet: Duration::ZERO,
+                },
+            );
+        }
+        if let Some(disk) = self.disks.get_mut(device) {
+            let quiet = now.duration_since(disk.last_io);
+            disk.longest_quiet = disk.longest_quiet.max(quiet);
+            if had_io {
+                disk.last_io = now;
+            }
+        }
+    }
+
+    /// Records the performance state of NVIDIA GPU `index` (`p0` = highest).
+    pub fn observe_gpu(&mut self, index: usize, name: &str, p0: bool, util: f32) {
+        if p0 && util < GPU_IDLE_UTIL {
+            self.gpu_p0_since
+                .entry(index)
+                .or_insert_with(|| (Instant::now(), name.to_string()));
+        } else {
+            self.gpu_p0_since.remove(&index);
+        }
+    }
+
+    /// Current suggestions, most relevant first.
+    pub fn suggestions(&self) -> Vec<Suggestion> {
+        let mut suggestions = Vec::new();
+
+        let mut gpus: Vec<_> = self.gpu_p0_since.iter().collect();
+        gpus.sort_by_key(|(index, _)| **index);
+        for (_, (since, name)) in gpus {
+            let stuck = since.elapsed();
+            if stuck >= GPU_STUCK_AFTER {
+                suggestions.push(Suggestion {
+                    title: format!("{} stuck in P0 at idle", name),
+                    detail: format!(
+                        "The GPU has stayed in its highest performance state for {} while \
+                         nearly idle. Check for an application keeping it awake (e.g. a \
+                         browser with hardware acceleration or a monitoring overlay), and \
+                         that PowerMizer is not set to \"Prefer Maximum Performance\".",
+                        crate::suspend::format_gap(stuck)
+                    ),
+                });
+            }
+        }
+
+        if self.idle_samples >= MIN_IDLE_SAMPLES {
+            let mut compositors: Vec<(&String, f32)> = self
+                .compositor_idle_cpu
+                .iter()
+                .map(|(name, sum)| (name, sum / self.idle_samples as f32))
+                .filter(|(_, avg)| *avg >= COMPOSITOR_IDLE_CPU)
+                .collect();
+            compositors.sort_by(|a, b| b.1.total_cmp(&a.1));
+            for (name, avg) in compositors {
+                suggestions.push(Suggestion {
+                    title: format!("{} using {:.0}% CPU at idle", name, avg),
+                    detail: "The compositor keeps redrawing while nobody is using the \
+                             machine. Animated wallpapers, widgets or a window that \
+                             updates constantly are common causes; a high refresh rate \
+                             monitor also multiplies the cost."
+                        .to_string(),
+                });
+            }
+        }
+
+        let mut disks: Vec<_> = self.disks.iter().collect();
+        disks.sort_by(|a, b| a.0.cmp(b.0));
+        for (device, disk) in disks {
+            let observed = disk.observed_since.elapsed();
+            let quiet = disk.longest_quiet.max(disk.last_io.elapsed());
+            if observed >= SPIN_DOWN_OBSERVATION && quiet < SPIN_DOWN_QUIET {
+                suggestions.push(Suggestion {
+                    title: format!("{} never spins down", device),
+                    detail: format!(
+                        "The disk has had I/O at least every {} for the last {}, so it \
+                         never reaches its standby timeout. Look for logs, indexers or \
+                         swap on it (`sudo iotop -o`), or mount it with `noatime`.",
+                        crate::suspend::format_gap(quiet.max(Duration::from_secs(1))),
+                        crate::suspend::format_gap(observed)
+                    ),
+                });
+            }
+        }
+
+        let avg_cpu = self.cpu_sum / self.cpu_samples.max(1) as f64;
+        if self.started.elapsed() >= GOVERNOR_OBSERVATION
+            && avg_cpu < GOVERNOR_IDLE_CPU as f64
+            && cpu_governor().as_deref() == Some("performance")
+        {
+            suggestions.push(Suggestion {
+                title: "CPU governor set to \"performance\"".to_string(),
+                detail: format!(
+                    "Average CPU usage is only {:.0}%, but the cores are kept at high \
+                     clocks. The \"powersave\" governor (or \"schedutil\") still boosts \
+                     under load; power-profiles-daemon or TLP can switch it.",
+                    avg_cpu
+                ),
+            });
+        }
+
+        suggestions
+    }
+}
@@ -0,0 +1,49 @@
+//! # Suspend Detection Module
+//!
+//! This module notices when the machine was suspended between two refreshes. The event
+//! loop simply stops while the system sleeps, so afterwards the charts join the values
+//! from before and after the suspend as if no time had passed, and counters sampled
+//! across the gap (network bytes, cgroup CPU time, ...) produce a one-off spike.
+//!
+//! `SystemMonitor` checks the wall-clock time between refreshes; after a large gap it
+//! adds a timeline marker and resets the baselines of its rate calculations.
+
+use std::time::{Duration, SystemTime};
+
+/// Smallest gap treated as a suspend, whatever the refresh interval.
+const MIN_GAP: Duration = Duration::from_secs(10);
+/// A gap must also span this many refresh intervals.
+const GAP_INTERVALS: u32 = 10;
+
+/// Remembers the wall-clock time of the previous refresh.
+#[derive(Default)]
+pub struct SuspendDetector {
+    last_tick: Option<SystemTime>,
+}
+
+impl SuspendDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a refresh and returns the time since the previous one if it is long
+    /// enough to mean the system was suspended (or the event loop was stalled).
+    pub fn check(&mut self, interval: Duration) -> Option<Duration> {
+        let now = SystemTime::now();
+        let gap = self
+            .last_tick
+            .and_then(|last| now.duration_since(last).ok());
+        self.last_tick = Some(now);
+        gap.filter(|gap| *gap >= MIN_GAP.max(interval * GAP_INTERVALS))
+    }
+}
+
+/// Formats a gap for the timeline marker, e.g. "45 s", "12 min" or "3 h 5 min".
+pub fn format_gap(gap: Duration) -> String {
+    let secs = gap.as_secs();
+    match secs {
+        0..=59 => format!("{} s", secs),
+        60..=3599 => format!("{} min", secs / 60),
+        _ => format!("{} h {} min", secs / 3600, secs % 3600 / 60),
+    }
+}
@@ -0,0 +1,120 @@
+//! # Syslog Forwarding Module
+//!
+//! This module forwards alert transitions to the system log so existing log pipelines
+//! pick up Gjallarhorn warnings without any extra integration.
+//!
+//! On systemd hosts entries are sent over journald's native protocol
+//! (`/run/systemd/journal/socket`) with structured `GJALLARHORN_*` fields, so they can be
+//! filtered with e.g. `journalctl GJALLARHORN_SEVERITY=critical`. Otherwise a classic
+//! syslog line is written to `/dev/log` with the same fields appended as `key=value` pairs.
+
+use crate::alerts::{AlertEvent, AlertSeverity, AlertTransition};
+use log::warn;
+use std::os::unix::net::UnixDatagram;
+
+const JOURNAL_SOCKET: &str = "/run/systemd/journal/socket";
+const SYSLOG_SOCKET: &str = "/dev/log";
+const IDENTIFIER: &str = "gjallarhorn";
+/// Syslog facility `user`.
+const FACILITY_USER: u8 = 1;
+
+/// Destination the forwarder writes to.
+enum Target {
+    Journal,
+    Syslog,
+}
+
+/// Sends alert events to journald or syslog.
+pub struct SyslogForwarder {
+    socket: UnixDatagram,
+    target: Target,
+}
+
+/// Syslog priority of an event: crit (2), warning (4) or notice (5) for recoveries.
+fn priority(event: &AlertEvent) -> u8 {
+    match (event.transition, event.alert.severity) {
+        (AlertTransition::Cleared, _) => 5,
+        (_, AlertSeverity::Critical) => 2,
+        (_, AlertSeverity::Warning) => 4,
+    }
+}
+
+pub fn transition_name(transition: AlertTransition) -> &'static str {
+    match transition {
+        AlertTransition::Raised => "raised",
+        AlertTransition::SeverityChanged => "severity-changed",
+        AlertTransition::Cleared => "cleared",
+    }
+}
+
+/// Journal field values must not contain newlines in the simple `KEY=value` encoding.
+fn single_line(value: &str) -> String {
+    value.replace('\n', " ")
+}
+
+impl SyslogForwarder {
+    /// Connects to journald, falling back to `/dev/log`. Returns `None` if neither exists.
+    pub fn connect() -> Option<Self> {
+        for (path, target) in [
+            (JOURNAL_SOCKET, Target::Journal),
+            (SYSLOG_SOCKET, Target::Syslog),
+        ] {
+            if let Ok(socket) = UnixDatagram::unbound() {
+                if socket.connect(path).is_ok() {
+                    return Some(Self { socket, target });
+                }
+            }
+        }
+        warn!("No journald or syslog socket available; alerts will not be forwarded");
+        None
+    }
+
+    /// Human readable name of the log the forwarder writes to.
+    pub fn destination(&self) -> &'static str {
+        match self.target {
+            Target::Journal => "journald",
+            Target::Syslog => SYSLOG_SOCKET,
+        }
+    }
+
+    /// Writes one entry for `event`.
+    pub fn forward(&self, event: &AlertEvent) {
+        let alert = &event.alert;
+        let severity = format!("{:?}", alert.severity).to_lowercase();
+        let state = transition_name(event.transition);
+        let message = match event.transition {
+            AlertTransition::Cleared => format!("Alert cleared: {}", alert.message),
+            _ => format!("Alert {}: {}", severity, alert.message),
+        };
+
+        let payload = match self.target {
+            Target::Journal => [
+                format!("MESSAGE={}", single_line(&message)),
+                format!("PRIORITY={}", priority(event)),
+                format!("SYSLOG_IDENTIFIER={}", IDENTIFIER),
+                format!("SYSLOG_FACILITY={}", FACILITY_USER),
+                format!("GJALLARHORN_ALERT_ID={}", single_line(&alert.id)),
+                format!("GJALLARHORN_SEVERITY={}", severity),
+                format!("GJALLARHORN_STATE={}", state),
+                format!("GJALLARHORN_SINCE={}", alert.since),
+            ]
+            .iter()
+            .map(|field| format!("{}\n", field))
+            .collect::<String>(),
+            Target::Syslog => format!(
+                "<{}>{}[{}]: {} alert_id={} severity={} state={}",
+                FACILITY_USER * 8 + priority(event),
+                IDENTIFIER,
+                std::process::id(),
+                single_line(&message),
+                alert.id,
+                severity,
+                state
+            ),
+        };
+
+        if let Err(e) = self.socket.send(payload.as_bytes()) {
+            warn!("Failed to forward alert to the system log: {}", e);
+        }
+    }
+}
@@ -0,0 +1,162 @@
+//! # Cumulative Totals Module
+//!
+//! This module keeps byte counters that survive restarts of the application and of the
+//! machine: network traffic per interface and data written per drive. The kernel's
+//! counters start at zero on every boot (and when an interface is re-created), so the
+//! totals are accumulated from the growth of those counters and stored with the boot id
+//! (`/proc/sys/kernel/random/boot_id`) in `totals.json` in the standard data directory.
+//! After a reboot, the new kernel counters are added on top of the stored totals.
+//!
+//! The totals at the start of the current billing period are kept as well, so the data
+//! budget (see `budget.rs`) can count the traffic of the period.
+//!
+//! The "since install" totals count from the first run of a version with this module.
+//! Only the main window keeps them, so the command line and the desktop widget can run
+//! alongside it without counting the same traffic twice.
+
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+const BOOT_ID_PATH: &str = "/proc/sys/kernel/random/boot_id";
+/// Time between two writes of `totals.json`.
+const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// One accumulated counter.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+struct CounterTotal {
+    /// Kernel counter at the last update, during the stored boot.
+    last: u64,
+    /// Growth of the kernel counter over all boots.
+    total: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
+struct StoredTotals {
+    boot_id: String,
+    counters: BTreeMap<String, CounterTotal>,
+    /// Current billing period (e.g. "2026-10"); empty until a budget is enabled.
+    period: String,
+    /// Totals when `period` began; counters missing here started during the period.
+    period_start: BTreeMap<String, u64>,
+}
+
+fn read_boot_id() -> String {
+    fs::read_to_string(BOOT_ID_PATH)
+        .map(|s| s.trim().to_string())
+        .unwrap_or_default()
+}
+
+/// Persistent totals, keyed by e.g. "net.eth0.rx" or "disk.sda.written".
+pub struct CumulativeTotals {
+    stored: StoredTotals,
+    last_save: Instant,
+    dirty: bool,
+}
+
+impl CumulativeTotals {
+    fn get_path() -> PathBuf {
+        if let Some(proj_dirs) = ProjectDirs::from("com", "gjallarhorn", "gjallarhorn") {
+            let data_dir = proj_dirs.data_dir();
+            if !data_dir.exists() {
+                let _ = fs::create_dir_all(data_dir);
+            }
+            data_dir.join("totals.json")
+        } else {
+            PathBuf::from("totals.json")
+        }
+    }
+
+    pub fn load() -> Self {
+        let mut stored: StoredTotals = fs::read_to_string(Self::get_path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        let boot_id = read_boot_id();
+        if boot_id.is_empty() || stored.boot_id != boot_id {
+            // The kernel counters restarted at zero, so all of them count from there
+            for counter in stored.counters.values_mut() {
+                counter.last = 0;
+            }
+            stored.boot_id = boot_id;
+        }
+        Self {
+            stored,
+            last_save: Instant::now(),
+            dirty: false,
+        }
+    }
+
+    pub fn save(&mut self) {
+        if let Ok(json) = serde_json::to_string(&self.stored) {
+            let _ = fs::write(Self::get_path(), json);
+        }
+        self.last_save = Instant::now();
+        self.dirty = false;
+    }
+
+    /// Adds the growth of the kernel counter `key` since its last update and returns the
+    /// total since install. A counter that went down was reset (e.g. the interface was
+    /// re-created), so its whole value is growth.
+    pub fn update(&mut self, key: &str, since_boot: u64) -> u64 {
+        let counter = self.stored.counters.entry(key.to_string()).or_default();
+        let growth = if since_boot >= counter.last {
+            since_boot - counter.last
+        } else {
+            since_boot
+        };
+        if since_boot != counter.last {
+            counter.total += growth;
+            counter.last = since_boot;
+            self.dirty = true;
+        }
+        counter.total
+    }
+
+    /// Total since install of `key`, as of its last update.
+    pub fn get(&self, key: &str) -> Option<u64> {
+        self.stored.counters.get(key).map(|c| c.total)
+    }
+
+    /// Starts the billing period `period` if it is not the current one: the totals as of
+    /// now become its baseline.
+    pub fn begin_period(&mut self, period: &str) {
+        if self.stored.period != period {
+            self.stored.period = period.to_string();
+            self.stored.period_start = self
+                .stored
+                .counters
+                .iter()
+                .map(|(key, counter)| (key.clone(), counter.total))
+                .collect();
+            self.dirty = true;
+        }
+    }
+
+    /// Growth of every counter since the current billing period began, by key.
+    pub fn period_totals(&self) -> impl Iterator<Item = (&str, u64)> {
+        self.stored.counters.iter().map(|(key, counter)| {
+            let start = self.stored.period_start.get(key).copied().unwrap_or(0);
+            (key.as_str(), counter.total.saturating_sub(start))
+        })
+    }
+
+    /// Writes `totals.json` when counters changed and the last write is a minute old.
+    pub fn save_if_due(&mut self) {
+        if self.dirty && self.last_save.elapsed() >= SAVE_INTERVAL {
+            self.save();
+        }
+    }
+}
+
+impl Drop for CumulativeTotals {
+    fn drop(&mut self) {
+        if self.dirty {
+            self.save();
+        }
+    }
+}
@@ -2,12 +2,19 @@
 //!
 //! This module provides shared helper functions used throughout the application.
 //! Key utilities include:
-//! - `generate_path`: A highly optimized function to generate SVG path commands from a history buffer.
-//!   it pre-allocates strings to minimize heap churn during real-time updates.
+//! - `generate_path`: A highly optimized function to generate SVG path commands from a timestamped
+//!   history buffer. It pre-allocates strings to minimize heap churn during real-time updates.
+//! - `generate_stacked_paths`: The stacked-area variant, drawing each series on top of the previous ones.
+//! - `generate_points_path`: The variant for stored (time, value) points, e.g. long-term history.
+//! - `generate_span_path`: The same for a fixed span of time, e.g. two compared ranges.
 //! - `hex_to_color` / `brush_to_hex`: Functions to convert between string representations of colors (for storage) and Slint types (for UI).
 
+use crate::history::{self, History};
 use slint::SharedString;
 
+/// Longest gap between two samples that `generate_path` still joins with a line.
+const MAX_BRIDGED_GAP_SECS: f64 = 5.0;
+
 /// Helper function to convert a hex string (e.g., "#RRGGBB") to a `slint::Color`.
 /// Returns a default gray color if parsing fails or format is invalid.
 pub fn hex_to_color(hex: &str) -> slint::Color {
@@ -33,40 +40,310 @@ pub fn brush_to_hex(brush: slint::Brush) -> String {
     )
 }
 
+/// Maps a value to the chart's Y axis (0 = top = `max_val`, 100 = bottom = 0). Values
+/// outside `0..=max_val` are clamped; NaN and a non-positive `max_val` give the bottom.
+fn normalize_y(val: f32, max_val: f32) -> f32 {
+    if val.is_nan() || max_val.is_nan() || max_val <= 0.0 {
+        return 100.0;
+    }
+    100.0 - val.clamp(0.0, max_val) / max_val * 100.0
+}
+
 /// Returns a `SharedString` containing the SVG `d` attribute commands (M, L).
-/// Optimized to accept both VecDeque and Vec slices and minimize allocations.
-pub fn generate_path<'a, I>(history: I, max_val: f32, max_history_len: usize) -> SharedString
-where
-    I: IntoIterator<Item = &'a f32>,
-    I::IntoIter: ExactSizeIterator,
-{
-    let mut iter = history.into_iter();
-    let len = iter.len();
+/// Samples are placed by their timestamp: the right edge of the chart is now and the left
+/// edge `window_secs` ago. Pre-allocates the string to minimize allocations.
+///
+/// NaN samples (a failed reading) are left out and break the line; other values are
+/// clamped to the chart. An empty window or `max_val` of 0 never produce NaN coordinates.
+pub fn generate_path(history: &History, max_val: f32, window_secs: f64) -> SharedString {
+    path_at(history, max_val, window_secs, history::now()).into()
+}
 
-    if len == 0 {
-        return "".into();
+/// `generate_path` with the right edge of the chart at `now`.
+fn path_at(history: &History, max_val: f32, window_secs: f64, now: f64) -> String {
+    if history.is_empty() || window_secs.is_nan() || window_secs <= 0.0 {
+        return String::new();
     }
 
     // Optimized capacity: "M 0 99.9" (9 bytes) + " L 59.9 99.9" (13 bytes per point)
-    let mut path = String::with_capacity(9 + len * 13);
-
-    let normalize_y = |val: f32| -> f32 { 100.0 - (val.min(max_val) / max_val * 100.0) };
+    let mut path = String::with_capacity(9 + history.len() * 13);
 
-    // Normalize X to fit in 60 units (matching the viewbox-width of 60 in appwindow.slint)
-    // Step is calculated based on the MAXIMUM history capacity, ensuring 1 unit of X always equals 1 unit of time.
+    // Normalize X to fit in 60 units (matching the viewbox-width of 60 in components.slint)
     let width = 60.0;
-    let step_x = width / ((max_history_len.max(2) - 1) as f32);
+    let start = now - window_secs;
+    let to_x = |time: f64| ((time - start) / window_secs * width) as f32;
 
     use std::fmt::Write;
-    // Reduced precision from .2 to .1 - imperceptible difference, faster formatting
-    if let Some(first) = iter.next() {
-        let _ = write!(path, "M 0 {:.1}", normalize_y(*first));
+    let mut prev: Option<f64> = None;
+    let mut prev_step = 0.0;
+    for sample in history.iter().filter(|s| s.time >= start) {
+        if sample.value.is_nan() {
+            prev = None;
+            continue;
+        }
+        // Reduced precision from .2 to .1 - imperceptible difference, faster formatting
+        let (x, y) = (to_x(sample.time), normalize_y(sample.value, max_val));
+        match prev {
+            // A gap much longer than the sampling interval (suspend, stalled event loop)
+            // starts a new segment instead of bridging it with a straight line
+            Some(p) if sample.time - p <= MAX_BRIDGED_GAP_SECS.max(prev_step * 5.0) => {
+                prev_step = sample.time - p;
+                let _ = write!(path, " L {:.1} {:.1}", x, y);
+            }
+            _ => {
+                let sep = if path.is_empty() { "" } else { " " };
+                let _ = write!(path, "{}M {:.1} {:.1}", sep, x, y);
+            }
+        }
+        prev = Some(sample.time);
+    }
+
+    path
+}
+
+/// Returns the SVG path of a horizontal line across the chart at `value` (e.g. a
+/// percentile), or an empty path for `None`.
+pub fn generate_level_path(value: Option<f32>, max_val: f32) -> SharedString {
+    match value {
+        Some(value) => {
+            let y = normalize_y(value, max_val);
+            format!("M 0 {:.1} L 60 {:.1}", y, y).into()
+        }
+        None => SharedString::default(),
     }
+}
+
+/// Returns the SVG path of `points` (time, value), oldest first, for a chart whose right
+/// edge is now and left edge `window_secs` ago. Points more than `max_gap_secs` apart
+/// start a new segment.
+pub fn generate_points_path(
+    points: &[(f64, f32)],
+    max_val: f32,
+    window_secs: f64,
+    max_gap_secs: f64,
+) -> SharedString {
+    generate_span_path(
+        points,
+        max_val,
+        history::now() - window_secs,
+        window_secs,
+        max_gap_secs,
+    )
+}
 
-    for (i, val) in iter.enumerate() {
-        let x = (i + 1) as f32 * step_x;
-        let _ = write!(path, " L {:.1} {:.1}", x, normalize_y(*val));
+/// Returns the SVG path of `points` (time, value), oldest first, for a chart spanning
+/// `span_secs` from `start` (seconds since the epoch). Points more than `max_gap_secs`
+/// apart start a new segment.
+pub fn generate_span_path(
+    points: &[(f64, f32)],
+    max_val: f32,
+    start: f64,
+    span_secs: f64,
+    max_gap_secs: f64,
+) -> SharedString {
+    if points.is_empty() || span_secs.is_nan() || span_secs <= 0.0 {
+        return SharedString::default();
     }
 
+    let width = 60.0;
+    let to_x = |time: f64| ((time - start) / span_secs * width) as f32;
+
+    use std::fmt::Write;
+    let mut path = String::with_capacity(points.len() * 13);
+    let mut prev: Option<f64> = None;
+    for &(time, value) in points.iter().filter(|(time, _)| *time >= start) {
+        let (x, y) = (to_x(time), normalize_y(value, max_val));
+        match prev {
+            Some(p) if time - p <= max_gap_secs => {
+                let _ = write!(path, " L {:.1} {:.1}", x, y);
+            }
+            _ => {
+                let sep = if path.is_empty() { "" } else { " " };
+                let _ = write!(path, "{}M {:.1} {:.1}", sep, x, y);
+            }
+        }
+        prev = Some(time);
+    }
     path.into()
 }
+
+/// Returns one closed SVG area per history, stacked on top of each other: area `k` spans
+/// from the sum of histories `0..k` to the sum of `0..=k`, so the top edge of the last one
+/// is the total. The histories must be sampled together (like the CPU cores); they are
+/// aligned by their newest sample and placed at the timestamps of the first one.
+pub fn generate_stacked_paths(
+    histories: &[&History],
+    max_val: f32,
+    window_secs: f64,
+) -> Vec<SharedString> {
+    let len = histories.iter().map(|h| h.len()).min().unwrap_or(0);
+    if len == 0 || window_secs.is_nan() || window_secs <= 0.0 {
+        return vec![SharedString::default(); histories.len()];
+    }
+
+    let width = 60.0;
+    let start = history::now() - window_secs;
+    let to_x = |time: f64| ((time - start) / window_secs * width) as f32;
+    let to_y = |val: f32| normalize_y(val, max_val);
+
+    let times: Vec<f64> = histories[0]
+        .iter()
+        .skip(histories[0].len() - len)
+        .map(|s| s.time)
+        .collect();
+    // Running totals: `tops[k][j]` is the sum of histories 0..=k at sample j
+    let mut tops: Vec<Vec<f32>> = Vec::with_capacity(histories.len());
+    for history in histories {
+        let below = tops.last().cloned().unwrap_or_else(|| vec![0.0; len]);
+        let values = history.values().skip(history.len() - len);
+        tops.push(values.zip(below).map(|(v, b)| v + b).collect());
+    }
+
+    // Split the visible samples into segments at the same gaps `generate_path` breaks at
+    let mut segments: Vec<(usize, usize)> = Vec::new();
+    let mut prev_step = 0.0;
+    for j in (0..len).filter(|&j| times[j] >= start) {
+        match segments.last_mut() {
+            Some((_, end))
+                if *end + 1 == j
+                    && times[j] - times[*end] <= MAX_BRIDGED_GAP_SECS.max(prev_step * 5.0) =>
+            {
+                prev_step = times[j] - times[*end];
+                *end = j;
+            }
+            _ => segments.push((j, j)),
+        }
+    }
+
+    use std::fmt::Write;
+    (0..histories.len())
+        .map(|k| {
+            let mut path = String::with_capacity(len * 26 + 16);
+            for &(first, last) in &segments {
+                // Upper edge left to right, then the lower edge back, then close
+                for j in first..=last {
+                    let cmd = if j == first { "M" } else { "L" };
+                    let sep = if path.is_empty() { "" } else { " " };
+                    let (x, y) = (to_x(times[j]), to_y(tops[k][j]));
+                    let _ = write!(path, "{}{} {:.1} {:.1}", sep, cmd, x, y);
+                }
+                for j in (first..=last).rev() {
+                    let bottom = if k == 0 { 0.0 } else { tops[k - 1][j] };
+                    let (x, y) = (to_x(times[j]), to_y(bottom));
+                    let _ = write!(path, " L {:.1} {:.1}", x, y);
+                }
+                path.push_str(" Z");
+            }
+            path.into()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: f64 = 1_000_000.0;
+
+    /// A history with one sample per second, the last one at `NOW`.
+    fn history_of(values: &[f32]) -> History {
+        let mut history = History::new(values.len());
+        for (i, value) in values.iter().enumerate() {
+            history.push_at(NOW - (values.len() - 1 - i) as f64, *value);
+        }
+        history
+    }
+
+    #[test]
+    fn empty_history_gives_empty_path() {
+        assert_eq!(path_at(&History::new(10), 100.0, 60.0, NOW), "");
+    }
+
+    #[test]
+    fn samples_are_placed_by_time_and_value() {
+        let path = path_at(&history_of(&[0.0, 50.0, 100.0]), 100.0, 60.0, NOW);
+        assert_eq!(path, "M 58.0 100.0 L 59.0 50.0 L 60.0 0.0");
+    }
+
+    #[test]
+    fn nan_samples_break_the_line() {
+        let path = path_at(&history_of(&[10.0, f32::NAN, 30.0]), 100.0, 60.0, NOW);
+        assert_eq!(path, "M 58.0 90.0 M 60.0 70.0");
+    }
+
+    #[test]
+    fn out_of_range_values_are_clamped() {
+        let values = [-5.0, 150.0, f32::INFINITY, f32::NEG_INFINITY];
+        let path = path_at(&history_of(&values), 100.0, 60.0, NOW);
+        assert_eq!(path, "M 57.0 100.0 L 58.0 0.0 L 59.0 0.0 L 60.0 100.0");
+    }
+
+    #[test]
+    fn zero_or_nan_max_draws_at_the_bottom() {
+        let history = history_of(&[5.0, 5.0]);
+        assert_eq!(
+            path_at(&history, 0.0, 60.0, NOW),
+            "M 59.0 100.0 L 60.0 100.0"
+        );
+        assert_eq!(
+            path_at(&history, f32::NAN, 60.0, NOW),
+            "M 59.0 100.0 L 60.0 100.0"
+        );
+    }
+
+    #[test]
+    fn empty_window_gives_empty_path() {
+        let history = history_of(&[1.0, 2.0]);
+        assert_eq!(path_at(&history, 100.0, 0.0, NOW), "");
+        assert_eq!(path_at(&history, 100.0, f64::NAN, NOW), "");
+    }
+
+    #[test]
+    fn long_gaps_start_a_new_segment() {
+        let mut history = History::new(10);
+        history.push_at(NOW - 20.0, 0.0);
+        history.push_at(NOW - 19.0, 0.0);
+        history.push_at(NOW, 100.0);
+        let path = path_at(&history, 100.0, 60.0, NOW);
+        assert_eq!(path, "M 40.0 100.0 L 41.0 100.0 M 60.0 0.0");
+    }
+
+    #[test]
+    fn samples_before_the_window_are_skipped() {
+        let path = path_at(&history_of(&[1.0, 2.0, 3.0]), 100.0, 1.0, NOW);
+        assert_eq!(path, "M 0.0 98.0 L 60.0 97.0");
+    }
+
+    #[test]
+    fn long_history_stays_within_the_chart() {
+        let mut history = History::new(history::MAX_SAMPLES);
+        for i in 0..20_000 {
+            let time = NOW - 60.0 + i as f64 * 60.0 / 20_000.0;
+            history.push_at(time, (i % 200) as f32 - 50.0);
+        }
+        let path = path_at(&history, 100.0, 60.0, NOW);
+        assert!(!path.contains("NaN") && !path.contains("inf"));
+        let coords: Vec<f32> = path
+            .split(' ')
+            .filter_map(|token| token.parse().ok())
+            .collect();
+        assert_eq!(coords.len(), history.len() * 2);
+        let xs: Vec<f32> = coords.iter().step_by(2).copied().collect();
+        assert!(xs.windows(2).all(|w| w[0] <= w[1]));
+        assert!(coords.iter().all(|c| (0.0..=100.0).contains(c)));
+        assert!(xs.iter().all(|x| *x <= 60.0));
+    }
+
+    #[test]
+    fn span_points_are_placed_from_the_span_start() {
+        let points = [
+            (NOW - 10.0, 20.0),
+            (NOW, 50.0),
+            (NOW + 30.0, 100.0),
+            (NOW + 45.0, 0.0),
+        ];
+        let path = generate_span_path(&points, 100.0, NOW, 60.0, 20.0);
+        // Points before the start are left out; a gap over 20 s starts a new segment
+        assert_eq!(path, "M 0.0 50.0 M 30.0 0.0 L 45.0 100.0");
+    }
+}
@@ -0,0 +1,316 @@
+//! # Virtual Machines Module
+//!
+//! This module lists the libvirt/QEMU virtual machines defined on this host with their
+//! state, vCPU usage, memory and disk/network throughput. It polls
+//! `virsh domstats --raw` in a background thread, against the system instance
+//! (`qemu:///system`) and falls back to the user's session instance when that is not
+//! accessible (the user is not in the `libvirt` group).
+
+use crate::history::{History, HistoryLimits};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Seconds between two `virsh domstats` polls.
+const VM_POLL_INTERVAL_SECS: u64 = 2;
+const LIBVIRT_URIS: [&str; 2] = ["qemu:///system", "qemu:///session"];
+
+/// Raw counters of one domain from `virsh domstats --raw`.
+#[derive(Debug, Clone, Default)]
+struct DomainStats {
+    name: String,
+    /// `virDomainState`: 1 = running, 3 = paused, 5 = shut off, ...
+    state: u32,
+    /// Total CPU time in nanoseconds.
+    cpu_time_ns: u64,
+    vcpus: u32,
+    /// Current balloon size in KiB.
+    memory_kib: u64,
+    block_bytes: u64,
+    net_bytes: u64,
+}
+
+/// Parses `virsh domstats --raw` output: a `Domain: 'name'` line followed by indented
+/// `key=value` lines. Disk and network counters are summed over all devices.
+fn parse_domstats(output: &str) -> Vec<DomainStats> {
+    let mut domains: Vec<DomainStats> = Vec::new();
+    for line in output.lines() {
+        if let Some(name) = line.strip_prefix("Domain: ") {
+            domains.push(DomainStats {
+                name: name.trim().trim_matches('\'').to_string(),
+                ..Default::default()
+            });
+            continue;
+        }
+        let (Some(domain), Some((key, value))) = (domains.last_mut(), line.trim().split_once('='))
+        else {
+            continue;
+        };
+        let value: u64 = value.parse().unwrap_or(0);
+        match key {
+            "state.state" => domain.state = value as u32,
+            "cpu.time" => domain.cpu_time_ns = value,
+            "vcpu.current" => domain.vcpus = value as u32,
+            "balloon.current" => domain.memory_kib = value,
+            _ if key.starts_with("block.")
+                && (key.ends_with(".rd.bytes") || key.ends_with(".wr.bytes")) =>
+            {
+                domain.block_bytes += value
+            }
+            _ if key.starts_with("net.")
+                && (key.ends_with(".rx.bytes") || key.ends_with(".tx.bytes")) =>
+            {
+                domain.net_bytes += value
+            }
+            _ => {}
+        }
+    }
+    domains
+}
+
+/// Human-readable `virDomainState`.
+fn state_name(state: u32) -> &'static str {
+    match state {
+        1 => "running",
+        2 => "blocked",
+        3 => "paused",
+        4 => "shutting down",
+        5 => "shut off",
+        6 => "crashed",
+        7 => "suspended",
+        _ => "unknown",
+    }
+}
+
+/// Queries every domain of the first libvirt instance that answers.
+fn collect() -> Result<(String, Vec<DomainStats>), String> {
+    let mut error = String::from("virsh failed");
+    for uri in LIBVIRT_URIS {
+        let output = Command::new("virsh")
+            .args(["--connect", uri, "domstats", "--raw"])
+            .output()
+            .map_err(|e| e.to_string())?;
+        if output.status.success() {
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            return Ok((uri.to_string(), parse_domstats(&stdout)));
+        }
+        error = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    }
+    Err(error)
+}
+
+/// Latest state and rates of one VM, computed by the polling thread.
+#[derive(Debug, Clone, Default)]
+struct VmState {
+    name: String,
+    state: u32,
+    vcpus: u32,
+    memory_kib: u64,
+    /// Percent of the VM's vCPUs.
+    cpu_usage: f32,
+    disk_bytes_per_sec: f64,
+    net_bytes_per_sec: f64,
+}
+
+#[derive(Debug, Clone, Default)]
+struct PollResult {
+    uri: String,
+    vms: Vec<VmState>,
+    error: String,
+}
+
+/// Holds VM data for external consumers
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VmData {
+    pub name: String,
+    pub state: String,
+    pub running: bool,
+    pub vcpus: u32,
+    pub memory_mb: u64,
+    pub cpu_usage: f32,
+    pub disk_bytes_per_sec: f64,
+    pub net_bytes_per_sec: f64,
+    pub cpu_history: History,
+    /// Disk throughput in MB/s.
+    pub disk_history: History,
+    /// Network throughput in MB/s.
+    pub net_history: History,
+}
+
+struct VmHistory {
+    cpu: History,
+    disk: History,
+    net: History,
+}
+
+/// Polls libvirt in a background thread and keeps per-VM history.
+pub struct VmTracker {
+    latest: Arc<Mutex<PollResult>>,
+    stop: Arc<AtomicBool>,
+    histories: HashMap<String, VmHistory>,
+}
+
+impl VmTracker {
+    /// Starts polling `virsh` every `VM_POLL_INTERVAL_SECS`.
+    pub fn start() -> Self {
+        let latest = Arc::new(Mutex::new(PollResult::default()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_latest = latest.clone();
+        let thread_stop = stop.clone();
+        std::thread::spawn(move || {
+            let mut prev: HashMap<String, (Instant, DomainStats)> = HashMap::new();
+            while !thread_stop.load(Ordering::Relaxed) {
+                let now = Instant::now();
+                let result = match collect() {
+                    Ok((uri, domains)) => {
+                        let vms = domains
+                            .iter()
+                            .map(|d| {
+                                let mut vm = VmState {
+                                    name: d.name.clone(),
+                                    state: d.state,
+                                    vcpus: d.vcpus,
+                                    memory_kib: d.memory_kib,
+                                    ..Default::default()
+                                };
+                                if let Some((then, p)) = prev.get(&d.name) {
+                                    let secs = now.duration_since(*then).as_secs_f64();
+                                    if secs > 0.0 {
+                                        let cpu_ns = d.cpu_time_ns.saturating_sub(p.cpu_time_ns);
+                                        vm.cpu_usage = (cpu_ns as f64
+                                            / (secs * 1e9 * d.vcpus.max(1) as f64)
+                                            * 100.0)
+                                            .min(100.0)
+                                            as f32;
+                                        vm.disk_bytes_per_sec =
+                                            d.block_bytes.saturating_sub(p.block_bytes) as f64
+                                                / secs;
+                                        vm.net_bytes_per_sec =
+                                            d.net_bytes.saturating_sub(p.net_bytes) as f64 / secs;
+                                    }
+                                }
+                                vm
+                            })
+                            .collect();
+                        prev = domains
+                            .into_iter()
+                            .map(|d| (d.name.clone(), (now, d)))
+                            .collect();
+                        PollResult {
+                            uri,
+                            vms,
+                            error: String::new(),
+                        }
+                    }
+                    Err(error) => {
+                        prev.clear();
+                        PollResult {
+                            error,
+                            ..Default::default()
+                        }
+                    }
+                };
+                if let Ok(mut guard) = thread_latest.lock() {
+                    *guard = result;
+                }
+                std::thread::sleep(Duration::from_secs(VM_POLL_INTERVAL_SECS));
+            }
+        });
+
+        Self {
+            latest,
+            stop,
+            histories: HashMap::new(),
+        }
+    }
+
+    /// Appends each VM's latest rates to its history (sample-and-hold between polls).
+    pub fn refresh(&mut self, limits: HistoryLimits) {
+        let latest = match self.latest.lock() {
+            Ok(guard) => guard.vms.clone(),
+            Err(_) => return,
+        };
+        self.histories
+            .retain(|name, _| latest.iter().any(|vm| vm.name == *name));
+        for vm in latest {
+            let history = self
+                .histories
+                .entry(vm.name.clone())
+                .or_insert_with(|| VmHistory {
+                    cpu: History::with_limits(limits),
+                    disk: History::with_limits(limits),
+                    net: History::with_limits(limits),
+                });
+            history.cpu.push(vm.cpu_usage);
+            history
+                .disk
+                .push((vm.disk_bytes_per_sec / 1024.0 / 1024.0) as f32);
+            history
+                .net
+                .push((vm.net_bytes_per_sec / 1024.0 / 1024.0) as f32);
+        }
+    }
+
+    pub fn resize_history(&mut self, limits: HistoryLimits) {
+        for history in self.histories.values_mut() {
+            history.cpu.set_limits(limits);
+            history.disk.set_limits(limits);
+            history.net.set_limits(limits);
+        }
+    }
+
+    /// Number of history buffers, for the monitor's sample budget.
+    pub fn history_count(&self) -> usize {
+        self.histories.len() * 3
+    }
+
+    /// libvirt URI in use, or the last `virsh` error when no instance answered.
+    pub fn status(&self) -> String {
+        match self.latest.lock() {
+            Ok(guard) if guard.error.is_empty() => guard.uri.clone(),
+            Ok(guard) => guard.error.clone(),
+            Err(_) => String::new(),
+        }
+    }
+
+    /// Defined VMs, running ones first, then by name.
+    pub fn get_data(&self) -> Vec<VmData> {
+        let latest = match self.latest.lock() {
+            Ok(guard) => guard.vms.clone(),
+            Err(_) => return Vec::new(),
+        };
+        let mut vms: Vec<VmData> = latest
+            .into_iter()
+            .map(|vm| {
+                let history = self.histories.get(&vm.name);
+                let history_or_empty = |pick: fn(&VmHistory) -> &History| {
+                    history.map(pick).cloned().unwrap_or_default()
+                };
+                VmData {
+                    state: state_name(vm.state).to_string(),
+                    running: vm.state == 1,
+                    vcpus: vm.vcpus,
+                    memory_mb: vm.memory_kib / 1024,
+                    cpu_usage: vm.cpu_usage,
+                    disk_bytes_per_sec: vm.disk_bytes_per_sec,
+                    net_bytes_per_sec: vm.net_bytes_per_sec,
+                    cpu_history: history_or_empty(|h| &h.cpu),
+                    disk_history: history_or_empty(|h| &h.disk),
+                    net_history: history_or_empty(|h| &h.net),
+                    name: vm.name,
+                }
+            })
+            .collect();
+        vms.sort_by(|a, b| b.running.cmp(&a.running).then_with(|| a.name.cmp(&b.name)));
+        vms
+    }
+}
+
+impl Drop for VmTracker {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,141 @@
+//! # Webhook Module
+//!
+//! This module POSTs the full `MetricsSnapshot` as JSON to a user-configured URL at a
+//! fixed interval, independently of alerts.
+//!
+//! Requests are sent with `curl` from a background thread, so a slow or unreachable
+//! endpoint never blocks the UI. Failed deliveries are retried with exponential backoff;
+//! while a delivery is pending at most one further snapshot waits, later ones are dropped.
+
+use crate::snapshot::MetricsSnapshot;
+use log::warn;
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Attempts per snapshot before giving up.
+const MAX_ATTEMPTS: u32 = 5;
+/// Delay before the first retry; doubled after every failed attempt.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(2);
+/// Per-request timeout passed to curl.
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+
+/// Result of the most recent delivery attempt and when it happened.
+type DeliveryStatus = Option<(Result<(), String>, Instant)>;
+
+/// Periodically delivers snapshots to a webhook URL.
+pub struct WebhookSink {
+    sender: SyncSender<String>,
+    interval: Duration,
+    last_sent: Option<Instant>,
+    /// Shared with the delivery thread, for display.
+    status: Arc<Mutex<DeliveryStatus>>,
+}
+
+/// POSTs `payload` to `url`. Returns curl's error message on failure.
+pub fn post(url: &str, payload: &str) -> Result<(), String> {
+    let mut child = Command::new("curl")
+        .args([
+            "--silent",
+            "--show-error",
+            "--fail",
+            "--max-time",
+            &REQUEST_TIMEOUT_SECS.to_string(),
+            "--header",
+            "Content-Type: application/json",
+            "--data-binary",
+            "@-",
+            "--output",
+            "/dev/null",
+            "--",
+            url,
+        ])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run curl: {}", e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(payload.as_bytes())
+            .map_err(|e| e.to_string())?;
+    }
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(String::from_utf8_lossy(&output.stderr).trim().to_string())
+    }
+}
+
+impl WebhookSink {
+    /// Starts the delivery thread for `url`, sending at most once every `interval_secs`.
+    pub fn start(url: String, interval_secs: u64) -> Self {
+        let (sender, receiver) = mpsc::sync_channel::<String>(1);
+        let status = Arc::new(Mutex::new(None));
+        let thread_status = status.clone();
+
+        // Ends when the sink (and with it the sender) is dropped
+        std::thread::spawn(move || {
+            for payload in receiver {
+                let mut backoff = INITIAL_BACKOFF;
+                for attempt in 1..=MAX_ATTEMPTS {
+                    let result = post(&url, &payload)
+                        .map_err(|e| format!("Attempt {}/{} failed: {}", attempt, MAX_ATTEMPTS, e));
+                    if let Ok(mut guard) = thread_status.lock() {
+                        *guard = Some((result.clone(), Instant::now()));
+                    }
+                    match result {
+                        Ok(()) => break,
+                        Err(e) if attempt == MAX_ATTEMPTS => {
+                            warn!("Webhook delivery to {} failed: {}", url, e);
+                        }
+                        Err(_) => {
+                            std::thread::sleep(backoff);
+                            backoff *= 2;
+                        }
+                    }
+                }
+            }
+        });
+
+        Self {
+            sender,
+            interval: Duration::from_secs(interval_secs.max(1)),
+            last_sent: None,
+            status,
+        }
+    }
+
+    /// Whether the interval has elapsed since the last snapshot was handed off.
+    pub fn is_due(&self) -> bool {
+        self.last_sent.is_none_or(|t| t.elapsed() >= self.interval)
+    }
+
+    /// Hands `snapshot` to the delivery thread. Dropped if a delivery is still pending.
+    pub fn send(&mut self, snapshot: &MetricsSnapshot) {
+        self.last_sent = Some(Instant::now());
+        let payload = match serde_json::to_string(snapshot) {
+            Ok(p) => p,
+            Err(e) => {
+                warn!("Failed to serialize snapshot: {}", e);
+                return;
+            }
+        };
+        if let Err(TrySendError::Full(_)) = self.sender.try_send(payload) {
+            warn!("Webhook delivery still pending, skipping snapshot");
+        }
+    }
+
+    /// Human readable result of the most recent delivery attempt.
+    pub fn status(&self) -> Result<String, String> {
+        match self.status.lock().ok().and_then(|s| s.clone()) {
+            Some((Ok(()), at)) => Ok(format!("Delivered {}s ago", at.elapsed().as_secs())),
+            Some((Err(e), at)) => Err(format!("{} ({}s ago)", e, at.elapsed().as_secs())),
+            None => Ok("Waiting for first snapshot".to_string()),
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! # Desktop Widget Module
+//!
+//! This module implements `gjallarhorn widget`, a Conky-style desktop layer: a frameless,
+//! transparent window with a stack of graphs, kept below other windows and (by default)
+//! click-through, so it behaves like part of the wallpaper.
+//!
+//! The graphed series use the same ids as the Overview pins (`pins.rs`) and are
+//! configured, together with the window position and width, in the `desktop_widget`
+//! settings section. The widget runs its own headless `SystemMonitor` without any
+//! integrations, so it can run next to the main window without duplicating exports.
+
+use crate::collector::{self, Collector};
+use crate::monitor::SystemMonitor;
+use crate::settings::{AppSettings, CsvSettings, WebhookSettings};
+use crate::utils::{generate_path, hex_to_color};
+use crate::{DesktopWidget, PinnedSeriesData};
+use log::warn;
+use slint::winit_030::{winit, WinitWindowAccessor};
+use slint::{ComponentHandle, Timer, TimerMode};
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+/// Series graphed when neither the widget nor the Overview has any configured.
+const DEFAULT_SERIES: &[&str] = &["cpu", "memory"];
+
+/// Shows the desktop widget and runs until the process is terminated.
+pub fn run() -> Result<(), slint::PlatformError> {
+    let settings = AppSettings::load();
+    let layout = settings.desktop_widget.clone();
+    let series = if !layout.series.is_empty() {
+        layout.series.clone()
+    } else if !settings.pinned_series.is_empty() {
+        settings.pinned_series.clone()
+    } else {
+        DEFAULT_SERIES.iter().map(|s| s.to_string()).collect()
+    };
+
+    // Only the graphed series are needed; integrations keep running in the main app
+    let monitor_settings = AppSettings {
+        pinned_series: series,
+        enable_dns_probe: false,
+        remote_hosts: Vec::new(),
+        forward_alerts_to_syslog: false,
+        webhook: WebhookSettings::default(),
+        csv: CsvSettings::default(),
+        ..settings.clone()
+    };
+    let monitor = Arc::new(Mutex::new(SystemMonitor::new_headless(&monitor_settings)));
+    let collector = Collector::spawn(monitor.clone(), settings.refresh_rate_ms);
+
+    let widget = DesktopWidget::new()?;
+    widget.set_widget_width(layout.width as f32);
+    widget.set_background_opacity(layout.background_opacity.clamp(0.0, 1.0));
+    widget.set_line_color(hex_to_color(&settings.cpu_color).into());
+    widget
+        .window()
+        .set_position(slint::PhysicalPosition::new(layout.x, layout.y));
+
+    let graphs = Rc::new(slint::VecModel::default());
+    widget.set_graphs(slint::ModelRc::from(graphs.clone()));
+
+    let timer = Timer::default();
+    let mut pending = false;
+    timer.start(TimerMode::Repeated, collector::DRAIN_INTERVAL, move || {
+        pending |= collector.drain().is_some();
+        if !pending {
+            return;
+        }
+        // The collector holds the monitor while it refreshes; redraw on a later tick
+        let Some(monitor) = collector::try_lock(&monitor) else {
+            return;
+        };
+        pending = false;
+        let window_secs = monitor.history_limits.window_secs;
+        let rows: Vec<PinnedSeriesData> = monitor
+            .get_pinned_data()
+            .into_iter()
+            .map(|p| PinnedSeriesData {
+                title: format!("{}: {}", p.label, p.value_str).into(),
+                path: generate_path(&p.history, p.scale, window_secs),
+                id: p.id.into(),
+            })
+            .collect();
+        graphs.set_vec(rows);
+    });
+
+    widget.show()?;
+    // Stacking and hit testing need the native window, which exists once shown
+    widget.window().with_winit_window(|window| {
+        window.set_window_level(winit::window::WindowLevel::AlwaysOnBottom);
+        if layout.click_through {
+            if let Err(e) = window.set_cursor_hittest(false) {
+                warn!("Click-through is not supported here: {}", e);
+            }
+        }
+    });
+    slint::run_event_loop()
+}
@@ -0,0 +1,155 @@
+//! # Wi-Fi Module
+//!
+//! This module scans for nearby Wi-Fi networks over nl80211 using `iw`, reporting SSID,
+//! channel, signal strength and security for each access point.
+//!
+//! Triggering a scan needs `CAP_NET_ADMIN`, so the GUI runs it through the one-shot
+//! privileged helper (`--wifi-scan`), which prints the results as JSON.
+
+use serde::{Deserialize, Serialize};
+use std::process::Command;
+
+/// Parsed strictly, since the GUI reads it from the privileged `--wifi-scan` helper.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct WifiNetwork {
+    /// Network name; empty for hidden networks.
+    pub ssid: String,
+    pub bssid: String,
+    /// Interface that saw the network.
+    pub interface: String,
+    pub frequency_mhz: u32,
+    pub channel: u32,
+    pub signal_dbm: f32,
+    /// "Open", "WEP", "WPA", "WPA2" or "WPA3".
+    pub security: String,
+    /// Whether we are associated with this access point.
+    pub connected: bool,
+}
+
+/// Lists the wireless network interfaces (those with a `wireless` sysfs directory).
+pub fn wireless_interfaces() -> Vec<String> {
+    let mut names: Vec<String> = std::fs::read_dir("/sys/class/net")
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.path().join("wireless").exists())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    names.sort();
+    names
+}
+
+/// Derives the channel number from a center frequency.
+fn frequency_to_channel(freq: u32) -> u32 {
+    match freq {
+        2484 => 14,
+        2412..=2472 => (freq - 2407) / 5,
+        5955..=7115 => (freq - 5950) / 5,
+        5000..=5900 => (freq - 5000) / 5,
+        _ => 0,
+    }
+}
+
+/// Parses the output of `iw dev <interface> scan`.
+fn parse_iw_scan(output: &str, interface: &str) -> Vec<WifiNetwork> {
+    let mut networks = Vec::new();
+    let mut current: Option<WifiNetwork> = None;
+    // Security flags of the current BSS: (privacy bit, WPA IE, RSN IE, SAE)
+    let mut security = (false, false, false, false);
+
+    let finish = |net: Option<WifiNetwork>, sec: (bool, bool, bool, bool), out: &mut Vec<_>| {
+        if let Some(mut net) = net {
+            net.security = match sec {
+                (_, _, true, true) => "WPA3",
+                (_, _, true, false) => "WPA2",
+                (_, true, false, _) => "WPA",
+                (true, false, false, _) => "WEP",
+                _ => "Open",
+            }
+            .to_string();
+            if net.channel == 0 {
+                net.channel = frequency_to_channel(net.frequency_mhz);
+            }
+            out.push(net);
+        }
+    };
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if let Some(rest) = line.strip_prefix("BSS ") {
+            finish(current.take(), security, &mut networks);
+            security = (false, false, false, false);
+            current = Some(WifiNetwork {
+                bssid: rest.chars().take(17).collect(),
+                interface: interface.to_string(),
+                connected: rest.contains("associated"),
+                ..Default::default()
+            });
+            continue;
+        }
+
+        let Some(net) = current.as_mut() else {
+            continue;
+        };
+        if let Some(v) = trimmed.strip_prefix("freq:") {
+            // Newer iw versions print fractional frequencies ("2437.0")
+            net.frequency_mhz = v.trim().parse::<f32>().map(|f| f as u32).unwrap_or(0);
+        } else if let Some(v) = trimmed.strip_prefix("signal:") {
+            net.signal_dbm = v
+                .split_whitespace()
+                .next()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0.0);
+        } else if let Some(v) = trimmed.strip_prefix("SSID:") {
+            net.ssid = v.trim().to_string();
+        } else if let Some(v) = trimmed.strip_prefix("DS Parameter set: channel") {
+            net.channel = v.trim().parse().unwrap_or(0);
+        } else if let Some(v) = trimmed.strip_prefix("* primary channel:") {
+            net.channel = v.trim().parse().unwrap_or(net.channel);
+        } else if trimmed.starts_with("capability:") && trimmed.contains("Privacy") {
+            security.0 = true;
+        } else if trimmed.starts_with("WPA:") {
+            security.1 = true;
+        } else if trimmed.starts_with("RSN:") {
+            security.2 = true;
+        } else if trimmed.contains("Authentication suites:") && trimmed.contains("SAE") {
+            security.3 = true;
+        }
+    }
+    finish(current.take(), security, &mut networks);
+    networks
+}
+
+/// Triggers a scan on every wireless interface and returns the results, strongest first.
+/// Requires root.
+pub fn scan() -> Result<Vec<WifiNetwork>, String> {
+    let interfaces = wireless_interfaces();
+    if interfaces.is_empty() {
+        return Err("No wireless interfaces found".to_string());
+    }
+
+    let mut networks = Vec::new();
+    for interface in &interfaces {
+        let output = Command::new("iw")
+            .args(["dev", interface, "scan"])
+            .output()
+            .map_err(|e| format!("Failed to run iw: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "Scan on {} failed: {}",
+                interface,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+        networks.extend(parse_iw_scan(
+            &String::from_utf8_lossy(&output.stdout),
+            interface,
+        ));
+    }
+
+    networks.sort_by(|a, b| b.signal_dbm.total_cmp(&a.signal_dbm));
+    Ok(networks)
+}
@@ -1,38 +1,276 @@
+use crate::capture::{CaptureCollector, ProtocolStats};
+use crate::kmsg::{KernelLogEntry, KmsgReader};
+use crate::mac::{DenialScanner, DenialSummary};
 use crate::monitor::{NetworkDetailedInfo, StorageDetailedInfo};
+use crate::netns::NetNamespace;
+use crate::settings::CollectorSettings;
+use crate::xid::{GpuKernelEvent, XidScanner};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use std::io::{self, Write};
+use std::io::{self, BufRead, Read, Write};
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
 use std::{thread, time::Duration};
 // Re-use logic from monitor or extract common logic?
 // Ideally, `worker` should just use `monitor`'s functions but print result instead of storing in struct.
 // But `Monitor` struct is tied to Slint `Weak<AppWindow>`.
 // So we need a headless data gatherer.
 
+/// Seconds between two `PrivilegedData` messages.
+pub const WORKER_INTERVAL_SECS: u64 = 2;
+/// Longest message the GUI accepts from the worker. Typical messages are a few KiB.
+pub const MAX_MESSAGE_BYTES: usize = 1024 * 1024;
+/// Most entries the GUI accepts in any list of a message.
+const MAX_LIST_LEN: usize = 4096;
+
+/// Messages are parsed strictly: unknown fields are rejected, since the worker is the
+/// same binary as the GUI.
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct PrivilegedData {
     pub storage: Vec<StorageDetailedInfo>,
     pub network: Vec<NetworkDetailedInfo>,
+    /// Non-host network namespaces (requires root to see other users' processes).
+    #[serde(default)]
+    pub namespaces: Vec<NetNamespace>,
+    /// Traffic per protocol during the last interval (only with `--capture`).
+    #[serde(default)]
+    pub protocols: Vec<ProtocolStats>,
+    /// Recent NVIDIA driver errors from the kernel log (the whole recent list each time).
+    #[serde(default)]
+    pub gpu_events: Vec<GpuKernelEvent>,
+    /// Recent kernel warnings and errors (the whole recent list each time).
+    #[serde(default)]
+    pub kernel_log: Vec<KernelLogEntry>,
+    /// SELinux/AppArmor denials and AppArmor profile counts.
+    #[serde(default)]
+    pub mac_denials: DenialSummary,
     // Add other fields if needed, e.g. DMI
 }
 
-pub fn run_worker() {
+impl PrivilegedData {
+    /// Checks the list sizes against what a well-behaved worker sends.
+    pub fn validate(&self) -> Result<(), String> {
+        let lists = [
+            ("storage", self.storage.len(), MAX_LIST_LEN),
+            ("network", self.network.len(), MAX_LIST_LEN),
+            ("namespaces", self.namespaces.len(), MAX_LIST_LEN),
+            ("protocols", self.protocols.len(), MAX_LIST_LEN),
+            ("gpu_events", self.gpu_events.len(), MAX_LIST_LEN),
+            (
+                "kernel_log",
+                self.kernel_log.len(),
+                crate::kmsg::MAX_ENTRIES,
+            ),
+        ];
+        match lists.iter().find(|(_, len, max)| len > max) {
+            Some((name, len, max)) => Err(format!("{} has {} entries (max {})", name, len, max)),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Parses and validates one line received from the worker.
+pub fn parse_message(line: &[u8]) -> Result<PrivilegedData, String> {
+    if line.len() > MAX_MESSAGE_BYTES {
+        return Err(format!("message of {} bytes is too large", line.len()));
+    }
+    let text = std::str::from_utf8(line).map_err(|e| format!("invalid UTF-8: {}", e))?;
+    let data: PrivilegedData =
+        serde_json::from_str(text.trim_end()).map_err(|e| format!("invalid message: {}", e))?;
+    data.validate()?;
+    Ok(data)
+}
+
+/// Reads messages from the worker's stdout and passes each to `on_data`, until the
+/// worker exits or sends a message that fails `parse_message`. A bad message ends the
+/// connection rather than being skipped, since a correct worker never sends one.
+pub fn read_messages(
+    reader: impl BufRead,
+    mut on_data: impl FnMut(PrivilegedData),
+) -> Result<(), String> {
+    let mut reader = reader;
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        // Read at most one byte over the limit, so an endless line can't exhaust memory
+        let read = reader
+            .by_ref()
+            .take(MAX_MESSAGE_BYTES as u64 + 1)
+            .read_until(b'\n', &mut line)
+            .map_err(|e| e.to_string())?;
+        if read == 0 {
+            return Ok(());
+        }
+        on_data(parse_message(&line)?);
+    }
+}
+
+/// Path of the running executable, to be started as the worker. Refuses binaries that
+/// were replaced or deleted since this process started, and binaries that anyone but
+/// their owner can modify, since the worker runs them as root.
+pub fn worker_executable() -> Result<PathBuf, String> {
+    let exe = std::env::current_exe().map_err(|e| format!("Executable not found: {}", e))?;
+    if exe.to_string_lossy().ends_with(" (deleted)") {
+        return Err(format!("{} was deleted", exe.display()));
+    }
+    let exe = exe
+        .canonicalize()
+        .map_err(|e| format!("{}: {}", exe.display(), e))?;
+
+    // The file at the path must still be the one this process was started from
+    let on_disk = std::fs::metadata(&exe).map_err(|e| format!("{}: {}", exe.display(), e))?;
+    let running =
+        std::fs::metadata("/proc/self/exe").map_err(|e| format!("/proc/self/exe: {}", e))?;
+    if (on_disk.dev(), on_disk.ino()) != (running.dev(), running.ino()) {
+        return Err(format!("{} was replaced", exe.display()));
+    }
+    if !on_disk.is_file() {
+        return Err(format!("{} is not a regular file", exe.display()));
+    }
+    if on_disk.permissions().mode() & 0o022 != 0 {
+        return Err(format!("{} is writable by other users", exe.display()));
+    }
+    if let Some(dir) = exe.parent() {
+        let dir_mode = std::fs::metadata(dir)
+            .map(|m| m.permissions().mode())
+            .unwrap_or(0);
+        // World-writable directories are fine only with the sticky bit (e.g. /tmp)
+        if dir_mode & 0o002 != 0 && dir_mode & 0o1000 == 0 {
+            return Err(format!("{} is world-writable", dir.display()));
+        }
+    }
+    Ok(exe)
+}
+
+/// Runs a one-shot privileged helper (`exe <args>`) through `pkexec` and returns what it
+/// printed. The executable is checked like the worker's (see `worker_executable`) and the
+/// output is read up to `MAX_MESSAGE_BYTES`; on failure the error is the helper's stderr.
+pub fn run_helper(args: &[&str]) -> Result<Vec<u8>, String> {
+    let exe = worker_executable()?;
+    let mut child = Command::new("pkexec")
+        .arg(exe)
+        .args(args)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to spawn pkexec: {}", e))?;
+
+    let mut stdout = Vec::new();
+    if let Some(pipe) = child.stdout.take() {
+        pipe.take(MAX_MESSAGE_BYTES as u64 + 1)
+            .read_to_end(&mut stdout)
+            .map_err(|e| e.to_string())?;
+    }
+    if stdout.len() > MAX_MESSAGE_BYTES {
+        let _ = child.kill();
+        let _ = child.wait();
+        return Err(format!("output of {} is too large", args.join(" ")));
+    }
+    let mut stderr = Vec::new();
+    if let Some(pipe) = child.stderr.take() {
+        let _ = pipe.take(MAX_MESSAGE_BYTES as u64).read_to_end(&mut stderr);
+    }
+    let status = child.wait().map_err(|e| e.to_string())?;
+    if status.success() {
+        Ok(stdout)
+    } else {
+        let stderr = String::from_utf8_lossy(&stderr);
+        Err(match stderr.trim() {
+            "" => format!("{} failed: {}", args.join(" "), status),
+            message => message.to_string(),
+        })
+    }
+}
+
+/// Parses the JSON list a one-shot helper printed, as strictly as worker messages: the
+/// size and entry count are limited and the item type should reject unknown fields.
+pub fn parse_helper_list<T: DeserializeOwned>(output: &[u8]) -> Result<Vec<T>, String> {
+    if output.len() > MAX_MESSAGE_BYTES {
+        return Err(format!("output of {} bytes is too large", output.len()));
+    }
+    let text = std::str::from_utf8(output).map_err(|e| format!("invalid UTF-8: {}", e))?;
+    let list: Vec<T> =
+        serde_json::from_str(text.trim_end()).map_err(|e| format!("invalid output: {}", e))?;
+    if list.len() > MAX_LIST_LEN {
+        return Err(format!("{} entries (max {})", list.len(), MAX_LIST_LEN));
+    }
+    Ok(list)
+}
+
+/// Runs the privileged worker loop. With `capture`, packets are also captured and
+/// attributed to protocols (see `capture.rs`). Disabled `collectors` are skipped.
+/// After startup the worker confines itself (see `sandbox.rs`).
+pub fn run_worker(capture: bool, collectors: &CollectorSettings) {
     // This runs as root
+    let collector = if capture {
+        match CaptureCollector::start() {
+            Ok(c) => Some(c),
+            Err(e) => {
+                eprintln!("{}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let mut kmsg = KmsgReader::open();
+    let mut xid_scanner = collectors.gpu.then(XidScanner::new);
+    let mut denial_scanner = DenialScanner::new();
     let mut system = sysinfo::System::new_all();
-    let mut networks = sysinfo::Networks::new_with_refreshed_list();
+    let mut networks = if collectors.network {
+        sysinfo::Networks::new_with_refreshed_list()
+    } else {
+        sysinfo::Networks::new()
+    };
+    let paths = crate::monitor::SysPaths::default();
+
+    // Everything that needs full root is open now
+    crate::sandbox::confine_worker(collectors.smart, collectors.network);
 
     loop {
         system.refresh_all();
-        networks.refresh(true);
+        if collectors.network {
+            networks.refresh(true);
+        }
 
         // 1. Storage (Privileged: SMART)
-        let storage_details = crate::monitor::get_storage_detailed_info_headless();
+        let storage_details =
+            crate::monitor::get_storage_detailed_info_headless(&paths, collectors.smart);
 
         // 2. Network (Privileged: Speed? Actually non-privileged usually fine, but consistent)
-        let network_details = crate::monitor::get_network_detailed_info_headless(&networks);
+        let network_details = crate::monitor::get_network_detailed_info_headless(&paths, &networks);
+
+        // 3. Network namespaces (Privileged: other users' /proc/<pid>/ns/net)
+        let namespaces = if collectors.network {
+            crate::netns::list_namespaces()
+        } else {
+            Vec::new()
+        };
+
+        // 4. Protocol breakdown (Privileged: packet capture, opt-in)
+        let protocols = collector.as_ref().map(|c| c.take()).unwrap_or_default();
+
+        // 5. Kernel log and GPU driver errors (Privileged: /dev/kmsg)
+        let new_entries = kmsg.read_new();
+        let gpu_events = xid_scanner
+            .as_mut()
+            .map(|s| s.scan(&new_entries))
+            .unwrap_or_default();
 
-        // 3. Serialize
+        // 6. SELinux/AppArmor denials (Privileged: audit log, AppArmor profile list)
+        let mac_denials = denial_scanner.scan(&new_entries);
+
+        // 7. Serialize
         let data = PrivilegedData {
             storage: storage_details,
             network: network_details,
+            namespaces,
+            protocols,
+            gpu_events,
+            kernel_log: kmsg.recent().to_vec(),
+            mac_denials,
         };
 
         if let Ok(json) = serde_json::to_string(&data) {
@@ -40,6 +278,154 @@ pub fn run_worker() {
             io::stdout().flush().unwrap();
         }
 
-        thread::sleep(Duration::from_secs(2));
+        thread::sleep(Duration::from_secs(WORKER_INTERVAL_SECS));
+    }
+}
+
+/// Sets the power management limit of the GPU at `index` to `watts`.
+///
+/// Invoked as a one-shot privileged helper (`--set-gpu-power-limit <index> <watts>`)
+/// because NVML only accepts limit changes from root. The requested value is checked
+/// against the device's reported constraints before it is applied.
+pub fn set_gpu_power_limit(index: u32, watts: f32) -> Result<(), String> {
+    let nvml = nvml_wrapper::Nvml::init().map_err(|e| format!("NVML init failed: {}", e))?;
+    let mut dev = nvml
+        .device_by_index(index)
+        .map_err(|e| format!("GPU {} not found: {}", index, e))?;
+
+    let limit_mw = (watts * 1000.0).round() as u32;
+    let constraints = dev
+        .power_management_limit_constraints()
+        .map_err(|e| format!("Power limit not supported: {}", e))?;
+    if limit_mw < constraints.min_limit || limit_mw > constraints.max_limit {
+        return Err(format!(
+            "{:.0} W is outside the allowed range {:.0}-{:.0} W",
+            watts,
+            constraints.min_limit as f32 / 1000.0,
+            constraints.max_limit as f32 / 1000.0
+        ));
+    }
+
+    dev.set_power_management_limit(limit_mw)
+        .map_err(|e| format!("Failed to set power limit: {}", e))
+}
+
+/// Sets the PWM duty cycle of fan `index` on hwmon chip `chip`, or hands it back to
+/// automatic control when `value` is `None`.
+///
+/// Invoked as a one-shot privileged helper (`--set-fan-pwm <chip> <index> <value|auto>`)
+/// because hwmon PWM files are only writable by root.
+pub fn set_fan_pwm(chip: &str, index: u32, value: Option<u8>) -> Result<(), String> {
+    // Only accept plain "hwmonN" names so the helper can't be pointed at arbitrary paths
+    if !chip
+        .strip_prefix("hwmon")
+        .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+    {
+        return Err(format!("Invalid hwmon chip: {}", chip));
+    }
+
+    let dir = std::path::Path::new(crate::hwmon::HWMON_ROOT).join(chip);
+    let pwm = dir.join(format!("pwm{}", index));
+    let enable = dir.join(format!("pwm{}_enable", index));
+    if !pwm.exists() {
+        return Err(format!("{} has no pwm{} control", chip, index));
+    }
+
+    match value {
+        Some(duty) => {
+            // 1 = manual control
+            std::fs::write(&enable, "1")
+                .map_err(|e| format!("Failed to enable manual control: {}", e))?;
+            std::fs::write(&pwm, duty.to_string())
+                .map_err(|e| format!("Failed to set fan speed: {}", e))
+        }
+        // 2 = automatic (driver/firmware) control on most chips
+        None => std::fs::write(&enable, "2")
+            .map_err(|e| format!("Failed to restore automatic control: {}", e)),
+    }
+}
+
+/// Sets the nice value, scheduling policy and CPU affinity of every thread of process
+/// `pid`, like `renice`, `chrt` and `taskset -a` together.
+///
+/// Invoked as a one-shot privileged helper (`--set-process-scheduling <pid> <nice>
+/// <policy> <cpus>`) because raising the priority of a process, or changing processes of
+/// other users, needs root. Only the `SchedPolicy::EDITABLE` policies are accepted.
+pub fn set_process_scheduling(pid: u32, nice: i32, policy: &str, cpus: &str) -> Result<(), String> {
+    use crate::processes::{parse_cpu_list, SchedPolicy};
+
+    if !(-20..=19).contains(&nice) {
+        return Err(format!("Nice value {} is outside -20 to 19", nice));
+    }
+    let policy = SchedPolicy::from_name(policy)
+        .filter(|p| SchedPolicy::EDITABLE.contains(p))
+        .ok_or_else(|| format!("Unsupported scheduling policy: {}", policy))?;
+    let cpus = parse_cpu_list(cpus)?;
+    let mut set: libc::cpu_set_t = unsafe { std::mem::zeroed() };
+    for &cpu in &cpus {
+        if cpu >= libc::CPU_SETSIZE as usize {
+            return Err(format!("CPU {} is out of range", cpu));
+        }
+        unsafe { libc::CPU_SET(cpu, &mut set) };
+    }
+
+    // PID 0 would mean the helper itself
+    if pid == 0 {
+        return Err("Invalid PID 0".to_string());
+    }
+    let tasks = std::fs::read_dir(format!("/proc/{}/task", pid))
+        .map_err(|e| format!("Process {} not found: {}", pid, e))?;
+    for task in tasks.flatten() {
+        let Ok(tid) = task.file_name().to_string_lossy().parse::<libc::pid_t>() else {
+            continue;
+        };
+        // SCHED_IDLE ignores the nice value, but it is kept for a later switch back
+        let param = libc::sched_param { sched_priority: 0 };
+        if unsafe { libc::sched_setscheduler(tid, policy.raw(), &param) } != 0 {
+            return Err(format!(
+                "Failed to set the policy of thread {}: {}",
+                tid,
+                std::io::Error::last_os_error()
+            ));
+        }
+        if unsafe { libc::setpriority(libc::PRIO_PROCESS, tid as libc::id_t, nice) } != 0 {
+            return Err(format!(
+                "Failed to set the nice value of thread {}: {}",
+                tid,
+                std::io::Error::last_os_error()
+            ));
+        }
+        if unsafe { libc::sched_setaffinity(tid, std::mem::size_of::<libc::cpu_set_t>(), &set) }
+            != 0
+        {
+            return Err(format!(
+                "Failed to set the CPU affinity of thread {}: {}",
+                tid,
+                std::io::Error::last_os_error()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Suspends or powers off the machine through systemd.
+///
+/// Invoked as a one-shot privileged helper (`--power-action <suspend|shutdown>`) when the
+/// idle action's countdown runs out, since polkit may refuse both to a plain user, e.g.
+/// shutting down while other users are logged in.
+pub fn power_action(action: &str) -> Result<(), String> {
+    let verb = match action {
+        "suspend" => "suspend",
+        "shutdown" => "poweroff",
+        _ => return Err(format!("Unknown power action: {}", action)),
+    };
+    let status = crate::sandbox::command("systemctl")
+        .arg(verb)
+        .status()
+        .map_err(|e| format!("Failed to run systemctl: {}", e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("systemctl {} failed: {}", verb, status))
     }
 }
@@ -0,0 +1,182 @@
+//! # GPU Xid Module
+//!
+//! This module picks NVIDIA driver errors out of the kernel log: Xid events
+//! (`NVRM: Xid (PCI:0000:01:00): 79, ...`) and failed video memory allocations. They
+//! usually precede a crashed CUDA job or a hung desktop, so they are raised as alerts.
+//!
+//! Reading the kernel log needs root on most distributions (`kernel.dmesg_restrict`),
+//! so the privileged worker picks the events out of the `/dev/kmsg` records (see
+//! `kmsg.rs`) and reports them; the `XidMonitor` on the GUI side turns events it has not
+//! seen yet into alerts and timeline markers.
+
+use crate::alerts::{AlertCenter, AlertSeverity};
+use crate::kmsg::{uptime_secs, KernelLogEntry};
+use crate::markers::MarkerStore;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+/// How long the alert stays active after the most recent event.
+const ALERT_SECS: u64 = 600;
+/// Events kept by the worker (the whole list is resent every interval).
+const MAX_EVENTS: usize = 50;
+const ALERT_ID: &str = "gpu.kernel-errors";
+
+/// An NVIDIA driver error from the kernel log.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GpuKernelEvent {
+    /// Kernel timestamp (seconds since boot).
+    pub timestamp: f64,
+    /// PCI address of the GPU, e.g. "0000:01:00".
+    pub pci: String,
+    /// Xid code; `None` for allocation failures.
+    pub xid: Option<u32>,
+    /// The log message after the `NVRM:` prefix.
+    pub message: String,
+}
+
+/// Meaning of common Xid codes (from NVIDIA's Xid error documentation).
+fn xid_description(xid: u32) -> &'static str {
+    match xid {
+        13 => "graphics engine exception",
+        31 => "GPU memory page fault",
+        32 => "corrupted push buffer stream",
+        43 => "GPU stopped processing",
+        45 => "preemptive cleanup after a previous error",
+        48 => "double-bit ECC error",
+        61 | 62 => "internal micro-controller error",
+        63 => "ECC page retirement or row remapping",
+        64 => "ECC page retirement or row remapping failure",
+        69 => "graphics engine class error",
+        74 => "NVLink error",
+        79 => "GPU has fallen off the bus",
+        92 => "high single-bit ECC error rate",
+        94 => "contained ECC error",
+        95 => "uncontained ECC error",
+        119 | 120 => "GSP firmware error",
+        _ => "driver error",
+    }
+}
+
+/// Xids that mean the GPU (or its memory) needs a reset or replacement.
+fn is_fatal(xid: u32) -> bool {
+    matches!(xid, 48 | 64 | 79 | 95 | 119 | 120)
+}
+
+impl GpuKernelEvent {
+    /// Short human readable description.
+    pub fn describe(&self) -> String {
+        match self.xid {
+            Some(xid) => format!("GPU {} Xid {}: {}", self.pci, xid, xid_description(xid)),
+            None => format!("GPU memory allocation failed: {}", self.message),
+        }
+    }
+
+    fn severity(&self) -> AlertSeverity {
+        match self.xid {
+            Some(xid) if is_fatal(xid) => AlertSeverity::Critical,
+            _ => AlertSeverity::Warning,
+        }
+    }
+}
+
+/// Turns a kernel log entry into an event, if it is one.
+fn parse_entry(entry: &KernelLogEntry) -> Option<GpuKernelEvent> {
+    let timestamp = entry.timestamp;
+    let message = entry.message.trim().strip_prefix("NVRM:")?.trim();
+
+    if let Some(rest) = message.strip_prefix("Xid (PCI:") {
+        // "0000:01:00): 79, pid=..., GPU has fallen off the bus."
+        let (pci, rest) = rest.split_once("):")?;
+        let xid = rest.trim().split(',').next()?.trim().parse().ok()?;
+        return Some(GpuKernelEvent {
+            timestamp,
+            pci: pci.to_string(),
+            xid: Some(xid),
+            message: message.to_string(),
+        });
+    }
+
+    let lower = message.to_lowercase();
+    (lower.contains("out of memory") || lower.contains("failed to allocate")).then(|| {
+        GpuKernelEvent {
+            timestamp,
+            pci: String::new(),
+            xid: None,
+            message: message.to_string(),
+        }
+    })
+}
+
+/// Worker side: collects events from the kernel log records.
+#[derive(Default)]
+pub struct XidScanner {
+    events: Vec<GpuKernelEvent>,
+}
+
+impl XidScanner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the events among the new kernel log `entries` and returns the most recent
+    /// events (oldest first).
+    pub fn scan(&mut self, entries: &[KernelLogEntry]) -> Vec<GpuKernelEvent> {
+        self.events.extend(entries.iter().filter_map(parse_entry));
+        let excess = self.events.len().saturating_sub(MAX_EVENTS);
+        self.events.drain(..excess);
+        self.events.clone()
+    }
+}
+
+/// GUI side: raises alerts and markers for events newer than the last one handled.
+pub struct XidMonitor {
+    /// Kernel timestamp of the newest handled event. Starts at the current uptime, so
+    /// errors from before Gjallarhorn started do not raise alerts.
+    handled_until: f64,
+    last_event: Option<Instant>,
+}
+
+impl Default for XidMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl XidMonitor {
+    pub fn new() -> Self {
+        Self {
+            handled_until: uptime_secs(),
+            last_event: None,
+        }
+    }
+
+    /// Handles new `events` and clears the alert once no event arrived for a while.
+    pub fn update(
+        &mut self,
+        events: &[GpuKernelEvent],
+        alerts: &mut AlertCenter,
+        markers: &mut MarkerStore,
+    ) {
+        let handled_until = self.handled_until;
+        for event in events.iter().filter(|e| e.timestamp > handled_until) {
+            // Keep the worst severity until the alert clears
+            let severity = alerts
+                .active()
+                .iter()
+                .find(|a| a.id == ALERT_ID)
+                .map_or(event.severity(), |a| a.severity.max(event.severity()));
+            markers.add(&event.describe());
+            alerts.raise(ALERT_ID, severity, event.describe());
+            self.handled_until = event.timestamp;
+            self.last_event = Some(Instant::now());
+        }
+
+        if self
+            .last_event
+            .is_some_and(|t| t.elapsed() > Duration::from_secs(ALERT_SECS))
+        {
+            alerts.clear(ALERT_ID);
+            self.last_event = None;
+        }
+    }
+}
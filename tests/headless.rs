@@ -0,0 +1,248 @@
+//! Runs the headless data gathering functions and the sysfs trackers against the fixture
+//! `/sys` and `/proc` trees and captured `dmidecode` output in `tests/fixtures`.
+
+use gjallarhorn::battery::BatteryTracker;
+use gjallarhorn::diskstats::DiskLatencyTracker;
+use gjallarhorn::history::HistoryLimits;
+use gjallarhorn::hwmon::FanTracker;
+use gjallarhorn::monitor::{
+    get_storage_detailed_info_headless, parse_dmidecode_memory, read_link_speed,
+    StorageDetailedInfo, SysPaths,
+};
+use gjallarhorn::processes::{read_info, read_sched, ProcessInfo, SchedInfo, SchedPolicy};
+use gjallarhorn::sensors::{SensorKind, SensorTracker};
+use std::path::PathBuf;
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+/// One minute of samples at one per second, as the trackers get from the monitor.
+fn limits() -> HistoryLimits {
+    HistoryLimits::new(60, 1000, 1)
+}
+
+fn storage() -> Vec<StorageDetailedInfo> {
+    get_storage_detailed_info_headless(&SysPaths::under(fixture("nvme-sata")), false)
+}
+
+fn dmidecode(name: &str) -> (String, String, u32) {
+    let output = std::fs::read_to_string(fixture("dmidecode").join(name)).unwrap();
+    parse_dmidecode_memory(&output)
+}
+
+#[test]
+fn storage_skips_partitions_loop_and_optical() {
+    let names: Vec<String> = storage().into_iter().map(|d| d.device_name).collect();
+    let mut sorted = names.clone();
+    sorted.sort();
+    assert_eq!(sorted, ["nvme0n1", "sda", "vdb"]);
+}
+
+#[test]
+fn storage_reads_nvme_controller_attributes() {
+    let nvme = storage()
+        .into_iter()
+        .find(|d| d.device_name == "nvme0n1")
+        .unwrap();
+    assert_eq!(nvme.model, "Samsung SSD 980 PRO 1TB");
+    assert_eq!(nvme.serial_number, "S5GXNF0R123456");
+    assert_eq!(nvme.firmware_version, "5B2QGXA7");
+    assert_eq!(nvme.interface_type, "NVMe");
+    assert!(nvme.is_ssd);
+    assert_eq!(nvme.capacity_bytes, 1000215216 * 512);
+    assert_eq!(nvme.health_status, "Disabled");
+}
+
+#[test]
+fn storage_reads_sata_attributes() {
+    let sda = storage()
+        .into_iter()
+        .find(|d| d.device_name == "sda")
+        .unwrap();
+    assert_eq!(sda.model, "WDC WD20EFRX-68E");
+    assert_eq!(sda.firmware_version, "0A82");
+    assert_eq!(sda.serial_number, "Unknown");
+    assert_eq!(sda.interface_type, "SATA");
+    assert!(!sda.is_ssd);
+}
+
+#[test]
+fn storage_tolerates_missing_files() {
+    let vdb = storage()
+        .into_iter()
+        .find(|d| d.device_name == "vdb")
+        .unwrap();
+    assert_eq!(vdb.model, "Unknown");
+    assert_eq!(vdb.serial_number, "Unknown");
+    assert_eq!(vdb.firmware_version, "Unknown");
+    assert_eq!(vdb.interface_type, "VirtIO");
+    assert_eq!(vdb.capacity_bytes, 41943040 * 512);
+}
+
+#[test]
+fn storage_without_sysfs_is_empty() {
+    let paths = SysPaths::under(fixture("does-not-exist"));
+    assert!(get_storage_detailed_info_headless(&paths, false).is_empty());
+}
+
+#[test]
+fn link_speed_of_virtual_interfaces_is_unknown() {
+    let paths = SysPaths::under(fixture("nvme-sata"));
+    assert_eq!(read_link_speed(&paths, "eth0"), "1000 Mbps");
+    assert_eq!(read_link_speed(&paths, "wlan0"), "Unknown");
+    assert_eq!(read_link_speed(&paths, "lo"), "Unknown");
+    assert_eq!(read_link_speed(&paths, "missing0"), "Unknown");
+}
+
+#[test]
+fn dmidecode_counts_populated_slots_only() {
+    assert_eq!(
+        dmidecode("ddr4.txt"),
+        ("DDR4".to_string(), "3200 MT/s".to_string(), 2)
+    );
+}
+
+#[test]
+fn dmidecode_keeps_unknown_speed_of_virtual_machines() {
+    assert_eq!(
+        dmidecode("vm.txt"),
+        ("RAM".to_string(), "Unknown".to_string(), 1)
+    );
+}
+
+#[test]
+fn dmidecode_skips_other_type() {
+    assert_eq!(
+        dmidecode("other-type.txt"),
+        ("LPDDR5".to_string(), "6400 MT/s".to_string(), 2)
+    );
+}
+
+#[test]
+fn dmidecode_without_smbios() {
+    assert_eq!(
+        dmidecode("no-smbios.txt"),
+        ("Unknown".to_string(), "Unknown".to_string(), 0)
+    );
+}
+
+#[test]
+fn batteries_skip_mains_and_peripherals() {
+    let mut tracker = BatteryTracker::new(&SysPaths::under(fixture("laptop")));
+    tracker.refresh(limits());
+    let batteries = tracker.get_data();
+    assert_eq!(batteries.len(), 1);
+    let battery = &batteries[0];
+    assert_eq!(battery.name, "BAT0");
+    assert_eq!(battery.model, "LGC 5B10W13930");
+    assert_eq!(battery.charge_percent, 80.0);
+    assert_eq!(battery.power_watts, -10.0);
+    // 40 Wh left at 10 W
+    assert_eq!(battery.time_remaining_secs, Some(4 * 3600));
+    assert_eq!(battery.cycle_count, Some(312));
+}
+
+#[test]
+fn disk_latency_tracks_whole_drives_only() {
+    let mut tracker = DiskLatencyTracker::new(&SysPaths::under(fixture("laptop")));
+    tracker.refresh(limits());
+    let devices: Vec<String> = tracker.get_data().into_iter().map(|d| d.device).collect();
+    assert_eq!(devices, ["nvme0n1"]);
+}
+
+#[test]
+fn sensors_read_every_hwmon_input() {
+    let mut tracker = SensorTracker::new(&SysPaths::under(fixture("laptop")));
+    tracker.refresh(limits());
+    let sensors: Vec<(String, SensorKind, String, Option<f32>)> = tracker
+        .get_data()
+        .into_iter()
+        .map(|s| (s.driver, s.kind, s.label, s.value))
+        .collect();
+    assert_eq!(
+        sensors,
+        [
+            (
+                "acpitz".into(),
+                SensorKind::Temperature,
+                "temp1".into(),
+                Some(45.5)
+            ),
+            (
+                "thinkpad".into(),
+                SensorKind::Temperature,
+                "CPU".into(),
+                Some(52.0)
+            ),
+            (
+                "thinkpad".into(),
+                SensorKind::Fan,
+                "fan1".into(),
+                Some(2450.0)
+            ),
+            (
+                "thinkpad".into(),
+                SensorKind::Voltage,
+                "in0".into(),
+                Some(12.15)
+            ),
+        ]
+    );
+    assert_eq!(tracker.get_data()[0].critical, Some(100.0));
+}
+
+#[test]
+fn fans_share_the_sensor_readings() {
+    let paths = SysPaths::under(fixture("laptop"));
+    let mut sensors = SensorTracker::new(&paths);
+    let mut fans = FanTracker::new(&paths);
+    for _ in 0..3 {
+        sensors.refresh(limits());
+        fans.refresh(&sensors);
+    }
+    let data = fans.get_data(&sensors);
+    assert_eq!(data.len(), 1);
+    let fan = &data[0];
+    assert_eq!(fan.label, "thinkpad: fan1");
+    assert_eq!(fan.rpm, 2450);
+    assert_eq!(fan.temperature, Some(52.0));
+    assert_eq!(fan.pwm, Some(128));
+    assert!(fan.pwm_auto);
+    assert_eq!(fan.rpm_history.len(), 3);
+    assert_eq!(fan.temp_history.len(), 3);
+}
+
+#[test]
+fn process_info_reads_proc_pid() {
+    let proc_root = SysPaths::under(fixture("laptop")).proc;
+    let info = read_info(&proc_root, 812);
+    assert_eq!(info.cmdline, "nginx: worker process");
+    assert_eq!(info.threads, Some(4));
+    assert_eq!(info.open_files, Some(5));
+
+    // Kernel thread: no command line; fd directory not readable
+    let info = read_info(&proc_root, 2);
+    assert_eq!(info.cmdline, "");
+    assert_eq!(info.threads, Some(1));
+    assert_eq!(info.open_files, None);
+
+    assert_eq!(read_info(&proc_root, 99999), ProcessInfo::default());
+}
+
+#[test]
+fn process_scheduling_reads_stat_and_status() {
+    let proc_root = SysPaths::under(fixture("laptop")).proc;
+    assert_eq!(
+        read_sched(&proc_root, 812),
+        Some(SchedInfo {
+            nice: 5,
+            policy: Some(SchedPolicy::Batch),
+            affinity: "0-3".to_string(),
+        })
+    );
+    // No stat file
+    assert_eq!(read_sched(&proc_root, 2), None);
+}